@@ -557,7 +557,7 @@ impl NodeConfig {
         let fees = FeeManager::new(env.cfg.spec_id, self.get_base_fee(), self.get_gas_price());
         let mut fork_timestamp = None;
 
-        let (db, fork): (Arc<tokio::sync::RwLock<dyn Db>>, Option<ClientFork>) =
+        let (db, fork): (Arc<tokio::sync::RwLock<Box<dyn Db>>>, Option<ClientFork>) =
             if let Some(eth_rpc_url) = self.eth_rpc_url.clone() {
                 // TODO make provider agnostic
                 let provider = Arc::new(
@@ -656,10 +656,7 @@ impl NodeConfig {
                     Some(fork_block_number.into()),
                 );
 
-                let db = Arc::new(tokio::sync::RwLock::new(ForkedDatabase::new(
-                    backend,
-                    block_chain_db,
-                )));
+                let db = ForkedDatabase::new(backend, block_chain_db);
                 let fork = ClientFork::new(
                     ClientForkConfig {
                         eth_rpc_url,
@@ -671,17 +668,18 @@ impl NodeConfig {
                         timestamp: block.timestamp.as_u64(),
                         base_fee: block.base_fee_per_gas,
                     },
-                    Arc::clone(&db),
+                    Arc::new(tokio::sync::RwLock::new(db.clone())),
                 );
 
-                (db, Some(fork))
+                (Arc::new(tokio::sync::RwLock::new(Box::new(db))), Some(fork))
             } else {
-                (Arc::new(tokio::sync::RwLock::new(MemDb::default())), None)
+                (Arc::new(tokio::sync::RwLock::new(Box::new(MemDb::default()))), None)
             };
 
         let genesis = GenesisConfig {
             balance: self.genesis_balance,
             accounts: self.genesis_accounts.iter().map(|acc| acc.address()).collect(),
+            chain_id: self.get_chain_id(),
         };
         // only memory based backend for now
 
@@ -862,7 +860,7 @@ impl AccountGenerator {
 ///
 /// This fetches the "latest" block and checks whether the `Block` is fully populated (`hash` field
 /// is present). This prevents edge cases where anvil forks the "latest" block but `eth_getBlockByNumber` still returns a pending block, <https://github.com/foundry-rs/foundry/issues/2036>
-async fn find_latest_fork_block<M: Middleware>(provider: M) -> Result<u64, M::Error> {
+pub(crate) async fn find_latest_fork_block<M: Middleware>(provider: M) -> Result<u64, M::Error> {
     let mut num = provider.get_block_number().await?.as_u64();
 
     // walk back from the head of the chain, but at most 2 blocks, which should be more than enough
@@ -16,7 +16,6 @@ use crate::{
         },
         sign,
         sign::Signer,
-        util::PRECOMPILES,
     },
     filter::{EthFilter, Filters, LogsFilter},
     mem::transaction_build,
@@ -42,7 +41,7 @@ use ethers::{
     providers::ProviderError,
     types::{
         transaction::{
-            eip2930::{AccessList, AccessListItem, AccessListWithGasUsed},
+            eip2930::AccessListWithGasUsed,
             eip712::TypedData,
         },
         Address, Block, BlockId, BlockNumber, Bytes, FeeHistory, Filter, FilteredParams, Log,
@@ -52,10 +51,7 @@ use ethers::{
     utils::rlp,
 };
 use foundry_common::ProviderBuilder;
-use foundry_evm::{
-    revm::{return_ok, return_revert, Return},
-    utils::u256_to_h256_be,
-};
+use foundry_evm::revm::{return_ok, return_revert, Return};
 use futures::channel::mpsc::Receiver;
 use parking_lot::RwLock;
 use std::{sync::Arc, time::Duration};
@@ -743,7 +739,8 @@ impl EthApi {
 
     /// This method creates an EIP2930 type accessList based on a given Transaction. The accessList
     /// contains all storage slots and addresses read and written by the transaction, except for the
-    /// sender account and the precompiles.
+    /// sender and the recipient accounts and the precompiles, which are only listed if their
+    /// storage is accessed.
     ///
     /// It returns list of addresses and storage keys used by the transaction, plus the gas
     /// consumed when the access list is added. That is, it gives you the list of addresses and
@@ -769,37 +766,20 @@ impl EthApi {
             }
         }
 
-        // ensure tx succeeds
-        let (exit, out, _, mut state) =
-            self.backend.call(request.clone(), FeeDetails::zero(), block_number).await?;
+        // trace the accesses and ensure tx succeeds
+        let (exit, out, _, access_list) = self
+            .backend
+            .build_access_list(request.clone(), FeeDetails::zero(), block_number)
+            .await?;
 
         ensure_return_ok(exit, &out)?;
 
-        // cleanup state map
-        if let Some(from) = request.from {
-            // remove the sender
-            let _ = state.remove(&from);
-        }
-
-        // remove all precompiles
-        for precompile in PRECOMPILES.iter() {
-            let _ = state.remove(precompile);
-        }
-
-        let items = state
-            .into_iter()
-            .map(|(address, acc)| {
-                let storage_keys = acc.storage.into_keys().map(u256_to_h256_be).collect();
-                AccessListItem { address, storage_keys }
-            })
-            .collect::<Vec<_>>();
-
-        // execute again but with access list set
-        request.access_list = Some(items.clone());
+        // execute again but with access list set, so the estimate includes its discount
+        request.access_list = Some(access_list.0.clone());
 
         let gas_used = self.do_estimate_gas(request, Some(number.into())).await?;
 
-        Ok(AccessListWithGasUsed { access_list: AccessList(items), gas_used })
+        Ok(AccessListWithGasUsed { access_list, gas_used })
     }
 
     /// Estimate gas needed for execution of given contract.
@@ -949,59 +929,84 @@ impl EthApi {
             BlockNumber::Number(n) => n.as_u64(),
         };
 
-        // check if the number predates the fork, if in fork mode
+        const MAX_BLOCK_COUNT: u64 = 1024u64;
+        let range_limit = U256::from(MAX_BLOCK_COUNT);
+        let block_count =
+            if block_count > range_limit { range_limit.as_u64() } else { block_count.as_u64() };
+
+        // highest and lowest block num in the requested range, `block_count` blocks ending with
+        // the newest block
+        let highest = number;
+        let lowest = highest.saturating_sub(block_count.saturating_sub(1));
+
+        let mut response = FeeHistory {
+            oldest_block: U256::from(lowest),
+            base_fee_per_gas: Vec::new(),
+            gas_used_ratio: Vec::new(),
+            reward: Default::default(),
+        };
+
+        // the local cache only has entries for blocks mined after the fork
+        let mut local_lowest = lowest;
         if let Some(fork) = self.get_fork() {
+            let fork_block = fork.block_number();
             // if we're still at the forked block we don't have any history and can't compute it
             // efficiently, instead we fetch it from the fork
-            if number <= fork.block_number() {
+            if highest <= fork_block {
                 return Ok(fork
                     .fee_history(
-                        block_count,
+                        block_count.into(),
                         BlockNumber::Number(number.into()),
                         &reward_percentiles,
                     )
                     .await?)
             }
+            // fetch the part of the range that predates the fork from the fork and fill the rest
+            // from the local cache
+            if lowest <= fork_block {
+                let upstream = fork
+                    .fee_history(
+                        (fork_block - lowest + 1).into(),
+                        BlockNumber::Number(fork_block.into()),
+                        &reward_percentiles,
+                    )
+                    .await?;
+                // the upstream response includes the base fee of the block after the fork block,
+                // which is recomputed below
+                let upstream_blocks = upstream.gas_used_ratio.len();
+                response.oldest_block = upstream.oldest_block;
+                response.base_fee_per_gas =
+                    upstream.base_fee_per_gas.into_iter().take(upstream_blocks).collect();
+                response.gas_used_ratio = upstream.gas_used_ratio;
+                response.reward = upstream.reward;
+                local_lowest = fork_block + 1;
+            }
         }
 
-        const MAX_BLOCK_COUNT: u64 = 1024u64;
-        let range_limit = U256::from(MAX_BLOCK_COUNT);
-        let block_count =
-            if block_count > range_limit { range_limit.as_u64() } else { block_count.as_u64() };
-
-        // highest and lowest block num in the requested range
-        let highest = number;
-        let lowest = highest.saturating_sub(block_count);
-
         // only support ranges that are in cache range
-        if lowest < self.backend.best_number().as_u64().saturating_sub(self.fee_history_limit) {
+        let oldest_cached =
+            self.backend.best_number().as_u64().saturating_sub(self.fee_history_limit);
+        if local_lowest < oldest_cached {
             return Err(FeeHistoryError::InvalidBlockRange.into())
         }
 
         let fee_history = self.fee_history_cache.lock();
 
-        let mut response = FeeHistory {
-            oldest_block: U256::from(lowest),
-            base_fee_per_gas: Vec::new(),
-            gas_used_ratio: Vec::new(),
-            reward: Default::default(),
-        };
-
-        let mut rewards = Vec::new();
         // iter over the requested block range
-        for n in lowest..highest + 1 {
+        for n in local_lowest..highest + 1 {
             // <https://eips.ethereum.org/EIPS/eip-1559>
             if let Some(block) = fee_history.get(&n) {
                 response.base_fee_per_gas.push(U256::from(block.base_fee));
-                response.gas_used_ratio.push(block.gas_used_ratio);
+                response.gas_used_ratio.push(block.gas_used_ratio());
 
                 // requested percentiles
                 if !reward_percentiles.is_empty() {
                     let mut block_rewards = Vec::new();
+                    // the cache holds rewards at a resolution of 0.5 percentiles
                     let resolution_per_percentile: f64 = 2.0;
                     for p in &reward_percentiles {
                         let p = p.clamp(0.0, 100.0);
-                        let index = ((p.round() / 2f64) * 2f64) * resolution_per_percentile;
+                        let index = (p * resolution_per_percentile).round();
                         let reward = if let Some(r) = block.rewards.get(index as usize) {
                             U256::from(*r)
                         } else {
@@ -1009,13 +1014,11 @@ impl EthApi {
                         };
                         block_rewards.push(reward);
                     }
-                    rewards.push(block_rewards);
+                    response.reward.push(block_rewards);
                 }
             }
         }
 
-        response.reward = rewards;
-
         // calculate next base fee
         if let (Some(last_gas_used), Some(last_fee_per_gas)) =
             (response.gas_used_ratio.last(), response.base_fee_per_gas.last())
@@ -1237,16 +1240,14 @@ impl EthApi {
 
     /// Reset the fork to a fresh forked state, and optionally update the fork config.
     ///
-    /// If `forking` is `None` then this will disable forking entirely.
+    /// If `forking` is `None` then the chain is reset to a local chain with a fresh genesis, a
+    /// local chain is forked if `forking` has a url, see
+    /// [`Backend::reset()`](crate::eth::backend::mem::Backend::reset)
     ///
     /// Handler for RPC call: `anvil_reset`
     pub async fn anvil_reset(&self, forking: Option<Forking>) -> Result<()> {
         node_info!("anvil_reset");
-        if let Some(forking) = forking {
-            self.backend.reset_fork(forking).await
-        } else {
-            Err(BlockchainError::RpcUnimplemented)
-        }
+        self.backend.reset(forking).await
     }
 
     /// Modifies the balance of an account.
@@ -1776,7 +1777,7 @@ impl EthApi {
         self.backend.chain_id().as_u64()
     }
 
-    pub fn get_fork(&self) -> Option<&ClientFork> {
+    pub fn get_fork(&self) -> Option<ClientFork> {
         self.backend.get_fork()
     }
 
@@ -91,6 +91,13 @@ pub trait Db: DatabaseRef + Database + DatabaseCommit + MaybeHashDatabase + Send
 
     /// Returns the current, standalone state of the Db
     fn current_state(&self) -> StateDb;
+
+    /// Removes all accounts, storage, block hashes and snapshots
+    ///
+    /// Returns `false` if the Db can not be cleared
+    fn clear(&mut self) -> bool {
+        false
+    }
 }
 
 /// Convenience impl only used to use any `Db` on the fly as the db layer for revm's CacheDB
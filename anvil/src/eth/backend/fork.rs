@@ -1,6 +1,9 @@
 //! Support for forking off another client
 
-use crate::eth::{backend::mem::fork_db::ForkedDatabase, error::BlockchainError};
+use crate::{
+    config::find_latest_fork_block,
+    eth::{backend::mem::fork_db::ForkedDatabase, error::BlockchainError},
+};
 use anvil_core::eth::transaction::EthTransactionRequest;
 use ethers::{
     prelude::BlockNumber,
@@ -11,7 +14,11 @@ use ethers::{
     },
 };
 use foundry_common::{ProviderBuilder, RetryProvider};
-use foundry_evm::utils::u256_to_h256_be;
+use foundry_evm::{
+    executor::fork::{BlockchainDb, BlockchainDbMeta, SharedBackend},
+    revm::Env,
+    utils::u256_to_h256_be,
+};
 use parking_lot::{
     lock_api::{RwLockReadGuard, RwLockWriteGuard},
     RawRwLock, RwLock,
@@ -33,6 +40,9 @@ pub struct ClientFork {
     // endpoints
     pub config: Arc<RwLock<ClientForkConfig>>,
     /// This also holds a handle to the underlying database
+    ///
+    /// The chain writes to its own copy of this database, both share the data fetched from the
+    /// remote client
     pub database: Arc<AsyncRwLock<ForkedDatabase>>,
 }
 
@@ -44,6 +54,51 @@ impl ClientFork {
         Self { storage: Default::default(), config: Arc::new(RwLock::new(config)), database }
     }
 
+    /// Connects to the remote client at the `eth_rpc_url` and forks off its `block_number`, or its
+    /// latest block if not set, for a chain with the `env`
+    pub async fn connect(
+        eth_rpc_url: String,
+        block_number: Option<u64>,
+        env: &Env,
+    ) -> Result<Self, BlockchainError> {
+        let provider = Arc::new(
+            ProviderBuilder::new(&eth_rpc_url)
+                .max_retry(10)
+                .initial_backoff(1000)
+                .connect()
+                .await
+                .map_err(|_| BlockchainError::InvalidUrl(eth_rpc_url.clone()))?,
+        );
+        let block_number = match block_number {
+            Some(block_number) => block_number,
+            None => find_latest_fork_block(&provider).await?,
+        };
+        let block = provider.get_block(block_number).await?.ok_or(BlockchainError::BlockNotFound)?;
+        let block_hash = block.hash.ok_or(BlockchainError::BlockNotFound)?;
+        let chain_id = provider.get_chainid().await?.as_u64();
+
+        let mut env = env.clone();
+        env.cfg.chain_id = chain_id.into();
+        env.block.number = block_number.into();
+        let db = BlockchainDb::new(BlockchainDbMeta::new(env, eth_rpc_url.clone()), None);
+        let backend = SharedBackend::spawn_backend_thread(
+            Arc::clone(&provider),
+            db.clone(),
+            Some(block_number.into()),
+        );
+        let config = ClientForkConfig {
+            eth_rpc_url,
+            block_number,
+            block_hash,
+            provider,
+            chain_id,
+            override_chain_id: None,
+            timestamp: block.timestamp.as_u64(),
+            base_fee: block.base_fee_per_gas,
+        };
+        Ok(Self::new(config, Arc::new(AsyncRwLock::new(ForkedDatabase::new(backend, db)))))
+    }
+
     /// Reset the fork to a fresh forked state, and optionally update the fork config
     pub async fn reset(
         &self,
@@ -11,6 +11,8 @@ pub struct GenesisConfig {
     pub balance: U256,
     /// All accounts that should be initialised at genesis
     pub accounts: Vec<Address>,
+    /// The chain id of the node config, which a local chain is reset to
+    pub chain_id: u64,
 }
 
 // === impl GenesisConfig ===
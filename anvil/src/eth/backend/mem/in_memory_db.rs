@@ -109,6 +109,11 @@ impl Db for MemDb {
         Some(state_merkle_trie_root(&self.inner.accounts))
     }
 
+    fn clear(&mut self) -> bool {
+        *self = MemDb::default();
+        true
+    }
+
     fn current_state(&self) -> StateDb {
         StateDb::new(MemDb { inner: self.inner.clone(), ..Default::default() })
     }
@@ -9,13 +9,14 @@ use crate::{
             fork::ClientFork,
             genesis::GenesisConfig,
             notifications::{NewBlockNotification, NewBlockNotifications},
-            time::{utc_from_secs, TimeManager},
+            time::{duration_since_unix_epoch, utc_from_secs, TimeManager},
             validate::TransactionValidator,
         },
         error::{BlockchainError, InvalidTransactionError},
         fees::{FeeDetails, FeeManager},
         macros::node_info,
         pool::transactions::PoolTransaction,
+        util::PRECOMPILES,
     },
     mem::{
         in_memory_db::MemDb,
@@ -41,12 +42,13 @@ use ethers::{
     abi::ethereum_types::BigEndianHash,
     prelude::{BlockNumber, TxHash, H256, U256, U64},
     types::{
-        Address, Block as EthersBlock, BlockId, Bytes, Filter, FilteredParams, Log, Trace,
-        Transaction, TransactionReceipt,
+        transaction::eip2930::AccessList, Address, Block as EthersBlock, BlockId, Bytes, Filter,
+        FilteredParams, Log, Trace, Transaction, TransactionReceipt,
     },
-    utils::{keccak256, rlp},
+    utils::{get_contract_address, keccak256, rlp},
 };
 use foundry_evm::{
+    executor::inspector::AccessListTracer,
     revm,
     revm::{
         db::CacheDB, Account, CreateScheme, Env, Return, SpecId, TransactOut, TransactTo, TxEnv,
@@ -91,7 +93,10 @@ pub struct Backend {
     /// endpoints. Therefor the `Db` is guarded by a `tokio::sync::RwLock` here so calls that
     /// need to read from it, while it's currently written to, don't block. E.g. a new block is
     /// currently mined and a new [`Self::set_storage()`] request is being executed.
-    db: Arc<AsyncRwLock<dyn Db>>,
+    ///
+    /// The `Db` is boxed so that it can be swapped when the chain is reset from a local chain to a
+    /// fork or vice versa, see [`Self::reset()`].
+    db: Arc<AsyncRwLock<Box<dyn Db>>>,
     /// stores all block related data in memory
     blockchain: Blockchain,
     /// Historic states of previous blocks
@@ -99,7 +104,7 @@ pub struct Backend {
     /// env data of the chain
     env: Arc<RwLock<Env>>,
     /// this is set if this is currently forked off another client
-    fork: Option<ClientFork>,
+    fork: Arc<RwLock<Option<ClientFork>>>,
     /// provides time related info, like timestamp
     time: TimeManager,
     /// Contains state of custom overrides
@@ -116,14 +121,14 @@ pub struct Backend {
 
 impl Backend {
     /// Create a new instance of in-mem backend.
-    pub fn new(db: Arc<AsyncRwLock<dyn Db>>, env: Arc<RwLock<Env>>, fees: FeeManager) -> Self {
+    pub fn new(db: Arc<AsyncRwLock<Box<dyn Db>>>, env: Arc<RwLock<Env>>, fees: FeeManager) -> Self {
         let blockchain = Blockchain::new(&env.read(), fees.is_eip1559().then(|| fees.base_fee()));
         Self {
             db,
             blockchain,
             states: Arc::new(RwLock::new(Default::default())),
             env,
-            fork: None,
+            fork: Default::default(),
             time: Default::default(),
             cheats: Default::default(),
             new_block_listeners: Default::default(),
@@ -137,12 +142,12 @@ impl Backend {
     pub fn empty(env: Arc<RwLock<Env>>, gas_price: U256) -> Self {
         let db = MemDb::default();
         let fees = FeeManager::new(env.read().cfg.spec_id, gas_price, gas_price);
-        Self::new(Arc::new(AsyncRwLock::new(db)), env, fees)
+        Self::new(Arc::new(AsyncRwLock::new(Box::new(db))), env, fees)
     }
 
     /// Initialises the balance of the given accounts
     pub async fn with_genesis(
-        db: Arc<AsyncRwLock<dyn Db>>,
+        db: Arc<AsyncRwLock<Box<dyn Db>>>,
         env: Arc<RwLock<Env>>,
         genesis: GenesisConfig,
         fees: FeeManager,
@@ -161,7 +166,7 @@ impl Backend {
             blockchain,
             states: Arc::new(RwLock::new(Default::default())),
             env,
-            fork,
+            fork: Arc::new(RwLock::new(fork)),
             time: Default::default(),
             cheats: Default::default(),
             new_block_listeners: Default::default(),
@@ -181,7 +186,7 @@ impl Backend {
         trace!(target: "backend", "setting genesis balances");
         let mut db = self.db.write().await;
 
-        if self.fork.is_some() {
+        if self.is_fork() {
             // in fork mode we only set the balance, this way the accountinfo is fetched from the
             // remote client, preserving code and nonce. The reason for that is private keys for dev
             // accounts are commonly known and are used on testnets
@@ -230,46 +235,114 @@ impl Backend {
     }
 
     /// Returns the configured fork, if any
-    pub fn get_fork(&self) -> Option<&ClientFork> {
-        self.fork.as_ref()
+    pub fn get_fork(&self) -> Option<ClientFork> {
+        self.fork.read().clone()
     }
 
     /// Returns the database
-    pub fn get_db(&self) -> &Arc<AsyncRwLock<dyn Db>> {
+    pub fn get_db(&self) -> &Arc<AsyncRwLock<Box<dyn Db>>> {
         &self.db
     }
 
     /// Whether we're forked off some remote client
     pub fn is_fork(&self) -> bool {
-        self.fork.is_some()
+        self.fork.read().is_some()
     }
 
-    /// Resets the fork to a fresh state
-    pub async fn reset_fork(&self, forking: Forking) -> Result<(), BlockchainError> {
-        if let Some(fork) = self.get_fork() {
-            // reset the fork entirely and reapply the genesis config
-            fork.reset(forking.json_rpc_url.clone(), forking.block_number).await?;
-            // update all settings related to the forked block
-            {
-                let mut env = self.env.write();
-                env.cfg.chain_id = fork.chain_id().into();
-                env.block.number = fork.block_number().into();
-                self.time.set_start_timestamp(fork.timestamp());
-                let base_fee = fork.base_fee().unwrap_or_default();
+    /// Resets the chain to a fresh state.
+    ///
+    /// With a `forking` config the chain is reset to a fork of the remote client and block it
+    /// points to, a forked chain keeps its remote client if the config has no url. Without one, the
+    /// chain is reset to a local chain with a fresh genesis. The chain can switch between forked
+    /// and local this way, the database is swapped accordingly.
+    pub async fn reset(&self, forking: Option<Forking>) -> Result<(), BlockchainError> {
+        match forking {
+            Some(forking) => self.reset_fork(forking).await,
+            None => self.reset_local().await,
+        }
+    }
+
+    /// Resets the chain to a local chain with a fresh genesis block and only the genesis accounts
+    async fn reset_local(&self) -> Result<(), BlockchainError> {
+        {
+            let mut db = self.db.write().await;
+            if self.is_fork() || !db.clear() {
+                *db = Box::new(MemDb::default());
+            }
+        }
+        *self.fork.write() = None;
+
+        // the base fee of the genesis block is the configured one
+        let genesis_base_fee = {
+            let storage = self.blockchain.storage.read();
+            storage
+                .blocks
+                .get(&storage.genesis_hash)
+                .and_then(|block| block.header.base_fee_per_gas)
+        };
+        {
+            let mut env = self.env.write();
+            // a forked chain may have switched to the chain id of the remote client
+            env.cfg.chain_id = self.genesis.chain_id.into();
+            env.tx.chain_id = self.genesis.chain_id.into();
+            env.block.number = U256::zero();
+            if let Some(base_fee) = genesis_base_fee {
                 self.fees.set_base_fee(base_fee);
                 env.block.basefee = base_fee;
             }
+        }
+        self.time.set_start_timestamp(duration_since_unix_epoch().as_secs());
 
-            // reset storage
-            *self.blockchain.storage.write() =
-                BlockchainStorage::forked(fork.block_number(), fork.block_hash());
-            self.states.write().clear();
+        *self.blockchain.storage.write() =
+            BlockchainStorage::new(&self.env.read(), genesis_base_fee);
+        self.states.write().clear();
+        self.active_snapshots.lock().clear();
 
-            self.apply_genesis().await;
-            Ok(())
-        } else {
-            Err(RpcError::invalid_params("Forking not enabled").into())
+        self.apply_genesis().await;
+        Ok(())
+    }
+
+    /// Resets the chain to a fresh fork of the remote client and block of the `forking` config,
+    /// a local chain requires the url of the remote client
+    pub async fn reset_fork(&self, forking: Forking) -> Result<(), BlockchainError> {
+        let fork = match self.get_fork() {
+            Some(fork) => {
+                // reset the fork entirely and reapply the genesis config
+                fork.reset(forking.json_rpc_url, forking.block_number).await?;
+                fork
+            }
+            None => {
+                let eth_rpc_url = forking.json_rpc_url.ok_or_else(|| {
+                    RpcError::invalid_params("A `jsonRpcUrl` is required to fork a local chain")
+                })?;
+                let env = self.env.read().clone();
+                ClientFork::connect(eth_rpc_url, forking.block_number, &env).await?
+            }
+        };
+        // the chain writes to a fresh copy of the forked database, which shares the data fetched
+        // from the remote client with the fork
+        *self.db.write().await = Box::new(fork.database.read().await.clone());
+
+        // update all settings related to the forked block
+        {
+            let mut env = self.env.write();
+            env.cfg.chain_id = fork.chain_id().into();
+            env.block.number = fork.block_number().into();
+            self.time.set_start_timestamp(fork.timestamp());
+            let base_fee = fork.base_fee().unwrap_or_default();
+            self.fees.set_base_fee(base_fee);
+            env.block.basefee = base_fee;
         }
+
+        // reset storage
+        *self.blockchain.storage.write() =
+            BlockchainStorage::forked(fork.block_number(), fork.block_hash());
+        self.states.write().clear();
+        self.active_snapshots.lock().clear();
+        *self.fork.write() = Some(fork);
+
+        self.apply_genesis().await;
+        Ok(())
     }
 
     /// Returns the `TimeManager` responsible for timestamps
@@ -485,7 +558,7 @@ impl Backend {
 
         let mut evm = revm::EVM::new();
         evm.env = env;
-        evm.database(&*db);
+        evm.database(&**db);
         evm.transact_ref()
     }
 
@@ -496,7 +569,7 @@ impl Backend {
         let db = self.db.read().await;
         let env = self.next_env();
 
-        let mut cache_db = CacheDB::new(&*db);
+        let mut cache_db = CacheDB::new(&**db);
 
         let storage = self.blockchain.storage.read();
 
@@ -550,7 +623,7 @@ impl Backend {
             let (executed_tx, block_hash) = {
                 let mut db = self.db.write().await;
                 let executor = TransactionExecutor {
-                    db: &mut *db,
+                    db: &mut **db,
                     validator: self,
                     pending: pool_transactions.into_iter(),
                     block_env: env.block.clone(),
@@ -650,35 +723,7 @@ impl Backend {
         fee_details: FeeDetails,
         block_number: Option<BlockNumber>,
     ) -> Result<(Return, TransactOut, u64, State), BlockchainError> {
-        let EthTransactionRequest { from, to, gas, value, data, nonce, access_list, .. } = request;
-
-        let FeeDetails { gas_price, max_fee_per_gas, max_priority_fee_per_gas } = fee_details;
-
-        let gas_limit = gas.unwrap_or_else(|| self.gas_limit());
-        let mut env = self.env.read().clone();
-        env.block.timestamp = self.time.current_call_timestamp().into();
-
-        if let Some(base) = max_fee_per_gas {
-            env.block.basefee = base;
-        }
-
-        let gas_price = gas_price.or(max_fee_per_gas).unwrap_or_else(|| self.gas_price());
-
-        env.tx = TxEnv {
-            caller: from.unwrap_or_default(),
-            gas_limit: gas_limit.as_u64(),
-            gas_price,
-            gas_priority_fee: max_priority_fee_per_gas,
-            transact_to: match to {
-                Some(addr) => TransactTo::Call(addr),
-                None => TransactTo::Create(CreateScheme::Create),
-            },
-            value: value.unwrap_or_default(),
-            data: data.unwrap_or_default().to_vec().into(),
-            chain_id: None,
-            nonce: nonce.map(|n| n.as_u64()),
-            access_list: to_access_list(access_list.unwrap_or_default()),
-        };
+        let mut env = self.build_call_env(request, fee_details);
 
         let block_number =
             U256::from(self.convert_block_number(block_number)).min(env.block.number);
@@ -712,7 +757,7 @@ impl Backend {
         let db = self.db.read().await;
         let mut evm = revm::EVM::new();
         evm.env = env;
-        evm.database(&*db);
+        evm.database(&**db);
 
         let (exit, out, gas, state, _) = evm.transact_ref();
         trace!(target: "backend", "call return {:?} out: {:?} gas {}", exit, out, gas);
@@ -720,6 +765,109 @@ impl Backend {
         Ok((exit, out, gas, state))
     }
 
+    /// Executes the `EthTransactionRequest` without writing to the DB, like [`Backend::call()`],
+    /// and collects the accounts and storage slots it accesses into an EIP-2930 access list.
+    ///
+    /// The sender, the recipient and the precompiles are warm anyway, so they are only listed if
+    /// their storage is accessed. The entries of the access list of the request are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `block_number` is greater than the current height
+    pub async fn build_access_list(
+        &self,
+        request: EthTransactionRequest,
+        fee_details: FeeDetails,
+        block_number: Option<BlockNumber>,
+    ) -> Result<(Return, TransactOut, u64, AccessList), BlockchainError> {
+        let from = request.from.unwrap_or_default();
+        let to = match request.to {
+            Some(to) => to,
+            None => {
+                let nonce = match request.nonce {
+                    Some(nonce) => nonce,
+                    None => self.get_nonce(from, block_number).await?,
+                };
+                get_contract_address(from, nonce)
+            }
+        };
+        let mut tracer = AccessListTracer::new(
+            &AccessList(request.access_list.clone().unwrap_or_default()),
+            [from, to].into_iter().chain(PRECOMPILES),
+        );
+        let mut env = self.build_call_env(request, fee_details);
+
+        let block_number =
+            U256::from(self.convert_block_number(block_number)).min(env.block.number);
+
+        if block_number < env.block.number {
+            // requested historic state
+            let states = self.states.read();
+
+            return if let Some(state) =
+                self.hash_for_block_number(block_number.as_u64()).and_then(|hash| states.get(&hash))
+            {
+                let mut evm = revm::EVM::new();
+                env.block.number = block_number;
+                evm.env = env;
+                evm.database(state);
+
+                let (exit, out, gas, _, _) = evm.inspect_ref(&mut tracer);
+                Ok((exit, out, gas, tracer.access_list()))
+            } else {
+                warn!(target: "backend", "Not historic state found for block={}", block_number);
+                Err(BlockchainError::BlockOutOfRange(
+                    env.block.number.as_u64(),
+                    block_number.as_u64(),
+                ))
+            }
+        }
+
+        let db = self.db.read().await;
+        let mut evm = revm::EVM::new();
+        evm.env = env;
+        evm.database(&**db);
+
+        let (exit, out, gas, _, _) = evm.inspect_ref(&mut tracer);
+        trace!(target: "backend", "access list call return {:?} out: {:?} gas {}", exit, out, gas);
+
+        Ok((exit, out, gas, tracer.access_list()))
+    }
+
+    /// Returns the environment to execute the `EthTransactionRequest` in with the `fee_details`
+    fn build_call_env(&self, request: EthTransactionRequest, fee_details: FeeDetails) -> Env {
+        let EthTransactionRequest { from, to, gas, value, data, nonce, access_list, .. } = request;
+
+        let FeeDetails { gas_price, max_fee_per_gas, max_priority_fee_per_gas } = fee_details;
+
+        let gas_limit = gas.unwrap_or_else(|| self.gas_limit());
+        let mut env = self.env.read().clone();
+        env.block.timestamp = self.time.current_call_timestamp().into();
+
+        if let Some(base) = max_fee_per_gas {
+            env.block.basefee = base;
+        }
+
+        let gas_price = gas_price.or(max_fee_per_gas).unwrap_or_else(|| self.gas_price());
+
+        env.tx = TxEnv {
+            caller: from.unwrap_or_default(),
+            gas_limit: gas_limit.as_u64(),
+            gas_price,
+            gas_priority_fee: max_priority_fee_per_gas,
+            transact_to: match to {
+                Some(addr) => TransactTo::Call(addr),
+                None => TransactTo::Create(CreateScheme::Create),
+            },
+            value: value.unwrap_or_default(),
+            data: data.unwrap_or_default().to_vec().into(),
+            chain_id: None,
+            nonce: nonce.map(|n| n.as_u64()),
+            access_list: to_access_list(access_list.unwrap_or_default()),
+        };
+        env
+    }
+
     /// returns all receipts for the given transactions
     fn get_receipts(&self, tx_hashes: impl IntoIterator<Item = TxHash>) -> Vec<TypedReceipt> {
         let storage = self.blockchain.storage.read();
@@ -1096,7 +1244,7 @@ impl Backend {
             }
         }
         let db = self.db.read().await;
-        f(Box::new(&*db))
+        f(Box::new(&**db))
     }
 
     pub async fn storage_at(
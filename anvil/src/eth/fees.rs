@@ -18,7 +18,7 @@ use std::{
 use tracing::trace;
 
 /// Maximum number of entries in the fee history cache
-pub const MAX_FEE_HISTORY_CACHE_SIZE: u64 = 2048u64;
+pub const MAX_FEE_HISTORY_CACHE_SIZE: u64 = 1024u64;
 
 /// Initial base fee for EIP-1559 blocks.
 pub const INITIAL_BASE_FEE: u64 = 1_000_000_000;
@@ -183,11 +183,7 @@ impl FeeHistoryService {
     }
 
     /// Create a new history entry for the block
-    fn create_cache_entry(
-        &self,
-        hash: H256,
-        elasticity: f64,
-    ) -> (FeeHistoryCacheItem, Option<u64>) {
+    fn create_cache_entry(&self, hash: H256) -> (FeeHistoryCacheItem, Option<u64>) {
         // percentile list from 0.0 to 100.0 with a 0.5 resolution.
         // this will create 200 percentile points
         let reward_percentiles: Vec<f64> = {
@@ -206,7 +202,8 @@ impl FeeHistoryService {
         let base_fee = self.fees.base_fee();
         let mut item = FeeHistoryCacheItem {
             base_fee: base_fee.as_u64(),
-            gas_used_ratio: 0f64,
+            gas_used: 0,
+            gas_limit: 0,
             rewards: Vec::new(),
         };
 
@@ -216,11 +213,9 @@ impl FeeHistoryService {
         if let (Some(block), Some(receipts)) = (current_block, current_receipts) {
             block_number = Some(block.header.number.as_u64());
 
-            let gas_used = block.header.gas_used.as_u64() as f64;
-            let gas_limit = block.header.gas_limit.as_u64() as f64;
-
-            let gas_target = gas_limit / elasticity;
-            item.gas_used_ratio = gas_used / (gas_target * elasticity);
+            item.gas_used = block.header.gas_used.as_u64();
+            item.gas_limit = block.header.gas_limit.as_u64();
+            let gas_used = item.gas_used as f64;
 
             // extract useful tx info (gas_used, effective_reward)
             let mut transactions: Vec<(u64, u64)> = receipts
@@ -276,13 +271,14 @@ impl FeeHistoryService {
             let mut cache = self.cache.lock();
             cache.insert(block_number, item);
 
-            // adhere to cache limit
-            let pop_next = block_number.saturating_sub(self.fee_history_limit);
-
-            let num_remove = (cache.len() as u64).saturating_sub(self.fee_history_limit);
-            for num in 0..num_remove {
-                let key = pop_next - num;
-                cache.remove(&key);
+            // adhere to cache limit by evicting the oldest entries
+            while cache.len() as u64 > self.fee_history_limit {
+                match cache.keys().next().copied() {
+                    Some(oldest) => {
+                        cache.remove(&oldest);
+                    }
+                    None => break,
+                }
             }
         }
     }
@@ -297,10 +293,9 @@ impl Future for FeeHistoryService {
 
         while let Poll::Ready(Some(notification)) = pin.new_blocks.poll_next_unpin(cx) {
             let hash = notification.hash;
-            let elasticity = default_elasticity();
 
             // add the imported block.
-            let (result, block_number) = pin.create_cache_entry(hash, elasticity);
+            let (result, block_number) = pin.create_cache_entry(hash);
             pin.insert_cache_entry(result, block_number)
         }
 
@@ -314,10 +309,21 @@ pub type FeeHistoryCache = Arc<Mutex<BTreeMap<u64, FeeHistoryCacheItem>>>;
 #[derive(Debug, Clone)]
 pub struct FeeHistoryCacheItem {
     pub base_fee: u64,
-    pub gas_used_ratio: f64,
+    pub gas_used: u64,
+    pub gas_limit: u64,
     pub rewards: Vec<u64>,
 }
 
+impl FeeHistoryCacheItem {
+    /// Returns the ratio of gas used to the gas limit of the block
+    pub fn gas_used_ratio(&self) -> f64 {
+        if self.gas_limit == 0 {
+            return 0f64
+        }
+        self.gas_used as f64 / self.gas_limit as f64
+    }
+}
+
 #[derive(Default, Clone)]
 pub struct FeeDetails {
     pub gas_price: Option<U256>,
@@ -77,7 +77,7 @@ pub async fn spawn(mut config: NodeConfig) -> (EthApi, NodeHandle) {
 
     let backend = Arc::new(config.setup().await);
 
-    let fork = backend.get_fork().cloned();
+    let fork = backend.get_fork();
 
     let NodeConfig {
         signer_accounts,
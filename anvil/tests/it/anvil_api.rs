@@ -1,7 +1,7 @@
 //! tests for custom anvil endpoints
 use crate::{abi::*, fork::fork_config};
 use anvil::{spawn, Hardfork, NodeConfig};
-use anvil_core::eth::EthRequest;
+use anvil_core::{eth::EthRequest, types::Forking};
 use ethers::{
     abi::ethereum_types::BigEndianHash,
     prelude::{Middleware, SignerMiddleware},
@@ -237,3 +237,34 @@ async fn test_timestamp_interval() {
     // check interval is disabled
     assert!(another_block.timestamp - new_block.timestamp < U256::from(interval));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_reset_local_chain() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let from = handle.dev_accounts().next().unwrap();
+    let to = Address::random();
+    let tx = TransactionRequest::new().to(to).value(1337u64).from(from);
+    provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    api.anvil_set_code(to, vec![0x00].into()).await.unwrap();
+    assert_eq!(provider.get_block_number().await.unwrap(), 1u64.into());
+
+    // resetting without a fork config resets the local chain to its genesis
+    let req = serde_json::from_str::<EthRequest>(r#"{"method": "hardhat_reset"}"#).unwrap();
+    api.execute(req).await;
+
+    assert_eq!(provider.get_block_number().await.unwrap(), 0u64.into());
+    assert_eq!(provider.get_transaction_count(from, None).await.unwrap(), 0u64.into());
+    assert_eq!(provider.get_balance(from, None).await.unwrap(), handle.genesis_balance());
+    assert!(provider.get_balance(to, None).await.unwrap().is_zero());
+    assert!(provider.get_code(to, None).await.unwrap().is_empty());
+
+    // the chain keeps working after the reset
+    let tx = TransactionRequest::new().to(to).value(1337u64).from(from);
+    provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    assert_eq!(provider.get_block_number().await.unwrap(), 1u64.into());
+
+    // a local chain can only be forked with a url
+    api.anvil_reset(Some(Forking::default())).await.unwrap_err();
+}
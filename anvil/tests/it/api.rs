@@ -2,10 +2,16 @@
 
 use anvil::{eth::api::CLIENT_VERSION, spawn, NodeConfig, CHAIN_ID};
 use ethers::{
-    prelude::Middleware,
+    contract::ContractFactory,
+    prelude::{Middleware, SignerMiddleware},
     signers::Signer,
-    types::{Block, BlockNumber, Chain, Transaction, TransactionRequest, U256},
+    types::{
+        transaction::eip2930::AccessListItem, Block, BlockNumber, Chain, Transaction,
+        TransactionRequest, H256, U256,
+    },
 };
+use ethers_solc::{project_util::TempProject, Artifact};
+use std::sync::Arc;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn can_get_block_number() {
@@ -141,3 +147,73 @@ async fn can_get_pending_block() {
     assert_eq!(block.number.unwrap().as_u64(), 1u64);
     assert_eq!(block.transactions.len(), 1);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_create_access_list() {
+    let prj = TempProject::dapptools().unwrap();
+    prj.add_source(
+        "Contract",
+        r#"
+pragma solidity 0.8.13;
+contract Store {
+    uint256 a = 1;
+    uint256 b = 2;
+    function sum() public view returns (uint256) {
+        return a + b;
+    }
+}
+contract Reader {
+    Store store;
+    uint256 c = 3;
+    constructor(Store _store) {
+        store = _store;
+    }
+    function total() public view returns (uint256) {
+        return store.sum() + c;
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    let mut compiled = prj.compile().unwrap();
+    assert!(!compiled.has_compiler_errors());
+    let (store_abi, store_bytecode, _) =
+        compiled.remove_first("Store").unwrap().into_contract_bytecode().into_parts();
+    let (reader_abi, reader_bytecode, _) =
+        compiled.remove_first("Reader").unwrap().into_contract_bytecode().into_parts();
+
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let wallet = handle.dev_wallets().next().unwrap();
+    let client = Arc::new(SignerMiddleware::new(handle.http_provider(), wallet.clone()));
+
+    let store = ContractFactory::new(store_abi.unwrap(), store_bytecode.unwrap(), client.clone())
+        .deploy(())
+        .unwrap()
+        .send()
+        .await
+        .unwrap();
+    let reader =
+        ContractFactory::new(reader_abi.unwrap(), reader_bytecode.unwrap(), client.clone())
+            .deploy(store.address())
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+
+    let mut tx = reader.method::<_, U256>("total", ()).unwrap().from(wallet.address()).tx;
+    let list = client.create_access_list(&tx, None).await.unwrap();
+
+    // the slots of both contracts are listed, the sender is warm anyway
+    let slots = vec![H256::from_low_u64_be(0), H256::from_low_u64_be(1)];
+    let mut expected = vec![
+        AccessListItem { address: store.address(), storage_keys: slots.clone() },
+        AccessListItem { address: reader.address(), storage_keys: slots },
+    ];
+    expected.sort_by_key(|item| item.address);
+    assert_eq!(list.access_list.0, expected);
+
+    // the gas is estimated with the access list
+    tx.set_access_list(list.access_list);
+    assert_eq!(list.gas_used, client.estimate_gas(&tx).await.unwrap());
+}
@@ -119,6 +119,16 @@ async fn test_fork_eth_fee_history() {
     let count = 10u64;
     let _history = api.fee_history(count.into(), BlockNumber::Latest, vec![]).await.unwrap();
     let _provider_history = provider.fee_history(count, BlockNumber::Latest, &[]).await.unwrap();
+
+    // mine blocks on top of the fork, the range now spans the fork and the local blocks
+    api.mine_one().await;
+    api.mine_one().await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let fork_block = api.get_fork().unwrap().block_number();
+    let history = api.fee_history(count.into(), BlockNumber::Latest, vec![]).await.unwrap();
+    assert_eq!(history.oldest_block, (fork_block + 2 - count + 1).into());
+    assert_eq!(history.gas_used_ratio.len(), count as usize);
+    assert_eq!(history.base_fee_per_gas.len(), count as usize + 1);
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -311,6 +321,70 @@ async fn can_reset_properly() {
     assert!(fork_provider.get_transaction(tx.transaction_hash).await.unwrap().is_none())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_reset_local_chain_to_fork() {
+    let (origin_api, origin_handle) = spawn(NodeConfig::test().with_chain_id(Some(99u64))).await;
+    let account = origin_handle.dev_accounts().next().unwrap();
+    origin_api.anvil_set_nonce(account, 5u64.into()).await.unwrap();
+    origin_api.evm_mine(None).await.unwrap();
+
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    assert!(api.get_fork().is_none());
+
+    api.anvil_reset(Some(Forking {
+        json_rpc_url: Some(origin_handle.http_endpoint()),
+        block_number: None,
+    }))
+    .await
+    .unwrap();
+
+    let fork = api.get_fork().unwrap();
+    assert_eq!(fork.block_number(), 1);
+    assert_eq!(api.chain_id(), 99);
+    assert_eq!(provider.get_block_number().await.unwrap(), 1u64.into());
+    assert_eq!(provider.get_transaction_count(account, None).await.unwrap(), 5u64.into());
+
+    // the forked chain keeps working
+    let to = Address::random();
+    let tx = TransactionRequest::new().from(account).to(to).value(1337u64);
+    provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    assert_eq!(provider.get_balance(to, None).await.unwrap(), 1337u64.into());
+    assert_eq!(provider.get_transaction_count(account, None).await.unwrap(), 6u64.into());
+
+    // resetting without a fork config restores the chain id of the node config
+    api.anvil_reset(None).await.unwrap();
+    assert_eq!(api.chain_id(), handle.config().get_chain_id());
+    assert_eq!(provider.get_chainid().await.unwrap(), handle.config().get_chain_id().into());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_reset_fork_to_local_chain() {
+    let (origin_api, origin_handle) = spawn(NodeConfig::test()).await;
+    let account = origin_handle.dev_accounts().next().unwrap();
+    origin_api.anvil_set_nonce(account, 5u64.into()).await.unwrap();
+
+    let (api, handle) =
+        spawn(NodeConfig::test().with_eth_rpc_url(Some(origin_handle.http_endpoint()))).await;
+    let provider = handle.http_provider();
+    assert!(api.get_fork().is_some());
+    assert_eq!(provider.get_transaction_count(account, None).await.unwrap(), 5u64.into());
+
+    api.anvil_reset(None).await.unwrap();
+
+    assert!(api.get_fork().is_none());
+    assert_eq!(provider.get_block_number().await.unwrap(), 0u64.into());
+    assert_eq!(provider.get_transaction_count(account, None).await.unwrap(), 0u64.into());
+    assert_eq!(provider.get_balance(account, None).await.unwrap(), handle.genesis_balance());
+
+    // the local chain keeps working
+    let to = Address::random();
+    let tx = TransactionRequest::new().from(account).to(to).value(1337u64);
+    provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    assert_eq!(provider.get_block_number().await.unwrap(), 1u64.into());
+    assert_eq!(provider.get_balance(to, None).await.unwrap(), 1337u64.into());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_fork_timestamp() {
     let (api, handle) = spawn(fork_config()).await;
@@ -5,6 +5,7 @@ use ethers::{
     prelude::Middleware,
     types::{transaction::eip2718::TypedTransaction, Address, BlockNumber, TransactionRequest},
 };
+use std::time::Duration;
 
 const GAS_TRANSFER: u64 = 21_000u64;
 
@@ -87,3 +88,24 @@ async fn test_respect_base_fee() {
     let tx = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
     assert_eq!(tx.status, Some(1u64.into()));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fee_history() {
+    let (api, handle) = spawn(NodeConfig::test().with_base_fee(Some(INITIAL_BASE_FEE))).await;
+    let provider = handle.http_provider();
+
+    for _ in 0..3 {
+        let tx = TransactionRequest::new().to(Address::random()).value(1337u64);
+        provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    }
+    // the fee history cache is updated in the background
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let history = api.fee_history(2u64.into(), BlockNumber::Latest, vec![50.0]).await.unwrap();
+    assert_eq!(history.oldest_block, 2u64.into());
+    assert_eq!(history.gas_used_ratio.len(), 2);
+    // includes the base fee of the next block
+    assert_eq!(history.base_fee_per_gas.len(), 3);
+    assert_eq!(history.reward.len(), 2);
+    assert!(history.gas_used_ratio.iter().all(|ratio| *ratio > 0.0));
+}
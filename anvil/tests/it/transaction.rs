@@ -5,7 +5,7 @@ use ethers::{
         signer::SignerMiddlewareError, BlockId, Middleware, Signer, SignerMiddleware,
         TransactionRequest,
     },
-    types::{Address, BlockNumber, Transaction, TransactionReceipt, H256, U256},
+    types::{Address, BlockNumber, Transaction, TransactionReceipt, H256, U256, U64},
 };
 
 use futures::{future::join_all, FutureExt, StreamExt};
@@ -739,3 +739,59 @@ async fn can_stream_pending_transactions() {
         }
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_get_transaction_by_block_and_index() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    // disable automine so that both transactions are included in the same block
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let accounts: Vec<_> = handle.dev_wallets().collect();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+
+    let tx = TransactionRequest::new().to(to).from(from).value(1u64).nonce(0u64);
+    let first = provider.send_transaction(tx, None).await.unwrap();
+    let tx = TransactionRequest::new().to(to).from(from).value(2u64).nonce(1u64);
+    let second = provider.send_transaction(tx, None).await.unwrap();
+
+    // pending transactions are not part of a block yet
+    let pending = provider.get_transaction(*second).await.unwrap().unwrap();
+    assert_eq!(pending.transaction_index, None);
+
+    api.mine_one().await;
+    let first = first.await.unwrap().unwrap();
+    let second = second.await.unwrap().unwrap();
+    let block = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+
+    let by_hash = provider.get_transaction(second.transaction_hash).await.unwrap().unwrap();
+    assert_eq!(by_hash.transaction_index, Some(second.transaction_index));
+
+    let by_number: Transaction = provider
+        .request(
+            "eth_getTransactionByBlockNumberAndIndex",
+            (block.number.unwrap(), second.transaction_index),
+        )
+        .await
+        .unwrap();
+    assert_eq!(by_number, by_hash);
+
+    let by_block_hash: Transaction = provider
+        .request(
+            "eth_getTransactionByBlockHashAndIndex",
+            (block.hash.unwrap(), first.transaction_index),
+        )
+        .await
+        .unwrap();
+    assert_eq!(by_block_hash.hash, first.transaction_hash);
+    assert_eq!(by_block_hash.transaction_index, Some(first.transaction_index));
+
+    // out of bounds
+    let missing: Option<Transaction> = provider
+        .request("eth_getTransactionByBlockHashAndIndex", (block.hash.unwrap(), U64::from(2)))
+        .await
+        .unwrap();
+    assert!(missing.is_none());
+}
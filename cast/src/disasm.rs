@@ -0,0 +1,209 @@
+//! Disassembly of EVM bytecode
+
+use foundry_evm::revm::{opcode, OpCode};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+};
+
+/// A single instruction of EVM bytecode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    /// The offset of the instruction in the bytecode
+    pub offset: usize,
+    pub opcode: u8,
+    /// The immediate data of a `PUSH` instruction, shorter than the push size if the bytecode
+    /// ends early
+    pub data: Vec<u8>,
+}
+
+impl Instruction {
+    /// Returns the mnemonic of the opcode, or `UNKNOWN(0x..)` if the opcode is undefined
+    pub fn name(&self) -> String {
+        OpCode::try_from_u8(self.opcode).map_or_else(
+            || format!("UNKNOWN(0x{:02x})", self.opcode),
+            |opcode| opcode.as_str().to_string(),
+        )
+    }
+
+    /// Returns `true` if this is a `PUSH` instruction
+    pub fn is_push(&self) -> bool {
+        (opcode::PUSH1..=opcode::PUSH32).contains(&self.opcode)
+    }
+
+    /// Returns the immediate data as a number if it is at most 8 bytes long
+    fn data_as_u64(&self) -> Option<u64> {
+        if !self.is_push() || self.data.len() > 8 {
+            return None
+        }
+        Some(self.data.iter().fold(0u64, |value, byte| (value << 8) | *byte as u64))
+    }
+}
+
+/// Splits the `code` into its instructions
+pub fn disassemble(code: &[u8]) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut offset = 0;
+    while offset < code.len() {
+        let op = code[offset];
+        let push_size = if (opcode::PUSH1..=opcode::PUSH32).contains(&op) {
+            (op - opcode::PUSH1 + 1) as usize
+        } else {
+            0
+        };
+        let data = code[offset + 1..code.len().min(offset + 1 + push_size)].to_vec();
+        instructions.push(Instruction { offset, opcode: op, data });
+        offset += 1 + push_size;
+    }
+    instructions
+}
+
+/// A branch of the function dispatcher, which jumps to `target` if the calldata starts with
+/// `selector`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DispatchEntry {
+    pub selector: [u8; 4],
+    /// The index of the instruction that pushes the selector
+    pub push_index: usize,
+    /// The offset of the `JUMPDEST` of the function
+    pub target: usize,
+}
+
+/// Finds the branches of the function dispatcher that solc and vyper generate, i.e. the sequences
+/// `PUSH4 <selector> EQ PUSH <target> JUMPI` and `PUSH4 <selector> DUP2 EQ PUSH <target> JUMPI`.
+///
+/// Only the prologue of the code before its first `JUMPDEST` is searched, so that the constants
+/// that the functions themselves compare against are not mistaken for selectors.
+pub fn dispatcher_entries(instructions: &[Instruction]) -> Vec<DispatchEntry> {
+    let prologue_len = instructions
+        .iter()
+        .position(|ins| ins.opcode == opcode::JUMPDEST)
+        .unwrap_or(instructions.len());
+    let prologue = &instructions[..prologue_len];
+
+    let mut entries = Vec::new();
+    for (idx, push) in prologue.iter().enumerate() {
+        if push.opcode != opcode::PUSH4 {
+            continue
+        }
+        let selector: [u8; 4] = match push.data.as_slice().try_into() {
+            Ok(selector) => selector,
+            Err(_) => continue,
+        };
+        let rest = &prologue[idx + 1..];
+        let rest = match rest.first().map(|ins| ins.opcode) {
+            Some(opcode::DUP2) => &rest[1..],
+            _ => rest,
+        };
+        if let [eq, target, jumpi, ..] = rest {
+            if eq.opcode == opcode::EQ && jumpi.opcode == opcode::JUMPI {
+                if let Some(target) = target.data_as_u64() {
+                    let target = target as usize;
+                    entries.push(DispatchEntry { selector, push_index: idx, target });
+                }
+            }
+        }
+    }
+    entries
+}
+
+/// Formats the `instructions` as one `<OFFSET>  <OPCODE>  <OPERAND>` line per instruction.
+///
+/// Push data of at most 8 bytes is annotated with its decimal value, the selectors of the
+/// function dispatcher and the `JUMPDEST`s of the functions with their signature from
+/// `signatures`, if known.
+pub fn format_disassembly(
+    instructions: &[Instruction],
+    signatures: &HashMap<[u8; 4], String>,
+) -> String {
+    let entries = dispatcher_entries(instructions);
+    let name = |selector: &[u8; 4]| {
+        signatures
+            .get(selector)
+            .cloned()
+            .unwrap_or_else(|| format!("function_0x{}", hex::encode(selector)))
+    };
+    let selectors: BTreeMap<usize, String> =
+        entries.iter().map(|entry| (entry.push_index, name(&entry.selector))).collect();
+    let labels: BTreeMap<usize, String> =
+        entries.iter().map(|entry| (entry.target, name(&entry.selector))).collect();
+
+    let offset_width = instructions
+        .last()
+        .map(|ins| format!("{:x}", ins.offset).len())
+        .unwrap_or_default()
+        .max(4);
+    let mut out = String::new();
+    for (idx, ins) in instructions.iter().enumerate() {
+        let mut line =
+            format!("{:0width$x}  {:<14}", ins.offset, ins.name(), width = offset_width);
+        if ins.is_push() {
+            let _ = write!(line, "  0x{}", hex::encode(&ins.data));
+            if let Some(selector) = selectors.get(&idx) {
+                let _ = write!(line, " ({selector})");
+            } else if let Some(value) = ins.data_as_u64() {
+                let _ = write!(line, " ({value})");
+            }
+        } else if ins.opcode == opcode::JUMPDEST {
+            if let Some(label) = labels.get(&ins.offset) {
+                let _ = write!(line, "  ; {label}");
+            }
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // PUSH1 0x00 CALLDATALOAD PUSH1 0xe0 SHR DUP1 PUSH4 0x06fdde03 EQ PUSH1 0x11 JUMPI STOP
+    // JUMPDEST STOP, followed by a truncated PUSH2
+    const CODE: &str = "60003560e01c806306fdde0314601157005b0061ff";
+
+    #[test]
+    fn disassembles_code() {
+        let instructions = disassemble(&hex::decode(CODE).unwrap());
+        assert_eq!(instructions.len(), 13);
+        assert_eq!(instructions[0], Instruction { offset: 0, opcode: 0x60, data: vec![0] });
+        assert_eq!(instructions[5].name(), "PUSH4");
+        assert_eq!(instructions[5].data, [0x06, 0xfd, 0xde, 0x03]);
+        assert_eq!(instructions[12], Instruction { offset: 19, opcode: 0x61, data: vec![0xff] });
+    }
+
+    #[test]
+    fn labels_dispatched_functions() {
+        let instructions = disassemble(&hex::decode(CODE).unwrap());
+        let entries = dispatcher_entries(&instructions);
+        assert_eq!(
+            entries,
+            [DispatchEntry { selector: [0x06, 0xfd, 0xde, 0x03], push_index: 5, target: 0x11 }]
+        );
+
+        let signatures = HashMap::from([([0x06, 0xfd, 0xde, 0x03], "name()".to_string())]);
+        let out = format_disassembly(&instructions, &signatures);
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(lines[2], "0003  PUSH1           0xe0 (224)");
+        assert_eq!(lines[5], "0007  PUSH4           0x06fdde03 (name())");
+        assert_eq!(lines[10], "0011  JUMPDEST        ; name()");
+
+        let out = format_disassembly(&instructions, &HashMap::new());
+        assert!(out.contains("0x06fdde03 (function_0x06fdde03)"));
+    }
+
+    #[test]
+    fn ignores_constants_outside_of_dispatcher() {
+        // PUSH1 0x00 CALLDATALOAD PUSH1 0xe0 SHR DUP1 PUSH2 0x1234 EQ PUSH1 0x0f JUMPI STOP
+        // JUMPDEST PUSH4 0x06fdde03 EQ PUSH1 0x0f JUMPI STOP
+        let code = "60003560e01c8061123414600f57005b6306fdde0314600f5700";
+        let instructions = disassemble(&hex::decode(code).unwrap());
+        assert!(dispatcher_entries(&instructions).is_empty());
+
+        let out = format_disassembly(&instructions, &HashMap::new());
+        assert!(out.contains("0x1234 (4660)"));
+        assert!(out.contains("0x06fdde03 (117300739)"));
+        assert!(!out.contains("function_0x"));
+    }
+}
@@ -6,9 +6,9 @@ use chrono::NaiveDateTime;
 use ethers_core::{
     abi::{
         token::{LenientTokenizer, Tokenizer},
-        Abi, Function, HumanReadableParser, Token,
+        Abi, Function, HumanReadableParser, ParamType, Token,
     },
-    types::{Chain, *},
+    types::{transaction::eip2718::TypedTransaction, Chain, *},
     utils::{
         self, format_bytes32_string, get_contract_address, keccak256, parse_bytes32_string,
         parse_units, rlp,
@@ -16,11 +16,13 @@ use ethers_core::{
 };
 use ethers_etherscan::Client;
 use ethers_providers::{Middleware, PendingTransaction};
+use ethers_signers::{LocalWallet, Signer};
 use eyre::{Context, Result};
 use foundry_common::fmt::*;
 pub use foundry_evm::*;
-use foundry_utils::encode_args;
+use foundry_utils::{encode_args, parse_tokens};
 use rustc_hex::{FromHexIter, ToHex};
+use sha2::{Digest, Sha256};
 use std::{
     ops::{Shl, Shr},
     path::PathBuf,
@@ -29,6 +31,7 @@ use std::{
 pub use tx::TxBuilder;
 use tx::{TxBuilderOutput, TxBuilderPeekOutput};
 
+pub mod disasm;
 mod rlp_converter;
 mod tx;
 
@@ -933,29 +936,80 @@ impl SimpleCast {
     /// # }
     /// ```
     pub fn abi_encode(sig: &str, args: &[impl AsRef<str>]) -> Result<String> {
-        let func = match HumanReadableParser::parse_function(sig) {
-            Ok(func) => func,
-            Err(err) => {
-                if let Ok(constructor) = HumanReadableParser::parse_constructor(sig) {
-                    #[allow(deprecated)]
-                    Function {
-                        name: "constructor".to_string(),
-                        inputs: constructor.inputs,
-                        outputs: vec![],
-                        constant: None,
-                        state_mutability: Default::default(),
-                    }
-                } else {
-                    // we return the `Function` parse error as this case is more likely
-                    return Err(err.into())
-                }
-            }
-        };
+        let func = parse_encoding_signature(sig)?;
         let calldata = encode_args(&func, args)?.to_hex::<String>();
         let encoded = &calldata[8..];
         Ok(format!("0x{encoded}"))
     }
 
+    /// Performs the packed encoding of Solidity's `abi.encodePacked` based off of the function
+    /// signature or a parenthesized list of types.
+    ///
+    /// Unlike in [`SimpleCast::abi_encode`], numbers, addresses, booleans and fixed size bytes
+    /// take only as many bytes as their type, and strings and bytes are not padded or prefixed by
+    /// their length. The elements of arrays are still padded to 32 bytes, arrays of dynamic types
+    /// and tuples are not supported.
+    ///
+    /// ```
+    /// # use cast::SimpleCast as Cast;
+    ///
+    /// # fn main() -> eyre::Result<()> {
+    ///     assert_eq!(Cast::abi_encode_packed("(uint16,string)", &["1", "hi"])?, "0x00016869");
+    ///     assert_eq!(
+    ///         Cast::abi_encode_packed("f(int8,bool,address)", &["-1", "true", "0x000000000000000000000000000000000000dEaD"])?,
+    ///         "0xff01000000000000000000000000000000000000dead"
+    ///     );
+    /// #    Ok(())
+    /// # }
+    /// ```
+    pub fn abi_encode_packed(sig: &str, args: &[impl AsRef<str>]) -> Result<String> {
+        let func = parse_packed_signature(sig)?;
+        if func.inputs.len() != args.len() {
+            eyre::bail!(
+                "expected {} arguments for `{sig}`, but got {}",
+                func.inputs.len(),
+                args.len()
+            )
+        }
+        let params = func
+            .inputs
+            .iter()
+            .zip(args)
+            .map(|(input, arg)| (&input.kind, arg.as_ref()))
+            .collect::<Vec<_>>();
+        let tokens = parse_tokens(params.iter().copied(), true)?;
+
+        let mut encoded = Vec::new();
+        for ((kind, _), token) in params.iter().zip(tokens.iter()) {
+            encode_packed(kind, token, &mut encoded)?;
+        }
+        Ok(format!("0x{}", hex::encode(encoded)))
+    }
+
+    /// Returns `true` if the packed encoding of the parameters of the signature is ambiguous
+    /// because two dynamic types are adjacent, e.g. `abi.encodePacked("a", "bc")` and
+    /// `abi.encodePacked("ab", "c")` are equal.
+    ///
+    /// ```
+    /// # use cast::SimpleCast as Cast;
+    ///
+    /// # fn main() -> eyre::Result<()> {
+    ///     assert!(Cast::is_packed_encoding_ambiguous("(string,bytes)")?);
+    ///     assert!(!Cast::is_packed_encoding_ambiguous("(string,uint256,bytes)")?);
+    /// #    Ok(())
+    /// # }
+    /// ```
+    pub fn is_packed_encoding_ambiguous(sig: &str) -> Result<bool> {
+        let func = parse_packed_signature(sig)?;
+        let is_dynamic = |kind: &ParamType| {
+            matches!(kind, ParamType::String | ParamType::Bytes | ParamType::Array(_))
+        };
+        Ok(func
+            .inputs
+            .windows(2)
+            .any(|pair| is_dynamic(&pair[0].kind) && is_dynamic(&pair[1].kind)))
+    }
+
     /// Converts decimal input to hex
     ///
     /// ```
@@ -1189,6 +1243,21 @@ impl SimpleCast {
         Ok(format!("{}", item))
     }
 
+    /// Signs the transaction with the `signer` and returns the hex encoded RLP of the signed
+    /// transaction, which can be broadcast with `cast publish`
+    ///
+    /// The transaction's chain id takes precedence over the chain id of the `signer`.
+    pub async fn sign_transaction(
+        mut tx: TypedTransaction,
+        signer: &LocalWallet,
+    ) -> Result<String> {
+        let chain_id = tx.chain_id().map(|id| id.as_u64()).unwrap_or_else(|| signer.chain_id());
+        tx.set_chain_id(chain_id);
+        let signer = signer.clone().with_chain_id(chain_id);
+        let signature = signer.sign_transaction(&tx).await?;
+        Ok(format!("0x{}", hex::encode(tx.rlp_signed(&signature))))
+    }
+
     /// Converts an Ethereum address to its checksum format
     /// according to [EIP-55](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-55.md)
     ///
@@ -1261,6 +1330,29 @@ impl SimpleCast {
         Ok(format!("0x{hash}"))
     }
 
+    /// SHA-256 hashes arbitrary data, like [`SimpleCast::keccak`] it reads data with a `0x` prefix
+    /// as hex and anything else as text
+    ///
+    /// ```
+    /// use cast::SimpleCast as Cast;
+    ///
+    /// fn main() -> eyre::Result<()> {
+    ///     assert_eq!(Cast::sha256("foo")?, "0x2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae");
+    ///     assert_eq!(Cast::sha256("0x12")?, "0xf299791cddd3d6664f6670842812ef6053eb6501bd6282a476bbbf3ee91e750c");
+    ///     assert_eq!(Cast::sha256("12")?, "0x6b51d431df5d7f141cbececcf79edf3dd861c3b4069f0b11661a3eefacbba918");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn sha256(data: &str) -> Result<String> {
+        let hash = match data.as_bytes() {
+            [b'0', b'x', rest @ ..] => Sha256::digest(hex::decode(rest)?),
+            _ => Sha256::digest(data),
+        };
+
+        Ok(format!("0x{}", hex::encode(hash)))
+    }
+
     /// Converts ENS names to their namehash representation
     /// [Namehash reference](https://docs.ens.domains/contract-api-reference/name-processing#hashing-names)
     /// [namehash-rust reference](https://github.com/InstateDev/namehash-rust/blob/master/src/lib.rs)
@@ -1416,6 +1508,67 @@ impl SimpleCast {
     }
 }
 
+/// Parses the function or constructor signature of the arguments to encode
+fn parse_encoding_signature(sig: &str) -> Result<Function> {
+    match HumanReadableParser::parse_function(sig) {
+        Ok(func) => Ok(func),
+        Err(err) => {
+            if let Ok(constructor) = HumanReadableParser::parse_constructor(sig) {
+                #[allow(deprecated)]
+                Ok(Function {
+                    name: "constructor".to_string(),
+                    inputs: constructor.inputs,
+                    outputs: vec![],
+                    constant: None,
+                    state_mutability: Default::default(),
+                })
+            } else {
+                // we return the `Function` parse error as this case is more likely
+                Err(err.into())
+            }
+        }
+    }
+}
+
+/// Parses the signature of the arguments to encode packed, which may also be just a
+/// parenthesized list of types like `(uint8,string)`
+fn parse_packed_signature(sig: &str) -> Result<Function> {
+    if sig.trim_start().starts_with('(') {
+        parse_encoding_signature(&format!("f{}", sig.trim_start()))
+    } else {
+        parse_encoding_signature(sig)
+    }
+}
+
+/// Appends the packed encoding of the `token` of type `kind` to `out`
+fn encode_packed(kind: &ParamType, token: &Token, out: &mut Vec<u8>) -> Result<()> {
+    match (kind, token) {
+        (ParamType::Uint(bits), Token::Uint(value)) | (ParamType::Int(bits), Token::Int(value)) => {
+            // signed values are in two's complement, so the lower bytes are their encoding
+            let mut word = [0u8; 32];
+            value.to_big_endian(&mut word);
+            out.extend_from_slice(&word[32 - bits / 8..]);
+        }
+        (ParamType::Address, Token::Address(address)) => out.extend_from_slice(address.as_bytes()),
+        (ParamType::Bool, Token::Bool(value)) => out.push(*value as u8),
+        (ParamType::FixedBytes(_), Token::FixedBytes(bytes)) |
+        (ParamType::Bytes, Token::Bytes(bytes)) => out.extend_from_slice(bytes),
+        (ParamType::String, Token::String(value)) => out.extend_from_slice(value.as_bytes()),
+        (ParamType::Array(inner), Token::Array(tokens)) |
+        (ParamType::FixedArray(inner, _), Token::FixedArray(tokens)) => {
+            // the elements of arrays are padded like in the standard encoding
+            if inner.is_dynamic() ||
+                matches!(**inner, ParamType::FixedArray(..) | ParamType::Tuple(_))
+            {
+                eyre::bail!("packed encoding does not support arrays of `{inner}`")
+            }
+            out.extend(ethers_core::abi::encode(tokens));
+        }
+        _ => eyre::bail!("packed encoding does not support `{kind}`"),
+    }
+    Ok(())
+}
+
 fn strip_0x(s: &str) -> &str {
     s.strip_prefix("0x").unwrap_or(s)
 }
@@ -1449,6 +1602,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn abi_encode_packed_arrays() {
+        assert_eq!(
+            Cast::abi_encode_packed("(uint8[],bytes2)", &["[1,2]", "0xabcd"]).unwrap(),
+            "0x00000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000002abcd"
+        );
+        assert!(Cast::abi_encode_packed("(string[])", &["[\"a\"]"]).is_err());
+        assert!(Cast::abi_encode_packed("(uint8,uint8)", &["1"]).is_err());
+    }
+
     #[test]
     fn concat_hex() {
         assert_eq!(Cast::concat_hex(vec!["0x00".to_string(), "0x01".to_string()]), "0x0001");
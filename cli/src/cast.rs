@@ -6,7 +6,7 @@ mod suggestions;
 mod term;
 mod utils;
 
-use cast::{Cast, SimpleCast, TxBuilder};
+use cast::{disasm, trace::identifier::SignaturesIdentifier, Cast, SimpleCast, TxBuilder};
 use foundry_config::Config;
 mod opts;
 use crate::{cmd::Cmd, utils::consume_config_rpc_url};
@@ -14,13 +14,17 @@ use cast::InterfacePath;
 use clap::{IntoApp, Parser};
 use clap_complete::generate;
 use ethers::{
-    abi::HumanReadableParser,
+    abi::{Abi, HumanReadableParser},
     core::types::{BlockId, BlockNumber::Latest, H256},
     providers::Middleware,
-    types::{Address, NameOrAddress, U256},
+    signers::Signer,
+    types::{
+        transaction::eip2718::TypedTransaction, Address, Eip1559TransactionRequest,
+        NameOrAddress, TransactionRequest, U256,
+    },
 };
 use eyre::WrapErr;
-use foundry_common::{fs, get_http_provider};
+use foundry_common::{fmt::UIfmt, fs, get_http_provider};
 use foundry_config::Chain;
 use foundry_utils::{
     format_tokens,
@@ -29,14 +33,16 @@ use foundry_utils::{
         parse_signatures, pretty_calldata, ParsedSignatures, SelectorImportData,
     },
 };
+use futures::StreamExt;
 use opts::{
     cast::{Opts, Subcommands},
     WalletType,
 };
 use rustc_hex::ToHex;
 use std::{
+    collections::HashMap,
     io::{self, Read, Write},
-    path::Path,
+    path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
 };
@@ -209,10 +215,23 @@ async fn main() -> eyre::Result<()> {
 
             println!("{}", Cast::new(&provider).access_list(builder_output, block, to_json).await?);
         }
-        Subcommands::Block { rpc_url, block, full, field, to_json } => {
+        Subcommands::Block { rpc_url, block, full, field, to_json, watch } => {
             let rpc_url = consume_config_rpc_url(rpc_url);
             let provider = get_http_provider(rpc_url);
-            println!("{}", Cast::new(provider).block(block, full, field, to_json).await?);
+            let cast = Cast::new(provider.clone());
+            if watch {
+                let mut blocks = provider.watch_blocks().await?;
+                println!("{}", cast.block(block, full, field.clone(), to_json).await?);
+                while let Some(hash) = blocks.next().await {
+                    if !to_json {
+                        // clears the terminal like `watch` does
+                        print!("\x1B[2J\x1B[1;1H");
+                    }
+                    println!("{}", cast.block(hash, full, field.clone(), to_json).await?);
+                }
+            } else {
+                println!("{}", cast.block(block, full, field, to_json).await?);
+            }
         }
         Subcommands::BlockNumber { rpc_url } => {
             let rpc_url = consume_config_rpc_url(rpc_url);
@@ -223,7 +242,7 @@ async fn main() -> eyre::Result<()> {
         Subcommands::Call { address, sig, args, block, eth } => {
             let config = Config::from(&eth);
             let provider = get_http_provider(
-                config.eth_rpc_url.unwrap_or_else(|| "http://localhost:8545".to_string()),
+                config.eth_rpc_url.clone().unwrap_or_else(|| "http://localhost:8545".to_string()),
             );
 
             let chain: Chain = if let Some(chain) = eth.chain {
@@ -234,7 +253,10 @@ async fn main() -> eyre::Result<()> {
 
             let mut builder =
                 TxBuilder::new(&provider, config.sender, Some(address), chain, false).await?;
-            builder.etherscan_api_key(config.etherscan_api_key).set_args(&sig, args).await?;
+            builder
+                .etherscan_api_key(config.get_etherscan_api_key(Some(chain)))
+                .set_args(&sig, args)
+                .await?;
             let builder_output = builder.build();
             println!("{}", Cast::new(provider).call(builder_output, block).await?);
         }
@@ -267,10 +289,16 @@ async fn main() -> eyre::Result<()> {
             let addr = Cast::new(&provider).compute_address(pubkey, nonce).await?;
             println!("Computed Address: {}", SimpleCast::checksum_address(&addr)?);
         }
-        Subcommands::Code { block, who, rpc_url } => {
+        Subcommands::Code { block, who, rpc_url, disassemble, abis } => {
             let rpc_url = consume_config_rpc_url(rpc_url);
             let provider = get_http_provider(rpc_url);
-            println!("{}", Cast::new(provider).code(who, block).await?);
+            let code = Cast::new(provider).code(who, block).await?;
+            if disassemble {
+                let code = hex::decode(code.strip_prefix("0x").unwrap_or(&code))?;
+                print!("{}", disassemble_code(&code, &abis).await?);
+            } else {
+                println!("{code}");
+            }
         }
         Subcommands::Namehash { name } => {
             println!("{}", SimpleCast::namehash(&name)?);
@@ -293,13 +321,14 @@ async fn main() -> eyre::Result<()> {
         } => {
             let config = Config::from(&eth);
             let provider = Arc::new(get_http_provider(
-                &config.eth_rpc_url.unwrap_or_else(|| "http://localhost:8545".to_string()),
+                &config.eth_rpc_url.clone().unwrap_or_else(|| "http://localhost:8545".to_string()),
             ));
             let chain: Chain = if let Some(chain) = eth.chain {
                 chain
             } else {
                 provider.get_chainid().await?.into()
             };
+            let etherscan_api_key = config.get_etherscan_api_key(Some(chain));
             let sig = sig.unwrap_or_default();
 
             if let Ok(Some(signer)) = eth.signer_with(chain.into(), provider.clone()).await {
@@ -334,7 +363,7 @@ async fn main() -> eyre::Result<()> {
                             tx.value,
                             tx.nonce,
                             chain,
-                            config.etherscan_api_key,
+                            etherscan_api_key,
                             cast_async,
                             tx.legacy,
                             confirmations,
@@ -354,7 +383,7 @@ async fn main() -> eyre::Result<()> {
                             tx.value,
                             tx.nonce,
                             chain,
-                            config.etherscan_api_key,
+                            etherscan_api_key,
                             cast_async,
                             tx.legacy,
                             confirmations,
@@ -374,7 +403,7 @@ async fn main() -> eyre::Result<()> {
                             tx.value,
                             tx.nonce,
                             chain,
-                            config.etherscan_api_key,
+                            etherscan_api_key,
                             cast_async,
                             tx.legacy,
                             confirmations,
@@ -402,7 +431,7 @@ async fn main() -> eyre::Result<()> {
                     tx.value,
                     tx.nonce,
                     chain,
-                    config.etherscan_api_key,
+                    etherscan_api_key,
                     cast_async,
                     tx.legacy,
                     confirmations,
@@ -413,7 +442,47 @@ async fn main() -> eyre::Result<()> {
                 eyre::bail!("No wallet or sender address provided. Consider passing it via the --from flag or setting the ETH_FROM env variable or setting in the foundry.toml file");
             }
         }
-        Subcommands::PublishTx { eth, raw_tx, cast_async } => {
+        Subcommands::MakeTx {
+            to,
+            value,
+            data,
+            nonce,
+            gas_limit,
+            gas_price,
+            max_fee,
+            priority_fee,
+            chain,
+            wallet,
+        } => {
+            let signer = wallet.local()?.ok_or_else(|| {
+                eyre::eyre!("a private key, mnemonic or keystore is required to sign transactions")
+            })?;
+            let data = match data {
+                Some(data) => hex::decode(data.strip_prefix("0x").unwrap_or(&data))?,
+                None => Vec::new(),
+            };
+
+            let mut tx: TypedTransaction = if let Some(max_fee) = max_fee {
+                Eip1559TransactionRequest::new()
+                    .max_fee_per_gas(max_fee)
+                    .max_priority_fee_per_gas(priority_fee.unwrap_or_default())
+                    .into()
+            } else {
+                TransactionRequest::new().gas_price(gas_price.unwrap_or_default()).into()
+            };
+            tx.set_from(signer.address())
+                .set_value(value.unwrap_or_default())
+                .set_data(data.into())
+                .set_nonce(nonce)
+                .set_gas(gas_limit)
+                .set_chain_id(chain.inner as u64);
+            if let Some(to) = to {
+                tx.set_to(to);
+            }
+
+            println!("{}", SimpleCast::sign_transaction(tx, &signer).await?);
+        }
+        Subcommands::PublishTx { eth, raw_tx, cast_async, confirmations, to_json } => {
             let config = Config::from(&eth);
             let provider = get_http_provider(
                 config.eth_rpc_url.unwrap_or_else(|| "http://localhost:8545".to_string()),
@@ -423,11 +492,19 @@ async fn main() -> eyre::Result<()> {
             let tx_hash = *pending_tx;
 
             if cast_async {
-                println!("{:?}", pending_tx);
+                println!("{:#x}", tx_hash);
             } else {
-                let receipt =
-                    pending_tx.await?.ok_or_else(|| eyre::eyre!("tx {tx_hash} not found"))?;
-                println!("{}", serde_json::json!(receipt));
+                let receipt = pending_tx
+                    .confirmations(confirmations)
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("tx {tx_hash:#x} not found"))?;
+                if to_json {
+                    println!("{}", serde_json::json!(receipt));
+                } else {
+                    println!("transactionHash         {}", receipt.transaction_hash.pretty());
+                    println!("blockNumber             {}", receipt.block_number.pretty());
+                    println!("gasUsed                 {}", receipt.gas_used.pretty());
+                }
             }
         }
         Subcommands::CalldataDecode { sig, calldata } => {
@@ -443,6 +520,15 @@ async fn main() -> eyre::Result<()> {
         Subcommands::AbiEncode { sig, args } => {
             println!("{}", SimpleCast::abi_encode(&sig, &args)?);
         }
+        Subcommands::AbiEncodePacked { sig, args } => {
+            println!("{}", SimpleCast::abi_encode_packed(&sig, &args)?);
+            if SimpleCast::is_packed_encoding_ambiguous(&sig)? {
+                eprintln!(
+                    "Warning: `{sig}` has adjacent dynamic types, their packed encoding is \
+                     ambiguous and may collide with other values, consider `cast abi-encode`"
+                );
+            }
+        }
         Subcommands::Index { key_type, key, slot_number } => {
             let encoded = SimpleCast::index(&key_type, &key, &slot_number)?;
             println!("{encoded}");
@@ -524,8 +610,11 @@ async fn main() -> eyre::Result<()> {
             let provider = get_http_provider(rpc_url);
             println!("{}", Cast::new(provider).gas_price().await?);
         }
-        Subcommands::Keccak { data } => {
-            println!("{}", SimpleCast::keccak(&data)?);
+        Subcommands::Keccak { input } => {
+            println!("{}", SimpleCast::keccak(&input.resolve()?)?);
+        }
+        Subcommands::Sha256 { input } => {
+            println!("{}", SimpleCast::sha256(&input.resolve()?)?);
         }
 
         Subcommands::Interface {
@@ -542,13 +631,7 @@ async fn main() -> eyre::Result<()> {
             } else {
                 let api_key = match etherscan_api_key {
                     Some(inner) => inner,
-                    _ => {
-                        if let Some(etherscan_api_key) = Config::load().etherscan_api_key {
-                            etherscan_api_key
-                        } else {
-                            eyre::bail!("No Etherscan API Key is set. Consider using the ETHERSCAN_API_KEY env var, or setting the -e CLI argument or etherscan-api-key in foundry.toml")
-                        }
-                    }
+                    None => Config::load().require_etherscan_api_key(Some(chain.inner))?,
                 };
                 SimpleCast::generate_interface(InterfacePath::Etherscan {
                     chain: chain.inner,
@@ -644,13 +727,7 @@ async fn main() -> eyre::Result<()> {
         Subcommands::EtherscanSource { chain, address, directory, etherscan_api_key } => {
             let api_key = match etherscan_api_key {
                 Some(inner) => inner,
-                _ => {
-                    if let Some(etherscan_api_key) = Config::load().etherscan_api_key {
-                        etherscan_api_key
-                    } else {
-                        eyre::bail!("No Etherscan API Key is set. Consider using the ETHERSCAN_API_KEY env var, or setting the -e CLI argument or etherscan-api-key in foundry.toml")
-                    }
-                }
+                None => Config::load().require_etherscan_api_key(Some(chain.inner))?,
             };
             match directory {
                 Some(dir) => {
@@ -746,6 +823,35 @@ fn format_uint(val: U256, base_out: u32) -> eyre::Result<String> {
     }
 }
 
+/// Disassembles the `code`, the selectors of its function dispatcher are looked up in the `abis`
+/// first and in the signature database otherwise
+async fn disassemble_code(code: &[u8], abis: &[PathBuf]) -> eyre::Result<String> {
+    let instructions = disasm::disassemble(code);
+
+    let mut signatures = HashMap::new();
+    for path in abis {
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+        // artifacts contain the ABI in their `abi` field
+        let abi = json.get("abi").cloned().unwrap_or(json);
+        let abi: Abi = serde_json::from_value(abi)
+            .wrap_err_with(|| format!("{} does not contain an ABI", path.display()))?;
+        signatures.extend(abi.functions().map(|func| (func.short_signature(), func.signature())));
+    }
+
+    let mut identifier =
+        SignaturesIdentifier::new(Config::foundry_cache_dir(), Config::load().offline)?;
+    for entry in disasm::dispatcher_entries(&instructions) {
+        if signatures.contains_key(&entry.selector) {
+            continue
+        }
+        if let Some(func) = identifier.identify_function(&entry.selector).await {
+            signatures.insert(entry.selector, func.signature());
+        }
+    }
+
+    Ok(disasm::format_disassembly(&instructions, &signatures))
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn cast_send<M: Middleware, F: Into<NameOrAddress>, T: Into<NameOrAddress>>(
     provider: M,
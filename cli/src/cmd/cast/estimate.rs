@@ -64,17 +64,18 @@ impl EstimateArgs {
         let EstimateArgs { to, sig, args, value, eth, command } = self;
         let config = Config::from(&eth);
         let provider = get_http_provider(
-            config.eth_rpc_url.unwrap_or_else(|| "http://localhost:8545".to_string()),
+            config.eth_rpc_url.clone().unwrap_or_else(|| "http://localhost:8545".to_string()),
         );
 
         let chain: Chain =
             if let Some(chain) = eth.chain { chain } else { provider.get_chainid().await?.into() };
+        let etherscan_api_key = config.get_etherscan_api_key(Some(chain));
 
         let from = eth.sender().await;
         let mut builder = TxBuilder::new(&provider, from, to, chain, false).await?;
         match command {
             Some(EstimateSubcommands::Create { code, sig, args, value }) => {
-                builder.etherscan_api_key(config.etherscan_api_key).value(value);
+                builder.etherscan_api_key(etherscan_api_key).value(value);
 
                 let mut data = hex::decode(code.strip_prefix("0x").unwrap_or(&code))?;
 
@@ -87,7 +88,7 @@ impl EstimateArgs {
             }
             _ => {
                 builder
-                    .etherscan_api_key(config.etherscan_api_key)
+                    .etherscan_api_key(etherscan_api_key)
                     .value(value)
                     .set_args(sig.unwrap().as_str(), args)
                     .await?;
@@ -5,8 +5,9 @@ use ethers::{abi::Address, prelude::Middleware, solc::utils::RuntimeOrHandle, ty
 use forge::{
     debug::DebugArena,
     executor::{
-        inspector::CheatsConfig, opts::EvmOpts, Backend, DeployResult, ExecutorBuilder,
-        RawCallResult,
+        inspector::{CheatsConfig, MAX_DEBUG_STEPS},
+        opts::EvmOpts,
+        Backend, DeployResult, ExecutorBuilder, RawCallResult,
     },
     trace::{identifier::EtherscanIdentifier, CallTraceArena, CallTraceDecoderBuilder, TraceKind},
 };
@@ -133,9 +134,10 @@ impl RunArgs {
 
             let etherscan_identifier = EtherscanIdentifier::new(
                 evm_opts.get_remote_chain_id(),
-                config.etherscan_api_key,
+                config.get_etherscan_api_key(evm_opts.get_remote_chain_id()),
                 Config::foundry_etherscan_chain_cache_dir(evm_opts.get_chain_id()),
                 Duration::from_secs(24 * 60 * 60),
+                config.offline,
             );
 
             let labeled_addresses: BTreeMap<Address, String> = self
@@ -155,8 +157,10 @@ impl RunArgs {
 
             let mut decoder = CallTraceDecoderBuilder::new().with_labels(labeled_addresses).build();
 
-            decoder
-                .add_signature_identifier(SignaturesIdentifier::new(Config::foundry_cache_dir())?);
+            decoder.add_signature_identifier(SignaturesIdentifier::new(
+                Config::foundry_cache_dir(),
+                config.offline,
+            )?);
 
             for (_, trace) in &mut result.traces {
                 decoder.identify(trace, &etherscan_identifier);
@@ -174,6 +178,14 @@ impl RunArgs {
 
 fn run_debugger(result: RunResult, decoder: CallTraceDecoder) -> eyre::Result<()> {
     // TODO Get source from etherscan
+    if result.debug.truncated {
+        eprintln!(
+            "{}",
+            Paint::yellow(format!(
+                "Warning: the transaction exceeded {MAX_DEBUG_STEPS} steps, only the first {MAX_DEBUG_STEPS} steps can be debugged."
+            ))
+        );
+    }
     let calls: Vec<DebugArena> = vec![result.debug];
     let flattened = calls.last().expect("we should have collected debug info").flatten(0);
     let tui = Tui::new(flattened, 0, decoder.contracts, HashMap::new(), BTreeMap::new())?;
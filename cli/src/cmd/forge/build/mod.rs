@@ -66,6 +66,14 @@ pub struct BuildArgs {
     #[serde(skip)]
     pub sizes: bool,
 
+    /// Print the compiler diagnostics, the written artifacts and the compiler versions as a
+    /// single JSON document instead of text.
+    ///
+    /// The document has a `version` field that is increased on breaking changes of its schema.
+    #[clap(long, conflicts_with_all = &["names", "sizes"])]
+    #[serde(skip)]
+    pub format_json: bool,
+
     #[clap(flatten, next_help_heading = "WATCH OPTIONS")]
     #[serde(skip)]
     pub watch: WatchArgs,
@@ -84,18 +92,19 @@ impl Cmd for BuildArgs {
         {
             // The extra newline is needed, otherwise the compiler output will overwrite the
             // message
-            p_println!(!self.args.silent => "Missing dependencies found. Installing now.\n");
+            let quiet = self.args.silent || self.format_json;
+            p_println!(!quiet => "Missing dependencies found. Installing now.\n");
             if install::install(
                 &mut config,
                 Vec::new(),
                 DependencyInstallOpts {
                     // TODO(onbjerg): We should settle on --quiet or --silent.
-                    quiet: self.args.silent,
+                    quiet,
                     ..Default::default()
                 },
             )
             .is_err() &&
-                !self.args.silent
+                !quiet
             {
                 eprintln!(
                     "{}",
@@ -106,7 +115,9 @@ impl Cmd for BuildArgs {
             }
         }
 
-        if self.args.silent {
+        if self.format_json {
+            compile::compile_json(&project)
+        } else if self.args.silent {
             compile::suppress_compile(&project)
         } else {
             compile::compile(&project, self.names, self.sizes)
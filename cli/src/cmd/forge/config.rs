@@ -6,7 +6,9 @@ use crate::{
 };
 use clap::Parser;
 use foundry_common::evm::EvmArgs;
-use foundry_config::fix::fix_tomls;
+use foundry_config::{figment::Figment, fix::fix_tomls, Config, FsAccessKind};
+use serde::Serialize;
+use std::{collections::BTreeMap, path::Path};
 
 foundry_config::impl_figment_convert!(ConfigArgs, opts, evm_opts);
 
@@ -19,6 +21,17 @@ pub struct ConfigArgs {
     basic: bool,
     #[clap(help = "attempts to fix any configuration warnings", long)]
     fix: bool,
+    #[clap(help = "prints where each config value originates from", long)]
+    origins: bool,
+    #[clap(help = "prints values that were interpolated from env vars, which may be secrets", long)]
+    show_secrets: bool,
+    #[clap(
+        help = "checks whether cheatcodes may `read` or `write` the given path",
+        long,
+        number_of_values = 2,
+        value_names = &["PATH", "OP"]
+    )]
+    check_fs: Option<Vec<String>>,
     // support nested build arguments
     #[clap(flatten)]
     opts: BuildArgs,
@@ -39,20 +52,88 @@ impl Cmd for ConfigArgs {
 
         let config = self.load_config_unsanitized_emit_warnings();
 
-        let s = if self.basic {
-            let config = config.into_basic();
+        if let Some(check) = &self.check_fs {
+            let kind = check[1].parse::<FsAccessKind>().map_err(|err| eyre::eyre!(err))?;
+            let resolution = config.resolved_fs_permissions().resolve(
+                &config.__root.0,
+                Path::new(&check[0]),
+                kind,
+            );
+            println!("{resolution}");
+            return Ok(())
+        }
+
+        let s = if self.origins {
+            let figment: Figment = (&self).into();
+            let values = match to_json(&config, &config, self.show_secrets)? {
+                serde_json::Value::Object(values) => values,
+                _ => unreachable!("config is serialized as an object"),
+            };
+            let origins = values
+                .into_iter()
+                .map(|(key, value)| {
+                    let origin = figment
+                        .find_metadata(&key)
+                        .map(|metadata| match &metadata.source {
+                            Some(source) => format!("{} ({source})", metadata.name),
+                            None => metadata.name.to_string(),
+                        })
+                        .unwrap_or_else(|| "default".to_string());
+                    (key, (value, origin))
+                })
+                .collect::<BTreeMap<_, _>>();
+
+            if self.json {
+                let origins = origins
+                    .into_iter()
+                    .map(|(key, (value, origin))| {
+                        (key, serde_json::json!({ "value": value, "origin": origin }))
+                    })
+                    .collect::<serde_json::Map<_, _>>();
+                serde_json::to_string_pretty(&origins)?
+            } else {
+                origins
+                    .into_iter()
+                    .map(|(key, (value, origin))| format!("{key} = {value} # {origin}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        } else if self.basic {
+            let basic = config.clone().into_basic();
             if self.json {
-                serde_json::to_string_pretty(&config)?
+                serde_json::to_string_pretty(&to_json(&basic, &config, self.show_secrets)?)?
+            } else if self.show_secrets {
+                basic.to_string_pretty()?
             } else {
-                config.to_string_pretty()?
+                basic.to_string_pretty_redacted(&config)?
             }
         } else if self.json {
-            serde_json::to_string_pretty(&config)?
-        } else {
+            serde_json::to_string_pretty(&to_json(&config, &config, self.show_secrets)?)?
+        } else if self.show_secrets {
             config.to_string_pretty()?
+        } else {
+            config.to_string_pretty_redacted()?
         };
 
         println!("{s}");
         Ok(())
     }
 }
+
+/// Serializes the `value` to JSON, the values that may contain secrets are replaced by the
+/// [`Config::redactions()`] of the `config` unless `show_secrets` is set
+fn to_json(
+    value: &impl Serialize,
+    config: &Config,
+    show_secrets: bool,
+) -> eyre::Result<serde_json::Value> {
+    let mut value = serde_json::to_value(value)?;
+    if let (false, Some(values)) = (show_secrets, value.as_object_mut()) {
+        for (key, redacted) in config.redactions()? {
+            if let Some(value) = values.get_mut(&key) {
+                *value = redacted;
+            }
+        }
+    }
+    Ok(value)
+}
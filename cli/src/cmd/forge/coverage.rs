@@ -1,7 +1,10 @@
 //! Coverage command
 use crate::{
     cmd::{
-        forge::{build::CoreBuildArgs, test::Filter},
+        forge::{
+            build::CoreBuildArgs,
+            test::{print_untested, untested_candidates, Filter},
+        },
         Cmd, LoadConfig,
     },
     compile::ProjectCompiler,
@@ -15,7 +18,10 @@ use ethers::{
         artifacts::{Ast, CompactBytecode, CompactDeployedBytecode},
         Artifact, Bytes, Project, ProjectCompileOutput,
     },
-    solc::{artifacts::contract::CompactContractBytecode, sourcemap::SourceMap},
+    solc::{
+        artifacts::contract::CompactContractBytecode, sourcemap::SourceMap,
+        utils::RuntimeOrHandle,
+    },
 };
 use eyre::Context;
 use forge::{
@@ -26,7 +32,8 @@ use forge::{
     executor::{inspector::CheatsConfig, opts::EvmOpts},
     result::SuiteResult,
     revm::SpecId,
-    trace::identifier::LocalTraceIdentifier,
+    trace::{identifier::LocalTraceIdentifier, CallTraceDecoderBuilder},
+    untested::CalledFunctions,
     utils::{build_ic_pc_map, ICPCMap},
     MultiContractRunnerBuilder, TestOptions,
 };
@@ -50,6 +57,17 @@ pub struct CoverageArgs {
     )]
     report: CoverageReportKind,
 
+    /// Warn about public and external functions of non-test contracts that are never called by
+    /// any test.
+    ///
+    /// `view` and `pure` functions are skipped unless `--include-view` is set.
+    #[clap(long)]
+    warn_untested: bool,
+
+    /// Include `view` and `pure` functions in the untested functions.
+    #[clap(long, requires = "warn-untested")]
+    include_view: bool,
+
     #[clap(flatten, next_help_heading = "TEST FILTERING")]
     filter: Filter,
 
@@ -278,6 +296,13 @@ impl CoverageArgs {
             .set_coverage(true)
             .build(root.clone(), output, env, evm_opts)?;
 
+        let untested_contracts = if self.warn_untested {
+            untested_candidates(&config, &runner.known_contracts)
+        } else {
+            Vec::new()
+        };
+        let mut called = CalledFunctions::default();
+
         // Set up identifier
         let local_identifier = LocalTraceIdentifier::new(&runner.known_contracts);
 
@@ -287,11 +312,21 @@ impl CoverageArgs {
             thread::spawn(move || runner.test(&self.filter, Some(tx), Default::default()).unwrap());
 
         // Add hit data to the coverage report
-        for (hit_map, traces) in rx
+        for (hit_map, mut traces) in rx
             .into_iter()
             .flat_map(|(_, suite)| suite.test_results.into_values())
             .flat_map(|mut result| Some((result.coverage.take()?, result.traces)))
         {
+            if self.warn_untested {
+                let mut decoder = CallTraceDecoderBuilder::new().build();
+                let rt = RuntimeOrHandle::new();
+                for (_, trace) in &mut traces {
+                    decoder.identify(trace, &local_identifier);
+                    rt.block_on(decoder.decode(trace));
+                }
+                called.analyze(&traces);
+            }
+
             let hits = traces
                 .into_iter()
                 .flat_map(|(_, trace)| {
@@ -329,7 +364,14 @@ impl CoverageArgs {
                 LcovReporter::new(&mut fs::create_file(root.join("lcov.info"))?).report(report)
             }
             CoverageReportKind::Debug => DebugReporter::default().report(report),
+        }?;
+
+        if self.warn_untested {
+            let candidates = untested_contracts.iter().map(|(id, abi)| (id.clone(), abi));
+            print_untested(&called.untested(candidates, self.include_view));
         }
+
+        Ok(())
     }
 }
 
@@ -1,5 +1,8 @@
 //! Create command
-use super::verify;
+use super::{
+    script::{ScriptArgs, ScriptSequence, TransactionWithMetadata},
+    verify,
+};
 use crate::{
     cmd::{forge::build::CoreBuildArgs, utils, LoadConfig, RetryArgs},
     compile,
@@ -20,8 +23,12 @@ use eyre::Context;
 use foundry_common::{fs, get_http_provider};
 use foundry_utils::parse_tokens;
 use rustc_hex::ToHex;
+use serde::Deserialize;
 use serde_json::json;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use tracing::log::trace;
 
 pub const RETRY_VERIFY_ON_CREATE: RetryArgs = RetryArgs { retries: 15, delay: Some(3) };
@@ -30,9 +37,25 @@ pub const RETRY_VERIFY_ON_CREATE: RetryArgs = RetryArgs { retries: 15, delay: So
 pub struct CreateArgs {
     #[clap(
         help = "The contract identifier in the form `<path>:<contractname>`.",
-        value_name = "CONTRACT"
+        value_name = "CONTRACT",
+        required_unless_present = "script"
     )]
-    contract: ContractInfo,
+    contract: Option<ContractInfo>,
+
+    #[clap(
+        long,
+        help = "Deploy by running the `run()` function of the script in broadcast mode and print the address of the last contract it created.",
+        value_hint = ValueHint::FilePath,
+        value_name = "PATH",
+        conflicts_with_all = &[
+            "contract",
+            "constructor_args",
+            "constructor_args_path",
+            "verify",
+            "unlocked"
+        ]
+    )]
+    script: Option<PathBuf>,
 
     #[clap(
         long,
@@ -81,9 +104,23 @@ pub struct CreateArgs {
     unlocked: bool,
 }
 
+/// The transactions of a script's broadcast log
+#[derive(Deserialize)]
+struct BroadcastLog {
+    transactions: Vec<TransactionWithMetadata>,
+}
+
 impl CreateArgs {
     /// Executes the command to create a contract
     pub async fn run(mut self) -> eyre::Result<()> {
+        let mut contract = match self.contract.take() {
+            Some(contract) => contract,
+            None => {
+                let script = self.script.take().expect("is required without a contract");
+                return self.run_script(script).await
+            }
+        };
+
         // Find Project & Compile
         let project = self.opts.project()?;
         let mut output = if self.json || self.opts.silent {
@@ -93,12 +130,12 @@ impl CreateArgs {
             compile::compile(&project, false, false)
         }?;
 
-        if let Some(ref mut path) = self.contract.path {
+        if let Some(ref mut path) = contract.path {
             // paths are absolute in the project's output
             *path = canonicalized(project.root().join(&path)).to_string_lossy().to_string();
         }
 
-        let (abi, bin, _) = utils::remove_contract(&mut output, &self.contract)?;
+        let (abi, bin, _) = utils::remove_contract(&mut output, &contract)?;
 
         let bin = match bin.object {
             BytecodeObject::Bytecode(_) => bin.object,
@@ -157,7 +194,7 @@ impl CreateArgs {
             // use unlocked provider
             let provider =
                 Arc::try_unwrap(provider).expect("Only one ref; qed.").with_sender(sender);
-            self.deploy(abi, bin, params, provider).await?;
+            self.deploy(contract, abi, bin, params, provider).await?;
             return Ok(())
         }
 
@@ -165,9 +202,15 @@ impl CreateArgs {
         let chain_id = provider.get_chainid().await?;
         match self.eth.signer_with(chain_id, provider).await? {
             Some(signer) => match signer {
-                WalletType::Ledger(signer) => self.deploy(abi, bin, params, signer).await?,
-                WalletType::Local(signer) => self.deploy(abi, bin, params, signer).await?,
-                WalletType::Trezor(signer) => self.deploy(abi, bin, params, signer).await?,
+                WalletType::Ledger(signer) => {
+                    self.deploy(contract, abi, bin, params, signer).await?
+                }
+                WalletType::Local(signer) => {
+                    self.deploy(contract, abi, bin, params, signer).await?
+                }
+                WalletType::Trezor(signer) => {
+                    self.deploy(contract, abi, bin, params, signer).await?
+                }
             },
             None => eyre::bail!("could not find artifact"),
         };
@@ -175,8 +218,82 @@ impl CreateArgs {
         Ok(())
     }
 
+    /// Broadcasts the `run()` function of the `script` with the wallet and transaction options of
+    /// this command and prints the address of the last contract the script created
+    async fn run_script(self, script: PathBuf) -> eyre::Result<()> {
+        let config = self.eth.load_config_emit_warnings();
+        let rpc_url =
+            config.eth_rpc_url.clone().unwrap_or_else(|| "http://localhost:8545".to_string());
+
+        let script_path = script.to_string_lossy().to_string();
+        let mut args = ScriptArgs::parse_from(["forge-script", script_path.as_str()]);
+        args.broadcast = true;
+        args.legacy = self.tx.legacy;
+        args.with_gas_price = self.tx.gas_price;
+        args.etherscan_api_key = self.eth.etherscan_api_key.clone();
+        args.opts.args = self.opts.clone();
+        args.evm_opts.fork_url = Some(rpc_url.clone());
+        args.evm_opts.sender = self.eth.wallet.from;
+
+        let wallet = &self.eth.wallet;
+        args.wallets.interactives = wallet.interactive as u32;
+        args.wallets.private_key = wallet.private_key.clone();
+        args.wallets.mnemonic_paths = wallet.mnemonic_path.clone().map(|path| vec![path]);
+        args.wallets.mnemonic_indexes = Some(vec![wallet.mnemonic_index]);
+        args.wallets.keystore_paths = wallet.keystore_path.clone().map(|path| vec![path]);
+        args.wallets.keystore_passwords =
+            wallet.keystore_password.clone().map(|password| vec![password]);
+        args.wallets.ledger = wallet.ledger;
+        args.wallets.trezor = wallet.trezor;
+        args.wallets.hd_paths = wallet.hd_path.clone().map(|path| vec![path]);
+        args.wallets.froms = wallet.from.map(|from| vec![from]);
+
+        let sig = args.sig.clone();
+        args.run_script().await?;
+
+        let chain = get_http_provider(&rpc_url).get_chainid().await?.as_u64();
+        self.print_script_deployment(&script, &sig, chain)
+    }
+
+    /// Prints the last contract that was created by broadcasting the `sig` of the `script`
+    fn print_script_deployment(&self, script: &Path, sig: &str, chain: u64) -> eyre::Result<()> {
+        let config = self.opts.load_config();
+        let path = ScriptSequence::get_path(&config.broadcast, sig, script, chain)?;
+        // only the transactions are needed, loading a full `ScriptSequence` would save it again
+        // on drop
+        let log: BroadcastLog = read_json_file(path)?;
+        let deployment = log
+            .transactions
+            .iter()
+            .rev()
+            .filter(|tx| tx.opcode == "CREATE" || tx.opcode == "CREATE2")
+            .find_map(|tx| tx.contract_address.map(|address| (tx, address)));
+
+        let (tx, address) = match deployment {
+            Some(deployment) => deployment,
+            None => eyre::bail!("Script {} did not create any contract", script.display()),
+        };
+        let contract_name = tx.contract_name.clone().unwrap_or_default();
+        if self.json {
+            let output = json!({
+                "contractName": contract_name,
+                "deployedTo": SimpleCast::checksum_address(&address)?,
+                "transactionHash": tx.hash,
+            });
+            println!("{output}");
+        } else {
+            println!("Contract: {contract_name}");
+            println!("Deployed to: {}", SimpleCast::checksum_address(&address)?);
+            if let Some(hash) = tx.hash {
+                println!("Transaction hash: {hash:?}");
+            }
+        }
+        Ok(())
+    }
+
     async fn deploy<M: Middleware + 'static>(
         self,
+        contract: ContractInfo,
         abi: Abi,
         bin: BytecodeObject,
         args: Vec<Token>,
@@ -186,7 +303,7 @@ impl CreateArgs {
         let deployer_address =
             provider.default_sender().expect("no sender address set for provider");
         let bin = bin.into_bytes().unwrap_or_else(|| {
-            panic!("no bytecode found in bin object for {}", self.contract.name)
+            panic!("no bytecode found in bin object for {}", contract.name)
         });
         let provider = Arc::new(provider);
         let factory = ContractFactory::new(abi.clone(), bin.clone(), provider.clone());
@@ -279,15 +396,12 @@ impl CreateArgs {
             if self.opts.compiler.optimize { self.opts.compiler.optimizer_runs } else { None };
         let verify = verify::VerifyArgs {
             address,
-            contract: self.contract,
+            contract,
             compiler_version: None,
             constructor_args,
             num_of_optimizations,
             chain: chain.into(),
-            etherscan_key: self
-                .eth
-                .etherscan_api_key
-                .ok_or(eyre::eyre!("ETHERSCAN_API_KEY must be set"))?,
+            etherscan_key: self.eth.etherscan_api_key,
             project_paths: self.opts.project_paths,
             flatten: false,
             force: false,
@@ -6,6 +6,7 @@ use crate::{
 use clap::{Parser, ValueHint};
 use foundry_common::evm::EvmArgs;
 use std::path::PathBuf;
+use ui::Breakpoint;
 
 // Loads project's figment and merges the build cli arguments into it
 foundry_config::impl_figment_convert!(DebugArgs, opts, evm_opts);
@@ -35,6 +36,12 @@ pub struct DebugArgs {
     #[clap(long)]
     pub debug: bool,
 
+    /// Set a breakpoint on a source line, e.g. `src/Counter.sol:12`.
+    ///
+    /// Jump to the next and previous breakpoint with `b` and `B`.
+    #[clap(long = "breakpoint", number_of_values = 1, value_name = "FILE:LINE")]
+    pub breakpoints: Vec<Breakpoint>,
+
     #[clap(flatten, next_help_heading = "BUILD OPTIONS")]
     pub opts: CoreBuildArgs,
 
@@ -57,12 +64,14 @@ impl DebugArgs {
                 args: self.opts,
                 names: false,
                 sizes: false,
+                format_json: false,
                 watch: WatchArgs::default(),
             },
             wallets: MultiWallet::default(),
             evm_opts: self.evm_opts,
             resume: false,
             debug: true,
+            breakpoints: self.breakpoints,
             slow: false,
             etherscan_api_key: None,
             verify: false,
@@ -0,0 +1,186 @@
+//! gas-history command
+
+use crate::{
+    cmd::{
+        forge::snapshot::{parse_snapshot, SnapshotEntry},
+        Cmd,
+    },
+    utils::CommandUtils,
+};
+use clap::{Parser, ValueHint};
+use std::{path::PathBuf, process::Command};
+
+/// The bars of a sparkline, from the lowest to the highest value
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// CLI arguments for `forge gas-history`.
+///
+/// Reads the gas snapshots of the last commits that changed the snapshot file from the git history
+/// and plots how the gas of a test changed over them.
+#[derive(Debug, Clone, Parser)]
+pub struct GasHistoryArgs {
+    /// The test to plot, e.g. `testDeposit()` or `VaultTest:testDeposit()`.
+    #[clap(value_name = "TEST")]
+    test: String,
+
+    /// The snapshot file whose history is read.
+    #[clap(
+        long,
+        default_value = ".gas-snapshot",
+        value_hint = ValueHint::FilePath,
+        value_name = "SNAPSHOT_FILE"
+    )]
+    snap: PathBuf,
+
+    /// The number of the most recent commits that changed the snapshot file to read.
+    #[clap(long, default_value = "20", value_name = "COMMITS")]
+    depth: usize,
+}
+
+impl Cmd for GasHistoryArgs {
+    type Output = ();
+
+    fn run(self) -> eyre::Result<Self::Output> {
+        let snap = self.snap.to_string_lossy();
+        let log = Command::new("git")
+            .args(["log", &format!("-n{}", self.depth), "--format=%H %ct", "--", &snap])
+            .get_stdout_lossy()?;
+
+        let mut points = Vec::new();
+        for line in log.lines() {
+            let (sha, commit_time) = match line.split_once(' ') {
+                Some(commit) => commit,
+                None => continue,
+            };
+            let content = Command::new("git")
+                .args(["show", &format!("{sha}:./{snap}")])
+                .get_stdout_lossy()?;
+            let entries = parse_snapshot(&content)?;
+            if let Some(gas) = find_gas(&entries, &self.test)? {
+                points.push(GasPoint {
+                    commit: sha.to_string(),
+                    timestamp: commit_time.parse().unwrap_or_default(),
+                    gas,
+                });
+            }
+        }
+        if points.is_empty() {
+            eyre::bail!(
+                "No snapshot of the last {} commits that changed {snap} contains `{}`",
+                self.depth,
+                self.test
+            )
+        }
+        points.sort_by_key(|point| point.timestamp);
+
+        let gas = points.iter().map(|point| point.gas).collect::<Vec<_>>();
+        println!("{} {}", self.test, sparkline(&gas));
+        let mut previous = None;
+        for point in &points {
+            let change = previous
+                .map(|previous| format!(" ({:+})", point.gas as i128 - previous as i128))
+                .unwrap_or_default();
+            let commit = point.commit.get(..8).unwrap_or(&point.commit);
+            println!("  {commit} {} {}{change}", format_date(point.timestamp), point.gas);
+            previous = Some(point.gas);
+        }
+        Ok(())
+    }
+}
+
+/// The gas of a test in the snapshot of a commit
+struct GasPoint {
+    commit: String,
+    timestamp: u64,
+    gas: u64,
+}
+
+/// Returns the gas of the `test` in the snapshot `entries`, which is either a signature or a
+/// signature qualified with its contract, like `VaultTest:testDeposit()`.
+///
+/// Fails if the signature is not qualified and several contracts have a test with it.
+fn find_gas(entries: &[SnapshotEntry], test: &str) -> eyre::Result<Option<u64>> {
+    let matches = entries
+        .iter()
+        .filter(|entry| {
+            entry.signature == test ||
+                format!("{}:{}", entry.contract_name, entry.signature) == test
+        })
+        .collect::<Vec<_>>();
+    match &matches[..] {
+        [] => Ok(None),
+        [entry] => Ok(Some(entry.gas_used.gas())),
+        _ => eyre::bail!(
+            "`{test}` matches the tests of several contracts, qualify it like `{}:{test}`",
+            matches[0].contract_name
+        ),
+    }
+}
+
+/// Plots the `values` as a line of bars whose heights scale from the lowest to the highest value
+fn sparkline(values: &[u64]) -> String {
+    let min = values.iter().copied().min().unwrap_or_default();
+    let max = values.iter().copied().max().unwrap_or_default();
+    values
+        .iter()
+        .map(|value| {
+            let index = if max == min {
+                0
+            } else {
+                ((value - min) as u128 * (SPARKS.len() - 1) as u128 / (max - min) as u128) as usize
+            };
+            SPARKS[index]
+        })
+        .collect()
+}
+
+/// Formats seconds since the unix epoch as a UTC date like `2022-10-05`
+fn format_date(timestamp: u64) -> String {
+    // converts the days since the epoch to a date of the proleptic Gregorian calendar, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forge::result::TestKindReport;
+
+    #[test]
+    fn plots_sparkline() {
+        assert_eq!(sparkline(&[100, 150, 200, 100]), "▁▄█▁");
+        assert_eq!(sparkline(&[42, 42]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn formats_dates() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_665_000_000), "2022-10-05");
+    }
+
+    #[test]
+    fn finds_gas_of_test() {
+        let entry = |contract: &str, gas| SnapshotEntry {
+            contract_name: contract.to_string(),
+            signature: "testDeposit()".to_string(),
+            gas_used: TestKindReport::Standard { gas },
+        };
+        let entries = [entry("VaultTest", 100), entry("PoolTest", 200)];
+        assert_eq!(find_gas(&entries, "PoolTest:testDeposit()").unwrap(), Some(200));
+        assert_eq!(find_gas(&entries, "testWithdraw()").unwrap(), None);
+        assert!(find_gas(&entries, "testDeposit()").is_err());
+        assert_eq!(find_gas(&entries[..1], "testDeposit()").unwrap(), Some(100));
+    }
+}
@@ -0,0 +1,310 @@
+//! gas-report command
+
+use crate::{
+    cmd::{forge::build::CoreBuildArgs, Cmd, LoadConfig},
+    compile,
+};
+use clap::{Parser, ValueHint};
+use ethers::{
+    abi::Abi,
+    prelude::{artifacts::CompactContractBytecode, ArtifactId, Middleware},
+    solc::{utils::RuntimeOrHandle, Artifact},
+    types::{Transaction, H256, U256},
+};
+use eyre::Context;
+use forge::{
+    executor::{inspector::CheatsConfig, opts::EvmOpts, Backend, ExecutorBuilder},
+    gas_report::{GasReport, GasReportHeader},
+    trace::{
+        identifier::{
+            CachedSignatures, EtherscanIdentifier, LocalTraceIdentifier, SignaturesIdentifier,
+        },
+        CallTraceArena, CallTraceDecoderBuilder, TraceKind,
+    },
+};
+use foundry_common::{evm::EvmArgs, fs, get_http_provider};
+use foundry_config::{impl_figment_convert, Config};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// CLI arguments for `forge gas-report`.
+///
+/// Replays transactions on a fork and reports the gas used by their calls like `forge test
+/// --gas-report` does for the calls of the tests.
+#[derive(Debug, Clone, Parser)]
+pub struct GasReportArgs {
+    /// A file with the hashes of the transactions to analyze, one per line.
+    #[clap(
+        long,
+        value_hint = ValueHint::FilePath,
+        value_name = "FILE",
+        required_unless_present = "from-block",
+        conflicts_with = "from-block"
+    )]
+    txs: Option<PathBuf>,
+
+    /// Analyze all transactions of the blocks from this one up to `--to-block`.
+    #[clap(long, value_name = "BLOCK", requires = "to-block")]
+    from_block: Option<u64>,
+
+    /// The last block whose transactions are analyzed.
+    #[clap(long, value_name = "BLOCK", requires = "from-block")]
+    to_block: Option<u64>,
+
+    /// Report the calls of a function in different blocks together, instead of in one row per
+    /// chain and block.
+    #[clap(long)]
+    merge_fork_contexts: bool,
+
+    /// Print the report as JSON.
+    #[clap(long)]
+    json: bool,
+
+    #[clap(flatten, next_help_heading = "BUILD OPTIONS")]
+    opts: CoreBuildArgs,
+
+    #[clap(flatten, next_help_heading = "EVM OPTIONS")]
+    evm_opts: EvmArgs,
+}
+impl_figment_convert!(GasReportArgs, opts, evm_opts);
+
+impl Cmd for GasReportArgs {
+    type Output = ();
+
+    fn run(self) -> eyre::Result<Self::Output> {
+        RuntimeOrHandle::new().block_on(self.report())
+    }
+}
+
+impl GasReportArgs {
+    async fn report(self) -> eyre::Result<()> {
+        let (config, evm_opts) = self.load_config_and_evm_opts_emit_warnings()?;
+        let rpc_url = evm_opts
+            .fork_url
+            .clone()
+            .ok_or_else(|| eyre::eyre!("An RPC endpoint is required, see `--rpc-url`"))?;
+        let provider = get_http_provider(&rpc_url);
+
+        // the analyzed transactions by block, `None` means all transactions of the block
+        let mut blocks: BTreeMap<u64, Option<HashSet<H256>>> = BTreeMap::new();
+        if let Some(path) = &self.txs {
+            for hash in read_tx_hashes(path)? {
+                let tx = provider
+                    .get_transaction(hash)
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("Transaction {hash:?} not found"))?;
+                let block = tx
+                    .block_number
+                    .ok_or_else(|| eyre::eyre!("Transaction {hash:?} is still pending"))?;
+                blocks
+                    .entry(block.as_u64())
+                    .or_insert_with(|| Some(HashSet::new()))
+                    .as_mut()
+                    .expect("only transaction hashes are collected")
+                    .insert(hash);
+            }
+        } else if let (Some(from), Some(to)) = (self.from_block, self.to_block) {
+            blocks.extend((from..=to).map(|block| (block, None)));
+        }
+
+        // the transactions of a single block are replayed on the same fork
+        let fork_block = match blocks.keys().collect::<Vec<_>>()[..] {
+            [block] => Some(block.saturating_sub(1)),
+            _ => None,
+        };
+
+        let mut traces = Vec::new();
+        for (block, selected) in blocks {
+            let transactions = provider
+                .get_block_with_txs(block)
+                .await?
+                .ok_or_else(|| eyre::eyre!("Block {block} not found"))?
+                .transactions;
+            traces.extend(
+                replay_block(&config, &evm_opts, block, transactions, selected.as_ref()).await?,
+            );
+        }
+
+        let project = self.opts.project()?;
+        let output = if self.json {
+            compile::suppress_compile(&project)
+        } else {
+            compile::compile(&project, false, false)
+        }?;
+        let known_contracts = output
+            .with_stripped_file_prefixes(project.root())
+            .into_artifacts()
+            .filter_map(|(id, artifact)| {
+                let CompactContractBytecode { abi, deployed_bytecode, .. } =
+                    artifact.into_contract_bytecode();
+                let code = deployed_bytecode?.bytecode?.object.into_bytes()?;
+                Some((id, (abi?, code.to_vec())))
+            })
+            .collect::<BTreeMap<ArtifactId, (Abi, Vec<u8>)>>();
+
+        let local_identifier = LocalTraceIdentifier::new(&known_contracts);
+        let remote_chain_id = evm_opts.get_remote_chain_id();
+        let etherscan_identifier = EtherscanIdentifier::new(
+            remote_chain_id,
+            config.get_etherscan_api_key(remote_chain_id),
+            remote_chain_id.and_then(Config::foundry_etherscan_chain_cache_dir),
+            Duration::from_secs(24 * 60 * 60),
+            config.offline,
+        );
+        let mut decoder = CallTraceDecoderBuilder::new().build();
+        decoder.add_signature_identifier(SignaturesIdentifier::new(
+            Config::foundry_cache_dir(),
+            config.offline,
+        )?);
+
+        let mut report = GasReport::new(config.gas_reports.clone())
+            .with_header(report_header(&config, known_contracts.keys()))
+            .with_report_upgrades(config.report_upgrades)
+            .with_streaming(config.gas_reports_streaming)
+            .with_max_samples_per_function(config.gas_reports_max_samples)
+            .with_relative_coloring(config.gas_reports_relative_coloring)
+            .with_memory_gas_column(config.gas_reports_memory_gas)
+            .with_flag_gas_griefing(config.gas_reports_flag_griefing)
+            .with_noise_floor(config.gas_reports_noise_floor.into())
+            .with_exclude_paths(&config.gas_report_exclude_paths)
+            .with_merge_fork_contexts(self.merge_fork_contexts)
+            .with_fork_block(fork_block)
+            .with_signatures(known_signatures(known_contracts.values().map(|(abi, _)| abi)))
+            .with_abi_contracts(known_contracts.keys().map(|id| id.identifier()));
+        report
+            .analyze_traces(
+                &mut traces,
+                &mut decoder,
+                &[&local_identifier, &etherscan_identifier],
+            )
+            .await;
+        let report = report.finalize();
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            println!("{report}");
+        }
+        Ok(())
+    }
+}
+
+/// Returns the header of the gas reports of the `contracts` that are built and run with the
+/// `config`
+pub(crate) fn report_header<'a>(
+    config: &Config,
+    contracts: impl IntoIterator<Item = &'a ArtifactId>,
+) -> GasReportHeader {
+    let solc_versions = contracts
+        .into_iter()
+        .map(|id| id.version.to_string())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    GasReportHeader {
+        forge_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        profile: Some(config.profile.to_string()),
+        solc_versions,
+        optimizer: Some(config.optimizer),
+        optimizer_runs: config.optimizer.then(|| config.optimizer_runs),
+        via_ir: Some(config.via_ir),
+        timestamp: SystemTime::now().duration_since(UNIX_EPOCH).ok().map(|time| time.as_secs()),
+        exclude_paths: config.gas_report_exclude_paths.clone(),
+        ..Default::default()
+    }
+}
+
+/// Returns the signatures of the functions of the `abis` and of the cached signature lookups by
+/// their selector, which the calls that could not be decoded are reported as
+pub(crate) fn known_signatures<'a>(
+    abis: impl IntoIterator<Item = &'a Abi>,
+) -> BTreeMap<String, String> {
+    let mut signatures = Config::foundry_cache_dir()
+        .and_then(|cache_dir| CachedSignatures::read(&cache_dir).ok())
+        .map(|cached| cached.functions)
+        .unwrap_or_default();
+    for func in abis.into_iter().flat_map(|abi| abi.functions()) {
+        signatures.insert(format!("0x{}", hex::encode(func.short_signature())), func.signature());
+    }
+    signatures
+}
+
+/// Reads the transaction hashes from the file at `path`, one per line, empty lines and lines
+/// starting with `#` are skipped
+fn read_tx_hashes(path: &Path) -> eyre::Result<Vec<H256>> {
+    fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            H256::from_str(line).wrap_err_with(|| format!("Invalid transaction hash `{line}`"))
+        })
+        .collect()
+}
+
+/// Executes the `transactions` of the `block` on a fork of the previous block and returns the
+/// traces of the `selected` ones, or of all if `None`
+async fn replay_block(
+    config: &Config,
+    evm_opts: &EvmOpts,
+    block: u64,
+    transactions: Vec<Transaction>,
+    selected: Option<&HashSet<H256>>,
+) -> eyre::Result<Vec<(TraceKind, CallTraceArena)>> {
+    let mut evm_opts = evm_opts.clone();
+    evm_opts.fork_block_number = Some(block.saturating_sub(1));
+    let env = evm_opts.evm_env().await;
+    let db = Backend::spawn(evm_opts.get_fork(config, env.clone()));
+    let mut executor = ExecutorBuilder::default()
+        .with_config(env)
+        .with_cheatcodes(CheatsConfig::new(config, &evm_opts))
+        .with_spec(crate::utils::evm_spec(&config.evm_version))
+        .set_access_list_savings(true)
+        .set_memory_gas(config.gas_reports_memory_gas)
+        .build(db);
+
+    let mut remaining = selected.map(HashSet::len);
+    let mut traces = Vec::new();
+    for tx in transactions {
+        if remaining == Some(0) {
+            break
+        }
+        let is_selected = selected.map(|selected| selected.contains(&tx.hash)).unwrap_or(true);
+        executor.set_tracing(is_selected).set_gas_limit(tx.gas);
+        // prewarm the accounts and slots of the access list like the original transaction did
+        executor.env_mut().tx.access_list = tx
+            .access_list
+            .as_ref()
+            .map(|access_list| {
+                access_list
+                    .0
+                    .iter()
+                    .map(|item| {
+                        let slots = item
+                            .storage_keys
+                            .iter()
+                            .map(|key| U256::from_big_endian(key.as_bytes()))
+                            .collect();
+                        (item.address, slots)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let trace = if let Some(to) = tx.to {
+            executor.call_raw_committing(tx.from, to, tx.input.0, tx.value)?.traces
+        } else {
+            executor.deploy(tx.from, tx.input.0, tx.value, None).ok().and_then(|res| res.traces)
+        };
+
+        if is_selected {
+            remaining = remaining.map(|remaining| remaining - 1);
+            traces.extend(trace.map(|trace| (TraceKind::Execution, trace)));
+        }
+    }
+    Ok(traces)
+}
@@ -47,6 +47,8 @@ pub mod debug;
 pub mod flatten;
 pub mod fmt;
 pub mod fourbyte;
+pub mod gas_history;
+pub mod gas_report;
 pub mod init;
 pub mod inspect;
 pub mod install;
@@ -2,8 +2,16 @@
 
 use crate::cmd::{Cmd, LoadConfig};
 use clap::{Parser, ValueHint};
-use foundry_config::impl_figment_convert_basic;
-use std::path::PathBuf;
+use ethers::solc::remappings::Remapping;
+use foundry_common::fs;
+use foundry_config::{impl_figment_convert_basic, Config};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// Matches the path of `import "..."`, `import "..." as X` and `import ... from "..."` statements
+static RE_IMPORT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"import\s+(?:[^;"']*?\s+from\s+)?["']([^"']+)["']"#).unwrap());
 
 /// Command to list remappings
 #[derive(Debug, Clone, Parser)]
@@ -15,6 +23,11 @@ pub struct RemappingArgs {
         value_name = "PATH"
     )]
     root: Option<PathBuf>,
+
+    /// Check that the target of every remapping exists and that every import of the sources
+    /// resolves, exits with an error otherwise.
+    #[clap(long)]
+    check: bool,
 }
 impl_figment_convert_basic!(RemappingArgs);
 
@@ -23,7 +36,86 @@ impl Cmd for RemappingArgs {
 
     fn run(self) -> eyre::Result<Self::Output> {
         let config = self.load_config_emit_warnings();
+        if self.check {
+            return check_remappings(&config)
+        }
         config.remappings.iter().for_each(|x| println!("{x}"));
         Ok(())
     }
 }
+
+/// Checks that all remapping targets exist and that all imports of the project's sources resolve
+fn check_remappings(config: &Config) -> eyre::Result<()> {
+    let root = &config.__root.0;
+    let remappings = config.get_all_remappings();
+    let mut problems = 0usize;
+
+    for remapping in remappings.iter() {
+        if !Path::new(&remapping.path).exists() {
+            eprintln!(
+                "Remapping `{}` points to `{}`, which does not exist",
+                remapping.name, remapping.path
+            );
+            problems += 1;
+        }
+    }
+
+    for file in ethers::solc::utils::source_files(&config.src) {
+        let content = fs::read_to_string(&file)?;
+        for import in RE_IMPORT.captures_iter(&content).filter_map(|cap| cap.get(1)) {
+            let import = import.as_str();
+            if resolve_import(config, &remappings, &file, import).is_none() {
+                let file = file.strip_prefix(root).unwrap_or(&file);
+                eprintln!("{}: cannot resolve import `{import}`", file.display());
+                problems += 1;
+            }
+        }
+    }
+
+    if problems > 0 {
+        eyre::bail!("Found {problems} problems with the remappings")
+    }
+    println!("All remappings and imports resolve.");
+    Ok(())
+}
+
+/// Resolves the `import` of the source `file` the way solc does and returns the imported file, if
+/// it exists.
+///
+/// The remapping with the longest matching name is applied, context-scoped remappings only apply
+/// to files within their context. Imports that are not remapped are looked up relative to the
+/// project root and the library folders.
+fn resolve_import(
+    config: &Config,
+    remappings: &[Remapping],
+    file: &Path,
+    import: &str,
+) -> Option<PathBuf> {
+    let existing = |path: PathBuf| if path.exists() { Some(path) } else { None };
+
+    if import.starts_with("./") || import.starts_with("../") {
+        return existing(file.parent()?.join(import))
+    }
+
+    let remapped = remappings
+        .iter()
+        .filter_map(|remapping| match remapping.name.split_once(':') {
+            Some((context, name)) if file.starts_with(context) => {
+                Some((context.len(), name, remapping))
+            }
+            Some(_) => None,
+            None => Some((0, remapping.name.as_str(), remapping)),
+        })
+        .filter(|(_, name, _)| import.starts_with(name))
+        .max_by_key(|(context, name, _)| (*context, name.len()))
+        .map(|(_, name, remapping)| {
+            PathBuf::from(format!("{}{}", remapping.path, &import[name.len()..]))
+        });
+    if let Some(path) = remapped {
+        return existing(path)
+    }
+
+    std::iter::once(config.__root.0.join(import))
+        .chain(config.libs.iter().map(|lib| config.__root.0.join(lib).join(import)))
+        .find_map(existing)
+}
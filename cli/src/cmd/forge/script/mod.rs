@@ -24,7 +24,7 @@ use ethers::{
 use forge::{
     debug::DebugArena,
     decode::decode_console_logs,
-    executor::{opts::EvmOpts, Backend},
+    executor::{inspector::MAX_DEBUG_STEPS, opts::EvmOpts, Backend},
     trace::{
         identifier::{EtherscanIdentifier, LocalTraceIdentifier, SignaturesIdentifier},
         CallTraceArena, CallTraceDecoder, CallTraceDecoderBuilder, TraceKind,
@@ -36,7 +36,7 @@ use foundry_utils::{encode_args, format_token, IntoFunction};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap, VecDeque},
-    path::PathBuf,
+    path::{Path, PathBuf},
     time::Duration,
 };
 use yansi::Paint;
@@ -48,13 +48,13 @@ mod runner;
 use runner::ScriptRunner;
 
 mod broadcast;
-use ui::{TUIExitReason, Tui, Ui};
+use ui::{Breakpoint, SourceLocation, TUIExitReason, Tui, Ui};
 
 mod cmd;
 mod executor;
 mod receipts;
 mod sequence;
-pub use sequence::TransactionWithMetadata;
+pub use sequence::{ScriptSequence, TransactionWithMetadata};
 
 // Loads project's figment and merges the build cli arguments into it
 foundry_config::impl_figment_convert!(ScriptArgs, opts, evm_opts);
@@ -122,6 +122,17 @@ pub struct ScriptArgs {
     #[clap(long, help = "Open the script in the debugger. Takes precedence over broadcast.")]
     pub debug: bool,
 
+    /// Set a breakpoint on a source line in the debugger, e.g. `src/Counter.sol:12`.
+    ///
+    /// Jump to the next and previous breakpoint with `b` and `B`.
+    #[clap(
+        long = "breakpoint",
+        requires = "debug",
+        number_of_values = 1,
+        value_name = "FILE:LINE"
+    )]
+    pub breakpoints: Vec<Breakpoint>,
+
     #[clap(
         long,
         help = "Makes sure a transaction is sent, only after its previous one has been confirmed and succeeded."
@@ -133,8 +144,7 @@ pub struct ScriptArgs {
 
     #[clap(
         long,
-        help = "If it finds a matching broadcast log, it tries to verify every contract found in the receipts.",
-        requires = "etherscan-api-key"
+        help = "If it finds a matching broadcast log, it tries to verify every contract found in the receipts."
     )]
     pub verify: bool,
 
@@ -163,18 +173,23 @@ impl ScriptArgs {
         result: &mut ScriptResult,
         known_contracts: &BTreeMap<ArtifactId, (Abi, Vec<u8>)>,
     ) -> eyre::Result<CallTraceDecoder> {
+        let remote_chain_id = script_config.evm_opts.get_remote_chain_id();
         let etherscan_identifier = EtherscanIdentifier::new(
-            script_config.evm_opts.get_remote_chain_id(),
-            script_config.config.etherscan_api_key.clone(),
+            remote_chain_id,
+            script_config.config.get_etherscan_api_key(remote_chain_id),
             Config::foundry_etherscan_chain_cache_dir(script_config.evm_opts.get_chain_id()),
             Duration::from_secs(24 * 60 * 60),
+            script_config.config.offline,
         );
 
         let local_identifier = LocalTraceIdentifier::new(known_contracts);
         let mut decoder =
             CallTraceDecoderBuilder::new().with_labels(result.labeled_addresses.clone()).build();
 
-        decoder.add_signature_identifier(SignaturesIdentifier::new(Config::foundry_cache_dir())?);
+        decoder.add_signature_identifier(SignaturesIdentifier::new(
+            Config::foundry_cache_dir(),
+            script_config.config.offline,
+        )?);
 
         for (_, trace) in &mut result.traces {
             decoder.identify(trace, &local_identifier);
@@ -378,24 +393,67 @@ impl ScriptArgs {
         project: Project,
         highlevel_known_contracts: BTreeMap<ArtifactId, ContractBytecodeSome>,
     ) -> eyre::Result<()> {
+        let breakpoints = self.resolve_breakpoints(&sources)?;
         let (sources, artifacts) =
             filter_sources_and_artifacts(&self.path, sources, highlevel_known_contracts, project)?;
-        let flattened = result
+
+        let source_lines = ui::source_lines(&artifacts, &sources);
+        for (breakpoint, location) in self.breakpoints.iter().zip(&breakpoints) {
+            if !source_lines.contains(location) {
+                eprintln!(
+                    "{}",
+                    Paint::yellow(format!(
+                        "Warning: breakpoint `{breakpoint}` does not map to any instruction and will never be hit."
+                    ))
+                );
+            }
+        }
+
+        let arena = result
             .debug
-            .and_then(|arena| arena.last().map(|arena| arena.flatten(0)))
+            .and_then(|mut arenas| arenas.pop())
             .expect("We should have collected debug information");
+        if arena.truncated {
+            eprintln!(
+                "{}",
+                Paint::yellow(format!(
+                    "Warning: the execution exceeded {MAX_DEBUG_STEPS} steps, only the first {MAX_DEBUG_STEPS} steps can be debugged."
+                ))
+            );
+        }
+        let flattened = arena.flatten(0);
         let identified_contracts = decoder
             .contracts
             .iter()
             .map(|(addr, identifier)| (*addr, get_contract_name(identifier).to_string()))
             .collect();
 
-        let tui = Tui::new(flattened, 0, identified_contracts, artifacts, sources)?;
+        let tui = Tui::new(flattened, 0, identified_contracts, artifacts, sources)?
+            .with_breakpoints(breakpoints);
         match tui.start().expect("Failed to start tui") {
             TUIExitReason::CharExit => Ok(()),
         }
     }
 
+    /// Resolves the file of every breakpoint to its source index, `sources` maps the source
+    /// indices to the paths of the sources
+    fn resolve_breakpoints(
+        &self,
+        sources: &BTreeMap<u32, String>,
+    ) -> eyre::Result<Vec<SourceLocation>> {
+        self.breakpoints
+            .iter()
+            .map(|breakpoint| {
+                let file = breakpoint.file.strip_prefix("./").unwrap_or(&breakpoint.file);
+                sources
+                    .iter()
+                    .find(|(_, path)| Path::new(path).ends_with(file))
+                    .map(|(source, _)| SourceLocation { source: *source, line: breakpoint.line })
+                    .ok_or_else(|| eyre::eyre!("No source file matches breakpoint `{breakpoint}`"))
+            })
+            .collect()
+    }
+
     pub fn get_method_and_calldata(&self, abi: &Abi) -> eyre::Result<(Function, Bytes)> {
         let (func, data) = match self.sig.strip_prefix("0x") {
             Some(calldata) => (
@@ -521,7 +579,8 @@ pub struct ScriptConfig {
 pub struct VerifyBundle {
     pub num_of_optimizations: Option<usize>,
     pub known_contracts: BTreeMap<ArtifactId, (Abi, Vec<u8>)>,
-    pub etherscan_key: Option<String>,
+    /// The config the Etherscan API key of the chain of the broadcast is picked from
+    pub config: Config,
     pub project_paths: ProjectPathsArgs,
     pub retry: RetryArgs,
 }
@@ -552,7 +611,7 @@ impl VerifyBundle {
         VerifyBundle {
             num_of_optimizations,
             known_contracts,
-            etherscan_key: config.etherscan_api_key.clone(),
+            config: config.clone(),
             project_paths,
             retry,
         }
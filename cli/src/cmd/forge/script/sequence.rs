@@ -43,7 +43,7 @@ impl ScriptSequence {
         config: &Config,
         chain_id: u64,
     ) -> eyre::Result<Self> {
-        let path = ScriptSequence::get_path(&config.broadcast, sig, target, chain_id)?;
+        let path = ScriptSequence::get_path(&config.broadcast, sig, &target.source, chain_id)?;
 
         Ok(ScriptSequence {
             transactions,
@@ -66,7 +66,7 @@ impl ScriptSequence {
         target: &ArtifactId,
         chain_id: u64,
     ) -> eyre::Result<Self> {
-        let path = ScriptSequence::get_path(&config.broadcast, sig, target, chain_id)?;
+        let path = ScriptSequence::get_path(&config.broadcast, sig, &target.source, chain_id)?;
         Ok(ethers::solc::utils::read_json_file(path)?)
     }
 
@@ -127,12 +127,12 @@ impl ScriptSequence {
     pub fn get_path(
         out: &Path,
         sig: &str,
-        target: &ArtifactId,
+        source: &Path,
         chain_id: u64,
     ) -> eyre::Result<PathBuf> {
         let mut out = out.to_path_buf();
 
-        let target_fname = target.source.file_name().wrap_err("No filename.")?;
+        let target_fname = source.file_name().wrap_err("No filename.")?;
         out.push(target_fname);
         out.push(chain_id.to_string());
 
@@ -147,77 +147,76 @@ impl ScriptSequence {
     /// created contract on etherscan.
     pub async fn verify_contracts(&mut self, verify: VerifyBundle, chain: u64) -> eyre::Result<()> {
         trace!(?chain, "verifying {} contracts", verify.known_contracts.len());
-        if let Some(etherscan_key) = &verify.etherscan_key {
-            let mut future_verifications = vec![];
+        let etherscan_key = verify.config.require_etherscan_api_key(Some(chain))?;
+        let mut future_verifications = vec![];
 
-            // Make sure the receipts have the right order first.
-            self.sort_receipts();
+        // Make sure the receipts have the right order first.
+        self.sort_receipts();
 
-            for (receipt, tx) in self.receipts.iter_mut().zip(self.transactions.iter()) {
-                let mut create2_offset = 0;
+        for (receipt, tx) in self.receipts.iter_mut().zip(self.transactions.iter()) {
+            let mut create2_offset = 0;
 
-                if tx.is_create2() {
-                    receipt.contract_address = tx.contract_address;
-                    create2_offset = 32;
-                }
+            if tx.is_create2() {
+                receipt.contract_address = tx.contract_address;
+                create2_offset = 32;
+            }
 
-                if let (Some(contract_address), Some(data)) =
-                    (receipt.contract_address, tx.typed_tx().data())
-                {
-                    for (artifact, (_contract, bytecode)) in &verify.known_contracts {
-                        // If it's a CREATE2, the tx.data comes with a 32-byte salt in the beginning
-                        // of the transaction
-                        if data.0.split_at(create2_offset).1.starts_with(bytecode) {
-                            let constructor_args =
-                                data.0.split_at(create2_offset + bytecode.len()).1.to_vec();
-
-                            let contract = ContractInfo {
-                                path: Some(
-                                    artifact
-                                        .source
-                                        .to_str()
-                                        .expect("There should be an artifact.")
-                                        .to_string(),
-                                ),
-                                name: artifact.name.clone(),
-                            };
-
-                            // We strip the build metadadata information, since it can lead to
-                            // etherscan not identifying it correctly. eg:
-                            // `v0.8.10+commit.fc410830.Linux.gcc` != `v0.8.10+commit.fc410830`
-                            let version = Version::new(
-                                artifact.version.major,
-                                artifact.version.minor,
-                                artifact.version.patch,
-                            );
-
-                            let verify = verify::VerifyArgs {
-                                address: contract_address,
-                                contract,
-                                compiler_version: Some(version.to_string()),
-                                constructor_args: Some(hex::encode(&constructor_args)),
-                                num_of_optimizations: verify.num_of_optimizations,
-                                chain: chain.into(),
-                                etherscan_key: etherscan_key.clone(),
-                                project_paths: verify.project_paths.clone(),
-                                flatten: false,
-                                force: false,
-                                watch: true,
-                                retry: verify.retry.clone(),
-                                libraries: self.libraries.clone(),
-                                root: None,
-                            };
-
-                            future_verifications.push(verify.run());
-                        }
+            if let (Some(contract_address), Some(data)) =
+                (receipt.contract_address, tx.typed_tx().data())
+            {
+                for (artifact, (_contract, bytecode)) in &verify.known_contracts {
+                    // If it's a CREATE2, the tx.data comes with a 32-byte salt in the beginning
+                    // of the transaction
+                    if data.0.split_at(create2_offset).1.starts_with(bytecode) {
+                        let constructor_args =
+                            data.0.split_at(create2_offset + bytecode.len()).1.to_vec();
+
+                        let contract = ContractInfo {
+                            path: Some(
+                                artifact
+                                    .source
+                                    .to_str()
+                                    .expect("There should be an artifact.")
+                                    .to_string(),
+                            ),
+                            name: artifact.name.clone(),
+                        };
+
+                        // We strip the build metadadata information, since it can lead to
+                        // etherscan not identifying it correctly. eg:
+                        // `v0.8.10+commit.fc410830.Linux.gcc` != `v0.8.10+commit.fc410830`
+                        let version = Version::new(
+                            artifact.version.major,
+                            artifact.version.minor,
+                            artifact.version.patch,
+                        );
+
+                        let verify = verify::VerifyArgs {
+                            address: contract_address,
+                            contract,
+                            compiler_version: Some(version.to_string()),
+                            constructor_args: Some(hex::encode(&constructor_args)),
+                            num_of_optimizations: verify.num_of_optimizations,
+                            chain: chain.into(),
+                            etherscan_key: Some(etherscan_key.clone()),
+                            project_paths: verify.project_paths.clone(),
+                            flatten: false,
+                            force: false,
+                            watch: true,
+                            retry: verify.retry.clone(),
+                            libraries: self.libraries.clone(),
+                            root: None,
+                        };
+
+                        future_verifications.push(verify.run());
                     }
                 }
             }
+        }
 
-            println!("##\nStart Contract Verification");
-            for verification in future_verifications {
-                verification.await?;
-            }
+        println!("##\nStart Contract Verification");
+        for verification in future_verifications {
+            verification.await?;
         }
 
         Ok(())
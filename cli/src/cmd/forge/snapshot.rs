@@ -18,7 +18,6 @@ use std::{
     collections::HashMap,
     fmt::Write,
     fs,
-    io::{self, BufRead},
     path::{Path, PathBuf},
     str::FromStr,
 };
@@ -256,16 +255,18 @@ impl FromStr for SnapshotEntry {
 /// Reads a list of snapshot entries from a snapshot file
 fn read_snapshot(path: impl AsRef<Path>) -> eyre::Result<Vec<SnapshotEntry>> {
     let path = path.as_ref();
-    let mut entries = Vec::new();
-    for line in io::BufReader::new(
-        fs::File::open(path)
-            .wrap_err(format!("failed to read snapshot file \"{}\"", path.display()))?,
-    )
-    .lines()
-    {
-        entries.push(SnapshotEntry::from_str(line?.as_str()).map_err(|err| eyre::eyre!("{err}"))?);
-    }
-    Ok(entries)
+    let content = fs::read_to_string(path)
+        .wrap_err(format!("failed to read snapshot file \"{}\"", path.display()))?;
+    parse_snapshot(&content)
+}
+
+/// Parses the entries of the `content` of a snapshot file, lines starting with `#` are skipped
+pub(crate) fn parse_snapshot(content: &str) -> eyre::Result<Vec<SnapshotEntry>> {
+    content
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| SnapshotEntry::from_str(line).map_err(|err| eyre::eyre!("{err}")))
+        .collect()
 }
 
 /// Writes a series of tests to a snapshot file
@@ -430,6 +431,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn skips_snapshot_comments() {
+        let content = "# commit: 1f3c2b0 timestamp: 1665000000\nTest:deposit() (gas: 7222)\n\n";
+        let entries = parse_snapshot(content).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].gas_used, TestKindReport::Standard { gas: 7222 });
+    }
+
     #[test]
     fn can_parse_fuzz_snapshot_entry() {
         let s = "Test:deposit() (runs: 256, μ: 100, ~:200)";
@@ -3,7 +3,11 @@ use clap::Parser;
 use ethers::solc::FileFilter;
 use forge::TestFilter;
 use foundry_config::Config;
-use std::{fmt, path::Path, str::FromStr};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 /// The filter to use during testing
 ///
@@ -70,6 +74,21 @@ pub struct Filter {
         value_name = "GLOB"
     )]
     pub path_pattern_inverse: Option<GlobMatcher>,
+
+    /// Only run tests in test files whose path, relative to the project root, does not match the
+    /// specified glob pattern.
+    #[clap(
+        long = "no-match-contract-source",
+        visible_alias = "nmcs",
+        conflicts_with = "pattern",
+        value_name = "GLOB"
+    )]
+    pub contract_source_pattern_inverse: Option<GlobMatcher>,
+
+    /// The project root, which is stripped from the source paths that
+    /// `--no-match-contract-source` is matched against
+    #[clap(skip)]
+    pub root: PathBuf,
 }
 
 impl Filter {
@@ -95,6 +114,11 @@ impl Filter {
         if filter.path_pattern_inverse.is_none() {
             filter.path_pattern_inverse = config.path_pattern_inverse.clone().map(Into::into);
         }
+        if filter.contract_source_pattern_inverse.is_none() {
+            filter.contract_source_pattern_inverse =
+                config.contract_source_pattern_inverse.clone().map(Into::into);
+        }
+        filter.root = config.__root.0.clone();
         filter
     }
 }
@@ -109,6 +133,10 @@ impl fmt::Debug for Filter {
             .field("no-match-contract", &self.contract_pattern_inverse.as_ref().map(|r| r.as_str()))
             .field("match-path", &self.path_pattern.as_ref().map(|g| g.as_str()))
             .field("no-match-path", &self.path_pattern_inverse.as_ref().map(|g| g.as_str()))
+            .field(
+                "no-match-contract-source",
+                &self.contract_source_pattern_inverse.as_ref().map(|g| g.as_str()),
+            )
             .finish_non_exhaustive()
     }
 }
@@ -169,6 +197,11 @@ impl TestFilter for Filter {
         if let Some(ref glob) = self.path_pattern_inverse {
             ok &= !glob.is_match(path);
         }
+        if let Some(ref glob) = self.contract_source_pattern_inverse {
+            // the sources are absolute paths, the pattern may be relative to the project root
+            let relative = Path::new(path).strip_prefix(&self.root).ok().and_then(Path::to_str);
+            ok &= !glob.is_match(path) && !relative.map_or(false, |path| glob.is_match(path));
+        }
         ok
     }
 }
@@ -197,6 +230,9 @@ impl fmt::Display for Filter {
         if let Some(ref p) = self.path_pattern_inverse {
             patterns.push(format!("\tno-match-path: `{}`", p.as_str()));
         }
+        if let Some(ref p) = self.contract_source_pattern_inverse {
+            patterns.push(format!("\tno-match-contract-source: `{}`", p.as_str()));
+        }
         write!(f, "{}", patterns.join("\n"))
     }
 }
@@ -263,4 +299,16 @@ mod tests {
         assert!(matcher.is_match("test/Contract.sol"));
         assert!(matcher.is_match("./test/Contract.sol"));
     }
+
+    #[test]
+    fn can_match_contract_source_relative_to_root() {
+        let filter = Filter {
+            contract_source_pattern_inverse: Some("src/legacy/**".parse().unwrap()),
+            root: PathBuf::from("/project"),
+            ..Filter::parse_from(["forge"])
+        };
+        assert!(!filter.matches_path("/project/src/legacy/LegacyTest.t.sol"));
+        assert!(!filter.matches_path("src/legacy/LegacyTest.t.sol"));
+        assert!(filter.matches_path("/project/src/ATest.t.sol"));
+    }
 }
@@ -1,7 +1,12 @@
 //! Test command
 use crate::{
     cmd::{
-        forge::{build::CoreBuildArgs, debug::DebugArgs, watch::WatchArgs},
+        forge::{
+            build::CoreBuildArgs,
+            debug::DebugArgs,
+            gas_report::{known_signatures, report_header},
+            watch::WatchArgs,
+        },
         Cmd, LoadConfig,
     },
     compile,
@@ -9,27 +14,50 @@ use crate::{
     suggestions, utils,
 };
 use cast::fuzz::CounterExample;
-use clap::{AppSettings, Parser};
-use ethers::{solc::utils::RuntimeOrHandle, types::U256};
+use clap::{AppSettings, Parser, ValueHint};
+use comfy_table::{
+    modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, Attribute, Cell, Color, Table,
+};
+use ethers::{
+    abi::Abi,
+    providers::Middleware,
+    solc::{utils::RuntimeOrHandle, ArtifactId},
+    types::{H256, U256},
+};
 use forge::{
     decode::decode_console_logs,
     executor::inspector::CheatsConfig,
+    fuzz::invariant::InvariantStats,
+    fuzz_inputs::{self, FuzzInputs},
+    gas_budget::parse_gas_budgets,
     gas_report::GasReport,
+    gas_source_map::GasSourceMap,
+    memory_report::{MemoryReport, TestMemory},
     result::{SuiteResult, TestKind, TestResult},
+    test_history::TestHistory,
     trace::{
         identifier::{EtherscanIdentifier, LocalTraceIdentifier},
         CallTraceDecoderBuilder, TraceKind,
     },
+    untested::{CalledFunctions, UntestedFunction},
     MultiContractRunner, MultiContractRunnerBuilder, TestOptions,
 };
-use foundry_common::evm::EvmArgs;
-use foundry_config::{figment, Config};
+use foundry_common::{evm::EvmArgs, fs, get_http_provider};
+use foundry_config::{figment, Config, TestOutputOrder};
 use regex::Regex;
-use std::{collections::BTreeMap, path::PathBuf, sync::mpsc::channel, thread, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::mpsc::channel,
+    thread,
+    time::Duration,
+};
 use tracing::trace;
+use ui::Breakpoint;
 use watchexec::config::{InitConfig, RuntimeConfig};
 use yansi::Paint;
 mod filter;
+mod mutate;
 pub use filter::Filter;
 use foundry_config::figment::{
     value::{Dict, Map},
@@ -63,10 +91,159 @@ pub struct TestArgs {
     #[clap(long, value_name = "TEST_FUNCTION")]
     debug: Option<Regex>,
 
+    /// Set a breakpoint on a source line in the debugger, e.g. `src/Counter.sol:12`.
+    ///
+    /// Jump to the next and previous breakpoint with `b` and `B`.
+    #[clap(
+        long = "breakpoint",
+        requires = "debug",
+        number_of_values = 1,
+        value_name = "FILE:LINE"
+    )]
+    breakpoints: Vec<Breakpoint>,
+
     /// Print a gas report.
     #[clap(long, env = "FORGE_GAS_REPORT")]
     gas_report: bool,
 
+    /// Attach metadata to the gas report, e.g. the git commit or solc version of the build.
+    ///
+    /// Can be repeated: --gas-report-metadata commit=abc123 --gas-report-metadata branch=main
+    #[clap(
+        long,
+        requires = "gas-report",
+        parse(try_from_str = utils::parse_key_value),
+        value_name = "KEY=VALUE"
+    )]
+    gas_report_metadata: Vec<(String, String)>,
+
+    /// Save the traces that the gas report is built from to this file, to build reports from them
+    /// with `--gas-report-only` later.
+    #[clap(long, requires = "gas-report", value_hint = ValueHint::FilePath, value_name = "PATH")]
+    save_traces: Option<PathBuf>,
+
+    /// Print the gas report of the traces saved with `--save-traces` to this file, without
+    /// compiling and running the tests.
+    #[clap(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    gas_report_only: Option<PathBuf>,
+
+    /// Report the calls of a function that were executed on different forks together in the gas
+    /// report, instead of in one row per chain and block.
+    #[clap(long, requires = "gas-report")]
+    merge_fork_contexts: bool,
+
+    /// Leave out the functions with fewer calls than this from the gas report.
+    #[clap(long, requires = "gas-report", value_name = "N")]
+    gas_report_min_calls: Option<usize>,
+
+    /// Print a table comparing the gas used by the function with this signature in every contract
+    /// that implements it, e.g. an interface and its mocks.
+    ///
+    /// Can be repeated: --gas-report-compare "transfer(address,uint256)"
+    #[clap(long, requires = "gas-report", number_of_values = 1, value_name = "SIGNATURE")]
+    gas_report_compare: Vec<String>,
+
+    /// Print the functions of the gas report grouped into tiers by their mean gas instead of a
+    /// table per contract.
+    ///
+    /// The tiers are separated by the `gas_report_tiers` of the config.
+    #[clap(long, requires = "gas-report")]
+    gas_report_tiered: bool,
+
+    /// Show a breakdown of the gas of every function in the gas report into storage, memory,
+    /// calls, arithmetic, hashing and other instructions, as averages per call.
+    ///
+    /// The gas of calls includes the gas used by the called contracts.
+    #[clap(long, requires = "gas-report")]
+    gas_categories: bool,
+
+    /// Print the source lines of the functions that used the most gas in total with the gas that
+    /// the instructions of every line used, resolved with the source maps of the compiler.
+    ///
+    /// Lists this many functions, 3 if no number is given. The 5 most expensive lines of every
+    /// function are highlighted. The gas of calls is attributed to the lines of the called
+    /// contracts.
+    #[clap(
+        long,
+        value_name = "FUNCTIONS",
+        min_values = 0,
+        default_missing_value = "3",
+        requires = "gas-report"
+    )]
+    gas_source_map: Option<usize>,
+
+    /// Print the peak memory and the number of memory stores and loads of every test, and a table
+    /// of them after the tests.
+    ///
+    /// Fuzz tests report the lowest, mean and highest peak memory of their runs.
+    #[clap(long)]
+    memory_report: bool,
+
+    /// Warn about public and external functions of non-test contracts that are never called by
+    /// any test.
+    ///
+    /// `view` and `pure` functions are skipped unless `--include-view` is set.
+    #[clap(long)]
+    warn_untested: bool,
+
+    /// Include `view` and `pure` functions in the untested functions.
+    #[clap(long, requires = "warn-untested")]
+    include_view: bool,
+
+    /// Print the traces of all tests, not only of the failing ones.
+    ///
+    /// Combine it with `--match-test` to trace only the selected tests.
+    #[clap(long, help_heading = "DISPLAY OPTIONS")]
+    show_all_traces: bool,
+
+    /// Leave out the calls nested deeper than this in the printed traces, where the call of the
+    /// test has depth 0.
+    #[clap(long, value_name = "DEPTH", help_heading = "DISPLAY OPTIONS")]
+    max_trace_depth: Option<usize>,
+
+    /// The number of call frames that are printed of the traces of failing tests, starting with
+    /// the call of the test, deeper calls are left out.
+    ///
+    /// All call frames are printed if this is not set.
+    #[clap(long, value_name = "N", help_heading = "DISPLAY OPTIONS")]
+    stack_frames: Option<usize>,
+
+    /// Print all call frames of the traces of failing tests, overrides `--stack-frames`.
+    #[clap(long, help_heading = "DISPLAY OPTIONS")]
+    full_stack: bool,
+
+    /// Leave out the durations of the test suites.
+    ///
+    /// Together with `test_output_order = "alphabetical"` identical runs print identical output.
+    #[clap(long, help_heading = "DISPLAY OPTIONS")]
+    no_timings: bool,
+
+    /// Print how often the invariant tests called each targeted function, how many of the calls
+    /// reverted, which invariant was broken first and the longest sequence of calls in a run.
+    #[clap(long)]
+    invariant_stats: bool,
+
+    /// Run the tests against mutants of the sources, copies with a small change like `>` replaced
+    /// with `>=`, and report the share of the mutants that make a test fail.
+    ///
+    /// The mutations that are applied are selected in the `[mutation]` config.
+    #[clap(long, conflicts_with_all = &["debug", "list", "gas-report"])]
+    mutate: bool,
+
+    /// Overwrite the snapshots that `assertSnapshotEq` compares values with in `snapshots/`
+    /// instead of failing the tests whose values changed.
+    #[clap(long)]
+    update_snapshots: bool,
+
+    /// Run the test suites that failed in the last run first, and start the suites with the
+    /// shortest average duration first.
+    ///
+    /// The failed tests and the durations are recorded by runs with this flag in
+    /// `<cache>/last-failed.json` and `<cache>/test-durations.json`, tests whose order is based on
+    /// them are marked with `[from cache]`.
+    #[clap(long)]
+    smart_order: bool,
+
     /// Exit with code 0 even if a test fails.
     #[clap(long, env = "FORGE_ALLOW_FAILURE")]
     allow_failure: bool,
@@ -102,6 +279,36 @@ pub struct TestArgs {
         parse(try_from_str = utils::parse_u256)
     )]
     pub fuzz_seed: Option<U256>,
+
+    /// Run the fuzz tests with the inputs of this file before fuzzing them.
+    ///
+    /// Every line of the file is a JSON object that maps the parameter names of a fuzz test to
+    /// the values of its arguments. The counterexamples of failing fuzz tests are appended to
+    /// such files in `<cache>/fuzz/<contract>/<test>.ndjson`.
+    #[clap(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    pub fuzz_input_file: Option<PathBuf>,
+
+    /// Prefetch the accounts and storage slots of the fork that are listed in this file, and
+    /// write the ones accessed by this run to it.
+    ///
+    /// The state of the fork is fetched lazily, on its first access. Prefetching the state a
+    /// previous run accessed fetches it concurrently instead, while the tests start.
+    #[clap(long, value_hint = ValueHint::FilePath, value_name = "FILE")]
+    pub fork_prefetch: Option<PathBuf>,
+
+    /// Fetch state from the block with this hash over a remote endpoint, instead of from a block
+    /// number.
+    ///
+    /// The number of the block is looked up first and printed, to pass it to --fork-block-number
+    /// instead in future runs.
+    #[clap(
+        long,
+        requires = "fork-url",
+        conflicts_with = "fork-block-number",
+        value_name = "HASH",
+        help_heading = "EVM OPTIONS"
+    )]
+    pub fork_block_hash: Option<H256>,
 }
 
 impl TestArgs {
@@ -158,6 +365,26 @@ impl Cmd for TestArgs {
     }
 }
 
+/// Converts the `gas_report_tiers` of the config into the thresholds of the gas report tiers
+fn gas_report_tiers(tiers: &[u64]) -> Vec<U256> {
+    tiers.iter().copied().map(U256::from).collect()
+}
+
+/// Returns the number of the block with the `hash` on the chain of the `fork_url`
+fn fork_block_number(fork_url: &str, hash: H256) -> eyre::Result<u64> {
+    let provider = get_http_provider(fork_url);
+    let block = utils::block_on(provider.get_block(hash))?
+        .ok_or_else(|| eyre::eyre!("Block {hash:?} not found"))?;
+    let number = block
+        .number
+        .ok_or_else(|| eyre::eyre!("Block {hash:?} is still pending"))?
+        .as_u64();
+    eprintln!(
+        "Block {hash:?} is block number {number}, pass `--fork-block-number {number}` next time"
+    );
+    Ok(number)
+}
+
 /// The result of a single test
 #[derive(Debug, Clone)]
 pub struct Test {
@@ -242,7 +469,7 @@ impl TestOutcome {
             let term = if failures > 1 { "tests" } else { "test" };
             println!("Encountered {} failing {} in {}", failures, term, suite_name);
             for (name, result) in suite.failures() {
-                short_test_result(name, result);
+                short_test_result(name, result, false);
             }
             println!();
         }
@@ -262,20 +489,27 @@ impl TestOutcome {
             .fold(Duration::ZERO, |acc, SuiteResult { duration, .. }| acc + *duration)
     }
 
-    pub fn summary(&self) -> String {
+    /// Returns the result line of the tests, with their total duration if `timings` is set
+    pub fn summary(&self, timings: bool) -> String {
         let failed = self.failures().count();
         let result = if failed == 0 { Paint::green("ok") } else { Paint::red("FAILED") };
-        format!(
-            "Test result: {}. {} passed; {} failed; finished in {:.2?}",
+        let summary = format!(
+            "Test result: {}. {} passed; {} failed",
             result,
             self.successes().count(),
-            failed,
-            self.duration()
-        )
+            failed
+        );
+        if timings {
+            format!("{summary}; finished in {:.2?}", self.duration())
+        } else {
+            summary
+        }
     }
 }
 
-fn short_test_result(name: &str, result: &TestResult) {
+/// Prints the result line of a test, marked with `[from cache]` if its order is based on the
+/// history of previous runs
+fn short_test_result(name: &str, result: &TestResult, from_cache: bool) {
     let status = if result.success {
         Paint::green("[PASS]".to_string())
     } else {
@@ -304,13 +538,46 @@ fn short_test_result(name: &str, result: &TestResult) {
         Paint::red(format!("[FAIL. {reason}{counterexample}"))
     };
 
-    println!("{} {} {}", status, name, result.kind.report());
+    let from_cache = if from_cache { " [from cache]" } else { "" };
+    println!("{} {} {}{}", status, name, result.kind.report(), from_cache);
+}
+
+/// Prints the calls per targeted function, the first broken invariant and the longest sequence of
+/// calls of an invariant test
+fn print_invariant_stats(stats: &InvariantStats) {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
+    table.set_header(vec![
+        Cell::new("Function").add_attribute(Attribute::Bold).fg(Color::Blue),
+        Cell::new("Calls").add_attribute(Attribute::Bold).fg(Color::Blue),
+        Cell::new("Succeeded").add_attribute(Attribute::Bold).fg(Color::Green),
+        Cell::new("Reverted").add_attribute(Attribute::Bold).fg(Color::Red),
+    ]);
+    for (name, calls) in &stats.calls {
+        table.add_row(vec![
+            Cell::new(name),
+            Cell::new(calls.calls),
+            Cell::new(calls.calls - calls.reverts),
+            Cell::new(calls.reverts),
+        ]);
+    }
+    println!("{table}");
+    println!("Max depth: {}", stats.max_depth);
+    if let Some(invariant) = &stats.first_violation {
+        println!("First broken invariant: {invariant}");
+    }
 }
 
-pub fn custom_run(args: TestArgs) -> eyre::Result<TestOutcome> {
+pub fn custom_run(mut args: TestArgs) -> eyre::Result<TestOutcome> {
     // Merge all configs
     let (config, mut evm_opts) = args.load_config_and_evm_opts_emit_warnings()?;
 
+    if let (Some(hash), Some(fork_url)) = (args.fork_block_hash, &evm_opts.fork_url) {
+        let block_number = fork_block_number(fork_url, hash)?;
+        evm_opts.fork_block_number = Some(block_number);
+        args.evm_opts.fork_block_number = Some(block_number);
+    }
+
     let test_options = TestOptions {
         fuzz_runs: config.fuzz_runs,
         fuzz_max_local_rejects: config.fuzz_max_local_rejects,
@@ -322,6 +589,27 @@ pub fn custom_run(args: TestArgs) -> eyre::Result<TestOutcome> {
         invariant_call_override: config.invariant_call_override,
     };
 
+    if let Some(path) = &args.gas_report_only {
+        let traces = GasReport::from_arenas_file(path)?;
+        let fork_block =
+            evm_opts.fork_url.is_some().then(|| evm_opts.evm_env_blocking().block.number.as_u64());
+        let mut report = new_gas_report(&config, &args, fork_block, &BTreeMap::new());
+        report.analyze(&traces);
+        let mut report = report.finalize();
+        if let Some(min_calls) = args.gas_report_min_calls {
+            report = report.filter_by_calls(min_calls);
+        }
+        if args.gas_report_tiered {
+            println!("{}", report.render_tiered(&gas_report_tiers(&config.gas_report_tiers)));
+        } else {
+            println!("{report}");
+        }
+        return Ok(TestOutcome::new(BTreeMap::new(), args.allow_failure))
+    }
+
+    let fuzz_inputs = args.fuzz_input_file.as_ref().map(FuzzInputs::read).transpose()?;
+    let history = args.smart_order.then(|| TestHistory::read(&config.cache_path)).transpose()?;
+
     let mut filter = args.filter(&config);
 
     trace!(target: "forge::test", ?filter, "using filter");
@@ -337,14 +625,25 @@ pub fn custom_run(args: TestArgs) -> eyre::Result<TestOutcome> {
         compiler.compile(&project)
     }?;
 
+    if args.mutate {
+        let results = mutate::run(&config, &project, output, &filter, evm_opts, test_options)?;
+        return Ok(TestOutcome::new(results, args.allow_failure))
+    }
+
     // Determine print verbosity and executor verbosity
     let verbosity = evm_opts.verbosity;
-    if args.gas_report && evm_opts.verbosity < 3 {
+    // the gas report and the untested functions are built from the traces
+    if (args.gas_report || args.show_all_traces || args.warn_untested) && evm_opts.verbosity < 3 {
         evm_opts.verbosity = 3;
     }
 
     let env = evm_opts.evm_env_blocking();
 
+    // the source maps are read before the runner takes the compiler output
+    let gas_source_map = args
+        .gas_source_map
+        .map(|functions| (functions, GasSourceMap::new(output.clone(), &project.paths.root)));
+
     // Prepare the test builder
     let evm_spec = utils::evm_spec(&config.evm_version);
 
@@ -353,8 +652,19 @@ pub fn custom_run(args: TestArgs) -> eyre::Result<TestOutcome> {
         .evm_spec(evm_spec)
         .sender(evm_opts.sender)
         .with_fork(evm_opts.get_fork(&config, env.clone()))
-        .with_cheats_config(CheatsConfig::new(&config, &evm_opts))
+        .with_cheats_config(CheatsConfig {
+            update_snapshots: args.update_snapshots,
+            ..CheatsConfig::new(&config, &evm_opts)
+        })
         .with_test_options(test_options)
+        .with_fuzz_inputs(fuzz_inputs)
+        .with_fork_prefetch(args.fork_prefetch.clone())
+        .with_history(history)
+        .set_memory_report(args.memory_report)
+        .set_access_list_savings(args.gas_report)
+        .set_memory_gas(args.gas_report && config.gas_reports_memory_gas)
+        .set_gas_categories(args.gas_categories)
+        .set_gas_by_pc(gas_source_map.is_some())
         .build(project.paths.root, output, env, evm_opts)?;
 
     if args.debug.is_some() {
@@ -391,6 +701,7 @@ pub fn custom_run(args: TestArgs) -> eyre::Result<TestOutcome> {
                         sig,
                         args: Vec::new(),
                         debug: true,
+                        breakpoints: args.breakpoints,
                         opts: args.opts,
                         evm_opts: args.evm_opts,
                     };
@@ -407,17 +718,132 @@ pub fn custom_run(args: TestArgs) -> eyre::Result<TestOutcome> {
     } else if args.list {
         list(runner, filter, args.json)
     } else {
-        test(
-            config,
-            runner,
+        let fork_block =
+            runner.evm_opts.fork_url.is_some().then(|| runner.env.block.number.as_u64());
+        let report = new_gas_report(&config, &args, fork_block, &runner.known_contracts);
+        let output = TestOutputOptions {
             verbosity,
-            filter,
-            args.json,
-            args.allow_failure,
-            test_options,
-            args.gas_report,
-        )
+            json: args.json,
+            allow_failure: args.allow_failure,
+            memory_report: args.memory_report,
+            warn_untested: args.warn_untested,
+            include_view: args.include_view,
+            invariant_stats: args.invariant_stats,
+            show_all_traces: args.show_all_traces,
+            max_trace_depth: args.max_trace_depth,
+            stack_frames: args.stack_frames.filter(|_| !args.full_stack),
+            no_timings: args.no_timings,
+        };
+        let gas_report_options = GasReportOptions {
+            enabled: args.gas_report,
+            report,
+            min_calls: args.gas_report_min_calls,
+            compare: args.gas_report_compare,
+            tiered: args.gas_report_tiered,
+            source_map: gas_source_map,
+            save_traces: args.save_traces,
+        };
+        test(config, runner, filter, test_options, output, gas_report_options)
+    }
+}
+
+/// Returns the gas report that the calls of the tests are recorded in, with the settings of the
+/// `config` and the `args`, for the compiled `known_contracts` of the project
+fn new_gas_report(
+    config: &Config,
+    args: &TestArgs,
+    fork_block: Option<u64>,
+    known_contracts: &BTreeMap<ArtifactId, (Abi, Vec<u8>)>,
+) -> GasReport {
+    GasReport::new(config.gas_reports.clone())
+        .with_header(report_header(config, known_contracts.keys()))
+        .with_report_upgrades(config.report_upgrades)
+        .with_streaming(config.gas_reports_streaming)
+        .with_max_samples_per_function(config.gas_reports_max_samples)
+        .with_relative_coloring(config.gas_reports_relative_coloring)
+        .with_memory_gas_column(config.gas_reports_memory_gas)
+        .with_flag_gas_griefing(config.gas_reports_flag_griefing)
+        .with_noise_floor(config.gas_reports_noise_floor.into())
+        .with_gas_categories(args.gas_categories)
+        .with_exclude_paths(&config.gas_report_exclude_paths)
+        .with_merge_fork_contexts(args.merge_fork_contexts)
+        .with_fork_block(fork_block)
+        .with_signatures(known_signatures(known_contracts.values().map(|(abi, _)| abi)))
+        .with_abi_contracts(known_contracts.keys().map(|id| id.identifier()))
+        .with_metadata(args.gas_report_metadata.clone())
+}
+
+/// Returns the identifiers and ABIs of all deployable contracts outside of the libraries, the
+/// contracts whose functions are checked by `--warn-untested`
+pub(crate) fn untested_candidates(
+    config: &Config,
+    known_contracts: &BTreeMap<ArtifactId, (Abi, Vec<u8>)>,
+) -> Vec<(String, Abi)> {
+    let project_paths = config.project_paths();
+    known_contracts
+        .iter()
+        .filter(|(_, (_, code))| !code.is_empty())
+        .filter(|(id, _)| !project_paths.has_library_ancestor(&config.__root.0.join(&id.source)))
+        .map(|(id, (abi, _))| (id.identifier(), abi.clone()))
+        .collect()
+}
+
+/// Prints a warning that lists all untested functions
+pub(crate) fn print_untested(untested: &[UntestedFunction]) {
+    if untested.is_empty() {
+        return
+    }
+    let term = if untested.len() > 1 { "functions are" } else { "function is" };
+    eprintln!(
+        "{} {} {} never called by any test:",
+        Paint::yellow("Warning:").bold(),
+        untested.len(),
+        term
+    );
+    for function in untested {
+        eprintln!("  {function}");
+    }
+}
+
+/// Appends the counterexample of the failed fuzz test `name` of the contract `contract_name` to
+/// the fuzz input file of the test in the `cache_path`, unless it is recorded already.
+///
+/// Returns the path of the file if the test has a counterexample that can be recorded.
+fn record_counterexample(
+    cache_path: &Path,
+    test_abis: &BTreeMap<String, Abi>,
+    contract_name: &str,
+    name: &str,
+    result: &TestResult,
+) -> eyre::Result<Option<PathBuf>> {
+    let counterexample = match &result.counterexample {
+        Some(CounterExample::Single(counterexample)) if !result.success => counterexample,
+        _ => return Ok(None),
+    };
+    let func = match test_abis
+        .get(contract_name)
+        .and_then(|abi| abi.functions().find(|func| func.signature() == name))
+    {
+        Some(func) => func,
+        None => return Ok(None),
+    };
+    let line = match fuzz_inputs::to_line(func, &counterexample.args) {
+        Some(line) => line,
+        None => return Ok(None),
+    };
+
+    let path = cache_path
+        .join("fuzz")
+        .join(utils::get_contract_name(contract_name))
+        .join(format!("{}.ndjson", func.name));
+    let mut content = if path.exists() { fs::read_to_string(&path)? } else { String::new() };
+    if !content.lines().any(|existing| existing == line) {
+        content.push_str(&line);
+        content.push('\n');
+        fs::create_dir_all(path.parent().expect("has a parent"))?;
+        fs::write(&path, content)?;
     }
+    Ok(Some(path))
 }
 
 /// Lists all matching tests
@@ -438,18 +864,82 @@ fn list(runner: MultiContractRunner, filter: Filter, json: bool) -> eyre::Result
     Ok(TestOutcome::new(BTreeMap::new(), false))
 }
 
+/// How the results of a test run are printed
+struct TestOutputOptions {
+    /// The verbosity the results are printed with
+    verbosity: u8,
+    /// Whether the results are printed as JSON
+    json: bool,
+    /// Whether failing tests are allowed
+    allow_failure: bool,
+    /// Whether the memory used by the tests is reported
+    memory_report: bool,
+    /// Whether the functions that are never called by a test are listed
+    warn_untested: bool,
+    /// Whether `view` and `pure` functions are listed as untested as well
+    include_view: bool,
+    /// Whether the calls of the invariant tests are reported
+    invariant_stats: bool,
+    /// Whether the traces of passing tests are printed as well
+    show_all_traces: bool,
+    /// The depth up to which the calls of the printed traces are shown
+    max_trace_depth: Option<usize>,
+    /// The number of call frames shown of the traces of failing tests, all if `None`
+    stack_frames: Option<usize>,
+    /// Whether the durations of the suites are left out
+    no_timings: bool,
+}
+
+/// The gas report of a test run
+struct GasReportOptions {
+    /// Whether a gas report is printed
+    enabled: bool,
+    /// The report the calls of the tests are recorded in, see [`new_gas_report()`]
+    report: GasReport,
+    /// The number of calls below which functions are left out
+    min_calls: Option<usize>,
+    /// The signatures of the functions compared across contracts
+    compare: Vec<String>,
+    /// Whether the functions are grouped into tiers by their mean gas
+    tiered: bool,
+    /// The number of functions printed with their source lines and the source maps to resolve
+    /// them with
+    source_map: Option<(usize, GasSourceMap)>,
+    /// The file the traces the report is built from are saved to
+    save_traces: Option<PathBuf>,
+}
+
 /// Runs all the tests
-#[allow(clippy::too_many_arguments)]
 fn test(
     config: Config,
     mut runner: MultiContractRunner,
-    verbosity: u8,
     filter: Filter,
-    json: bool,
-    allow_failure: bool,
     test_options: TestOptions,
-    gas_reporting: bool,
+    output: TestOutputOptions,
+    gas_report_options: GasReportOptions,
 ) -> eyre::Result<TestOutcome> {
+    let TestOutputOptions {
+        verbosity,
+        json,
+        allow_failure,
+        memory_report: memory_reporting,
+        warn_untested,
+        include_view,
+        invariant_stats,
+        show_all_traces,
+        max_trace_depth,
+        stack_frames,
+        no_timings,
+    } = output;
+    let GasReportOptions {
+        enabled: gas_reporting,
+        report: mut gas_report,
+        min_calls: gas_report_min_calls,
+        compare: gas_report_compare,
+        tiered: gas_report_tiered,
+        source_map: gas_source_map,
+        save_traces,
+    } = gas_report_options;
     trace!(target: "forge::test", "running all tests");
     if runner.count_filtered_tests(&filter) == 0 {
         let filter_str = filter.to_string();
@@ -471,11 +961,32 @@ fn test(
         }
     }
 
+    // the ABIs of the test contracts, used to record the counterexamples of fuzz tests
+    let test_abis: BTreeMap<String, Abi> =
+        runner.contracts.iter().map(|(id, (abi, _, _))| (id.identifier(), abi.clone())).collect();
+    // the history the suites are ordered by, updated with the results of this run
+    let history = runner.history.clone();
+
     if json {
         let results = runner.test(&filter, None, test_options)?;
+        for (contract_name, suite_result) in &results {
+            for (name, result) in &suite_result.test_results {
+                record_counterexample(&config.cache_path, &test_abis, contract_name, name, result)?;
+            }
+        }
+        if let Some(mut history) = history {
+            history.record(&results);
+            history.write(&config.cache_path)?;
+        }
         println!("{}", serde_json::to_string(&results)?);
         Ok(TestOutcome::new(results, allow_failure))
     } else {
+        let untested_contracts = if warn_untested {
+            untested_candidates(&config, &runner.known_contracts)
+        } else {
+            Vec::new()
+        };
+
         // Set up identifiers
         let local_identifier = LocalTraceIdentifier::new(&runner.known_contracts);
         let remote_chain_id = runner.evm_opts.get_remote_chain_id();
@@ -484,20 +995,63 @@ fn test(
         let cache_ttl = Duration::from_secs(24 * 60 * 60);
         let etherscan_identifier = EtherscanIdentifier::new(
             remote_chain_id,
-            config.etherscan_api_key,
+            config.get_etherscan_api_key(remote_chain_id),
             remote_chain_id.and_then(Config::foundry_etherscan_chain_cache_dir),
             cache_ttl,
+            config.offline,
         );
 
+        let inline_config = runner.inline_config.clone();
+
+        // the gas budgets declared in the sources of the contracts, checked against the gas report
+        let mut gas_budgets = BTreeMap::new();
+        if gas_reporting {
+            let sources: BTreeSet<_> = runner.known_contracts.keys().map(|id| &id.source).collect();
+            for source in sources {
+                if let Ok(content) = std::fs::read_to_string(config.__root.0.join(source)) {
+                    for warning in parse_gas_budgets(&content, &mut gas_budgets) {
+                        eprintln!("{} {warning}", Paint::yellow("Warning:").bold());
+                    }
+                }
+            }
+        }
+        let mut exceeded_budgets = Vec::new();
+        let mut called = CalledFunctions::default();
+        // the traces the gas report is built from, if they are saved
+        let mut saved_traces = Vec::new();
+
         // Set up test reporter channel
         let (tx, rx) = channel::<(String, SuiteResult)>();
 
         // Run tests
+        // the gas of a single selected test is reported on its own
+        let gas_receipts = filter.test_pattern.is_some();
         let handle = thread::spawn(move || runner.test(&filter, Some(tx), test_options).unwrap());
 
         let mut results: BTreeMap<String, SuiteResult> = BTreeMap::new();
-        let mut gas_report = GasReport::new(config.gas_reports);
-        for (contract_name, suite_result) in rx {
+        let mut gas_source_map = gas_source_map.map(|(functions, map)| {
+            (functions, map.with_skipped_contracts(test_abis.keys().cloned()))
+        });
+        let mut memory_report = MemoryReport::default();
+        // suites are printed as they finish unless they are ordered, then all are buffered
+        let suites: Box<dyn Iterator<Item = (String, SuiteResult)>> =
+            match config.test_output_order {
+                TestOutputOrder::Completion => Box::new(rx.into_iter()),
+                TestOutputOrder::Alphabetical => {
+                    let mut suites = rx.into_iter().collect::<Vec<_>>();
+                    suites.sort_by(|(a, _), (b, _)| a.cmp(b));
+                    for (_, suite) in suites.iter_mut() {
+                        suite.warnings.sort();
+                    }
+                    Box::new(suites.into_iter())
+                }
+                TestOutputOrder::Duration => {
+                    let mut suites = rx.into_iter().collect::<Vec<_>>();
+                    suites.sort_by_key(|(_, suite)| suite.duration);
+                    Box::new(suites.into_iter())
+                }
+            };
+        for (contract_name, suite_result) in suites {
             let mut tests = suite_result.test_results.clone();
             println!();
             for warning in suite_result.warnings.iter() {
@@ -508,7 +1062,40 @@ fn test(
                 println!("Running {} {} for {}", tests.len(), term, contract_name);
             }
             for (name, result) in &mut tests {
-                short_test_result(name, result);
+                let from_cache = history
+                    .as_ref()
+                    .map_or(false, |history| history.is_known(&contract_name, name));
+                short_test_result(name, result, from_cache);
+                if memory_reporting {
+                    if let Some(memory) = TestMemory::new(&result.memory) {
+                        println!("  {memory}");
+                    }
+                    memory_report.add_test(&contract_name, name, result);
+                }
+                if invariant_stats {
+                    if let TestKind::Invariant(_, _, stats) = &result.kind {
+                        print_invariant_stats(stats);
+                    }
+                }
+                if let Some(path) = record_counterexample(
+                    &config.cache_path,
+                    &test_abis,
+                    &contract_name,
+                    name,
+                    result,
+                )? {
+                    println!(
+                        "Counterexample recorded, rerun it with `--fuzz-input-file {}`",
+                        path.display()
+                    );
+                }
+
+                // Apply the test's inline config overrides
+                let test_config = inline_config.get(&contract_name, name);
+                let verbosity =
+                    test_config.and_then(|config| config.verbosity).unwrap_or(verbosity);
+                let gas_reporting = gas_reporting &&
+                    test_config.and_then(|config| config.gas_report).unwrap_or(true);
 
                 // We only display logs at level 2 and above
                 if verbosity >= 2 {
@@ -546,19 +1133,29 @@ fn test(
                                 (verbosity >= 5) || (verbosity == 4 && !result.success)
                             }
                             TraceKind::Execution => {
-                                verbosity > 3 || (verbosity == 3 && !result.success)
+                                show_all_traces ||
+                                    verbosity > 3 ||
+                                    (verbosity == 3 && !result.success)
                             }
                             _ => false,
                         };
 
-                        // We decode the trace if we either need to build a gas report or we need
-                        // to print it
-                        if should_include || gas_reporting {
+                        // We decode the trace if we either need to build a gas report, look for
+                        // untested functions or we need to print it
+                        if should_include || gas_reporting || warn_untested {
                             rt.block_on(decoder.decode(trace));
                         }
 
                         if should_include {
-                            decoded_traces.push(trace.to_string());
+                            // the traces of failing tests are cut after `stack_frames` frames
+                            let frames_depth = (!result.success)
+                                .then(|| stack_frames.map(|frames| frames.saturating_sub(1)))
+                                .flatten();
+                            let max_depth = max_trace_depth.into_iter().chain(frames_depth).min();
+                            decoded_traces.push(match max_depth {
+                                Some(depth) => trace.truncated(depth).to_string(),
+                                None => trace.to_string(),
+                            });
                         }
                     }
 
@@ -568,7 +1165,22 @@ fn test(
                     }
 
                     if gas_reporting {
-                        gas_report.analyze(&result.traces);
+                        if let Some((_, map)) = &mut gas_source_map {
+                            map.analyze(&mut result.traces);
+                        }
+                        if gas_receipts {
+                            let label = format!("{contract_name}::{name}");
+                            gas_report.analyze_labeled(label, &result.traces);
+                        } else {
+                            gas_report.analyze(&result.traces);
+                        }
+                        if save_traces.is_some() {
+                            saved_traces.extend(result.traces.iter().cloned());
+                        }
+                    }
+
+                    if warn_untested {
+                        called.analyze(&result.traces);
                     }
                 }
             }
@@ -576,17 +1188,66 @@ fn test(
                 [(contract_name.clone(), suite_result.clone())].into(),
                 allow_failure,
             );
-            println!("{}", block_outcome.summary());
+            println!("{}", block_outcome.summary(!no_timings));
             results.insert(contract_name, suite_result);
         }
 
         if gas_reporting {
-            println!("{}", gas_report.finalize());
+            let mut gas_report = gas_report.finalize();
+            // the gas of a single selected test is reported as its receipt
+            if gas_report.labeled.len() == 1 {
+                let label = gas_report.labeled.keys().next().cloned().expect("a test is labeled");
+                if let Some(receipt) = gas_report.receipt_for(&label) {
+                    println!("Gas receipt of {label}:");
+                    gas_report = receipt;
+                }
+            }
+            if let Some(min_calls) = gas_report_min_calls {
+                gas_report = gas_report.filter_by_calls(min_calls);
+            }
+            if gas_report_tiered {
+                let tiers = gas_report_tiers(&config.gas_report_tiers);
+                println!("{}", gas_report.render_tiered(&tiers));
+            } else {
+                println!("{gas_report}");
+            }
+            for sig in gas_report_compare.iter() {
+                println!("{}", gas_report.compare_signature(sig));
+            }
+            if let Some((functions, map)) = &gas_source_map {
+                println!("{}", map.render_top_functions(*functions));
+            }
+            exceeded_budgets = gas_report.check_budgets(&gas_budgets);
+            for exceeded in exceeded_budgets.iter() {
+                eprintln!("{} {exceeded}", Paint::red("Error:").bold());
+            }
+        }
+
+        if memory_reporting && !memory_report.is_empty() {
+            println!("{memory_report}");
+        }
+
+        if let Some(path) = &save_traces {
+            GasReport::save_arenas(path, &saved_traces)?;
+        }
+
+        if warn_untested {
+            let candidates = untested_contracts.iter().map(|(id, abi)| (id.clone(), abi));
+            print_untested(&called.untested(candidates, include_view));
         }
 
         // reattach the thread
         let _ = handle.join();
 
+        if let Some(mut history) = history {
+            history.record(&results);
+            history.write(&config.cache_path)?;
+        }
+
+        if !exceeded_budgets.is_empty() {
+            eyre::bail!("{} functions exceed their gas budget", exceeded_budgets.len())
+        }
+
         trace!(target: "forge::test", "received {} results", results.len());
         Ok(TestOutcome::new(results, allow_failure))
     }
@@ -0,0 +1,191 @@
+//! Mutation testing for `forge test --mutate`
+
+use super::Filter;
+use crate::{compile, utils};
+use ethers::prelude::{Project, ProjectCompileOutput};
+use forge::{
+    executor::{inspector::CheatsConfig, opts::EvmOpts},
+    mutation::mutants,
+    result::SuiteResult,
+    MultiContractRunnerBuilder, TestOptions,
+};
+use foundry_common::fs;
+use foundry_config::Config;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use tempfile::TempDir;
+use walkdir::WalkDir;
+use yansi::Paint;
+
+/// Runs the tests against every mutant of the sources and prints the mutants that no test caught
+/// and the share of the mutants that were caught, the mutation score.
+///
+/// The tests have to pass on the unmodified sources, their results are returned. Mutants that do
+/// not compile are not counted.
+///
+/// The sources of the project are never modified, the mutants are compiled from a temporary copy
+/// of its sources, tests and scripts.
+pub(crate) fn run(
+    config: &Config,
+    project: &Project,
+    output: ProjectCompileOutput,
+    filter: &Filter,
+    evm_opts: EvmOpts,
+    test_options: TestOptions,
+) -> eyre::Result<BTreeMap<String, SuiteResult>> {
+    let env = evm_opts.evm_env_blocking();
+    let run_tests = |root: &Path, output| -> eyre::Result<BTreeMap<String, SuiteResult>> {
+        MultiContractRunnerBuilder::default()
+            .initial_balance(evm_opts.initial_balance)
+            .evm_spec(utils::evm_spec(&config.evm_version))
+            .sender(evm_opts.sender)
+            .with_fork(evm_opts.get_fork(config, env.clone()))
+            .with_cheats_config(CheatsConfig::new(config, &evm_opts))
+            .with_test_options(test_options)
+            .build(root, output, env.clone(), evm_opts.clone())?
+            .test(filter, None, test_options)
+    };
+
+    let results = run_tests(&project.paths.root, output)?;
+    let failures = results.values().map(|suite| suite.failures().count()).sum::<usize>();
+    if failures > 0 {
+        eyre::bail!("{failures} tests fail on the unmodified sources, fix them before mutating")
+    }
+
+    let copy = ProjectCopy::new(config)?;
+    let mutated_project = copy.config.project()?;
+    let sources = ethers::solc::utils::source_files(&config.src)
+        .into_iter()
+        .filter(|path| !is_test_or_script(path))
+        .collect::<Vec<_>>();
+    let (mut killed, mut survived, mut invalid) = (0usize, 0usize, 0usize);
+    for path in sources {
+        let source = fs::read_to_string(&path)?;
+        let copied = copy.path_of(config, &path);
+        for mutant in mutants(&source, &config.mutation.operators) {
+            fs::write(&copied, mutant.apply(&source))?;
+            let output = match compile::suppress_compile(&mutated_project) {
+                Ok(output) => output,
+                Err(_) => {
+                    invalid += 1;
+                    continue
+                }
+            };
+            let results = run_tests(&mutated_project.paths.root, output)?;
+            if results.values().any(|suite| suite.failures().next().is_some()) {
+                killed += 1;
+            } else {
+                survived += 1;
+                println!("{} {}:{}: {mutant}", Paint::red("Survived"), path.display(), mutant.line);
+            }
+        }
+        fs::write(&copied, &source)?;
+    }
+
+    let total = killed + survived;
+    let score = if total == 0 { 100.0 } else { killed as f64 * 100.0 / total as f64 };
+    println!();
+    println!("Mutation score: {score:.2}% ({killed} of {total} mutants killed)");
+    if invalid > 0 {
+        println!("{invalid} mutants did not compile and are not counted");
+    }
+    Ok(results)
+}
+
+/// Whether the file is a test or a script, by its extension
+fn is_test_or_script(path: &Path) -> bool {
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    name.ends_with(".t.sol") || name.ends_with(".s.sol")
+}
+
+/// A copy of the sources, tests and scripts of a project in a temporary directory, with a config
+/// that compiles the copy into the same directory.
+///
+/// Libraries and remappings still point to the original project, they are not mutated.
+struct ProjectCopy {
+    /// The config of the copied project
+    config: Config,
+    /// The directory of the copy, removed when dropped
+    _dir: TempDir,
+}
+
+impl ProjectCopy {
+    fn new(config: &Config) -> eyre::Result<Self> {
+        let dir = tempfile::tempdir()?;
+        let root = &config.__root.0;
+        let mut copy = config.clone();
+        copy.__root.0 = dir.path().to_path_buf();
+        copy.libs = config.libs.iter().map(|lib| root.join(lib)).collect();
+        copy.allow_paths = config.allow_paths.iter().map(|path| root.join(path)).collect();
+        copy.allow_paths.push(root.clone());
+        copy.out = dir.path().join("out");
+        copy.cache_path = dir.path().join("cache");
+        copy.build_info_path = None;
+
+        let mut this = Self { config: copy, _dir: dir };
+        this.config.src = this.path_of(config, &config.src);
+        this.config.test = this.path_of(config, &config.test);
+        this.config.script = this.path_of(config, &config.script);
+        for (original, copied) in [
+            (&config.src, &this.config.src),
+            (&config.test, &this.config.test),
+            (&config.script, &this.config.script),
+        ] {
+            copy_dir(&root.join(original), copied)?;
+        }
+        Ok(this)
+    }
+
+    /// Returns the path of the `original` file or directory of the project of the `config` in the
+    /// copy
+    fn path_of(&self, config: &Config, original: &Path) -> PathBuf {
+        let original = config.__root.0.join(original);
+        let relative = original.strip_prefix(&config.__root.0).unwrap_or(&original);
+        self.config.__root.0.join(relative.strip_prefix("/").unwrap_or(relative))
+    }
+}
+
+/// Copies all files in the `from` directory to the `to` directory, if `from` exists
+fn copy_dir(from: &Path, to: &Path) -> eyre::Result<()> {
+    if !from.is_dir() {
+        return Ok(())
+    }
+    for entry in WalkDir::new(from) {
+        let entry = entry?;
+        let target = to.join(entry.path().strip_prefix(from)?);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copies_project_sources() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("src/math")).unwrap();
+        fs::create_dir_all(root.path().join("test")).unwrap();
+        fs::write(root.path().join("src/math/Math.sol"), "contract Math {}").unwrap();
+        fs::write(root.path().join("test/Math.t.sol"), "contract MathTest {}").unwrap();
+        let config = Config::load_with_root(root.path());
+
+        let copy = ProjectCopy::new(&config).unwrap();
+        let copied = copy.path_of(&config, &config.src.join("math/Math.sol"));
+        assert!(copied.starts_with(&copy.config.__root.0));
+        assert_eq!(fs::read_to_string(&copied).unwrap(), "contract Math {}");
+        assert!(copy.config.test.join("Math.t.sol").is_file());
+
+        // mutating the copy leaves the project untouched
+        fs::write(&copied, "contract Mutant {}").unwrap();
+        let original = fs::read_to_string(root.path().join("src/math/Math.sol")).unwrap();
+        assert_eq!(original, "contract Math {}");
+    }
+}
@@ -80,11 +80,11 @@ pub struct VerifyArgs {
     pub chain: Chain,
 
     #[clap(
-        help = "Your Etherscan API key.",
+        help = "Your Etherscan API key, by default the key of the chain is read from the config.",
         env = "ETHERSCAN_API_KEY",
         value_name = "ETHERSCAN_KEY"
     )]
-    pub etherscan_key: String,
+    pub etherscan_key: Option<String>,
 
     #[clap(help = "Flatten the source code before verifying.", long = "flatten")]
     pub flatten: bool,
@@ -128,7 +128,10 @@ impl_figment_convert_basic!(VerifyArgs);
 impl VerifyArgs {
     /// Run the verify command to submit the contract's source code for verification on etherscan
     pub async fn run(mut self) -> eyre::Result<()> {
-        let etherscan = Client::new(self.chain.try_into()?, &self.etherscan_key)
+        let config = self.load_config();
+        ensure_online(&config)?;
+        let etherscan_key = etherscan_key(&config, self.chain, self.etherscan_key.take())?;
+        let etherscan = Client::new(self.chain.try_into()?, &etherscan_key)
             .wrap_err("Failed to create etherscan client")?;
 
         let verify_args = self.create_verify_request().await?;
@@ -180,7 +183,7 @@ impl VerifyArgs {
                     guid: resp.result,
                     chain: self.chain,
                     retry: RETRY_CHECK_ON_VERIFY,
-                    etherscan_key: self.etherscan_key,
+                    etherscan_key: Some(etherscan_key),
                 };
                 return check_args.run().await
             }
@@ -439,6 +442,25 @@ To skip this solc dry, pass `--force`.
     }
 }
 
+/// Fails if the `config` is in offline mode, since the verification is submitted to Etherscan
+fn ensure_online(config: &Config) -> eyre::Result<()> {
+    if config.offline {
+        eyre::bail!(
+            "Contract verification requires network access to Etherscan but offline mode is \
+             enabled, unset `offline` in foundry.toml or `FOUNDRY_OFFLINE` to verify"
+        )
+    }
+    Ok(())
+}
+
+/// Returns the `key` if it's set explicitly or the Etherscan API key of the `chain` in the `config`
+fn etherscan_key(config: &Config, chain: Chain, key: Option<String>) -> eyre::Result<String> {
+    match key {
+        Some(key) => Ok(key),
+        None => Ok(config.require_etherscan_api_key(Some(chain))?),
+    }
+}
+
 /// Strips [BuildMetadata] from the [Version]
 ///
 /// **Note:** this is only for local compilation as a dry run, therefore this will return a
@@ -488,17 +510,20 @@ pub struct VerifyCheckArgs {
     retry: RetryArgs,
 
     #[clap(
-        help = "Your Etherscan API key.",
+        help = "Your Etherscan API key, by default the key of the chain is read from the config.",
         env = "ETHERSCAN_API_KEY",
         value_name = "ETHERSCAN_KEY"
     )]
-    etherscan_key: String,
+    etherscan_key: Option<String>,
 }
 
 impl VerifyCheckArgs {
     /// Executes the command to check verification status on Etherscan
     pub async fn run(self) -> eyre::Result<()> {
-        let etherscan = Client::new(self.chain.try_into()?, &self.etherscan_key)
+        let config = Config::load();
+        ensure_online(&config)?;
+        let etherscan_key = etherscan_key(&config, self.chain, self.etherscan_key)?;
+        let etherscan = Client::new(self.chain.try_into()?, &etherscan_key)
             .wrap_err("Failed to create etherscan client")?;
 
         println!("Waiting for verification result...");
@@ -4,13 +4,19 @@ use crate::term;
 use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, *};
 use ethers::{
     prelude::Graph,
-    solc::{report::NoReporter, Artifact, FileFilter, Project, ProjectCompileOutput},
+    solc::{
+        artifacts::{Error, SourceLocation},
+        report::NoReporter,
+        Artifact, FileFilter, Project, ProjectCompileOutput,
+    },
 };
-use foundry_common::TestFunctionExt;
+use foundry_common::{fs, TestFunctionExt};
+use serde::Serialize;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     fmt::Display,
     path::{Path, PathBuf},
+    time::Instant,
 };
 
 /// Compiles the provided [`Project`], throws if there's any compiler error and logs whether
@@ -212,6 +218,183 @@ impl ProjectCompiler {
     }
 }
 
+/// The version of the schema of the [`BuildReport`], increased on breaking changes
+pub const BUILD_REPORT_VERSION: u32 = 1;
+
+/// The machine-readable output of `forge build --format-json`
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildReport {
+    /// The version of the schema, see [`BUILD_REPORT_VERSION`]
+    pub version: u32,
+    /// Whether the project compiled without errors
+    pub success: bool,
+    /// Whether nothing changed and the compilation was skipped
+    pub cached: bool,
+    /// The versions of the compilers that compiled the sources
+    pub compiler_versions: Vec<String>,
+    /// How long the compilation took, in milliseconds
+    pub duration_ms: u64,
+    /// The errors and warnings of the compiler, the warnings of the `ignored_error_codes` are left
+    /// out like in the text output
+    pub diagnostics: Vec<Diagnostic>,
+    /// The artifacts that were written
+    pub artifacts: Vec<BuiltArtifact>,
+}
+
+/// A single error or warning of the compiler
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    /// `error`, `warning` or `info`
+    pub severity: &'static str,
+    /// The error code of solc
+    pub code: Option<u64>,
+    /// The type of the error, e.g. `TypeError`
+    pub kind: String,
+    /// The short message
+    pub message: String,
+    /// The message formatted by solc, including the source snippet
+    pub formatted_message: Option<String>,
+    /// Where in the sources the error occurred
+    pub location: Option<DiagnosticLocation>,
+}
+
+/// The source location of a [`Diagnostic`], offsets are in bytes, lines and columns start at 1
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiagnosticLocation {
+    pub file: String,
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+/// An artifact written by the compilation
+#[derive(Debug, Clone, Serialize)]
+pub struct BuiltArtifact {
+    /// The source file of the contract
+    pub source: String,
+    /// The name of the contract
+    pub contract: String,
+    /// The path of the artifact
+    pub path: PathBuf,
+    /// The version of the compiler that compiled the contract
+    pub compiler_version: String,
+}
+
+impl BuildReport {
+    /// Creates the report of the `output` of compiling the `project`
+    pub fn new(project: &Project, output: &ProjectCompileOutput, duration_ms: u64) -> Self {
+        let mut sources = HashMap::new();
+        let diagnostics = output
+            .output()
+            .errors
+            .iter()
+            .filter(|err| {
+                !(err.severity.is_warning() &&
+                    err.error_code.map_or(false, |code| {
+                        project.ignored_error_codes.contains(&code)
+                    }))
+            })
+            .map(|err| Diagnostic::new(project, err, &mut sources))
+            .collect();
+
+        let mut compiler_versions = BTreeSet::new();
+        let mut artifacts = Vec::new();
+        for (source, contracts) in &output.compiled_artifacts().0 {
+            for (contract, files) in contracts {
+                for file in files {
+                    compiler_versions.insert(file.version.to_string());
+                    artifacts.push(BuiltArtifact {
+                        source: source.clone(),
+                        contract: contract.clone(),
+                        path: file.file.clone(),
+                        compiler_version: file.version.to_string(),
+                    });
+                }
+            }
+        }
+
+        Self {
+            version: BUILD_REPORT_VERSION,
+            success: !output.has_compiler_errors(),
+            cached: output.is_unchanged(),
+            compiler_versions: compiler_versions.into_iter().collect(),
+            duration_ms,
+            diagnostics,
+            artifacts,
+        }
+    }
+}
+
+impl Diagnostic {
+    /// Converts the compiler `err`, the `sources` cache the contents of the files by their path
+    fn new(project: &Project, err: &Error, sources: &mut HashMap<String, Option<String>>) -> Self {
+        let severity = if err.severity.is_error() {
+            "error"
+        } else if err.severity.is_warning() {
+            "warning"
+        } else {
+            "info"
+        };
+        let location = err.source_location.as_ref().map(|loc| {
+            let content = sources.entry(loc.file.clone()).or_insert_with(|| {
+                let path = Path::new(&loc.file);
+                fs::read_to_string(project.root().join(path)).ok()
+            });
+            DiagnosticLocation::new(loc, content.as_deref().unwrap_or_default())
+        });
+        Self {
+            severity,
+            code: err.error_code,
+            kind: err.r#type.clone(),
+            message: err.message.clone(),
+            formatted_message: err.formatted_message.clone(),
+            location,
+        }
+    }
+}
+
+impl DiagnosticLocation {
+    /// Resolves the lines and columns of the `loc` in the `content` of its file
+    fn new(loc: &SourceLocation, content: &str) -> Self {
+        let start = loc.start.max(0) as usize;
+        let end = (loc.end.max(0) as usize).max(start);
+        let (line, column) = line_column(content, start);
+        let (end_line, end_column) = line_column(content, end);
+        Self { file: loc.file.clone(), start, end, line, column, end_line, end_column }
+    }
+}
+
+/// Returns the line and the column of the byte `offset` in the `content`, both starting at 1
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(content.len());
+    let before = &content.as_bytes()[..offset];
+    let line = before.iter().filter(|b| **b == b'\n').count() + 1;
+    let line_start = before.iter().rposition(|b| *b == b'\n').map_or(0, |pos| pos + 1);
+    (line, offset - line_start + 1)
+}
+
+/// Compiles the [`Project`] without printing anything but the [`BuildReport`] of the compilation
+/// as JSON, exits with an error if the compiler reports errors
+pub fn compile_json(project: &Project) -> eyre::Result<ProjectCompileOutput> {
+    let now = Instant::now();
+    let output = ethers::solc::report::with_scoped(
+        &ethers::solc::report::Report::new(NoReporter::default()),
+        || project.compile(),
+    )?;
+    let duration_ms = now.elapsed().as_millis() as u64;
+
+    let report = BuildReport::new(project, &output, duration_ms);
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    if !report.success {
+        std::process::exit(1);
+    }
+
+    Ok(output)
+}
+
 /// Compiles the provided [`Project`], throws if there's any compiler error and logs whether
 /// compilation was successful or if there was a cache hit.
 /// Doesn't print anything to stdout, thus is "suppressed".
@@ -284,3 +467,17 @@ pub fn compile_target(
         compile(project, false, false)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_resolve_line_column() {
+        let content = "pragma solidity ^0.8.0;\ncontract A {\n    uint x;\n}\n";
+        assert_eq!(line_column(content, 0), (1, 1));
+        assert_eq!(line_column(content, 24), (2, 1));
+        assert_eq!(line_column(content, 41), (3, 5));
+        assert_eq!(line_column(content, 1000), (5, 1));
+    }
+}
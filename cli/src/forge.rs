@@ -40,6 +40,12 @@ fn main() -> eyre::Result<()> {
         Subcommands::Coverage(cmd) => {
             cmd.run()?;
         }
+        Subcommands::GasReport(cmd) => {
+            cmd.run()?;
+        }
+        Subcommands::GasHistory(cmd) => {
+            cmd.run()?;
+        }
         Subcommands::Bind(cmd) => {
             cmd.run()?;
         }
@@ -1,13 +1,15 @@
-use super::{ClapChain, EthereumOpts, TransactionOpts};
+use super::{ClapChain, EthereumOpts, TransactionOpts, Wallet};
 use crate::{
     cmd::cast::{
         estimate::EstimateArgs, find_block::FindBlockArgs, rpc::RpcArgs, run::RunArgs,
         wallet::WalletSubcommands,
     },
-    utils::parse_u256,
+    utils::{parse_ether_value, parse_u256},
 };
-use clap::{Parser, Subcommand, ValueHint};
+use cast::SimpleCast;
+use clap::{ArgEnum, Parser, Subcommand, ValueHint};
 use ethers::types::{Address, BlockId, BlockNumber, NameOrAddress, H256, U256};
+use foundry_common::fs;
 use std::{path::PathBuf, str::FromStr};
 
 #[derive(Debug, Parser)]
@@ -273,6 +275,12 @@ Examples:
         full: bool,
         #[clap(long = "json", short = 'j', help_heading = "DISPLAY OPTIONS")]
         to_json: bool,
+        #[clap(
+            long,
+            help = "Keep running and print every new block as it arrives, replacing the previous one.",
+            long_help = "Keep running and print every new block as it arrives, replacing the previous one. The node is polled for new blocks, every 100ms for local nodes. With --json every block is printed on its own line instead."
+        )]
+        watch: bool,
         #[clap(long, env = "ETH_RPC_URL", value_name = "URL")]
         rpc_url: Option<String>,
     },
@@ -434,6 +442,71 @@ Examples:
         )]
         resend: bool,
     },
+    #[clap(name = "mktx")]
+    #[clap(about = "Build and sign a transaction without broadcasting it.")]
+    MakeTx {
+        #[clap(
+            long,
+            help = "The destination of the transaction. Omit it to deploy a contract.",
+            value_name = "ADDRESS"
+        )]
+        to: Option<Address>,
+        #[clap(
+            long,
+            help = "Ether to send in the transaction.",
+            parse(try_from_str = parse_ether_value),
+            value_name = "VALUE"
+        )]
+        value: Option<U256>,
+        #[clap(
+            long,
+            help = "The hex encoded calldata, or the init code when deploying a contract.",
+            value_name = "DATA"
+        )]
+        data: Option<String>,
+        #[clap(
+            long,
+            help = "Nonce for the transaction.",
+            parse(try_from_str = parse_u256),
+            value_name = "NONCE"
+        )]
+        nonce: U256,
+        #[clap(
+            long = "gas-limit",
+            help = "Gas limit for the transaction.",
+            parse(try_from_str = parse_u256),
+            value_name = "GAS_LIMIT"
+        )]
+        gas_limit: U256,
+        #[clap(
+            long = "gas-price",
+            help = "Gas price for a legacy transaction.",
+            parse(try_from_str = parse_ether_value),
+            required_unless_present = "max-fee",
+            conflicts_with_all = &["max-fee", "priority-fee"],
+            value_name = "PRICE"
+        )]
+        gas_price: Option<U256>,
+        #[clap(
+            long = "max-fee",
+            help = "Max fee per gas for an EIP1559 transaction.",
+            parse(try_from_str = parse_ether_value),
+            value_name = "PRICE"
+        )]
+        max_fee: Option<U256>,
+        #[clap(
+            long = "priority-fee",
+            help = "Max priority fee per gas for an EIP1559 transaction.",
+            parse(try_from_str = parse_ether_value),
+            requires = "max-fee",
+            value_name = "PRICE"
+        )]
+        priority_fee: Option<U256>,
+        #[clap(flatten)]
+        chain: ClapChain,
+        #[clap(flatten, next_help_heading = "WALLET OPTIONS")]
+        wallet: Wallet,
+    },
     #[clap(name = "publish")]
     #[clap(visible_alias = "p")]
     #[clap(about = "Publish a raw transaction to the network.")]
@@ -448,6 +521,16 @@ Examples:
             help = "Only print the transaction hash and exit immediately."
         )]
         cast_async: bool,
+        #[clap(
+            short,
+            long,
+            help = "The number of confirmations until the receipt is fetched.",
+            default_value = "1",
+            value_name = "CONFIRMATIONS"
+        )]
+        confirmations: usize,
+        #[clap(long = "json", short = 'j', help_heading = "DISPLAY OPTIONS")]
+        to_json: bool,
         // FIXME: We only need the RPC URL and `--flashbots` options from this.
         #[clap(flatten)]
         eth: EthereumOpts,
@@ -497,6 +580,30 @@ Defaults to decoding output data. To decode input data pass --input or use cast
         #[clap(allow_hyphen_values = true)]
         args: Vec<String>,
     },
+    #[clap(name = "abi-encode-packed")]
+    #[clap(visible_alias = "aep")]
+    #[clap(
+        about = "Encode the given arguments like Solidity's `abi.encodePacked`.",
+        long_about = r#"Encode the given arguments like Solidity's `abi.encodePacked`.
+
+Unlike `cast abi-encode`, numbers, addresses, booleans and fixed size bytes take only as many bytes as their type, and strings and bytes are neither padded nor prefixed by their length. The elements of arrays are still padded to 32 bytes.
+
+The encoding of adjacent dynamic types is ambiguous, e.g. ("a", "bc") and ("ab", "c") are encoded the same, so a warning is printed for them.
+
+Examples:
+- cast abi-encode-packed "(uint8,string)" 1 hello
+- cast abi-encode-packed "f(address,uint256)" 0xdead000000000000000000000000000000000000 42"#
+    )]
+    AbiEncodePacked {
+        #[clap(
+            help = "The function signature or a parenthesized list of types, e.g. \"(uint8,string)\".",
+            value_name = "TYPES"
+        )]
+        sig: String,
+        #[clap(help = "The values to encode.", value_name = "VALUES")]
+        #[clap(allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
     #[clap(name = "index")]
     #[clap(visible_alias = "in")]
     #[clap(about = "Compute the storage slot for an entry in a mapping.")]
@@ -632,6 +739,21 @@ Tries to decode the calldata using https://sig.eth.samczsun.com unless --offline
         who: NameOrAddress,
         #[clap(short, long, env = "ETH_RPC_URL", value_name = "URL")]
         rpc_url: Option<String>,
+        #[clap(
+            long,
+            short,
+            help = "Print the bytecode as EVM assembly, annotating the function dispatcher with the signatures of the selectors."
+        )]
+        disassemble: bool,
+        #[clap(
+            long = "abi",
+            help = "An ABI or artifact JSON file to look up function selectors in before querying the signature database.",
+            requires = "disassemble",
+            number_of_values = 1,
+            value_hint = ValueHint::FilePath,
+            value_name = "PATH"
+        )]
+        abis: Vec<PathBuf>,
     },
     #[clap(name = "gas-price")]
     #[clap(visible_alias = "g")]
@@ -641,11 +763,17 @@ Tries to decode the calldata using https://sig.eth.samczsun.com unless --offline
         rpc_url: Option<String>,
     },
     #[clap(name = "keccak")]
-    #[clap(visible_alias = "k")]
+    #[clap(visible_alias = "k", alias = "keccak256")]
     #[clap(about = "Hash arbitrary data using keccak-256.")]
     Keccak {
-        #[clap(value_name = "DATA")]
-        data: String,
+        #[clap(flatten)]
+        input: HashInput,
+    },
+    #[clap(name = "sha256")]
+    #[clap(about = "Hash arbitrary data using SHA-256.")]
+    Sha256 {
+        #[clap(flatten)]
+        input: HashInput,
     },
     #[clap(name = "resolve-name")]
     #[clap(visible_alias = "rn")]
@@ -844,6 +972,65 @@ If an address is specified, then the ABI is fetched from Etherscan."#,
     },
 }
 
+/// The data hashed by `cast keccak` and `cast sha256`
+#[derive(Debug, Clone, Parser)]
+pub struct HashInput {
+    #[clap(
+        help = "The data to hash, read as hex if it has a 0x prefix and as text otherwise, unless --encoding is set.",
+        value_name = "DATA",
+        required_unless_present = "input-file"
+    )]
+    data: Option<String>,
+    #[clap(
+        long,
+        help = "Hash the contents of the file instead, which are read as raw bytes unless --encoding is set.",
+        value_hint = ValueHint::FilePath,
+        value_name = "PATH",
+        conflicts_with = "data"
+    )]
+    input_file: Option<PathBuf>,
+    #[clap(long, arg_enum, help = "How the data is interpreted.", value_name = "ENCODING")]
+    encoding: Option<InputEncoding>,
+}
+
+impl HashInput {
+    /// Returns the data to hash, as `0x` prefixed hex unless it is inline data without an
+    /// explicit encoding
+    pub fn resolve(&self) -> eyre::Result<String> {
+        let data = match &self.input_file {
+            Some(path) => {
+                let contents = fs::read(path)?;
+                match self.encoding {
+                    None | Some(InputEncoding::Utf8) => {
+                        return Ok(format!("0x{}", hex::encode(contents)))
+                    }
+                    Some(_) => String::from_utf8(contents)?.trim().to_string(),
+                }
+            }
+            None => self.data.clone().unwrap_or_default(),
+        };
+        Ok(match self.encoding {
+            None => data,
+            Some(InputEncoding::Utf8) => format!("0x{}", hex::encode(data)),
+            Some(InputEncoding::Hex) => {
+                format!("0x{}", hex::encode(hex::decode(data.trim_start_matches("0x"))?))
+            }
+            Some(InputEncoding::Bytes32) => SimpleCast::bytes32(&data)?,
+        })
+    }
+}
+
+/// How `cast keccak` and `cast sha256` interpret their input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ArgEnum)]
+pub enum InputEncoding {
+    /// The bytes of the text
+    Utf8,
+    /// Hex data, with or without a 0x prefix
+    Hex,
+    /// Hex data of at most 32 bytes, right-padded to 32 bytes like `cast --to-bytes32`
+    Bytes32,
+}
+
 pub fn parse_name_or_address(s: &str) -> eyre::Result<NameOrAddress> {
     Ok(if s.starts_with("0x") {
         NameOrAddress::Address(s.parse::<Address>()?)
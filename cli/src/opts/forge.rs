@@ -13,6 +13,8 @@ use crate::cmd::forge::{
     flatten,
     fmt::FmtArgs,
     fourbyte::UploadSelectorsArgs,
+    gas_history::GasHistoryArgs,
+    gas_report::GasReportArgs,
     init::InitArgs,
     inspect,
     install::InstallArgs,
@@ -60,6 +62,12 @@ pub enum Subcommands {
     #[clap(about = "Generate coverage reports.")]
     Coverage(coverage::CoverageArgs),
 
+    #[clap(about = "Replay transactions on a fork and report the gas used by their calls.")]
+    GasReport(GasReportArgs),
+
+    #[clap(about = "Plot the gas of a test over the commits that changed the gas snapshot.")]
+    GasHistory(GasHistoryArgs),
+
     #[clap(alias = "bi", about = "Generate Rust bindings for smart contracts.")]
     Bind(BindArgs),
 
@@ -150,6 +150,17 @@ pub struct Wallet {
 }
 
 impl Wallet {
+    /// Returns the first local wallet that is configured, in the order private key, interactive
+    /// prompt, mnemonic and keystore
+    pub fn local(&self) -> Result<Option<LocalWallet>> {
+        self.private_key()
+            .transpose()
+            .or_else(|| self.interactive().transpose())
+            .or_else(|| self.mnemonic().transpose())
+            .or_else(|| self.keystore().transpose())
+            .transpose()
+    }
+
     pub fn interactive(&self) -> Result<Option<LocalWallet>> {
         Ok(if self.interactive { Some(self.get_from_interactive()?) } else { None })
     }
@@ -163,6 +163,17 @@ pub fn parse_delay(delay: &str) -> eyre::Result<Duration> {
     Ok(delay)
 }
 
+/// Parses a `KEY=VALUE` pair
+pub fn parse_key_value(s: &str) -> eyre::Result<(String, String)> {
+    let (key, value) =
+        s.split_once('=').ok_or_else(|| eyre::eyre!("expected `KEY=VALUE`, got `{s}`"))?;
+    let key = key.trim();
+    if key.is_empty() {
+        eyre::bail!("the key of `{s}` is empty");
+    }
+    Ok((key.to_string(), value.trim().to_string()))
+}
+
 /// Runs the `future` in a new [`tokio::runtime::Runtime`]
 #[allow(unused)]
 pub fn block_on<F: Future>(future: F) -> F::Output {
@@ -1,5 +1,12 @@
 //! Contains various tests for checking cast commands
 
+use ethers::{
+    types::{
+        transaction::eip2718::TypedTransaction, Address, NameOrAddress, TransactionRequest, U256,
+        U64,
+    },
+    utils::rlp::Rlp,
+};
 use foundry_cli_test_utils::{
     casttest,
     util::{TestCommand, TestProject},
@@ -206,3 +213,97 @@ casttest!(calldata_array, |_: TestProject, mut cmd: TestCommand| {
     assert_eq!(out.trim(),"0xcde2baba0000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000000"
     );
 });
+
+// checks `cast abi-encode-packed` packs the values and warns about ambiguous encodings
+casttest!(abi_encode_packed, |_: TestProject, mut cmd: TestCommand| {
+    cmd.args(["abi-encode-packed", "(uint16,bool,string)", "1", "true", "hi"]);
+    assert_eq!(cmd.stdout_lossy().trim(), "0x0001016869");
+
+    cmd.cast_fuse().args(["abi-encode-packed", "(string,bytes)", "a", "0xbc"]);
+    let output = cmd.unchecked_output();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "0x61bc");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("ambiguous"));
+});
+
+// tests that `cast mktx` signs legacy and EIP1559 transactions offline
+casttest!(cast_mktx, |_: TestProject, mut cmd: TestCommand| {
+    let private_key = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    let signer: Address = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".parse().unwrap();
+    let to = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+    let to_address = NameOrAddress::Address(to.parse().unwrap());
+    let decode = |out: String| hex::decode(out.trim().trim_start_matches("0x")).unwrap();
+
+    cmd.args([
+        "mktx",
+        "--to",
+        to,
+        "--value",
+        "1ether",
+        "--nonce",
+        "0",
+        "--gas-limit",
+        "21000",
+        "--gas-price",
+        "10gwei",
+        "--private-key",
+        private_key,
+    ]);
+    let raw = decode(cmd.stdout_lossy());
+    let (tx, _) = TransactionRequest::decode_signed_rlp(&Rlp::new(&raw)).unwrap();
+    assert_eq!(tx.chain_id, Some(U64::from(1)));
+    assert_eq!(tx.nonce, Some(U256::zero()));
+    assert_eq!(tx.to, Some(to_address.clone()));
+    assert_eq!(tx.value, Some(U256::exp10(18)));
+    assert_eq!(tx.from, Some(signer));
+
+    cmd.cast_fuse().args([
+        "mktx",
+        "--to",
+        to,
+        "--data",
+        "0xdeadbeef",
+        "--nonce",
+        "1",
+        "--gas-limit",
+        "50000",
+        "--max-fee",
+        "20gwei",
+        "--priority-fee",
+        "1gwei",
+        "--chain",
+        "goerli",
+        "--private-key",
+        private_key,
+    ]);
+    let raw = decode(cmd.stdout_lossy());
+    assert_eq!(raw[0], 0x02);
+    let (tx, signature) = TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap();
+    assert_eq!(tx.chain_id(), Some(U64::from(5)));
+    assert_eq!(tx.nonce(), Some(&U256::one()));
+    assert_eq!(tx.to(), Some(&to_address));
+    assert_eq!(tx.value(), Some(&U256::zero()));
+    assert_eq!(signature.recover(tx.sighash()).unwrap(), signer);
+});
+
+// tests that `cast keccak` and `cast sha256` hash files and honor the input encoding
+casttest!(hash_input_file_and_encoding, |prj: TestProject, mut cmd: TestCommand| {
+    let file = prj.create_file("data.txt", "foo");
+
+    cmd.args(["keccak", "--input-file"]).arg(&file);
+    let out = cmd.stdout_lossy();
+    assert_eq!(out.trim(), "0x41b1a0649752af1b28b3dc29a1556eee781e4a4c3a1f7f53f90fa834de098c4d");
+
+    cmd.cast_fuse().args(["sha256", "--input-file"]).arg(&file);
+    let out = cmd.stdout_lossy();
+    assert_eq!(out.trim(), "0x2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae");
+
+    cmd.cast_fuse().args(["sha256", "--encoding", "hex", "12"]);
+    let out = cmd.stdout_lossy();
+    assert_eq!(out.trim(), "0xf299791cddd3d6664f6670842812ef6053eb6501bd6282a476bbbf3ee91e750c");
+
+    // the text `0x12` is hashed instead of the byte
+    cmd.cast_fuse().args(["keccak256", "--encoding", "utf8", "0x12"]);
+    let text = cmd.stdout_lossy();
+    cmd.cast_fuse().args(["keccak", "0x30783132"]);
+    assert_eq!(text, cmd.stdout_lossy());
+});
@@ -432,6 +432,40 @@ warning[5667]: Warning: Unused function parameter. Remove or comment out the var
     ));
 });
 
+// checks that `build --format-json` reports the diagnostics and artifacts as JSON
+forgetest!(can_print_json_build_output, |prj: TestProject, mut cmd: TestCommand| {
+    prj.inner()
+        .add_source(
+            "Foo",
+            r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity >0.8.9;
+contract Greeter {
+    function foo(uint256 a) public {
+        uint256 x = 1;
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    cmd.args(["build", "--format-json"]);
+    let report: serde_json::Value = serde_json::from_str(&cmd.stdout_lossy()).unwrap();
+    assert_eq!(report["version"], 1);
+    assert_eq!(report["success"], true);
+    assert_eq!(report["artifacts"][0]["contract"], "Greeter");
+
+    let unused = report["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|diagnostic| diagnostic["code"] == 5667)
+        .unwrap();
+    assert_eq!(unused["severity"], "warning");
+    assert_eq!(unused["location"]["line"], 5);
+    assert_eq!(unused["location"]["column"], 18);
+});
+
 // Tests that direct import paths are handled correctly
 //
 // NOTE(onbjerg): Disabled for Windows -- for some reason solc fails with a bogus error message
@@ -13,7 +13,7 @@ use foundry_cli_test_utils::{
 };
 use foundry_config::{
     cache::{CachedChains, CachedEndpoints, StorageCachingConfig},
-    Config, OptimizerDetails, SolcReq,
+    Config, OptimizerDetails, SolcReq, TestOutputOrder,
 };
 use path_slash::PathBufExt;
 use std::{fs, path::PathBuf, str::FromStr};
@@ -35,6 +35,16 @@ forgetest!(can_extract_config_values, |prj: TestProject, mut cmd: TestCommand| {
         force: true,
         evm_version: EvmVersion::Byzantium,
         gas_reports: vec!["Contract".to_string()],
+        report_upgrades: true,
+        gas_reports_streaming: true,
+        gas_reports_max_samples: Some(10_000),
+        gas_reports_relative_coloring: true,
+        gas_reports_memory_gas: true,
+        gas_reports_flag_griefing: true,
+        gas_reports_noise_floor: 100,
+        test_output_order: TestOutputOrder::Alphabetical,
+        gas_report_exclude_paths: vec!["test/**".to_string(), "script/**".to_string()],
+        gas_report_tiers: vec![10_000, 100_000],
         solc: Some(SolcReq::Local(PathBuf::from("custom-solc"))),
         auto_detect_solc: false,
         offline: true,
@@ -56,6 +66,7 @@ forgetest!(can_extract_config_values, |prj: TestProject, mut cmd: TestCommand| {
         contract_pattern_inverse: None,
         path_pattern: None,
         path_pattern_inverse: None,
+        contract_source_pattern_inverse: None,
         fuzz_runs: 1000,
         fuzz_max_local_rejects: 2000,
         fuzz_max_global_rejects: 100203,
@@ -65,6 +76,9 @@ forgetest!(can_extract_config_values, |prj: TestProject, mut cmd: TestCommand| {
         invariant_fail_on_revert: false,
         invariant_call_override: false,
         ffi: true,
+        fs_permissions: Default::default(),
+        isolate: false,
+        recorded_logs_limit: 1_000,
         sender: "00a329c0648769A73afAc7F9381D08FB43dBEA72".parse().unwrap(),
         tx_origin: "00a329c0648769A73afAc7F9F81E08FB43dBEA72".parse().unwrap(),
         initial_balance: U256::from(0xffffffffffffffffffffffffu128),
@@ -83,6 +97,7 @@ forgetest!(can_extract_config_values, |prj: TestProject, mut cmd: TestCommand| {
         etherscan_api_key: None,
         verbosity: 4,
         remappings: vec![Remapping::from_str("forge-std=lib/forge-std/").unwrap().into()],
+        deny_remapping_conflicts: true,
         libraries: vec![
             "src/DssSpell.sol:DssExecLib:0x8De6DDbCd5053d32292AAA0D2105A32d108484a6".to_string()
         ],
@@ -98,11 +113,14 @@ forgetest!(can_extract_config_values, |prj: TestProject, mut cmd: TestCommand| {
         sparse_mode: true,
         allow_paths: vec![],
         rpc_endpoints: Default::default(),
+        etherscan: Default::default(),
         build_info: false,
         build_info_path: None,
         fmt: Default::default(),
+        mutation: Default::default(),
         __non_exhaustive: (),
         __warnings: vec![],
+        __interpolated: Default::default(),
     };
     prj.write_config(input.clone());
     let config = cmd.config();
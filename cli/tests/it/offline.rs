@@ -0,0 +1,56 @@
+//! Contains tests for running forge commands in offline mode without network access
+
+use foundry_cli_test_utils::{
+    forgetest,
+    util::{TestCommand, TestProject},
+};
+
+/// Blocks all network access of the command by routing it through a proxy that refuses every
+/// connection, and enables offline mode
+fn block_network(cmd: &mut TestCommand) {
+    for proxy in ["HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY", "http_proxy", "https_proxy"] {
+        cmd.set_env(proxy, "http://127.0.0.1:9");
+    }
+    cmd.set_env("FOUNDRY_OFFLINE", 1);
+}
+
+// tests that the test suite runs without network access once solc is installed
+forgetest!(can_test_offline, |prj: TestProject, mut cmd: TestCommand| {
+    prj.insert_ds_test();
+    prj.inner()
+        .add_source(
+            "ATest.t.sol",
+            r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+import "./test.sol";
+contract ATest is DSTest {
+    function testCall() external {
+        (bool success, ) = address(0xdead).call(abi.encodeWithSelector(0x70a08231, address(this)));
+        assertTrue(success);
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    // installs the solc version while the network is still reachable
+    cmd.arg("build");
+    cmd.assert_non_empty_stdout();
+
+    cmd.forge_fuse().args(["test", "-vvvv", "--gas-report"]);
+    block_network(&mut cmd);
+    assert!(cmd.stdout_lossy().contains("[PASS] testCall()"));
+});
+
+// tests that verification fails fast in offline mode
+forgetest!(verify_fails_offline, |_prj: TestProject, mut cmd: TestCommand| {
+    cmd.args([
+        "verify-contract",
+        "0x000000000000000000000000000000000000dEaD",
+        "src/Verify.sol:Verify",
+        "ETHERSCAN_KEY",
+    ]);
+    block_network(&mut cmd);
+    assert!(cmd.stderr_lossy().contains("offline mode is enabled"));
+});
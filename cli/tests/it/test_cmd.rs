@@ -3,7 +3,7 @@ use foundry_cli_test_utils::{
     forgetest, forgetest_init,
     util::{OutputExt, TestCommand, TestProject},
 };
-use foundry_config::Config;
+use foundry_config::{Config, TestOutputOrder};
 use std::{path::PathBuf, str::FromStr};
 
 // tests that test filters are handled correctly
@@ -199,6 +199,47 @@ contract FailTest is DSTest {
     cmd.stdout().contains("[PASS]") && !cmd.stdout().contains("[FAIL]")
 });
 
+// tests that using the --no-match-contract-source option skips test files matching the glob
+forgetest!(can_test_with_no_match_contract_source, |prj: TestProject, mut cmd: TestCommand| {
+    prj.insert_ds_test();
+
+    prj.inner()
+        .add_source(
+            "ATest.t.sol",
+            r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+import "./test.sol";
+contract ATest is DSTest {
+    function testPass() external {
+        assertTrue(true);
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    prj.inner()
+        .add_source(
+            "legacy/LegacyTest.t.sol",
+            r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+import "../test.sol";
+contract LegacyTest is DSTest {
+    function testNothing() external {
+        assertTrue(false);
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    cmd.args(["test", "--no-match-contract-source", "src/legacy/**"]);
+    let stdout = cmd.stdout();
+    assert!(stdout.contains("[PASS]") && !stdout.contains("LegacyTest"), "{stdout}");
+});
+
 // tests that `forge test` will pick up tests that are stored in the `test = <path>` config value
 forgetest!(can_run_test_in_custom_test_folder, |prj: TestProject, mut cmd: TestCommand| {
     prj.insert_ds_test();
@@ -299,3 +340,248 @@ forgetest_init!(can_test_forge_std, |prj: TestProject, mut cmd: TestCommand| {
 
     cmd.stdout().contains("[PASS]") && !cmd.stdout().contains("[FAIL]")
 });
+
+// tests that alphabetically ordered results without timings are identical between runs
+forgetest!(can_print_stable_output, |prj: TestProject, mut cmd: TestCommand| {
+    prj.insert_ds_test();
+    for name in ["B", "A", "C"] {
+        prj.inner()
+            .add_source(
+                &format!("{name}Test.t.sol"),
+                format!(
+                    r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+import "./test.sol";
+contract {name}Test is DSTest {{
+    function testPass() external {{
+        assertTrue(true);
+    }}
+}}
+   "#
+                ),
+            )
+            .unwrap();
+    }
+    prj.write_config(Config {
+        test_output_order: TestOutputOrder::Alphabetical,
+        ..Default::default()
+    });
+
+    cmd.args(["test", "--no-timings"]);
+    let first = cmd.stdout_lossy();
+    let second = cmd.stdout_lossy();
+    let first = &first[first.find("Running").unwrap()..];
+    let second = &second[second.find("Running").unwrap()..];
+    assert_eq!(first, second);
+    assert!(!first.contains("finished in"));
+
+    let a = first.find("for src/ATest.t.sol:ATest").unwrap();
+    let b = first.find("for src/BTest.t.sol:BTest").unwrap();
+    let c = first.find("for src/CTest.t.sol:CTest").unwrap();
+    assert!(a < b && b < c);
+});
+
+// tests that `--memory-report` reports the memory of unit and fuzz tests
+forgetest!(can_report_memory, |prj: TestProject, mut cmd: TestCommand| {
+    prj.insert_ds_test();
+    prj.inner()
+        .add_source(
+            "MemoryTest.t.sol",
+            r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+import "./test.sol";
+contract MemoryTest is DSTest {
+    function testAllocate() external {
+        uint256[] memory values = new uint256[](16);
+        assertEq(values.length, 16);
+    }
+
+    function testFuzzAllocate(uint8 len) external {
+        uint256[] memory values = new uint256[](len);
+        assertEq(values.length, len);
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    cmd.args(["test", "--memory-report"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("(memory: "));
+    assert!(stdout.contains("(memory min: "));
+    assert!(stdout.contains("mstores: "));
+    assert!(stdout.contains("src/MemoryTest.t.sol:MemoryTest memory"));
+});
+
+// tests that `--smart-order` runs the suites that failed in the last run first
+forgetest!(can_run_failed_suites_first, |prj: TestProject, mut cmd: TestCommand| {
+    prj.insert_ds_test();
+    for (name, passes) in [("A", true), ("Z", false)] {
+        prj.inner()
+            .add_source(
+                &format!("{name}Test.t.sol"),
+                format!(
+                    r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+import "./test.sol";
+contract {name}Test is DSTest {{
+    function testOutcome() external {{
+        assertTrue({passes});
+    }}
+}}
+   "#
+                ),
+            )
+            .unwrap();
+    }
+
+    cmd.args(["test", "--smart-order", "--allow-failure"]);
+    let first = cmd.stdout_lossy();
+    assert!(!first.contains("[from cache]"));
+    assert!(prj.root().join("cache/last-failed.json").exists());
+    assert!(prj.root().join("cache/test-durations.json").exists());
+
+    let second = cmd.stdout_lossy();
+    assert!(second.contains("[from cache]"));
+    let a = second.find("for src/ATest.t.sol:ATest").unwrap();
+    let z = second.find("for src/ZTest.t.sol:ZTest").unwrap();
+    assert!(z < a);
+});
+
+// tests that `--warn-untested` on its own records the calls of the tests
+forgetest!(can_warn_untested_without_gas_report, |prj: TestProject, mut cmd: TestCommand| {
+    prj.insert_ds_test();
+    prj.inner()
+        .add_source(
+            "Counter.sol",
+            r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+contract Counter {
+    uint256 public number;
+
+    function increment() external {
+        number++;
+    }
+
+    function reset() external {
+        number = 0;
+    }
+}
+   "#,
+        )
+        .unwrap();
+    prj.inner()
+        .add_source(
+            "CounterTest.t.sol",
+            r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+import "./test.sol";
+import "./Counter.sol";
+contract CounterTest is DSTest {
+    function testIncrement() external {
+        Counter counter = new Counter();
+        counter.increment();
+        assertEq(counter.number(), 1);
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    cmd.args(["test", "--warn-untested"]);
+    let stderr = cmd.stderr_lossy();
+    assert!(stderr.contains("1 function is never called by any test"));
+    assert!(stderr.contains("src/Counter.sol:Counter: reset()"));
+    assert!(!stderr.contains("increment()"));
+});
+
+// tests that the traces of failing tests are only cut if `--stack-frames` is set
+forgetest!(can_limit_stack_frames, |prj: TestProject, mut cmd: TestCommand| {
+    prj.insert_ds_test();
+    prj.inner()
+        .add_source(
+            "StackTest.t.sol",
+            r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+import "./test.sol";
+contract Inner {
+    function fail() external pure {
+        revert("inner failure");
+    }
+}
+contract Outer {
+    Inner inner = new Inner();
+
+    function call() external view {
+        inner.fail();
+    }
+}
+contract StackTest is DSTest {
+    function testNestedRevert() external {
+        Outer outer = new Outer();
+        outer.call();
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    cmd.args(["test", "-vvv", "--allow-failure"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("Outer::call()"));
+    assert!(stdout.contains("Inner::fail()"));
+
+    cmd.forge_fuse().args(["test", "-vvv", "--allow-failure", "--stack-frames", "2"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("Outer::call()"));
+    assert!(!stdout.contains("Inner::fail()"));
+});
+
+// tests that a gas receipt is only printed if a single test is selected
+forgetest!(can_print_gas_receipt_of_single_test, |prj: TestProject, mut cmd: TestCommand| {
+    prj.insert_ds_test();
+    prj.inner()
+        .add_source(
+            "CounterTest.t.sol",
+            r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity 0.8.10;
+import "./test.sol";
+contract Counter {
+    uint256 public number;
+
+    function increment() external {
+        number++;
+    }
+}
+contract CounterTest is DSTest {
+    function testIncrement() external {
+        Counter counter = new Counter();
+        counter.increment();
+    }
+
+    function testIncrementTwice() external {
+        Counter counter = new Counter();
+        counter.increment();
+        counter.increment();
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    cmd.args(["test", "--gas-report", "--match-test", "testIncrementTwice"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("Gas receipt of CounterTest::testIncrementTwice()"));
+
+    cmd.forge_fuse().args(["test", "--gas-report", "--match-test", "testIncrement"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(!stdout.contains("Gas receipt of"));
+    assert!(stdout.contains("increment"));
+});
@@ -1,9 +1,11 @@
 //! commonly used calculations
 
-use ethers_core::types::U256;
+use ethers_core::types::{U256, U512};
 use std::ops::{Add, Div};
 
-/// Returns the mean of the slice
+/// Returns the mean of the slice, rounded down
+///
+/// This does not overflow, even if the sum of all values exceeds `U256::MAX`.
 #[inline]
 pub fn mean<T>(values: &[T]) -> U256
 where
@@ -13,7 +15,20 @@ where
         return U256::zero()
     }
 
-    values.iter().copied().fold(U256::zero(), |sum, val| sum + val.into()) / values.len()
+    // floor(sum / n) = sum(v / n) + floor(sum(v % n) / n), neither of which can overflow
+    let len = U256::from(values.len());
+    let (quotients, remainders) =
+        values.iter().fold((U256::zero(), U256::zero()), |(quotients, remainders), val| {
+            let val: U256 = (*val).into();
+            let (quotient, remainder) = val.div_mod(len);
+            (quotients + quotient, remainders + remainder)
+        });
+    quotients + remainders / len
+}
+
+/// Returns the sum of all values, or `None` if it overflows
+pub fn sum_checked(values: &[U256]) -> Option<U256> {
+    values.iter().try_fold(U256::zero(), |sum, val| sum.checked_add(*val))
 }
 
 /// Returns the median of a _sorted_ slice
@@ -35,9 +50,77 @@ where
     }
 }
 
+/// Returns the `p`-th percentile of a _sorted_ slice, with `p` between 0 and 100
+///
+/// This uses the nearest-rank method: the result is the smallest value that is greater than or
+/// equal to `p` percent of all values. The 0th percentile is the minimum.
+pub fn percentile_sorted(values: &[U256], p: f64) -> U256 {
+    if values.is_empty() {
+        return U256::zero()
+    }
+    let rank = (p.clamp(0.0, 100.0) * values.len() as f64 / 100.0).ceil() as usize;
+    values[rank.saturating_sub(1).min(values.len() - 1)]
+}
+
+/// Returns the population standard deviation of the slice, rounded down
+///
+/// The squared deviations are computed with 512 bits, so this does not overflow for any input.
+pub fn std_dev(values: &[U256]) -> U256 {
+    if values.len() < 2 {
+        return U256::zero()
+    }
+    let mean = mean(values);
+    let len = U512::from(values.len());
+
+    // same as for the mean, the variance is bounded by the largest squared deviation
+    let (quotients, remainders) =
+        values.iter().fold((U512::zero(), U512::zero()), |(quotients, remainders), val| {
+            let deviation = if *val > mean { *val - mean } else { mean - *val };
+            let (quotient, remainder) = deviation.full_mul(deviation).div_mod(len);
+            (quotients + quotient, remainders + remainder)
+        });
+    let variance = quotients + remainders / len;
+    U256::try_from(variance.integer_sqrt()).expect("square root of a 512 bit value fits 256 bits")
+}
+
+/// Summary statistics of a set of values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of values
+    pub count: usize,
+    pub min: U256,
+    pub max: U256,
+    /// The mean, rounded down
+    pub mean: U256,
+    pub median: U256,
+    /// The 90th percentile, see [`percentile_sorted`]
+    pub p90: U256,
+    /// The 95th percentile, see [`percentile_sorted`]
+    pub p95: U256,
+    /// The population standard deviation, rounded down
+    pub std_dev: U256,
+}
+
+impl Stats {
+    /// Computes the statistics of a _sorted_ slice, all of them are zero for an empty slice
+    pub fn from_sorted(values: &[U256]) -> Self {
+        Self {
+            count: values.len(),
+            min: values.first().copied().unwrap_or_default(),
+            max: values.last().copied().unwrap_or_default(),
+            mean: mean(values),
+            median: median_sorted(values),
+            p90: percentile_sorted(values, 90.0),
+            p95: percentile_sorted(values, 95.0),
+            std_dev: std_dev(values),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn calc_mean_empty() {
@@ -75,4 +158,90 @@ mod tests {
         let m = median_sorted(&values);
         assert_eq!(m, 45);
     }
+
+    #[test]
+    fn calc_mean_does_not_overflow() {
+        let values = [U256::MAX, U256::MAX, U256::MAX - 2];
+        assert_eq!(mean(&values), U256::MAX - 1);
+        assert_eq!(sum_checked(&values), None);
+        assert_eq!(sum_checked(&[U256::one(), U256::from(2)]), Some(U256::from(3)));
+    }
+
+    #[test]
+    fn calc_percentile() {
+        let values: Vec<U256> = (1u64..=10).map(U256::from).collect();
+        assert_eq!(percentile_sorted(&values, 0.0), U256::from(1));
+        assert_eq!(percentile_sorted(&values, 50.0), U256::from(5));
+        assert_eq!(percentile_sorted(&values, 90.0), U256::from(9));
+        assert_eq!(percentile_sorted(&values, 100.0), U256::from(10));
+        assert_eq!(percentile_sorted(&[], 90.0), U256::zero());
+        assert_eq!(percentile_sorted(&[U256::from(7)], 90.0), U256::from(7));
+    }
+
+    #[test]
+    fn calc_std_dev() {
+        let values: Vec<U256> = [2u64, 4, 4, 4, 5, 5, 7, 9].into_iter().map(U256::from).collect();
+        assert_eq!(std_dev(&values), U256::from(2));
+        assert_eq!(std_dev(&[U256::from(5)]), U256::zero());
+        assert_eq!(std_dev(&[U256::zero(), U256::MAX]), U256::MAX / 2);
+    }
+
+    #[test]
+    fn calc_stats() {
+        assert_eq!(Stats::from_sorted(&[]), Stats::default());
+
+        let single = Stats::from_sorted(&[U256::from(42)]);
+        assert_eq!((single.min, single.max, single.mean), (42.into(), 42.into(), 42.into()));
+        assert_eq!((single.median, single.p90, single.std_dev), (42.into(), 42.into(), 0.into()));
+        assert_eq!(single.p95, 42.into());
+    }
+
+    /// Straightforward implementations to compare against, exact for `u128` inputs
+    mod reference {
+        use super::*;
+
+        pub fn mean(values: &[u128]) -> U512 {
+            values.iter().fold(U512::zero(), |sum, val| sum + U512::from(*val)) /
+                U512::from(values.len())
+        }
+
+        pub fn std_dev(values: &[u128]) -> U512 {
+            let mean = mean(values);
+            let sum = values.iter().fold(U512::zero(), |sum, val| {
+                let val = U512::from(*val);
+                let deviation = if val > mean { val - mean } else { mean - val };
+                sum + deviation * deviation
+            });
+            (sum / U512::from(values.len())).integer_sqrt()
+        }
+
+        /// The smallest value that at least `p` percent of all values are lower than or equal to
+        pub fn percentile(values: &[u128], p: f64) -> u128 {
+            let mut sorted = values.to_vec();
+            sorted.sort_unstable();
+            *sorted
+                .iter()
+                .find(|val| {
+                    let below = values.iter().filter(|other| other <= val).count();
+                    below as f64 * 100.0 >= p * values.len() as f64
+                })
+                .unwrap()
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn stats_match_reference(mut values in proptest::collection::vec(any::<u128>(), 1..64)) {
+            values.sort_unstable();
+            let sorted: Vec<U256> = values.iter().map(|val| U256::from(*val)).collect();
+            let stats = Stats::from_sorted(&sorted);
+
+            prop_assert_eq!(stats.count, values.len());
+            prop_assert_eq!(stats.min, U256::from(values[0]));
+            prop_assert_eq!(stats.max, U256::from(*values.last().unwrap()));
+            prop_assert_eq!(U512::from(stats.mean), reference::mean(&values));
+            prop_assert_eq!(U512::from(stats.std_dev), reference::std_dev(&values));
+            prop_assert_eq!(stats.p90, U256::from(reference::percentile(&values, 90.0)));
+        }
+    }
 }
@@ -0,0 +1,262 @@
+//! Support for multiple Etherscan keys
+
+use crate::{rpc::RE_PLACEHOLDER, Chain, RpcEndpoint, UnresolvedEnvVarError};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::BTreeMap, fmt, ops::Deref, str::FromStr};
+
+/// The value that is displayed instead of API keys that are set in plain text
+pub const REDACTED_KEY: &str = "<redacted>";
+
+/// Container type for the Etherscan configs of multiple chains, by their alias
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EtherscanConfigs {
+    configs: BTreeMap<String, EtherscanConfig>,
+}
+
+// === impl EtherscanConfigs ===
+
+impl EtherscanConfigs {
+    /// Creates a new list of Etherscan configs
+    pub fn new(configs: impl IntoIterator<Item = (impl Into<String>, EtherscanConfig)>) -> Self {
+        Self { configs: configs.into_iter().map(|(name, config)| (name.into(), config)).collect() }
+    }
+
+    /// Returns `true` if this type holds no configs
+    pub fn is_empty(&self) -> bool {
+        self.configs.is_empty()
+    }
+
+    /// Returns the alias and the config of the entry for the `chain`
+    pub fn find_chain(&self, chain: Chain) -> Option<(&str, &EtherscanConfig)> {
+        self.configs
+            .iter()
+            .find(|(alias, config)| config.chain_or_alias(alias) == Some(chain.id()))
+            .map(|(alias, config)| (alias.as_str(), config))
+    }
+
+    /// Returns a copy of the configs in which all keys that are set in plain text are replaced by
+    /// [`REDACTED_KEY`], keys that reference env vars keep their placeholder
+    pub fn redacted(&self) -> Self {
+        let configs = self
+            .configs
+            .iter()
+            .map(|(alias, config)| {
+                let key = match &config.key {
+                    EtherscanApiKey::Key(_) => EtherscanApiKey::Key(REDACTED_KEY.to_string()),
+                    EtherscanApiKey::Env(val) => EtherscanApiKey::Env(val.clone()),
+                };
+                (alias.clone(), EtherscanConfig { key, ..config.clone() })
+            })
+            .collect();
+        Self { configs }
+    }
+}
+
+impl Deref for EtherscanConfigs {
+    type Target = BTreeMap<String, EtherscanConfig>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.configs
+    }
+}
+
+/// The Etherscan config of a single chain
+///
+/// ```toml
+/// [etherscan]
+/// mainnet = { key = "${ETHERSCAN_MAINNET_KEY}" }
+/// l2 = { key = "ABCDEF", chain = 10, url = "https://api-optimistic.etherscan.io/api" }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EtherscanConfig {
+    /// The chain of this config, if not set the alias of the config is used as the chain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain: Option<Chain>,
+    /// The Etherscan API URL of the chain, for Etherscan deployments the chain is not known for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The Etherscan API key
+    pub key: EtherscanApiKey,
+}
+
+// === impl EtherscanConfig ===
+
+impl EtherscanConfig {
+    /// Returns the id of the chain of this config or of the chain named by its `alias`
+    fn chain_or_alias(&self, alias: &str) -> Option<u64> {
+        self.chain.or_else(|| Chain::from_str(alias).ok()).map(|chain| chain.id())
+    }
+
+    /// Returns the config with its env var placeholders resolved
+    ///
+    /// # Error
+    ///
+    /// Returns an error if the key or the url reference an env var that is not set
+    pub fn resolve(self) -> Result<ResolvedEtherscanConfig, UnresolvedEnvVarError> {
+        let EtherscanConfig { chain, url, key } = self;
+        let url = url.map(|url| RpcEndpoint::interpolate(&url)).transpose()?;
+        Ok(ResolvedEtherscanConfig { chain, url, key: key.resolve()? })
+    }
+}
+
+/// An Etherscan config with its env var placeholders resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedEtherscanConfig {
+    /// The chain of the config, `None` if it is named by the alias of the config
+    pub chain: Option<Chain>,
+    /// The Etherscan API URL of the chain
+    pub url: Option<String>,
+    /// The Etherscan API key
+    pub key: String,
+}
+
+/// Represents a single Etherscan API key
+///
+/// Like [`RpcEndpoint`], this type preserves the value as it's stored in the config. If the key is
+/// a reference to an env var, then `EtherscanApiKey::Env` holds the reference (`${MAIN_NET_KEY}`)
+/// and _not_ the value of the env var itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EtherscanApiKey {
+    /// A key in plain text
+    Key(String),
+    /// A key that contains at least one `${ENV_VAR}` placeholder
+    Env(String),
+}
+
+// === impl EtherscanApiKey ===
+
+impl EtherscanApiKey {
+    /// Returns the key this type holds
+    ///
+    /// # Error
+    ///
+    /// Returns an error if the type holds a reference to an env var and the env var is not set
+    pub fn resolve(self) -> Result<String, UnresolvedEnvVarError> {
+        match self {
+            EtherscanApiKey::Key(key) => Ok(key),
+            EtherscanApiKey::Env(val) => RpcEndpoint::interpolate(&val),
+        }
+    }
+}
+
+impl fmt::Display for EtherscanApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EtherscanApiKey::Key(key) => key.fmt(f),
+            EtherscanApiKey::Env(var) => var.fmt(f),
+        }
+    }
+}
+
+impl Serialize for EtherscanApiKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for EtherscanApiKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let val = String::deserialize(deserializer)?;
+        let key = if RE_PLACEHOLDER.is_match(&val) {
+            EtherscanApiKey::Env(val)
+        } else {
+            EtherscanApiKey::Key(val)
+        };
+
+        Ok(key)
+    }
+}
+
+/// Error when no Etherscan API key could be found for a chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EtherscanConfigError {
+    /// The key of the `[etherscan]` entry of the chain could not be resolved
+    Unresolved {
+        /// The alias of the entry
+        alias: String,
+        /// The error of resolving the key
+        err: UnresolvedEnvVarError,
+    },
+    /// Neither the `[etherscan]` table nor `etherscan_api_key` provide a key for the chain
+    MissingKey {
+        /// The chain a key was required for
+        chain: Option<Chain>,
+        /// The aliases of the `[etherscan]` entries whose key could not be resolved
+        unresolved: Vec<String>,
+    },
+}
+
+impl fmt::Display for EtherscanConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EtherscanConfigError::Unresolved { alias, err } => {
+                write!(f, "Failed to resolve the Etherscan API key of `[etherscan.{alias}]`: {err}")
+            }
+            EtherscanConfigError::MissingKey { chain, unresolved } => {
+                match chain {
+                    Some(chain) => write!(f, "No Etherscan API key found for chain `{chain}`.")?,
+                    None => write!(f, "No Etherscan API key found.")?,
+                }
+                write!(
+                    f,
+                    " Consider adding an entry for the chain to the `[etherscan]` table in \
+                     foundry.toml, or setting the ETHERSCAN_API_KEY env var or the \
+                     --etherscan-api-key argument"
+                )?;
+                if !unresolved.is_empty() {
+                    write!(
+                        f,
+                        "\nThe keys of these `[etherscan]` entries reference env vars that are not \
+                         set: {}",
+                        unresolved.join(", ")
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for EtherscanConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_find_chain() {
+        let configs = EtherscanConfigs::new([
+            (
+                "mainnet",
+                EtherscanConfig {
+                    chain: None,
+                    url: None,
+                    key: EtherscanApiKey::Key("mainnet-key".to_string()),
+                },
+            ),
+            (
+                "l2",
+                EtherscanConfig {
+                    chain: Some(Chain::Id(10)),
+                    url: None,
+                    key: EtherscanApiKey::Env("${L2_KEY}".to_string()),
+                },
+            ),
+        ]);
+
+        assert_eq!(configs.find_chain(Chain::Id(1)).unwrap().0, "mainnet");
+        assert_eq!(configs.find_chain(Chain::Id(10)).unwrap().0, "l2");
+        assert!(configs.find_chain(Chain::Id(137)).is_none());
+
+        let redacted = configs.redacted();
+        assert_eq!(redacted["mainnet"].key, EtherscanApiKey::Key(REDACTED_KEY.to_string()));
+        assert_eq!(redacted["l2"].key, configs["l2"].key);
+    }
+}
@@ -0,0 +1,361 @@
+//! Support for controlling file system access of cheatcodes
+//!
+//! ```toml
+//! [profile.default]
+//! fs_permissions = [
+//!     { access = "read", path = "./config/**.json" },
+//!     { access = "none", path = "./config/secrets.json" },
+//!     { access = "read-write", path = "./out" },
+//! ]
+//! ```
+//!
+//! A path without glob characters grants access to the path itself and everything below it. If
+//! multiple entries match a path, the most specific one wins, ties are resolved in favor of the
+//! entry that is declared last.
+
+use globset::{GlobBuilder, GlobMatcher};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+};
+
+/// The access level granted by a [`PathPermission`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FsAccessPermission {
+    /// No access at all, overrides broader grants
+    None,
+    /// Only reading is allowed
+    Read,
+    /// Only writing is allowed
+    Write,
+    /// Reading and writing is allowed
+    ReadWrite,
+}
+
+impl FsAccessPermission {
+    /// Returns whether this permission allows the given operation
+    pub fn allows(&self, kind: FsAccessKind) -> bool {
+        matches!(
+            (self, kind),
+            (FsAccessPermission::ReadWrite, _) |
+                (FsAccessPermission::Read, FsAccessKind::Read) |
+                (FsAccessPermission::Write, FsAccessKind::Write)
+        )
+    }
+}
+
+impl fmt::Display for FsAccessPermission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FsAccessPermission::None => f.write_str("none"),
+            FsAccessPermission::Read => f.write_str("read"),
+            FsAccessPermission::Write => f.write_str("write"),
+            FsAccessPermission::ReadWrite => f.write_str("read-write"),
+        }
+    }
+}
+
+/// An operation on the file system
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsAccessKind {
+    Read,
+    Write,
+}
+
+impl fmt::Display for FsAccessKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FsAccessKind::Read => f.write_str("read"),
+            FsAccessKind::Write => f.write_str("write"),
+        }
+    }
+}
+
+impl FromStr for FsAccessKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(FsAccessKind::Read),
+            "write" => Ok(FsAccessKind::Write),
+            _ => Err(format!("unknown file system operation `{s}`, expected `read` or `write`")),
+        }
+    }
+}
+
+/// Grants `access` to all paths matching `path`, relative to the project root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathPermission {
+    pub access: FsAccessPermission,
+    /// A path or glob pattern
+    pub path: String,
+}
+
+impl PathPermission {
+    pub fn new(access: FsAccessPermission, path: impl Into<String>) -> Self {
+        Self { access, path: path.into() }
+    }
+
+    /// Returns `true` if the path contains glob characters
+    fn is_glob(&self) -> bool {
+        self.path.contains(is_glob_char)
+    }
+
+    /// Returns the normalized part of the path before the first glob character, joined with `root`
+    fn base(&self, root: &Path) -> PathBuf {
+        let literal = self
+            .path
+            .split('/')
+            .take_while(|component| !component.contains(is_glob_char))
+            .collect::<Vec<_>>()
+            .join("/");
+        normalize(&root.join(literal))
+    }
+
+    /// Returns the glob matching absolute paths, if this is a glob pattern
+    fn matcher(&self, root: &Path) -> Option<GlobMatcher> {
+        if !self.is_glob() {
+            return None
+        }
+        let pattern = normalize(&root.join(&self.path)).to_string_lossy().to_string();
+        let glob = GlobBuilder::new(&expand_recursive_wildcards(&pattern))
+            .literal_separator(true)
+            .build()
+            .ok()?;
+        Some(glob.compile_matcher())
+    }
+
+    /// Returns `true` if this permission applies to the normalized `path`
+    fn matches(&self, root: &Path, path: &Path) -> bool {
+        match self.matcher(root) {
+            Some(matcher) => matcher.is_match(path),
+            None => path.starts_with(self.base(root)),
+        }
+    }
+
+    /// How specific this permission is, literal paths are more specific than globs with the same
+    /// base
+    fn specificity(&self, root: &Path) -> (usize, bool) {
+        (self.base(root).components().count(), !self.is_glob())
+    }
+}
+
+impl fmt::Display for PathPermission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, r#"{{ access = "{}", path = "{}" }}"#, self.access, self.path)
+    }
+}
+
+/// The file system permissions of cheatcodes
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FsPermissions {
+    permissions: Vec<PathPermission>,
+}
+
+impl FsPermissions {
+    pub fn new(permissions: impl IntoIterator<Item = PathPermission>) -> Self {
+        Self { permissions: permissions.into_iter().collect() }
+    }
+
+    /// Returns `true` if no permissions are configured
+    pub fn is_empty(&self) -> bool {
+        self.permissions.is_empty()
+    }
+
+    /// Returns all configured permissions
+    pub fn permissions(&self) -> &[PathPermission] {
+        &self.permissions
+    }
+
+    /// Resolves which permission applies to a `kind` access of the `path`
+    ///
+    /// Relative paths are resolved against the `root`, `..` components and symlinks are resolved
+    /// before any permission is consulted.
+    pub fn resolve(&self, root: &Path, path: &Path, kind: FsAccessKind) -> FsAccessResolution {
+        let root = canonicalize(root);
+        let path = canonicalize(&root.join(path));
+
+        let rule = self
+            .permissions
+            .iter()
+            .enumerate()
+            .filter(|(_, permission)| permission.matches(&root, &path))
+            .max_by_key(|(idx, permission)| (permission.specificity(&root), *idx))
+            .map(|(_, permission)| permission.clone());
+
+        let nearest = if rule.is_none() {
+            self.permissions
+                .iter()
+                .enumerate()
+                .max_by_key(|(idx, permission)| {
+                    let base = permission.base(&root);
+                    let common =
+                        base.components().zip(path.components()).take_while(|(a, b)| a == b);
+                    (common.count(), *idx)
+                })
+                .map(|(_, permission)| permission.clone())
+        } else {
+            None
+        };
+
+        FsAccessResolution { path, root, kind, rule, nearest }
+    }
+}
+
+/// The outcome of [`FsPermissions::resolve()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FsAccessResolution {
+    /// The normalized path that was accessed
+    pub path: PathBuf,
+    /// The normalized root the path was resolved against, paths below it are displayed relative
+    /// to it
+    pub root: PathBuf,
+    /// The attempted operation
+    pub kind: FsAccessKind,
+    /// The permission that applies to the path, if any
+    pub rule: Option<PathPermission>,
+    /// The permission closest to the path, if none applies
+    pub nearest: Option<PathPermission>,
+}
+
+impl FsAccessResolution {
+    /// Returns `true` if the access is allowed
+    pub fn is_allowed(&self) -> bool {
+        self.rule.as_ref().map(|rule| rule.access.allows(self.kind)).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for FsAccessResolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.is_allowed() { "allowed" } else { "denied" };
+        let path = self.path.strip_prefix(&self.root).unwrap_or(&self.path);
+        write!(f, "{} access to `{}` is {verdict}", self.kind, path.display())?;
+        match (&self.rule, &self.nearest) {
+            (Some(rule), _) => write!(f, " by `fs_permissions` entry {rule}"),
+            (None, Some(nearest)) => {
+                write!(f, ", no `fs_permissions` entry matches, the nearest is {nearest}")
+            }
+            (None, None) => write!(f, ", no `fs_permissions` are configured"),
+        }
+    }
+}
+
+fn is_glob_char(c: char) -> bool {
+    matches!(c, '*' | '?' | '[' | '{')
+}
+
+/// Lexically resolves `.` and `..` components
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Resolves symlinks of the longest existing ancestor of the `path` and normalizes the rest
+fn canonicalize(path: &Path) -> PathBuf {
+    let path = normalize(path);
+    let mut existing = path.as_path();
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return rest.into_iter().rev().fold(canonical, |path, component| path.join(component))
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_owned());
+                existing = parent;
+            }
+            _ => return path,
+        }
+    }
+}
+
+/// Turns a `**` that is not a whole path component into a recursive match, so that
+/// `config/**.json` matches the same paths as `config/**/*.json`
+fn expand_recursive_wildcards(pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(pos) = rest.find("**") {
+        let (head, tail) = rest.split_at(pos);
+        let tail = &tail[2..];
+        out.push_str(head);
+        if !head.is_empty() && !head.ends_with('/') {
+            out.push_str("*/");
+        }
+        out.push_str("**");
+        if !tail.is_empty() && !tail.starts_with('/') {
+            out.push_str("/*");
+        }
+        rest = tail;
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn permissions() -> FsPermissions {
+        FsPermissions::new([
+            PathPermission::new(FsAccessPermission::Read, "./config/**.json"),
+            PathPermission::new(FsAccessPermission::None, "./config/secrets.json"),
+            PathPermission::new(FsAccessPermission::ReadWrite, "./out"),
+        ])
+    }
+
+    #[test]
+    fn expands_recursive_wildcards() {
+        assert_eq!(expand_recursive_wildcards("config/**.json"), "config/**/*.json");
+        assert_eq!(expand_recursive_wildcards("config/**/*.json"), "config/**/*.json");
+        assert_eq!(expand_recursive_wildcards("config/a**"), "config/a*/**");
+    }
+
+    #[test]
+    fn resolves_most_specific_permission() {
+        let root = Path::new("/nonexistent/project");
+        let permissions = permissions();
+
+        let resolution =
+            permissions.resolve(root, Path::new("config/a/b.json"), FsAccessKind::Read);
+        assert!(resolution.is_allowed(), "{resolution}");
+        assert!(!permissions
+            .resolve(root, Path::new("config/a/b.json"), FsAccessKind::Write)
+            .is_allowed());
+
+        let resolution =
+            permissions.resolve(root, Path::new("config/secrets.json"), FsAccessKind::Read);
+        assert!(!resolution.is_allowed());
+        assert_eq!(resolution.rule.unwrap().access, FsAccessPermission::None);
+
+        assert!(permissions
+            .resolve(root, Path::new("out/nested/data.txt"), FsAccessKind::Write)
+            .is_allowed());
+    }
+
+    #[test]
+    fn prevents_escaping_grants() {
+        let root = Path::new("/nonexistent/project");
+        let resolution =
+            permissions().resolve(root, Path::new("out/../foundry.toml"), FsAccessKind::Read);
+        assert!(!resolution.is_allowed());
+        assert_eq!(resolution.path, PathBuf::from("/nonexistent/project/foundry.toml"));
+        assert!(resolution.rule.is_none());
+        assert_eq!(
+            resolution.to_string(),
+            r#"read access to `foundry.toml` is denied, no `fs_permissions` entry matches, the nearest is { access = "read-write", path = "./out" }"#
+        );
+    }
+}
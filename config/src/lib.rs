@@ -23,10 +23,10 @@ use figment::{
 use inflector::Inflector;
 use regex::Regex;
 use semver::Version;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     borrow::Cow,
-    collections::{hash_map::Entry, BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet},
     fs,
     path::{Path, PathBuf},
     str::FromStr,
@@ -43,6 +43,12 @@ pub use crate::utils::*;
 mod rpc;
 pub use rpc::{ResolvedRpcEndpoints, RpcEndpoint, RpcEndpoints, UnresolvedEnvVarError};
 
+mod etherscan;
+pub use etherscan::{
+    EtherscanApiKey, EtherscanConfig, EtherscanConfigError, EtherscanConfigs,
+    ResolvedEtherscanConfig, REDACTED_KEY,
+};
+
 pub mod cache;
 use cache::{Cache, ChainCache};
 
@@ -52,6 +58,12 @@ pub use chain::Chain;
 pub mod fmt;
 pub use fmt::FormatterConfig;
 
+pub mod mutation;
+pub use mutation::{MutationConfig, MutationOperator};
+
+pub mod fs_permissions;
+pub use fs_permissions::{FsAccessKind, FsAccessPermission, FsPermissions, PathPermission};
+
 mod error;
 pub use error::SolidityErrorCode;
 
@@ -120,6 +132,8 @@ pub struct Config {
     pub libs: Vec<PathBuf>,
     /// `Remappings` to use for this repo
     pub remappings: Vec<RelativeRemapping>,
+    /// whether conflicting auto-detected remappings are an error instead of a warning
+    pub deny_remapping_conflicts: bool,
     /// library addresses to link
     pub libraries: Vec<String>,
     /// whether to enable cache
@@ -137,6 +151,35 @@ pub struct Config {
     pub evm_version: EvmVersion,
     /// list of contracts to report gas of
     pub gas_reports: Vec<String>,
+    /// whether calls of proxy upgrade functions are reported separately in the gas report
+    #[serde(alias = "gas_reports_upgrades")]
+    pub report_upgrades: bool,
+    /// whether the gas report only keeps running statistics per function instead of the gas of
+    /// every call, which bounds its memory but omits the median
+    pub gas_reports_streaming: bool,
+    /// the maximum number of calls whose gas the gas report keeps per function, beyond it a
+    /// reservoir sample of the calls is kept and the statistics are approximations
+    pub gas_reports_max_samples: Option<usize>,
+    /// whether the gas report colors the average gas of every function relative to the median
+    /// function of its contract instead of with a fixed color
+    pub gas_reports_relative_coloring: bool,
+    /// whether the gas report shows the average gas per call that every function spent on
+    /// expanding memory
+    pub gas_reports_memory_gas: bool,
+    /// whether the gas report flags the functions that forward all of their remaining gas to an
+    /// address that is not a known contract, as potential gas griefing vectors
+    pub gas_reports_flag_griefing: bool,
+    /// the gas that the gas report subtracts from every call of a function, e.g. the overhead the
+    /// test harness adds to every measured call
+    pub gas_reports_noise_floor: u64,
+    /// the order in which `forge test` prints the results of the test suites
+    pub test_output_order: TestOutputOrder,
+    /// glob patterns of the source paths whose contracts are left out of the gas report, e.g.
+    /// test helpers, the calls they make are still reported
+    pub gas_report_exclude_paths: Vec<String>,
+    /// the mean gas thresholds that separate the tiers of `forge test --gas-report-tiered`, by
+    /// default functions are split into cheap, moderate and expensive ones
+    pub gas_report_tiers: Vec<u64>,
     /// The Solc instance to use if any.
     ///
     /// This takes precedence over `auto_detect_solc`, if a version is set then this overrides
@@ -147,12 +190,16 @@ pub struct Config {
     pub solc: Option<SolcReq>,
     /// whether to autodetect the solc compiler version to use
     pub auto_detect_solc: bool,
-    /// Offline mode, if set, network access (downloading solc) is disallowed.
+    /// Offline mode, if set, network access is disallowed: solc is not downloaded, traces are
+    /// only decoded with cached signatures and without Etherscan, and verification fails.
+    ///
+    /// Can also be enabled with `FOUNDRY_OFFLINE=1`.
     ///
     /// Relationship with `auto_detect_solc`:
     ///    - if `auto_detect_solc = true` and `offline = true`, the required solc version(s) will
     ///      be auto detected but if the solc version is not installed, it will _not_ try to
     ///      install it
+    #[serde(deserialize_with = "utils::deserialize_bool_or_int")]
     pub offline: bool,
     /// Whether to activate optimizer
     pub optimizer: bool,
@@ -170,6 +217,9 @@ pub struct Config {
     pub eth_rpc_url: Option<String>,
     /// etherscan API key
     pub etherscan_api_key: Option<String>,
+    /// Etherscan API keys of multiple chains and their aliases
+    #[serde(default, skip_serializing_if = "EtherscanConfigs::is_empty")]
+    pub etherscan: EtherscanConfigs,
     /// list of solidity error codes to always silence in the compiler output
     pub ignored_error_codes: Vec<SolidityErrorCode>,
     /// Only run test functions matching the specified regex pattern.
@@ -190,6 +240,10 @@ pub struct Config {
     /// Only run tests in source files that do not match the specified glob pattern.
     #[serde(rename = "no_match_path", with = "from_opt_glob")]
     pub path_pattern_inverse: Option<globset::Glob>,
+    /// Only run tests in source files that do not match the specified glob pattern, relative to
+    /// the project root.
+    #[serde(rename = "no_match_contract_source", with = "from_opt_glob")]
+    pub contract_source_pattern_inverse: Option<globset::Glob>,
     /// The number of test cases that must execute for each property test
     pub fuzz_runs: u32,
     /// The number of runs that must execute for each invariant test group.
@@ -203,6 +257,16 @@ pub struct Config {
     pub invariant_call_override: bool,
     /// Whether to allow ffi cheatcodes in test
     pub ffi: bool,
+    /// The file system permissions of cheatcodes, see [`FsPermissions`]
+    ///
+    /// If empty, cheatcodes can read and write the project root, the `libs` and the `allow_paths`.
+    pub fs_permissions: FsPermissions,
+    /// Whether to execute every top-level call of a test as its own transaction, which bumps the
+    /// nonce of the test contract and charges the intrinsic gas of each call
+    pub isolate: bool,
+    /// The maximum number of logs that the `recordLogs` cheatcode keeps, the oldest ones are
+    /// dropped once more are recorded
+    pub recorded_logs_limit: usize,
     /// The address which will be executing all tests
     pub sender: Address,
     /// The tx.origin value during EVM execution
@@ -311,6 +375,8 @@ pub struct Config {
     pub build_info_path: Option<PathBuf>,
     /// Configuration for `forge fmt`
     pub fmt: FormatterConfig,
+    /// Configuration for `forge test --mutate`
+    pub mutation: MutationConfig,
     /// The root path where the config detection started from, `Config::with_root`
     #[doc(hidden)]
     //  We're skipping serialization here, so it won't be included in the [`Config::to_string()`]
@@ -335,6 +401,10 @@ pub struct Config {
     /// Warnings gathered when loading the Config. See [`WarningsProvider`] for more information
     #[serde(default, skip_serializing)]
     pub __warnings: Vec<Warning>,
+    /// The original values of all keys that contained env var placeholders, see
+    /// [`EnvInterpolationProvider`]
+    #[serde(default, skip_serializing)]
+    pub __interpolated: BTreeMap<String, Value>,
 }
 
 impl Config {
@@ -347,8 +417,12 @@ impl Config {
     /// TOML section for profiles
     pub const PROFILE_SECTION: &'static str = "profile";
 
+    /// The key of a profile that names the profile it inherits from
+    pub const INHERITS_KEY: &'static str = "inherits";
+
     /// Standalone sections in the config which get integrated into the selected profile
-    pub const STANDALONE_SECTIONS: &'static [&'static str] = &["rpc_endpoints", "fmt"];
+    pub const STANDALONE_SECTIONS: &'static [&'static str] =
+        &["rpc_endpoints", "etherscan", "fmt", "mutation"];
 
     /// File name of config toml file
     pub const FILE_NAME: &'static str = "foundry.toml";
@@ -711,10 +785,20 @@ impl Config {
     /// contracts/tokens/token.sol
     /// contracts/math/math.sol
     /// ```
+    ///
+    /// The context of context-scoped remappings, `<context>:<name>=<path>`, is resolved against
+    /// the root as well, because solc matches it against the absolute source unit names.
     pub fn get_all_remappings(&self) -> Vec<Remapping> {
         self.remappings
             .iter()
-            .map(|m| m.clone().into())
+            .map(|m| {
+                let mut remapping: Remapping = m.clone().into();
+                if let Some((context, name)) = remapping.name.split_once(':') {
+                    let context = self.__root.0.join(context).to_string_lossy().to_string();
+                    remapping.name = format!("{}/:{name}", context.trim_end_matches('/'));
+                }
+                remapping
+            })
             .chain(self.get_source_dir_remapping())
             .chain(self.get_test_dir_remapping())
             .chain(self.get_script_dir_remapping())
@@ -982,7 +1066,19 @@ impl Config {
     /// ```
     pub fn to_string_pretty(&self) -> Result<String, toml::ser::Error> {
         // serializing to value first to prevent `ValueAfterTable` errors
-        let mut value = toml::Value::try_from(self)?;
+        self.value_to_string_pretty(toml::Value::try_from(self)?)
+    }
+
+    /// Same as [`Config::to_string_pretty()`], but the values that may contain secrets are
+    /// replaced by their [`Config::redactions()`]
+    pub fn to_string_pretty_redacted(&self) -> Result<String, Error> {
+        let mut value = toml::Value::try_from(self).map_err(|err| err.to_string())?;
+        redact_toml(&mut value, self.redactions()?);
+        Ok(self.value_to_string_pretty(value).map_err(|err| err.to_string())?)
+    }
+
+    /// Serializes the config `value` as a String of TOML, see [`Config::to_string_pretty()`]
+    fn value_to_string_pretty(&self, mut value: toml::Value) -> Result<String, toml::ser::Error> {
         // Config map always gets serialized as a table
         let value_table = value.as_table_mut().unwrap();
         // remove standalone sections from inner table
@@ -1008,6 +1104,26 @@ impl Config {
         toml::to_string_pretty(&toml::Value::Table(wrapping_table))
     }
 
+    /// Returns the values to display instead of the values of the serialized config that may
+    /// contain secrets, by their key: the placeholders of the values that were interpolated from
+    /// env vars and the etherscan configs with their plain text keys redacted.
+    ///
+    /// The values are converted to `V`, e.g. a `toml::Value` or `serde_json::Value`, to replace
+    /// the values of the same keys in the serialized config, keys it does not contain, e.g. in a
+    /// [`BasicConfig`], are meant to be skipped.
+    pub fn redactions<V: DeserializeOwned>(&self) -> Result<Vec<(String, V)>, Error> {
+        let mut redactions = self
+            .__interpolated
+            .iter()
+            .map(|(key, placeholder)| Ok((key.clone(), placeholder.deserialize()?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        if !self.etherscan.is_empty() {
+            let etherscan = Value::serialize(self.etherscan.redacted())?.deserialize()?;
+            redactions.push(("etherscan".to_string(), etherscan));
+        }
+        Ok(redactions)
+    }
+
     /// Returns the path to the `foundry.toml`  of this `Config`
     pub fn get_config_path(&self) -> PathBuf {
         self.__root.0.join(Config::FILE_NAME)
@@ -1259,19 +1375,25 @@ impl Config {
             figment.merge(warnings)
         };
 
+        // resolve the profiles the selected profile inherits from, `[default, ..bases, profile]`
+        let profiles = match Config::resolve_profile_chain(&toml_provider, &profile) {
+            Ok(profiles) => profiles,
+            Err(mut err) => {
+                err.metadata = Some(toml_provider.metadata());
+                return figment.merge(FailingProvider(err))
+            }
+        };
+
         // use [profile.<profile>] as [<profile>]
-        let mut profiles = vec![Config::DEFAULT_PROFILE];
-        if profile != Config::DEFAULT_PROFILE {
-            profiles.push(profile.clone());
-        }
-        let provider = toml_provider.strict_select(profiles);
+        let provider = toml_provider.strict_select(profiles.clone());
 
-        // apply any key fixes
-        let provider = BackwardsCompatTomlProvider(ForcedSnakeCaseData(provider));
+        // apply any key fixes and resolve env var placeholders
+        let provider =
+            EnvInterpolationProvider(BackwardsCompatTomlProvider(ForcedSnakeCaseData(provider)));
 
-        // merge the default profile as a base
-        if profile != Config::DEFAULT_PROFILE {
-            figment = figment.merge(provider.rename(Config::DEFAULT_PROFILE, profile.clone()));
+        // merge the default profile and all inherited profiles as a base
+        for base in profiles.iter().filter(|base| **base != profile) {
+            figment = figment.merge(provider.rename(base.clone(), profile.clone()));
         }
         // merge special keys into config
         for standalone_key in Config::STANDALONE_SECTIONS {
@@ -1281,6 +1403,72 @@ impl Config {
         figment = figment.merge(provider);
         figment
     }
+
+    /// Returns the chain of profiles the given `profile` is based on, starting with the
+    /// [`Config::DEFAULT_PROFILE`] and ending with the `profile` itself.
+    ///
+    /// Every profile implicitly inherits from the default profile, another base profile can be
+    /// set explicitly via `inherits = "<profile>"`:
+    ///
+    /// ```toml
+    /// [profile.ci]
+    /// fuzz_runs = 10_000
+    ///
+    /// [profile.ci-fork]
+    /// inherits = "ci"
+    /// eth_rpc_url = "https://mainnet.infura.io"
+    /// ```
+    ///
+    /// Returns an error if the inheritance chain contains a cycle.
+    fn resolve_profile_chain(
+        toml_provider: &impl Provider,
+        profile: &Profile,
+    ) -> Result<Vec<Profile>, Error> {
+        let data = toml_provider.data().unwrap_or_default();
+        let profiles =
+            data.get(&Profile::new(Config::PROFILE_SECTION)).cloned().unwrap_or_default();
+
+        let base_of = |profile: &Profile| -> Result<Option<Profile>, Error> {
+            let base = profiles
+                .iter()
+                .find(|(name, _)| Profile::new(name) == *profile)
+                .and_then(|(_, value)| value.as_dict())
+                .and_then(|dict| dict.get(Config::INHERITS_KEY));
+            match base {
+                None => Ok(None),
+                Some(base) => base.as_str().map(|base| Some(Profile::new(base))).ok_or_else(|| {
+                    Error::from(format!(
+                        "`{}` of profile `{profile}` must be a profile name",
+                        Config::INHERITS_KEY
+                    ))
+                }),
+            }
+        };
+
+        let mut chain = vec![profile.clone()];
+        let mut current = profile.clone();
+        while current != Config::DEFAULT_PROFILE {
+            let base = match base_of(&current)? {
+                Some(base) => base,
+                None => break,
+            };
+            if chain.contains(&base) {
+                chain.push(base);
+                let cycle = chain.iter().map(|p| p.as_str().as_str()).collect::<Vec<_>>();
+                return Err(Error::from(format!(
+                    "profile inheritance cycle detected: {}",
+                    cycle.join(" -> ")
+                )))
+            }
+            chain.push(base.clone());
+            current = base;
+        }
+        if current != Config::DEFAULT_PROFILE {
+            chain.push(Config::DEFAULT_PROFILE);
+        }
+        chain.reverse();
+        Ok(chain)
+    }
 }
 
 impl From<Config> for Figment {
@@ -1325,6 +1513,10 @@ impl From<Config> for Figment {
                 .unwrap_or_else(|_| Cow::Borrowed(&c.libs)),
             root: &c.__root.0,
             remappings: figment.extract_inner::<Vec<Remapping>>("remappings"),
+            deny_conflicts: figment
+                .extract_inner::<bool>("deny_remapping_conflicts")
+                .unwrap_or(c.deny_remapping_conflicts),
+            warnings: figment.extract_inner::<Vec<Warning>>("__warnings").unwrap_or_default(),
         };
         let merge = figment.merge(remappings);
 
@@ -1470,6 +1662,16 @@ impl Default for Config {
             force: false,
             evm_version: Default::default(),
             gas_reports: vec!["*".to_string()],
+            report_upgrades: false,
+            gas_reports_streaming: false,
+            gas_reports_max_samples: None,
+            gas_reports_relative_coloring: false,
+            gas_reports_memory_gas: false,
+            gas_reports_flag_griefing: false,
+            gas_reports_noise_floor: 0,
+            test_output_order: TestOutputOrder::Completion,
+            gas_report_exclude_paths: vec!["test/**".to_string()],
+            gas_report_tiers: vec![50_000, 200_000],
             solc: None,
             auto_detect_solc: true,
             offline: false,
@@ -1487,6 +1689,7 @@ impl Default for Config {
             contract_pattern_inverse: None,
             path_pattern: None,
             path_pattern_inverse: None,
+            contract_source_pattern_inverse: None,
             fuzz_runs: 256,
             fuzz_max_local_rejects: 1024,
             fuzz_max_global_rejects: 65536,
@@ -1496,6 +1699,9 @@ impl Default for Config {
             invariant_fail_on_revert: false,
             invariant_call_override: false,
             ffi: false,
+            fs_permissions: Default::default(),
+            isolate: false,
+            recorded_logs_limit: 100_000,
             sender: Config::DEFAULT_SENDER,
             tx_origin: Config::DEFAULT_SENDER,
             initial_balance: U256::from(0xffffffffffffffffffffffffu128),
@@ -1512,8 +1718,10 @@ impl Default for Config {
             memory_limit: 2u64.pow(25),
             eth_rpc_url: None,
             etherscan_api_key: None,
+            etherscan: Default::default(),
             verbosity: 0,
             remappings: vec![],
+            deny_remapping_conflicts: false,
             libraries: vec![],
             ignored_error_codes: vec![
                 SolidityErrorCode::SpdxLicenseNotProvided,
@@ -1529,8 +1737,10 @@ impl Default for Config {
             build_info: false,
             build_info_path: None,
             fmt: Default::default(),
+            mutation: Default::default(),
             __non_exhaustive: (),
             __warnings: vec![],
+            __interpolated: Default::default(),
         }
     }
 }
@@ -1605,6 +1815,25 @@ impl<'de> Deserialize<'de> for GasLimit {
     }
 }
 
+/// The order in which `forge test` prints the results of the test suites, which run in parallel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestOutputOrder {
+    /// Print every suite as soon as it finished, the order differs between runs
+    Completion,
+    /// Print the suites sorted by name once all finished, which makes the output of identical
+    /// runs comparable
+    Alphabetical,
+    /// Print the suites from the fastest to the slowest once all finished
+    Duration,
+}
+
+impl Default for TestOutputOrder {
+    fn default() -> Self {
+        TestOutputOrder::Completion
+    }
+}
+
 /// Variants for selecting the [`Solc`] instance
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -1875,6 +2104,10 @@ struct RemappingsProvider<'a> {
     ///   - a `MissingField` error, which means previous provider didn't set the "remappings" field
     ///   - other error, like formatting
     remappings: Result<Vec<Remapping>, figment::Error>,
+    /// whether conflicting auto-detected remappings are an error
+    deny_conflicts: bool,
+    /// the warnings of the previous providers, conflicts are added to them
+    warnings: Vec<Warning>,
 }
 
 impl<'a> RemappingsProvider<'a> {
@@ -1888,20 +2121,24 @@ impl<'a> RemappingsProvider<'a> {
     /// - `remappings.txt`
     /// - Environment variables
     /// - CLI parameters
-    fn get_remappings(&self, remappings: Vec<Remapping>) -> Result<Vec<Remapping>, Error> {
+    ///
+    /// If multiple libraries provide an auto-detected remapping with the same name, the closest
+    /// one is used and a [`Warning::RemappingConflict`] is returned for it, unless the remapping
+    /// is set explicitly.
+    fn get_remappings(
+        &self,
+        remappings: Vec<Remapping>,
+    ) -> Result<(Vec<Remapping>, Vec<Warning>), Error> {
         trace!("get all remappings from {:?}", self.root);
-        /// prioritizes remappings that are closer: shorter `path`
-        ///   - ("a", "1/2") over ("a", "1/2/3")
-        fn insert_closest(mappings: &mut HashMap<String, PathBuf>, key: String, path: PathBuf) {
-            match mappings.entry(key) {
-                Entry::Occupied(mut e) => {
-                    if e.get().components().count() > path.components().count() {
-                        e.insert(path);
-                    }
-                }
-                Entry::Vacant(e) => {
-                    e.insert(path);
-                }
+        /// records another candidate path for the remapping `key`
+        fn insert_candidate(
+            mappings: &mut BTreeMap<String, Vec<PathBuf>>,
+            key: String,
+            path: PathBuf,
+        ) {
+            let candidates = mappings.entry(key).or_default();
+            if !candidates.contains(&path) {
+                candidates.push(path);
             }
         }
 
@@ -1927,10 +2164,10 @@ impl<'a> RemappingsProvider<'a> {
 
         new_remappings.extend(remappings);
 
-        let mut lib_remappings = HashMap::new();
+        let mut lib_remappings = BTreeMap::new();
         // find all remappings of from libs that use a foundry.toml
         for r in self.lib_foundry_toml_remappings() {
-            insert_closest(&mut lib_remappings, r.name, r.path.into());
+            insert_candidate(&mut lib_remappings, r.name, r.path.into());
         }
         // use auto detection for all libs
         for r in self
@@ -1946,20 +2183,71 @@ impl<'a> RemappingsProvider<'a> {
             if ["lib/", "src/", "contracts/"].contains(&r.name.as_str()) {
                 continue
             }
-            insert_closest(&mut lib_remappings, r.name, r.path.into());
+            insert_candidate(&mut lib_remappings, r.name, r.path.into());
         }
 
-        new_remappings.extend(
-            lib_remappings
-                .into_iter()
-                .map(|(name, path)| Remapping { name, path: path.to_string_lossy().into() }),
-        );
+        let mut conflicts = Vec::new();
+        for (name, candidates) in lib_remappings {
+            // prioritizes remappings that are closer: shorter `path`
+            //   - ("a", "1/2") over ("a", "1/2/3")
+            let chosen = candidates
+                .iter()
+                .min_by_key(|path| path.components().count())
+                .cloned()
+                .expect("at least one candidate");
+
+            if candidates.len() > 1 && !new_remappings.iter().any(|r| r.name == name) {
+                // every dependency that ships its own copy resolves its imports to that copy
+                for path in candidates.iter().filter(|path| **path != chosen) {
+                    if let Some(context) = self.dependency_context(path) {
+                        new_remappings.push(Remapping {
+                            name: format!("{context}:{name}"),
+                            path: path.to_string_lossy().into(),
+                        });
+                    }
+                }
+                conflicts.push(Warning::RemappingConflict {
+                    name: name.clone(),
+                    candidates: candidates.iter().map(|path| self.display_path(path)).collect(),
+                    chosen: self.display_path(&chosen),
+                });
+            }
+
+            new_remappings.push(Remapping { name, path: chosen.to_string_lossy().into() });
+        }
+
+        if self.deny_conflicts && !conflicts.is_empty() {
+            let conflicts = conflicts.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+            return Err(conflicts.join("\n").into())
+        }
 
         // remove duplicates at this point
         new_remappings.sort_by(|a, b| a.name.cmp(&b.name));
         new_remappings.dedup_by(|a, b| a.name.eq(&b.name));
 
-        Ok(new_remappings)
+        Ok((new_remappings, conflicts))
+    }
+
+    /// Returns the root of the nested dependency that contains the library at `path`, relative to
+    /// the project root, e.g. `lib/a/` for `lib/a/lib/b/src/`.
+    ///
+    /// Returns `None` for libraries installed in one of the project's own library folders.
+    fn dependency_context(&self, path: &Path) -> Option<String> {
+        let lib_names: Vec<_> = self.lib_paths.iter().filter_map(|lib| lib.file_name()).collect();
+        let components: Vec<_> = path.strip_prefix(self.root).ok()?.components().collect();
+        let idx = components
+            .iter()
+            .rposition(|component| lib_names.contains(&component.as_os_str()))?;
+        if idx == 0 {
+            return None
+        }
+        let context: PathBuf = components[..idx].iter().collect();
+        Some(format!("{}/", context.to_string_lossy()))
+    }
+
+    /// Returns the `path` relative to the project root
+    fn display_path(&self, path: &Path) -> String {
+        path.strip_prefix(self.root).unwrap_or(path).to_string_lossy().to_string()
     }
 
     /// Returns all remappings declared in foundry.toml files of libraries
@@ -1994,8 +2282,13 @@ impl<'a> RemappingsProvider<'a> {
                     }
                 }
 
-                let mut remappings =
-                    config.remappings.into_iter().map(|m| m.into()).collect::<Vec<Remapping>>();
+                // context-scoped remappings are relative to the nested lib and are not inherited
+                let mut remappings = config
+                    .remappings
+                    .into_iter()
+                    .map(|m| m.into())
+                    .filter(|m: &Remapping| !m.name.contains(':'))
+                    .collect::<Vec<Remapping>>();
 
                 if let Some(r) = src_remapping {
                     remappings.push(r);
@@ -2011,7 +2304,7 @@ impl<'a> Provider for RemappingsProvider<'a> {
     }
 
     fn data(&self) -> Result<Map<Profile, Dict>, Error> {
-        let remappings = match &self.remappings {
+        let (remappings, conflicts) = match &self.remappings {
             Ok(remappings) => self.get_remappings(remappings.clone()),
             Err(err) => {
                 if let figment::error::Kind::MissingField(_) = err.kind {
@@ -2028,10 +2321,14 @@ impl<'a> Provider for RemappingsProvider<'a> {
             .map(|r| RelativeRemapping::new(r, &self.root).to_string())
             .collect::<Vec<_>>();
 
-        Ok(Map::from([(
-            Config::selected_profile(),
-            Dict::from([("remappings".to_string(), figment::value::Value::from(remappings))]),
-        )]))
+        let mut dict =
+            Dict::from([("remappings".to_string(), figment::value::Value::from(remappings))]);
+        if !conflicts.is_empty() {
+            let warnings = self.warnings.iter().cloned().chain(conflicts).collect::<Vec<_>>();
+            dict.insert("__warnings".to_string(), figment::value::Value::serialize(warnings)?);
+        }
+
+        Ok(Map::from([(Config::selected_profile(), dict)]))
     }
 
     fn profile(&self) -> Option<Profile> {
@@ -2068,7 +2365,9 @@ impl<P> RenameProfileProvider<P> {
 
 impl<P: Provider> Provider for RenameProfileProvider<P> {
     fn metadata(&self) -> Metadata {
-        self.provider.metadata()
+        let mut metadata = self.provider.metadata();
+        metadata.name = format!("{} [profile.{}]", metadata.name, self.from).into();
+        metadata
     }
     fn data(&self) -> Result<Map<Profile, Dict>, Error> {
         let mut data = self.provider.data()?;
@@ -2082,6 +2381,19 @@ impl<P: Provider> Provider for RenameProfileProvider<P> {
     }
 }
 
+/// A Provider that fails with the given error, used to surface errors encountered while
+/// assembling a `Figment`
+struct FailingProvider(Error);
+
+impl Provider for FailingProvider {
+    fn metadata(&self) -> Metadata {
+        self.0.metadata.clone().unwrap_or_else(|| Metadata::named("foundry config"))
+    }
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        Err(self.0.clone())
+    }
+}
+
 /// Unwraps a profile reducing the key depth
 ///
 /// For example given:
@@ -2300,12 +2612,36 @@ impl BasicConfig {
     /// This serializes to a table with the name of the profile
     pub fn to_string_pretty(&self) -> Result<String, toml::ser::Error> {
         let s = toml::to_string_pretty(self)?;
-        Ok(format!(
+        Ok(self.wrap_in_profile(&s))
+    }
+
+    /// Same as [`BasicConfig::to_string_pretty()`], but the values that may contain secrets are
+    /// replaced by the [`Config::redactions()`] of the `config` this was created from
+    pub fn to_string_pretty_redacted(&self, config: &Config) -> Result<String, Error> {
+        let mut value = toml::Value::try_from(self).map_err(|err| err.to_string())?;
+        redact_toml(&mut value, config.redactions()?);
+        let s = toml::to_string_pretty(&value).map_err(|err| err.to_string())?;
+        Ok(self.wrap_in_profile(&s))
+    }
+
+    fn wrap_in_profile(&self, s: &str) -> String {
+        format!(
             r#"[profile.{}]
 {}
 # See more config options https://github.com/foundry-rs/foundry/tree/master/config"#,
             self.profile, s
-        ))
+        )
+    }
+}
+
+/// Replaces the values of the serialized config `value` by the `redactions` of the same keys
+fn redact_toml(value: &mut toml::Value, redactions: Vec<(String, toml::Value)>) {
+    if let Some(table) = value.as_table_mut() {
+        for (key, redacted) in redactions {
+            if let Some(value) = table.get_mut(&key) {
+                *value = redacted;
+            }
+        }
     }
 }
 
@@ -2447,6 +2783,169 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_profile_inheritance() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.default]
+                libs = ['lib']
+                fuzz_runs = 256
+                [profile.ci]
+                fuzz_runs = 10000
+                verbosity = 3
+                [profile.ci-fork]
+                inherits = "ci"
+                verbosity = 4
+            "#,
+            )?;
+            jail.set_env("FOUNDRY_PROFILE", "ci-fork");
+            let config = Config::load();
+            assert_eq!(config.libs, vec![PathBuf::from("lib")]);
+            assert_eq!(config.fuzz_runs, 10000);
+            assert_eq!(config.verbosity, 4);
+
+            // env vars still take precedence over inherited values
+            jail.set_env("FOUNDRY_FUZZ_RUNS", 500);
+            let config = Config::load();
+            assert_eq!(config.fuzz_runs, 500);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_profile_inheritance_cycle() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.a]
+                inherits = "b"
+                [profile.b]
+                inherits = "a"
+            "#,
+            )?;
+            jail.set_env("FOUNDRY_PROFILE", "a");
+            let err = Config::try_from(Config::figment()).unwrap_err();
+            assert!(err.to_string().contains("profile inheritance cycle detected: a -> b -> a"));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_env_var_interpolation() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.default]
+                etherscan_api_key = "${ETHERSCAN_KEY}"
+                eth_rpc_url = "https://rpc.io/$${escaped}/${API_KEY:-demo}"
+                libs = ["${LIB_DIR:-lib}", "modules"]
+                sender = "${SENDER}"
+                [rpc_endpoints]
+                mainnet = "${RPC_MAINNET}"
+            "#,
+            )?;
+            jail.set_env("ETHERSCAN_KEY", "secret");
+            jail.set_env("LIB_DIR", "dependencies");
+            jail.set_env("SENDER", "0x00a329c0648769a73afac7f9381e08fb43dbea72");
+
+            let config = Config::load();
+            assert_eq!(config.etherscan_api_key, Some("secret".to_string()));
+            assert_eq!(config.eth_rpc_url, Some("https://rpc.io/${escaped}/demo".to_string()));
+            assert_eq!(config.libs, vec![PathBuf::from("dependencies"), PathBuf::from("modules")]);
+            assert_eq!(
+                config.rpc_endpoints,
+                RpcEndpoints::new([("mainnet", RpcEndpoint::Env("${RPC_MAINNET}".to_string()))])
+            );
+
+            assert_eq!(
+                config.sender,
+                "0x00a329c0648769a73afac7f9381e08fb43dbea72".parse::<Address>().unwrap()
+            );
+
+            let redacted = config.to_string_pretty_redacted().unwrap();
+            assert!(redacted.contains(r#"etherscan_api_key = "${ETHERSCAN_KEY}""#), "{redacted}");
+            assert!(redacted.contains(r#"sender = "${SENDER}""#), "{redacted}");
+            assert!(redacted.contains(r#""${LIB_DIR:-lib}""#), "{redacted}");
+            assert!(!redacted.contains("secret"), "{redacted}");
+            assert!(!redacted.contains("0x00a329c0648769a73afac7f9381e08fb43dbea72"), "{redacted}");
+
+            let basic = config.clone().into_basic().to_string_pretty_redacted(&config).unwrap();
+            assert!(basic.contains(r#""${LIB_DIR:-lib}""#), "{basic}");
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_etherscan_configs() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.default]
+                etherscan_api_key = "fallback"
+                [etherscan]
+                mainnet = { key = "${MAINNET_KEY}" }
+                l2 = { key = "${L2_KEY}", chain = 10 }
+                polygon = { key = "plain" }
+            "#,
+            )?;
+            jail.set_env("MAINNET_KEY", "mainnet-secret");
+
+            let config = Config::load();
+            assert_eq!(
+                config.etherscan["mainnet"].key,
+                EtherscanApiKey::Env("${MAINNET_KEY}".to_string())
+            );
+            assert_eq!(
+                config.get_etherscan_api_key(Some(Chain::Id(1))),
+                Some("mainnet-secret".to_string())
+            );
+            assert_eq!(config.get_etherscan_api_key(Some(Chain::Id(137))), Some("plain".into()));
+            assert_eq!(config.get_etherscan_api_key(Some(Chain::Id(5))), Some("fallback".into()));
+
+            let err = config.require_etherscan_api_key(Some(Chain::Id(10))).unwrap_err();
+            assert!(
+                matches!(err, EtherscanConfigError::Unresolved { ref alias, .. } if alias == "l2")
+            );
+
+            let config = Config { etherscan_api_key: None, ..config };
+            let err = config.require_etherscan_api_key(Some(Chain::Id(5))).unwrap_err();
+            assert!(err.to_string().contains("l2 (`L2_KEY`)"), "{err}");
+
+            let redactions = config.redactions::<toml::Value>().unwrap();
+            let (_, configs) = redactions.iter().find(|(key, _)| key == "etherscan").unwrap();
+            assert_eq!(configs["mainnet"]["key"].as_str(), Some("${MAINNET_KEY}"));
+            assert_eq!(configs["polygon"]["key"].as_str(), Some(etherscan::REDACTED_KEY));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_env_var_interpolation_unresolved() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.default]
+                etherscan_api_key = "${MISSING_ETHERSCAN_KEY}"
+            "#,
+            )?;
+            let err = Config::try_from(Config::figment()).unwrap_err().to_string();
+            assert!(err.contains("`MISSING_ETHERSCAN_KEY`"), "{err}");
+            assert!(err.contains("`etherscan_api_key`"), "{err}");
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_default_test_path() {
         figment::Jail::expect_with(|_| {
@@ -2576,6 +3075,26 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_remapping_dependency_context() {
+        let root = PathBuf::from("/project");
+        let lib_paths = vec![PathBuf::from("lib")];
+        let provider = RemappingsProvider {
+            lib_paths: Cow::Borrowed(&lib_paths),
+            root: &root,
+            remappings: Ok(vec![]),
+            deny_conflicts: false,
+            warnings: vec![],
+        };
+
+        let context = |path: &str| provider.dependency_context(Path::new(path));
+        assert_eq!(context("/project/lib/oz/contracts/"), None);
+        assert_eq!(context("/project/lib/a/lib/oz/contracts/"), Some("lib/a/".to_string()));
+        assert_eq!(context("/project/lib/a/lib/b/lib/oz/"), Some("lib/a/lib/b/".to_string()));
+        assert_eq!(context("/elsewhere/lib/oz/"), None);
+        assert_eq!(provider.display_path(Path::new("/project/lib/oz/")), "lib/oz");
+    }
+
     #[test]
     fn test_remappings_override() {
         figment::Jail::expect_with(|jail| {
@@ -2682,6 +3201,24 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_report_upgrades_alias() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.default]
+                gas_reports_upgrades = true
+            "#,
+            )?;
+
+            let config = Config::load();
+            assert!(config.report_upgrades);
+
+            Ok(())
+        });
+    }
+
     #[test]
     #[should_panic]
     fn test_toml_file_parse_failure() {
@@ -3054,6 +3591,25 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_offline_env() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("foundry.toml", "[profile.default]")?;
+            assert!(!Config::load().offline);
+
+            jail.set_env("FOUNDRY_OFFLINE", 1);
+            assert!(Config::load().offline);
+
+            jail.set_env("FOUNDRY_OFFLINE", "false");
+            assert!(!Config::load().offline);
+
+            jail.set_env("FOUNDRY_OFFLINE", 2);
+            assert!(Config::figment().extract::<Config>().is_err());
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_precedence() {
         figment::Jail::expect_with(|jail| {
@@ -3453,6 +4009,26 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_mutation_config() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [mutation]
+                operators = ["boundary", "require_removal"]
+            "#,
+            )?;
+            let loaded = Config::load().sanitized();
+            assert_eq!(
+                loaded.mutation.operators,
+                vec![MutationOperator::Boundary, MutationOperator::RequireRemoval]
+            );
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_parse_with_profile() {
         let foundry_str = r#"
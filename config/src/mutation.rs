@@ -0,0 +1,36 @@
+//! Configuration specific to `forge test --mutate`
+
+use serde::{Deserialize, Serialize};
+
+/// Contains the mutation operators that are applied to the sources
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MutationConfig {
+    /// The kinds of mutations that are applied, all by default
+    pub operators: Vec<MutationOperator>,
+}
+
+/// A kind of change that is made to the sources to check whether the tests notice it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MutationOperator {
+    /// Replace `+` with `-`, `*` with `/` and the other way around
+    Arithmetic,
+    /// Replace `<` with `<=`, `>` with `>=` and the other way around
+    Boundary,
+    /// Remove `require` statements
+    RequireRemoval,
+}
+
+impl MutationOperator {
+    pub const ALL: [MutationOperator; 3] = [
+        MutationOperator::Arithmetic,
+        MutationOperator::Boundary,
+        MutationOperator::RequireRemoval,
+    ];
+}
+
+impl Default for MutationConfig {
+    fn default() -> Self {
+        MutationConfig { operators: MutationOperator::ALL.to_vec() }
+    }
+}
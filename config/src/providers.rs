@@ -77,3 +77,118 @@ impl<P: Provider> Provider for WarningsProvider<P> {
         Some(self.profile.clone())
     }
 }
+
+/// Interpolates `${VAR}` env var placeholders in all string values
+///
+/// A placeholder can declare a fallback that is used if the variable is unset or empty:
+/// `${VAR:-default}`. A literal `$` is escaped as `$$`.
+///
+/// The `rpc_endpoints` and the `etherscan` keys are left untouched because their placeholders are
+/// resolved lazily, see [`RpcEndpoint`](crate::RpcEndpoint) and
+/// [`EtherscanApiKey`](crate::EtherscanApiKey).
+///
+/// The original values of all interpolated keys are recorded under `__interpolated`, so that they
+/// can be displayed instead of the resolved values, which may contain secrets.
+pub struct EnvInterpolationProvider<P>(pub P);
+
+impl<P> EnvInterpolationProvider<P> {
+    pub const INTERPOLATED_KEY: &'static str = "__interpolated";
+    const LAZY_KEYS: &'static [&'static str] = &["rpc_endpoints", "etherscan"];
+}
+
+impl<P: Provider> Provider for EnvInterpolationProvider<P> {
+    fn metadata(&self) -> Metadata {
+        self.0.metadata()
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut data = self.0.data()?;
+        for (profile, dict) in data.iter_mut() {
+            if Self::LAZY_KEYS.iter().any(|key| profile == key) {
+                continue
+            }
+            let is_standalone = Config::STANDALONE_SECTIONS.iter().any(|s| profile == s);
+
+            let mut interpolated = Dict::new();
+            for (key, value) in dict.iter_mut() {
+                if Self::LAZY_KEYS.contains(&key.as_str()) {
+                    continue
+                }
+                let original = value.clone();
+                if interpolate_value(value, key)? {
+                    interpolated.insert(key.clone(), original);
+                }
+            }
+            if !interpolated.is_empty() && !is_standalone {
+                dict.insert(Self::INTERPOLATED_KEY.to_string(), interpolated.into());
+            }
+        }
+        Ok(data)
+    }
+}
+
+/// Interpolates all strings in the `value` of the config `key`, returns `true` if the value
+/// contained any placeholder
+fn interpolate_value(value: &mut Value, key: &str) -> Result<bool, Error> {
+    match value {
+        Value::String(_, s) if s.contains('$') => {
+            *s = interpolate(s, key)?;
+            Ok(true)
+        }
+        Value::Array(_, values) => {
+            let mut interpolated = false;
+            for (idx, value) in values.iter_mut().enumerate() {
+                interpolated |= interpolate_value(value, &format!("{key}[{idx}]"))?;
+            }
+            Ok(interpolated)
+        }
+        Value::Dict(_, dict) => {
+            let mut interpolated = false;
+            for (name, value) in dict.iter_mut() {
+                interpolated |= interpolate_value(value, &format!("{key}.{name}"))?;
+            }
+            Ok(interpolated)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Replaces all `${VAR}` and `${VAR:-default}` placeholders in the `input` with the values of the
+/// env vars and unescapes `$$`
+fn interpolate(input: &str, key: &str) -> Result<String, Error> {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        if let Some(tail) = rest.strip_prefix("$$") {
+            out.push('$');
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("${") {
+            let end = tail.find('}').ok_or_else(|| {
+                Error::from(format!("unterminated env var placeholder in `{key}`: `{input}`"))
+            })?;
+            let (var, default) = match tail[..end].split_once(":-") {
+                Some((var, default)) => (var.trim(), Some(default)),
+                None => (tail[..end].trim(), None),
+            };
+            match (std::env::var(var), default) {
+                (Ok(value), Some(default)) if value.is_empty() => out.push_str(default),
+                (Ok(value), _) => out.push_str(&value),
+                (Err(_), Some(default)) => out.push_str(default),
+                (Err(err), None) => {
+                    return Err(Error::from(format!(
+                        "failed to resolve env var `{var}` in `{key}`: {err}"
+                    )))
+                }
+            }
+            rest = &tail[end + 1..];
+        } else {
+            out.push('$');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
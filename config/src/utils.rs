@@ -120,6 +120,28 @@ pub fn to_array_value(val: &str) -> Result<Value, figment::Error> {
     Ok(value)
 }
 
+/// Deserializes a `bool` that can also be set as `0`/`1`, e.g. `FOUNDRY_OFFLINE=1`
+pub(crate) fn deserialize_bool_or_int<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrInt {
+        Bool(bool),
+        Int(u8),
+    }
+
+    match serde::Deserialize::deserialize(deserializer)? {
+        BoolOrInt::Bool(value) => Ok(value),
+        BoolOrInt::Int(0) => Ok(false),
+        BoolOrInt::Int(1) => Ok(true),
+        BoolOrInt::Int(value) => {
+            Err(serde::de::Error::custom(format!("invalid value `{value}`, expected a boolean")))
+        }
+    }
+}
+
 /// Returns a list of _unique_ paths to all folders under `root` that contain a `foundry.toml` file
 ///
 /// This will also resolve symlinks
@@ -38,6 +38,15 @@ pub enum Warning {
         /// The error message that occured
         err: String,
     },
+    /// Multiple libraries provide an auto-detected remapping with the same name
+    RemappingConflict {
+        /// The name of the remapping
+        name: String,
+        /// The paths of all candidates, relative to the project root
+        candidates: Vec<String>,
+        /// The path of the candidate that is used
+        chosen: String,
+    },
 }
 
 impl fmt::Display for Warning {
@@ -63,6 +72,10 @@ impl fmt::Display for Warning {
                 path.display(),
                 err
             )),
+            Self::RemappingConflict { name, candidates, chosen } => f.write_fmt(format_args!(
+                "Found conflicting remappings for `{name}`: {}. Using `{chosen}` because it is the closest to the project root. Add an explicit remapping to choose another candidate, or set `deny_remapping_conflicts = true` to make this an error.",
+                candidates.join(", ")
+            )),
         }
     }
 }
@@ -8,6 +8,9 @@ use std::fmt::Display;
 pub struct DebugArena {
     /// The arena of nodes
     pub arena: Vec<DebugNode>,
+    /// Whether steps were dropped because the execution exceeded
+    /// [`MAX_DEBUG_STEPS`](crate::executor::inspector::MAX_DEBUG_STEPS)
+    pub truncated: bool,
 }
 
 impl DebugArena {
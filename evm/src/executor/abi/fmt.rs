@@ -1,5 +1,9 @@
 use super::HardhatConsoleCalls;
-use ethers::types::{Address, Bytes, I256, U256};
+use ethers::{
+    abi::{ParamType, Token},
+    types::{Address, Bytes, I256, U256},
+    utils::to_checksum,
+};
 use foundry_common::fmt::UIfmt;
 use std::fmt::Write;
 
@@ -148,6 +152,84 @@ fn console_log_format<'a>(
     result
 }
 
+/// Formats a `console.logf(format, args)` call, where `args` are the ABI-encoded values of the
+/// placeholders of the `format`, e.g. `console.logf("%s has %d", abi.encode("alice", 3))`.
+///
+/// Unlike [`console_log_format`] the placeholders determine how the values are decoded:
+/// - %s: a `string`
+/// - %d: an `int256`, unsigned values below 2^255 are encoded the same way
+/// - %x: a `uint256` in hexadecimal
+/// - %e: a `uint256` in scientific notation, e.g. `1.5e18`
+/// - %a: an `address` with its checksum
+/// - %%: a single percent sign ('%') without consuming any value
+///
+/// Other characters after a `%` are kept as they are. Fails if the `args` cannot be decoded as
+/// the values of the placeholders.
+pub fn console_logf_format(format: &str, args: &[u8]) -> Result<String, String> {
+    // the formatted output is literal text and the placeholders in between
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek().copied()) {
+            ('%', Some('%')) => {
+                chars.next();
+                text.push('%');
+            }
+            ('%', Some(spec @ ('s' | 'd' | 'x' | 'e' | 'a'))) => {
+                chars.next();
+                pieces.push((std::mem::take(&mut text), Some(spec)));
+            }
+            _ => text.push(c),
+        }
+    }
+    pieces.push((text, None));
+
+    let types = pieces
+        .iter()
+        .filter_map(|(_, spec)| match (*spec)? {
+            's' => Some(ParamType::String),
+            'd' => Some(ParamType::Int(256)),
+            'a' => Some(ParamType::Address),
+            _ => Some(ParamType::Uint(256)),
+        })
+        .collect::<Vec<_>>();
+    let mut values = ethers::abi::decode(&types, args)
+        .map_err(|err| format!("logf: the arguments do not match `{format}`: {err}"))?
+        .into_iter();
+
+    let mut result = String::new();
+    for (text, spec) in pieces {
+        result.push_str(&text);
+        let (spec, value) = match (spec, values.next()) {
+            (Some(spec), Some(value)) => (spec, value),
+            _ => continue,
+        };
+        match (spec, value) {
+            ('s', Token::String(value)) => result.push_str(&value),
+            ('d', Token::Int(value)) => result.push_str(&I256::from_raw(value).to_string()),
+            ('x', Token::Uint(value)) => write!(result, "{value:#x}").unwrap(),
+            ('e', Token::Uint(value)) => result.push_str(&to_scientific(value)),
+            ('a', Token::Address(value)) => result.push_str(&to_checksum(&value, None)),
+            _ => unreachable!("decoded as the type of the placeholder"),
+        }
+    }
+    Ok(result)
+}
+
+/// Formats the `value` in scientific notation with all significant digits, e.g. `1.5e18`
+fn to_scientific(value: U256) -> String {
+    let digits = value.to_string();
+    let (first, rest) = digits.split_at(1);
+    let fraction = rest.trim_end_matches('0');
+    let exponent = rest.len();
+    if fraction.is_empty() {
+        format!("{first}e{exponent}")
+    } else {
+        format!("{first}.{fraction}e{exponent}")
+    }
+}
+
 macro_rules! logf1 {
     ($a:ident) => {{
         let args: [&dyn FormatValue; 1] = [&$a.p_1];
@@ -257,6 +339,9 @@ pub fn format_hardhat_call(call: &HardhatConsoleCalls) -> String {
         HardhatConsoleCalls::Log330(c) => logf3!(c),
         HardhatConsoleCalls::Log335(c) => logf3!(c),
         HardhatConsoleCalls::Log338(c) => logf3!(c),
+        HardhatConsoleCalls::Logf(c) => {
+            console_logf_format(&c.p_0, &c.p_1).unwrap_or_else(|err| err)
+        }
         _ => call.to_string(),
     }
 }
@@ -314,6 +399,31 @@ mod tests {
         assert_eq!("100", console_log_format_1("%o", &I256::from(100)));
     }
 
+    #[test]
+    fn test_console_logf_format() {
+        use std::str::FromStr;
+
+        let addr = Address::from_str("0xdeadbeef00000000000000000000000000000000").unwrap();
+        let args = ethers::abi::encode(&[
+            Token::String("alice".to_string()),
+            Token::Int(I256::from(-3).into_raw()),
+            Token::Uint(U256::from(255)),
+            Token::Uint(U256::from(1_500_000_000_000_000_000u128)),
+            Token::Address(addr),
+        ]);
+        assert_eq!(
+            console_logf_format("%s %d %x %e %a 100%%", &args).unwrap(),
+            "alice -3 0xff 1.5e18 0xdEADBEeF00000000000000000000000000000000 100%"
+        );
+
+        assert_eq!(console_logf_format("no values %q", &[]).unwrap(), "no values %q");
+        assert_eq!(to_scientific(U256::zero()), "0e0");
+        assert_eq!(to_scientific(U256::from(1000)), "1e3");
+        assert!(
+            console_logf_format("%s %s", &ethers::abi::encode(&[Token::Uint(1.into())])).is_err()
+        );
+    }
+
     #[test]
     fn test_console_log_format() {
         use std::str::FromStr;
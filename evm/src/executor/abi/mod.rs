@@ -16,12 +16,16 @@ ethers::contract::abigen!(
     HEVM,
     r#"[
             struct Log {bytes32[] topics; bytes data;}
+            struct RecordedLog {bytes32[] topics; bytes data; address emitter; uint256 depth; uint256 index;}
             roll(uint256)
             warp(uint256)
             fee(uint256)
             coinbase(address)
             store(address,bytes32,bytes32)
             load(address,bytes32)(bytes32)
+            storePacked(address,bytes32,uint256,uint256,bytes32)
+            loadPacked(address,bytes32,uint256,uint256)(bytes32)
+            copyStorage(address,address)
             ffi(string[])(bytes)
             setEnv(string,string)
             envBool(string)(bool)
@@ -49,23 +53,37 @@ ethers::contract::abigen!(
             stopPrank()
             deal(address,uint256)
             etch(address,bytes)
+            etchContract(address,string)
+            etchWithConstructor(address,string,bytes)
             expectRevert()
             expectRevert(bytes)
             expectRevert(bytes4)
+            expectRevert(address,bytes4)
+            expectRevertAtDepth(uint64,bytes4)
             record()
             accesses(address)(bytes32[],bytes32[])
             recordLogs()
             getRecordedLogs()(Log[])
+            getRecordedLogs(address)(RecordedLog[])
+            getRecordedLogs(bytes32)(RecordedLog[])
+            clearRecordedLogs()
             expectEmit(bool,bool,bool,bool)
             expectEmit(bool,bool,bool,bool,address)
             mockCall(address,bytes,bytes)
             mockCall(address,uint256,bytes,bytes)
+            mockReturn(address,string,bytes)
+            mockReturnUint(address,string,uint256)
+            mockReturnAddress(address,string,address)
+            mockReturnBool(address,string,bool)
+            mockReturnBytes32(address,string,bytes32)
             clearMockedCalls()
             expectCall(address,bytes)
             expectCall(address,uint256,bytes)
             getCode(string)
             label(address,string)
             assume(bool)
+            bound(uint256,uint256,uint256)(uint256)
+            boundInt(int256,int256,int256)(int256)
             setNonce(address,uint64)
             getNonce(address)
             chainId(uint256)
@@ -106,6 +124,15 @@ ethers::contract::abigen!(
             rollFork(uint256,uint256)
             rpcUrl(string)(string)
             rpcUrls()(string[2][])
+            transact(address,bytes)(bytes)
+            transact(address,uint256,bytes)(bytes)
+            expectSafeMemory(uint64,uint64)
+            stopExpectSafeMemory()
+            assertTokenBalance(address,address,uint256,string)
+            assertTokenAllowance(address,address,address,uint256,string)
+            expectBalanceChange(address,int256)
+            assertBalanceChange(address,int256)
+            assertSnapshotEq(bytes,string)
     ]"#,
 );
 pub use hevm::{HEVMCalls, HEVM_ABI};
@@ -11,7 +11,7 @@ use revm::{
     db::DatabaseRef, Account, AccountInfo, Bytecode, Database, Env, Inspector, Log, Return,
     SubRoutine, TransactOut,
 };
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::BTreeMap};
 use tracing::trace;
 
 /// A wrapper around `Backend` that ensures only `revm::DatabaseRef` functions are called.
@@ -134,6 +134,10 @@ impl<'a> DatabaseExt for FuzzBackendWrapper<'a> {
     fn add_persistent_account(&mut self, account: Address) -> bool {
         self.backend.to_mut().add_persistent_account(account)
     }
+
+    fn storage_slots(&self, address: Address) -> BTreeMap<U256, U256> {
+        self.backend.storage_slots(address)
+    }
 }
 
 impl<'a> DatabaseRef for FuzzBackendWrapper<'a> {
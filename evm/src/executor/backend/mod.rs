@@ -19,7 +19,7 @@ use revm::{
     Account, AccountInfo, Bytecode, Database, DatabaseCommit, Env, InMemoryDB, Inspector, Log,
     Return, SubRoutine, TransactOut, TransactTo, KECCAK_EMPTY,
 };
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use tracing::{trace, warn};
 
 mod fuzz;
@@ -178,6 +178,12 @@ pub trait DatabaseExt: Database {
     /// Marks the given account as persistent.
     fn add_persistent_account(&mut self, account: Address) -> bool;
 
+    /// Returns the storage slots of the `address` that the active database holds.
+    ///
+    /// These are all slots that were committed to the database, on a fork only the slots that were
+    /// already fetched from the remote endpoint are included.
+    fn storage_slots(&self, address: Address) -> BTreeMap<U256, U256>;
+
     /// Removes persistent status from all given accounts
     fn remove_persistent_accounts(&mut self, accounts: impl IntoIterator<Item = Address>) {
         for acc in accounts {
@@ -730,6 +736,14 @@ impl DatabaseExt for Backend {
         trace!(?account, "add persistent account");
         self.inner.persistent_accounts.insert(account)
     }
+
+    fn storage_slots(&self, address: Address) -> BTreeMap<U256, U256> {
+        let account = match self.active_fork_db() {
+            Some(db) => db.accounts.get(&address),
+            None => self.mem_db.accounts.get(&address),
+        };
+        account.map(|account| account.storage.clone()).unwrap_or_default()
+    }
 }
 
 impl DatabaseRef for Backend {
@@ -50,6 +50,46 @@ impl ExecutorBuilder {
         self
     }
 
+    /// Enables or disables the collection of memory usage
+    #[must_use]
+    pub fn set_memory_report(mut self, enable: bool) -> Self {
+        self.inspector_config.memory = enable;
+        self
+    }
+
+    /// Enables or disables recording the gas that the access list of the transaction saves in the
+    /// traces, see [`CallTrace::access_list_savings`](crate::trace::CallTrace::access_list_savings)
+    #[must_use]
+    pub fn set_access_list_savings(mut self, enable: bool) -> Self {
+        self.inspector_config.access_list_savings = enable;
+        self
+    }
+
+    /// Enables or disables recording the gas that the calls spend on expanding their memory in the
+    /// traces, see [`CallTrace::memory_gas`](crate::trace::CallTrace::memory_gas)
+    #[must_use]
+    pub fn set_memory_gas(mut self, enable: bool) -> Self {
+        self.inspector_config.memory_gas = enable;
+        self
+    }
+
+    /// Enables or disables recording the gas of the instructions of every call by the kind of
+    /// instruction in the traces, see
+    /// [`CallTrace::gas_categories`](crate::trace::CallTrace::gas_categories)
+    #[must_use]
+    pub fn set_gas_categories(mut self, enable: bool) -> Self {
+        self.inspector_config.gas_categories = enable;
+        self
+    }
+
+    /// Enables or disables recording the gas of the instructions of every call by their program
+    /// counter in the traces, see [`CallTrace::pc_gas`](crate::trace::CallTrace::pc_gas)
+    #[must_use]
+    pub fn set_gas_by_pc(mut self, enable: bool) -> Self {
+        self.inspector_config.gas_by_pc = enable;
+        self
+    }
+
     /// Enables the fuzzer for data collection and maybe call overriding
     #[must_use]
     pub fn with_fuzzer(
@@ -1,5 +1,5 @@
 //! Smart caching and deduplication of requests when using a forking provider
-use crate::executor::fork::{cache::FlushJsonBlockCacheDB, BlockchainDb};
+use crate::executor::fork::{cache::FlushJsonBlockCacheDB, BlockchainDb, ForkAccessLog};
 use ethers::{
     core::abi::ethereum_types::BigEndianHash,
     providers::Middleware,
@@ -46,6 +46,9 @@ enum BackendRequest {
     BlockHash(u64, OneshotSender<H256>),
     /// Sets the pinned block to fetch data from
     SetPinnedBlock(BlockId),
+    /// Fetch all accounts and storage slots of the log that are not cached yet, without waiting
+    /// for them
+    Prefetch(ForkAccessLog),
 }
 
 /// Handles an internal provider and listens for requests.
@@ -136,6 +139,31 @@ where
             BackendRequest::SetPinnedBlock(block_id) => {
                 self.block_id = Some(block_id);
             }
+            BackendRequest::Prefetch(log) => {
+                trace!(target: "backendhandler", accounts = log.accounts.len(), "prefetching");
+                for address in log.accounts {
+                    if !self.db.accounts().read().contains_key(&address) &&
+                        !self.account_requests.contains_key(&address)
+                    {
+                        self.account_requests.insert(address, Vec::new());
+                        self.pending_requests.push(self.get_account_req(address));
+                    }
+                }
+                for (address, slots) in log.storage {
+                    for idx in slots {
+                        let is_cached = self
+                            .db
+                            .storage()
+                            .read()
+                            .get(&address)
+                            .map_or(false, |storage| storage.contains_key(&idx));
+                        if !is_cached && !self.storage_requests.contains_key(&(address, idx)) {
+                            self.storage_requests.insert((address, idx), Vec::new());
+                            self.pending_requests.push(self.get_storage_req(address, idx));
+                        }
+                    }
+                }
+            }
         }
     }
 
@@ -151,23 +179,28 @@ where
                 entry.get_mut().push(listener);
             }
             Entry::Vacant(entry) => {
-                trace!(target: "backendhandler", "preparing storage request, address={:?}, idx={}", address, idx);
                 entry.insert(vec![listener]);
-                let provider = self.provider.clone();
-                let block_id = self.block_id;
-                let fut = Box::pin(async move {
-                    // serialize & deserialize back to U256
-                    let idx_req = H256::from_uint(&idx);
-                    let storage = provider.get_storage_at(address, idx_req, block_id).await;
-                    let storage =
-                        storage.map(|storage| storage.into_uint()).map_err(|err| eyre::eyre!(err));
-                    (storage, address, idx)
-                });
-                self.pending_requests.push(ProviderRequest::Storage(fut));
+                self.pending_requests.push(self.get_storage_req(address, idx));
             }
         }
     }
 
+    /// returns the future that fetches the storage slot
+    fn get_storage_req(&self, address: Address, idx: U256) -> ProviderRequest<eyre::Error> {
+        trace!(target: "backendhandler", "preparing storage request, address={:?}, idx={}", address, idx);
+        let provider = self.provider.clone();
+        let block_id = self.block_id;
+        let fut = Box::pin(async move {
+            // serialize & deserialize back to U256
+            let idx_req = H256::from_uint(&idx);
+            let storage = provider.get_storage_at(address, idx_req, block_id).await;
+            let storage =
+                storage.map(|storage| storage.into_uint()).map_err(|err| eyre::eyre!(err));
+            (storage, address, idx)
+        });
+        ProviderRequest::Storage(fut)
+    }
+
     /// returns the future that fetches the account data
     fn get_account_req(&self, address: Address) -> ProviderRequest<eyre::Error> {
         trace!(target: "backendhandler", "preparing account request, address={:?}", address);
@@ -484,6 +517,22 @@ impl SharedBackend {
         })
     }
 
+    /// Starts fetching all accounts and storage slots of the `log` that are not cached yet, e.g.
+    /// the ones a previous run accessed, so that they are ready by the time they are accessed.
+    ///
+    /// This does not wait for the fetched values.
+    pub fn prefetch(&self, log: ForkAccessLog) -> eyre::Result<()> {
+        tokio::task::block_in_place(|| {
+            let req = BackendRequest::Prefetch(log);
+            self.backend.clone().try_send(req).map_err(|e| eyre::eyre!("{:?}", e))
+        })
+    }
+
+    /// Returns all accounts and storage slots that were fetched so far, including the cached ones
+    pub fn access_log(&self) -> ForkAccessLog {
+        ForkAccessLog::from_db(self.cache.0.db())
+    }
+
     /// Flushes the DB to disk if caching is enabled
     pub(crate) fn flush_cache(&self) {
         self.cache.0.flush();
@@ -537,7 +586,13 @@ mod tests {
     use crate::executor::fork::CreateFork;
     use ethers::types::Chain;
     use foundry_config::Config;
-    use std::{collections::BTreeSet, convert::TryFrom, path::PathBuf, sync::Arc};
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        convert::TryFrom,
+        path::PathBuf,
+        sync::Arc,
+        time::Duration,
+    };
 
     use super::*;
     const ENDPOINT: &str = "https://mainnet.infura.io/v3/c60b0bb42f8a4c6481ecd229eddaca27";
@@ -587,6 +642,38 @@ mod tests {
         assert_eq!(slots.len() as u64, max_slots);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn serves_prefetched_storage_from_cache() {
+        let (provider, mock) = Provider::mocked();
+        let meta = BlockchainDbMeta {
+            cfg_env: Default::default(),
+            block_env: Default::default(),
+            hosts: Default::default(),
+        };
+        let db = BlockchainDb::new(meta, None);
+        let backend = SharedBackend::spawn_backend(Arc::new(provider), db.clone(), None).await;
+
+        let address = Address::random();
+        let idx = U256::from(1u64);
+        let value = U256::from(1337u64);
+        // the mock answers a single request, so fetching the slot a second time fails
+        mock.push::<H256, _>(H256::from_uint(&value)).unwrap();
+        let log = ForkAccessLog {
+            accounts: Default::default(),
+            storage: BTreeMap::from([(address, BTreeSet::from([idx]))]),
+        };
+        backend.prefetch(log.clone()).unwrap();
+
+        let is_cached = || {
+            db.storage().read().get(&address).map_or(false, |slots| slots.contains_key(&idx))
+        };
+        while !is_cached() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(backend.storage(address, idx), value);
+        assert_eq!(backend.access_log(), log);
+    }
+
     #[test]
     fn can_read_cache() {
         let cache_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-data/storage.json");
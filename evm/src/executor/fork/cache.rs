@@ -7,7 +7,7 @@ use std::{
     collections::{BTreeMap, BTreeSet},
     fs,
     io::BufWriter,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 use tracing::{trace, trace_span, warn};
@@ -222,6 +222,45 @@ impl MemDb {
     }
 }
 
+/// The accounts and storage slots that were fetched from a fork during a run, which a later run
+/// can prefetch, see [SharedBackend::prefetch()](crate::executor::fork::SharedBackend::prefetch)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkAccessLog {
+    pub accounts: BTreeSet<Address>,
+    pub storage: BTreeMap<Address, BTreeSet<U256>>,
+}
+
+impl ForkAccessLog {
+    /// Returns all accounts and storage slots held by the `db`
+    pub fn from_db(db: &MemDb) -> Self {
+        let accounts = db.accounts.read().keys().copied().collect();
+        let storage = db
+            .storage
+            .read()
+            .iter()
+            .map(|(address, slots)| (*address, slots.keys().copied().collect()))
+            .collect();
+        Self { accounts, storage }
+    }
+
+    /// Reads the log from the JSON file at `path`
+    pub fn read(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let file = std::io::BufReader::new(fs::File::open(path)?);
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    /// Writes the log to the JSON file at `path`
+    pub fn write(&self, path: impl AsRef<Path>) -> eyre::Result<()> {
+        let file = BufWriter::new(fs::File::create(path)?);
+        Ok(serde_json::to_writer(file, self)?)
+    }
+
+    /// Returns whether nothing was accessed
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty() && self.storage.is_empty()
+    }
+}
+
 impl Clone for MemDb {
     fn clone(&self) -> Self {
         Self {
@@ -383,3 +422,28 @@ impl Drop for FlushJsonBlockCacheDB {
         trace!(target: "fork::cache", "flushed cache");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_read_write_access_log() {
+        let address = Address::random();
+        let db = MemDb::default();
+        db.do_insert_account(address, AccountInfo::default());
+        db.do_insert_account(Address::random(), AccountInfo::default());
+        db.storage.write().insert(address, BTreeMap::from([(1u64.into(), 2u64.into())]));
+
+        let log = ForkAccessLog::from_db(&db);
+        assert_eq!(log.accounts.len(), 2);
+        assert_eq!(log.storage[&address], BTreeSet::from([U256::one()]));
+
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("fork-access.json");
+        log.write(&path).unwrap();
+        assert_eq!(ForkAccessLog::read(&path).unwrap(), log);
+        assert!(ForkAccessLog::read(tmp.path().join("missing.json")).is_err());
+        assert!(ForkAccessLog::from_db(&MemDb::default()).is_empty());
+    }
+}
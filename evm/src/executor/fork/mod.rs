@@ -9,7 +9,7 @@ mod init;
 pub use init::environment;
 
 mod cache;
-pub use cache::{BlockchainDb, BlockchainDbMeta, JsonBlockCacheDB, MemDb};
+pub use cache::{BlockchainDb, BlockchainDbMeta, ForkAccessLog, JsonBlockCacheDB, MemDb};
 
 pub mod database;
 
@@ -0,0 +1,85 @@
+use crate::{executor::inspector::utils::word_to_address, utils::u256_to_h256_be};
+use ethers::types::{
+    transaction::eip2930::{AccessList, AccessListItem},
+    Address, H256,
+};
+use revm::{opcode, Database, EVMData, Inspector, Interpreter, Return};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// An inspector that collects the accounts and storage slots that an execution accesses, i.e. the
+/// EIP-2930 access list that makes all of its first accesses warm.
+#[derive(Default, Debug)]
+pub struct AccessListTracer {
+    /// The accounts that are warm anyway, e.g. the sender, the recipient and the precompiles, they
+    /// are only listed if their storage is accessed
+    excluded: HashSet<Address>,
+    /// The accessed storage slots by the address of their account
+    accesses: BTreeMap<Address, BTreeSet<H256>>,
+}
+
+impl AccessListTracer {
+    /// Creates a tracer that starts with the entries of the `access_list` and does not list the
+    /// accounts of the `excluded` addresses
+    pub fn new(access_list: &AccessList, excluded: impl IntoIterator<Item = Address>) -> Self {
+        let accesses = access_list
+            .0
+            .iter()
+            .map(|item| (item.address, item.storage_keys.iter().copied().collect()))
+            .collect();
+        Self { excluded: excluded.into_iter().collect(), accesses }
+    }
+
+    /// Returns the collected access list, ordered by address and storage slot
+    pub fn access_list(&self) -> AccessList {
+        AccessList(
+            self.accesses
+                .iter()
+                .map(|(address, slots)| AccessListItem {
+                    address: *address,
+                    storage_keys: slots.iter().copied().collect(),
+                })
+                .collect(),
+        )
+    }
+
+    fn access_account(&mut self, address: Address) {
+        if !self.excluded.contains(&address) {
+            self.accesses.entry(address).or_default();
+        }
+    }
+}
+
+impl<DB> Inspector<DB> for AccessListTracer
+where
+    DB: Database,
+{
+    fn step(
+        &mut self,
+        interpreter: &mut Interpreter,
+        _: &mut EVMData<'_, DB>,
+        _is_static: bool,
+    ) -> Return {
+        match interpreter.contract.bytecode.bytecode()[interpreter.program_counter()] {
+            opcode::SLOAD | opcode::SSTORE => {
+                let slot = try_or_continue!(interpreter.stack().peek(0));
+                self.accesses
+                    .entry(interpreter.contract().address)
+                    .or_default()
+                    .insert(u256_to_h256_be(slot));
+            }
+            opcode::BALANCE |
+            opcode::EXTCODESIZE |
+            opcode::EXTCODECOPY |
+            opcode::EXTCODEHASH |
+            opcode::SELFDESTRUCT => {
+                self.access_account(word_to_address(try_or_continue!(interpreter.stack().peek(0))));
+            }
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => {
+                self.access_account(word_to_address(try_or_continue!(interpreter.stack().peek(1))));
+            }
+            _ => {}
+        }
+
+        Return::Continue
+    }
+}
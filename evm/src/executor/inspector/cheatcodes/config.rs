@@ -1,7 +1,9 @@
 use crate::executor::opts::EvmOpts;
 use bytes::Bytes;
 
-use foundry_config::{cache::StorageCachingConfig, Config, ResolvedRpcEndpoints};
+use foundry_config::{
+    cache::StorageCachingConfig, Config, FsAccessKind, FsPermissions, ResolvedRpcEndpoints,
+};
 use std::path::{Path, PathBuf};
 use tracing::trace;
 
@@ -23,8 +25,17 @@ pub struct CheatsConfig {
     /// Project root
     pub root: PathBuf,
 
-    /// Paths (directories) where file reading/writing is allowed
-    pub allowed_paths: Vec<PathBuf>,
+    /// Paths where file reading/writing is allowed
+    pub fs_permissions: FsPermissions,
+
+    /// Whether the top-level calls of a test are executed as separate transactions
+    pub isolate: bool,
+
+    /// The maximum number of logs `recordLogs` keeps, the oldest ones are dropped beyond it
+    pub recorded_logs_limit: Option<usize>,
+
+    /// Whether `assertSnapshotEq` overwrites the stored snapshots instead of comparing them
+    pub update_snapshots: bool,
 
     /// How the evm was configured by the user
     pub evm_opts: EvmOpts,
@@ -35,10 +46,6 @@ pub struct CheatsConfig {
 impl CheatsConfig {
     /// Extracts the necessary settings from the Config
     pub fn new(config: &Config, evm_opts: &EvmOpts) -> Self {
-        let mut allowed_paths = vec![config.__root.0.clone()];
-        allowed_paths.extend(config.libs.clone());
-        allowed_paths.extend(config.allow_paths.clone());
-
         let rpc_endpoints = config.rpc_endpoints.clone().resolved();
         trace!(?rpc_endpoints, "using resolved rpc endpoints");
         Self {
@@ -46,18 +53,29 @@ impl CheatsConfig {
             rpc_storage_caching: config.rpc_storage_caching.clone(),
             rpc_endpoints,
             root: config.__root.0.clone(),
-            allowed_paths,
+            fs_permissions: config.resolved_fs_permissions(),
+            isolate: config.isolate,
+            recorded_logs_limit: Some(config.recorded_logs_limit),
+            update_snapshots: false,
             evm_opts: evm_opts.clone(),
         }
     }
 
-    pub fn is_path_allowed(&self, path: impl AsRef<Path>) -> bool {
-        return self.allowed_paths.iter().any(|allowed_path| path.as_ref().starts_with(allowed_path))
+    /// Returns `true` if the given `kind` of access to the `path` is allowed
+    pub fn is_path_allowed(&self, path: impl AsRef<Path>, kind: FsAccessKind) -> bool {
+        self.fs_permissions.resolve(&self.root, path.as_ref(), kind).is_allowed()
     }
 
-    pub fn ensure_path_allowed(&self, path: impl AsRef<Path>) -> Result<(), String> {
-        if !self.is_path_allowed(path) {
-            return Err("Path is not allowed.".to_string())
+    /// Returns an error naming the path, the operation and the nearest `fs_permissions` entry if
+    /// the given `kind` of access to the `path` is not allowed
+    pub fn ensure_path_allowed(
+        &self,
+        path: impl AsRef<Path>,
+        kind: FsAccessKind,
+    ) -> Result<(), String> {
+        let resolution = self.fs_permissions.resolve(&self.root, path.as_ref(), kind);
+        if !resolution.is_allowed() {
+            return Err(resolution.to_string())
         }
 
         Ok(())
@@ -73,7 +91,7 @@ impl CheatsConfig {
     ///
     ///  - Returns an error if `url_or_alias` is a known alias but references an unresolved env var.
     ///  - Returns an error if `url_or_alias` is not an alias but does not start with a `http` or
-    ///    `scheme`
+    ///    `ws` scheme, naming the known aliases
     pub fn get_rpc_url(&self, url_or_alias: impl Into<String>) -> Result<String, Bytes> {
         let url_or_alias = url_or_alias.into();
         match self.rpc_endpoints.get(&url_or_alias) {
@@ -87,7 +105,15 @@ impl CheatsConfig {
             }
             None => {
                 if !url_or_alias.starts_with("http") && !url_or_alias.starts_with("ws") {
-                    Err(util::encode_error(format!("invalid rpc url {}", url_or_alias)))
+                    let aliases = if self.rpc_endpoints.is_empty() {
+                        "none are configured".to_string()
+                    } else {
+                        self.rpc_endpoints.keys().cloned().collect::<Vec<_>>().join(", ")
+                    };
+                    Err(util::encode_error(format!(
+                        "invalid rpc url `{url_or_alias}`, it is neither a URL nor one of the \
+                         `[rpc_endpoints]` aliases: {aliases}"
+                    )))
                 } else {
                     Ok(url_or_alias)
                 }
@@ -1,13 +1,17 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 
-use super::Cheatcodes;
-use crate::abi::HEVMCalls;
+use super::{ext::artifact_code, Cheatcodes};
+use crate::{abi::HEVMCalls, executor::backend::DatabaseExt};
 use bytes::Bytes;
 use ethers::{
-    abi::{self, AbiEncode, RawLog, Token, Tokenizable, Tokenize},
-    types::{Address, U256},
+    abi::{self, AbiEncode, ParamType, RawLog, Token, Tokenizable, Tokenize},
+    types::{Address, H256, I256, U256},
+    utils::{id, to_checksum},
+};
+use revm::{
+    return_ok, AccountInfo, Bytecode, CallInputs, CreateScheme, Database, EVMData, Env, Gas,
+    NoOpInspector, Return, SubRoutine, TransactOut, TransactTo,
 };
-use revm::{Bytecode, Database, EVMData};
 
 #[derive(Clone, Debug, Default)]
 pub struct Broadcast {
@@ -105,7 +109,49 @@ fn accesses(state: &mut Cheatcodes, address: Address) -> Bytes {
 
 #[derive(Clone, Debug, Default)]
 pub struct RecordedLogs {
-    pub entries: Vec<RawLog>,
+    /// The recorded logs, the oldest ones are dropped once there are more than the limit
+    pub entries: VecDeque<RecordedLog>,
+    /// The number of logs recorded since `recordLogs` was called, including the dropped ones
+    pub count: u64,
+}
+
+impl RecordedLogs {
+    /// Records the `log` that the `emitter` emitted at the call `depth`, keeping at most `limit`
+    /// logs
+    pub fn record(&mut self, emitter: Address, log: RawLog, depth: u64, limit: Option<usize>) {
+        self.entries.push_back(RecordedLog { emitter, log, depth, index: self.count });
+        self.count += 1;
+        if let Some(limit) = limit {
+            while self.entries.len() > limit {
+                self.entries.pop_front();
+            }
+        }
+    }
+}
+
+/// A log recorded by `recordLogs`
+#[derive(Clone, Debug)]
+pub struct RecordedLog {
+    /// The address of the contract that emitted the log
+    pub emitter: Address,
+    pub log: RawLog,
+    /// The call depth the log was emitted at
+    pub depth: u64,
+    /// The position of the log among all logs recorded since `recordLogs` was called
+    pub index: u64,
+}
+
+impl RecordedLog {
+    /// Returns the log as a `RecordedLog` struct of the cheatcode interface
+    fn into_token(self) -> Token {
+        Token::Tuple(vec![
+            self.log.topics.into_token(),
+            Token::Bytes(self.log.data),
+            Token::Address(self.emitter),
+            Token::Uint(self.depth.into()),
+            Token::Uint(self.index.into()),
+        ])
+    }
 }
 
 fn start_record_logs(state: &mut Cheatcodes) {
@@ -113,15 +159,14 @@ fn start_record_logs(state: &mut Cheatcodes) {
 }
 
 fn get_recorded_logs(state: &mut Cheatcodes) -> Bytes {
-    if let Some(recorded_logs) = state.recorded_logs.replace(Default::default()) {
+    if let Some(recorded_logs) = &mut state.recorded_logs {
         ethers::abi::encode(
-            &recorded_logs
-                .entries
-                .iter()
+            &std::mem::take(&mut recorded_logs.entries)
+                .into_iter()
                 .map(|entry| {
                     Token::Tuple(vec![
-                        entry.topics.clone().into_token(),
-                        Token::Bytes(entry.data.clone()),
+                        entry.log.topics.into_token(),
+                        Token::Bytes(entry.log.data),
                     ])
                 })
                 .collect::<Vec<Token>>()
@@ -133,7 +178,434 @@ fn get_recorded_logs(state: &mut Cheatcodes) -> Bytes {
     }
 }
 
-pub fn apply<DB: Database>(
+/// Returns the recorded logs that match the `filter` with their emitter, depth and index, without
+/// removing them from the recorded logs
+fn get_filtered_recorded_logs(
+    state: &Cheatcodes,
+    filter: impl Fn(&RecordedLog) -> bool,
+) -> Bytes {
+    let entries = state
+        .recorded_logs
+        .iter()
+        .flat_map(|recorded_logs| recorded_logs.entries.iter())
+        .filter(|&entry| filter(entry))
+        .cloned()
+        .map(RecordedLog::into_token)
+        .collect::<Vec<Token>>();
+    ethers::abi::encode(&entries.into_tokens()).into()
+}
+
+fn clear_recorded_logs(state: &mut Cheatcodes) {
+    if let Some(recorded_logs) = &mut state.recorded_logs {
+        recorded_logs.entries.clear();
+    }
+}
+
+/// A `Database` that serves the state of the given `SubRoutine`, which has not been committed yet,
+/// before falling back to the underlying `Database`
+struct SubRoutineDatabase<'a, DB> {
+    subroutine: &'a SubRoutine,
+    db: &'a mut DB,
+}
+
+impl<'a, DB: Database> Database for SubRoutineDatabase<'a, DB> {
+    fn basic(&mut self, address: Address) -> AccountInfo {
+        match self.subroutine.state.get(&address) {
+            Some(account) => account.info.clone(),
+            None => self.db.basic(address),
+        }
+    }
+
+    fn code_by_hash(&mut self, code_hash: H256) -> Bytecode {
+        self.db.code_by_hash(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> U256 {
+        match self.subroutine.state.get(&address).and_then(|account| account.storage.get(&index)) {
+            Some(slot) => slot.present_value,
+            None => self.db.storage(address, index),
+        }
+    }
+
+    fn block_hash(&mut self, number: U256) -> H256 {
+        self.db.block_hash(number)
+    }
+}
+
+/// Executes a call to `target` as a new top-level transaction sent by `tx.origin` and applies its
+/// state changes to the current `SubRoutine`.
+///
+/// The changes are journaled like those of a regular call, so they are rolled back together if
+/// the calling frame reverts. The gas used by the transaction is charged to the call of the
+/// cheatcode.
+fn transact<DB: Database>(
+    state: &mut Cheatcodes,
+    data: &mut EVMData<'_, DB>,
+    target: Address,
+    value: U256,
+    calldata: Bytes,
+) -> Result<Bytes, Bytes> {
+    let mut env = data.env.clone();
+    env.tx.transact_to = TransactTo::Call(target);
+    env.tx.value = value;
+    env.tx.data = calldata;
+
+    let (status, retdata, gas_used) = execute_transaction(data, &mut env, false);
+    state.cheatcode_gas += gas_used;
+    if !matches!(status, return_ok!()) {
+        return Err(retdata)
+    }
+    Ok(abi::encode(&[Token::Bytes(retdata.to_vec())]).into())
+}
+
+/// Sets the code of the `target` to the runtime `code`
+fn etch<DB: Database>(data: &mut EVMData<'_, DB>, target: Address, code: Vec<u8>) {
+    // TODO: Does this increase gas usage?
+    data.subroutine.load_account(target, data.db);
+    data.subroutine.set_code(target, Bytecode::new_raw(code.into()).to_checked());
+}
+
+/// Runs the constructor of the creation `code` with the ABI encoded `args` on top of the current
+/// `SubRoutine`, etches the runtime code it returns at the `target` and writes the storage that
+/// the constructor wrote to its own contract to the `target`.
+///
+/// The other state changes of the constructor, e.g. of the contracts it called, are discarded and
+/// `msg.sender` of the constructor is `tx.origin`.
+fn etch_with_constructor<DB: Database>(
+    data: &mut EVMData<'_, DB>,
+    target: Address,
+    code: Vec<u8>,
+    args: &[u8],
+) -> Result<Bytes, Bytes> {
+    let mut env = data.env.clone();
+    env.tx.transact_to = TransactTo::Create(CreateScheme::Create);
+    env.tx.value = U256::zero();
+    env.tx.data = [code, args.to_vec()].concat().into();
+
+    let (status, out, _, changes, _) = {
+        let mut db = SubRoutineDatabase { subroutine: &data.subroutine, db: &mut *data.db };
+        revm::evm_inner::<_, false>(&mut env, &mut db, &mut NoOpInspector()).transact()
+    };
+    let (runtime_code, created) = match out {
+        TransactOut::Create(runtime_code, Some(created)) if matches!(status, return_ok!()) => {
+            (runtime_code, created)
+        }
+        TransactOut::Create(retdata, _) => return Err(retdata),
+        _ => return Err("The constructor did not create a contract".to_string().encode().into()),
+    };
+
+    etch(data, target, runtime_code.to_vec());
+    if let Some(account) = changes.get(&created) {
+        for (index, slot) in account.storage.iter() {
+            data.subroutine.sstore(target, *index, slot.present_value, data.db);
+        }
+    }
+    Ok(Bytes::new())
+}
+
+/// Calls `target` with `calldata` on top of the current `SubRoutine` without applying its state
+/// changes and returns its output, or `None` if the call failed
+fn view_call<DB: Database>(
+    data: &mut EVMData<'_, DB>,
+    target: Address,
+    calldata: Vec<u8>,
+) -> Option<Bytes> {
+    let mut env = data.env.clone();
+    env.tx.transact_to = TransactTo::Call(target);
+    env.tx.value = U256::zero();
+    env.tx.data = calldata.into();
+
+    let (status, out, ..) = {
+        let mut db = SubRoutineDatabase { subroutine: &data.subroutine, db: &mut *data.db };
+        revm::evm_inner::<_, false>(&mut env, &mut db, &mut NoOpInspector()).transact()
+    };
+    match out {
+        TransactOut::Call(retdata) if matches!(status, return_ok!()) => Some(retdata),
+        _ => None,
+    }
+}
+
+/// Calls the view function `signature` of the `token` with the `args` and returns the `uint256`
+/// it returns
+fn token_uint<DB: Database>(
+    data: &mut EVMData<'_, DB>,
+    token: Address,
+    signature: &str,
+    args: &[Token],
+) -> Result<U256, Bytes> {
+    let calldata = [&id(signature)[..], &abi::encode(args)].concat();
+    view_call(data, token, calldata)
+        .and_then(|retdata| abi::decode(&[ParamType::Uint(256)], &retdata).ok())
+        .and_then(|tokens| tokens.into_iter().next()?.into_uint())
+        .ok_or_else(|| {
+            format!("Failed to call `{signature}` of the token {}", to_checksum(&token, None))
+                .encode()
+                .into()
+        })
+}
+
+/// Returns the symbol of the `token`, which is either a `string` or a `bytes32`, or the address of
+/// the token if it has no symbol
+fn token_symbol<DB: Database>(data: &mut EVMData<'_, DB>, token: Address) -> String {
+    let symbol = view_call(data, token, id("symbol()").to_vec()).and_then(|retdata| {
+        match abi::decode(&[ParamType::String], &retdata) {
+            Ok(tokens) => tokens.into_iter().next()?.into_string(),
+            // some tokens, like MKR, return their symbol as a `bytes32`
+            Err(_) if retdata.len() == 32 => {
+                Some(String::from_utf8_lossy(&retdata).trim_end_matches('\0').to_string())
+            }
+            Err(_) => None,
+        }
+    });
+    symbol
+        .filter(|symbol| !symbol.is_empty())
+        .unwrap_or_else(|| format!("tokens of {}", to_checksum(&token, None)))
+}
+
+/// Returns the label of the `account` set by `label` together with its address, or only the
+/// address if it has no label
+fn account_name(state: &Cheatcodes, account: Address) -> String {
+    let address = to_checksum(&account, None);
+    match state.labels.get(&account) {
+        Some(label) => format!("{label} ({address})"),
+        None => address,
+    }
+}
+
+/// Prefixes the failure `message` of a token assertion with its `label`, if any
+fn token_assertion_failure(label: &str, message: String) -> Bytes {
+    let message = if label.is_empty() { message } else { format!("{label}: {message}") };
+    message.encode().into()
+}
+
+fn assert_token_balance<DB: Database>(
+    state: &Cheatcodes,
+    data: &mut EVMData<'_, DB>,
+    token: Address,
+    account: Address,
+    expected: U256,
+    label: &str,
+) -> Result<Bytes, Bytes> {
+    let actual = token_uint(data, token, "balanceOf(address)", &[Token::Address(account)])?;
+    if actual == expected {
+        return Ok(Bytes::new())
+    }
+    let message = format!(
+        "expected {} to hold {expected} {}, but it holds {actual}",
+        account_name(state, account),
+        token_symbol(data, token)
+    );
+    Err(token_assertion_failure(label, message))
+}
+
+fn assert_token_allowance<DB: Database>(
+    state: &Cheatcodes,
+    data: &mut EVMData<'_, DB>,
+    token: Address,
+    owner: Address,
+    spender: Address,
+    expected: U256,
+    label: &str,
+) -> Result<Bytes, Bytes> {
+    let args = [Token::Address(owner), Token::Address(spender)];
+    let actual = token_uint(data, token, "allowance(address,address)", &args)?;
+    if actual == expected {
+        return Ok(Bytes::new())
+    }
+    let message = format!(
+        "expected {} to allow {} to spend {expected} {}, but the allowance is {actual}",
+        account_name(state, owner),
+        account_name(state, spender),
+        token_symbol(data, token)
+    );
+    Err(token_assertion_failure(label, message))
+}
+
+fn account_balance<DB: Database>(data: &mut EVMData<'_, DB>, account: Address) -> U256 {
+    data.subroutine.load_account(account, data.db);
+    data.subroutine.account(account).info.balance
+}
+
+/// Describes a change of a balance with the verbs for an increase, a decrease and no change, e.g.
+/// `increase by 5`
+fn describe_delta(delta: I256, [increase, decrease, unchanged]: [&str; 3]) -> String {
+    if delta.is_zero() {
+        unchanged.to_string()
+    } else if delta.is_positive() {
+        format!("{increase} by {delta}")
+    } else {
+        format!("{decrease} by {}", delta.unsigned_abs())
+    }
+}
+
+/// Returns the change of a balance from `before` to `after`, `None` if it does not fit into an
+/// `int256`, e.g. for balances dealt with `type(uint256).max`
+fn balance_delta(before: U256, after: U256) -> Option<I256> {
+    if let Some(increase) = after.checked_sub(before) {
+        return (increase <= I256::MAX.into_raw()).then(|| I256::from_raw(increase))
+    }
+    let decrease = before.checked_sub(after)?;
+    // the magnitude of the most negative `int256` is one more than the largest one
+    (decrease <= I256::MIN.into_raw())
+        .then(|| I256::from_raw(U256::zero().overflowing_sub(decrease).0))
+}
+
+fn assert_balance_change<DB: Database>(
+    state: &Cheatcodes,
+    data: &mut EVMData<'_, DB>,
+    account: Address,
+    expected: I256,
+) -> Result<Bytes, Bytes> {
+    let before = state.balance_snapshots.get(&account).copied().ok_or_else(|| {
+        Bytes::from(
+            format!(
+                "no balance of {} was recorded, call `expectBalanceChange` first",
+                account_name(state, account)
+            )
+            .encode(),
+        )
+    })?;
+    let after = account_balance(data, account);
+    let actual = match balance_delta(before, after) {
+        Some(actual) if actual == expected => return Ok(Bytes::new()),
+        Some(actual) => describe_delta(actual, ["increased", "decreased", "did not change"]),
+        None if after > before => {
+            format!("increased by {}, which is out of int256 range", after - before)
+        }
+        None => format!("decreased by {}, which is out of int256 range", before - after),
+    };
+    Err(format!(
+        "expected the balance of {} to {}, but it {actual}",
+        account_name(state, account),
+        describe_delta(expected, ["increase", "decrease", "not change"])
+    )
+    .encode()
+    .into())
+}
+
+/// Executes the top-level `call` of the test contract as its own transaction, see
+/// [`Cheatcodes::isolate`]
+///
+/// Unlike a regular call this increments the nonce of the caller and charges the intrinsic gas of
+/// the transaction to the call. The state changes of a reverted transaction are kept as well, so
+/// the nonce increment persists.
+pub(super) fn isolated_call<DB: Database>(
+    data: &mut EVMData<'_, DB>,
+    call: &CallInputs,
+) -> (Return, Gas, Bytes) {
+    let mut env = data.env.clone();
+    env.tx.caller = call.context.caller;
+    env.tx.transact_to = TransactTo::Call(call.contract);
+    env.tx.value = call.transfer.value;
+    env.tx.data = call.input.clone();
+    env.tx.gas_limit = call.gas_limit;
+
+    let (status, retdata, gas_used) = execute_transaction(data, &mut env, true);
+    let mut gas = Gas::new(call.gas_limit);
+    gas.record_cost(gas_used.min(call.gas_limit));
+    (status, gas, retdata)
+}
+
+/// Executes the transaction of the `env` on top of the current, uncommitted `SubRoutine` and
+/// returns its status, output and the gas it used
+///
+/// The nonce of the sender is incremented once for every executed transaction. The other state
+/// changes and logs of the transaction are applied to the `SubRoutine` if it succeeded, or
+/// regardless of its outcome if `always_apply` is set.
+fn execute_transaction<DB: Database>(
+    data: &mut EVMData<'_, DB>,
+    env: &mut Env,
+    always_apply: bool,
+) -> (Return, Bytes, u64) {
+    let (status, out, gas_used, changes, mut logs) = {
+        let mut db = SubRoutineDatabase { subroutine: &data.subroutine, db: &mut *data.db };
+        revm::evm_inner::<_, false>(env, &mut db, &mut NoOpInspector()).transact()
+    };
+
+    let retdata = match out {
+        TransactOut::Call(retdata) => retdata,
+        _ => Bytes::new(),
+    };
+    // transactions that are rejected before they are executed do not change any account
+    let sender = env.tx.caller;
+    if changes.contains_key(&sender) {
+        data.subroutine.load_account(sender, data.db);
+        data.subroutine.inc_nonce(sender);
+    }
+    if !always_apply && !matches!(status, return_ok!()) {
+        return (status, retdata, gas_used)
+    }
+
+    for (address, account) in changes.iter() {
+        data.subroutine.load_account(*address, data.db);
+        let current = &data.subroutine.account(*address).info;
+        let (before, after) = (current.balance, account.info.balance);
+        // the sender's nonce is incremented above
+        let nonce = if *address == sender { current.nonce - 1 } else { current.nonce };
+        let code_changed = current.code_hash != account.info.code_hash;
+
+        // the fees the sender paid are burned unless they went to the coinbase, so the balances
+        // are changed individually instead of with transfers
+        if after < before {
+            data.subroutine.balance_sub(*address, before - after);
+        } else if after > before {
+            data.subroutine.balance_add(*address, after - before);
+        }
+        for _ in nonce..account.info.nonce {
+            data.subroutine.inc_nonce(*address);
+        }
+        if code_changed {
+            if let Some(code) = account.info.code.clone() {
+                data.subroutine.set_code(*address, code);
+            }
+        }
+        for (index, slot) in account.storage.iter() {
+            data.subroutine.sstore(*address, *index, slot.present_value, data.db);
+        }
+    }
+    data.subroutine.logs.append(&mut logs);
+
+    (status, retdata, gas_used)
+}
+
+/// Returns the shift in bits and the unshifted mask of the `size` bytes at `offset` within a
+/// storage slot, counted from the low-order end like solc packs values into a slot.
+fn packed_mask(offset: U256, size: U256) -> Result<(usize, U256), Bytes> {
+    if size.is_zero() || size > U256::from(32) || offset > U256::from(32) - size {
+        return Err(format!("Invalid packed storage range: {size} bytes at offset {offset}")
+            .encode()
+            .into())
+    }
+    let (offset, size) = (offset.as_usize(), size.as_usize());
+    let mask = if size == 32 { U256::MAX } else { (U256::one() << (size * 8)) - 1 };
+    Ok((offset * 8, mask))
+}
+
+/// The gas charged per slot copied by `copyStorage`: a cold `SLOAD` of the source slot and an
+/// `SSTORE` of a new non-zero value into the target slot
+const COPY_STORAGE_SLOT_GAS: u64 = 2_100 + 20_000;
+
+/// Copies all non-zero storage slots of `from` to `to` and returns how many slots were copied.
+///
+/// Storage cannot be enumerated in the EVM, so the slots are taken from the database and from the
+/// changes to `from` in the current `SubRoutine`.
+fn copy_storage<DB: DatabaseExt>(data: &mut EVMData<'_, DB>, from: Address, to: Address) -> u64 {
+    let mut slots = data.db.storage_slots(from);
+    data.subroutine.load_account(from, data.db);
+    if let Some(account) = data.subroutine.state.get(&from) {
+        slots.extend(account.storage.iter().map(|(index, slot)| (*index, slot.present_value)));
+    }
+
+    data.subroutine.load_account(to, data.db);
+    let mut copied = 0;
+    for (index, value) in slots.into_iter().filter(|(_, value)| !value.is_zero()) {
+        data.subroutine.sstore(to, index, value, data.db);
+        copied += 1;
+    }
+    copied
+}
+
+pub fn apply<DB: DatabaseExt>(
     state: &mut Cheatcodes,
     data: &mut EVMData<'_, DB>,
     caller: Address,
@@ -168,14 +640,39 @@ pub fn apply<DB: Database>(
             let (val, _) = data.subroutine.sload(inner.0, inner.1.into(), data.db);
             Ok(val.encode().into())
         }
-        HEVMCalls::Etch(inner) => {
-            let code = inner.1.clone();
-
-            // TODO: Does this increase gas usage?
+        HEVMCalls::StorePacked(inner) => packed_mask(inner.2, inner.3).and_then(|(shift, mask)| {
+            let value: U256 = inner.4.into();
+            if value > mask {
+                return Err(format!("Value {value} does not fit into {} bytes", inner.3)
+                    .encode()
+                    .into())
+            }
+            data.subroutine.load_account(inner.0, data.db);
+            let (current, _) = data.subroutine.sload(inner.0, inner.1.into(), data.db);
+            let packed = (current & !(mask << shift)) | (value << shift);
+            data.subroutine.sstore(inner.0, inner.1.into(), packed, data.db);
+            Ok(Bytes::new())
+        }),
+        HEVMCalls::LoadPacked(inner) => packed_mask(inner.2, inner.3).map(|(shift, mask)| {
             data.subroutine.load_account(inner.0, data.db);
-            data.subroutine.set_code(inner.0, Bytecode::new_raw(code.0).to_checked());
+            let (current, _) = data.subroutine.sload(inner.0, inner.1.into(), data.db);
+            ((current >> shift) & mask).encode().into()
+        }),
+        HEVMCalls::CopyStorage(inner) => {
+            let copied = copy_storage(data, inner.0, inner.1);
+            state.cheatcode_gas += copied * COPY_STORAGE_SLOT_GAS;
             Ok(Bytes::new())
         }
+        HEVMCalls::Etch(inner) => {
+            etch(data, inner.0, inner.1.to_vec());
+            Ok(Bytes::new())
+        }
+        HEVMCalls::EtchContract(inner) => artifact_code(&inner.1, true).map(|code| {
+            etch(data, inner.0, code);
+            Bytes::new()
+        }),
+        HEVMCalls::EtchWithConstructor(inner) => artifact_code(&inner.1, false)
+            .and_then(|code| etch_with_constructor(data, inner.0, code, &inner.2)),
         HEVMCalls::Deal(inner) => {
             let who = inner.0;
             let value = inner.1;
@@ -229,7 +726,17 @@ pub fn apply<DB: Database>(
             start_record_logs(state);
             Ok(Bytes::new())
         }
-        HEVMCalls::GetRecordedLogs(_) => Ok(get_recorded_logs(state)),
+        HEVMCalls::GetRecordedLogs0(_) => Ok(get_recorded_logs(state)),
+        HEVMCalls::GetRecordedLogs1(inner) => {
+            Ok(get_filtered_recorded_logs(state, |entry| entry.emitter == inner.0))
+        }
+        HEVMCalls::GetRecordedLogs2(inner) => Ok(get_filtered_recorded_logs(state, |entry| {
+            entry.log.topics.first().map_or(false, |topic0| topic0.0 == inner.0)
+        })),
+        HEVMCalls::ClearRecordedLogs(_) => {
+            clear_recorded_logs(state);
+            Ok(Bytes::new())
+        }
         HEVMCalls::SetNonce(inner) => {
             // TODO:  this is probably not a good long-term solution since it might mess up the gas
             // calculations
@@ -280,6 +787,24 @@ pub fn apply<DB: Database>(
             state.broadcast = None;
             Ok(Bytes::new())
         }
+        HEVMCalls::Transact0(inner) => {
+            transact(state, data, inner.0, U256::zero(), inner.1 .0.clone())
+        }
+        HEVMCalls::Transact1(inner) => transact(state, data, inner.0, inner.1, inner.2 .0.clone()),
+        HEVMCalls::AssertTokenBalance(inner) => {
+            assert_token_balance(state, data, inner.0, inner.1, inner.2, &inner.3)
+        }
+        HEVMCalls::AssertTokenAllowance(inner) => {
+            assert_token_allowance(state, data, inner.0, inner.1, inner.2, inner.3, &inner.4)
+        }
+        HEVMCalls::ExpectBalanceChange(inner) => {
+            let balance = account_balance(data, inner.0);
+            state.balance_snapshots.insert(inner.0, balance);
+            Ok(Bytes::new())
+        }
+        HEVMCalls::AssertBalanceChange(inner) => {
+            assert_balance_change(state, data, inner.0, inner.1)
+        }
         _ => return None,
     })
 }
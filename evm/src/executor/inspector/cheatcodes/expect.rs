@@ -1,12 +1,18 @@
 use super::Cheatcodes;
 use crate::{
     abi::HEVMCalls,
-    executor::inspector::cheatcodes::util::{ERROR_PREFIX, REVERT_PREFIX},
+    decode,
+    executor::{
+        inspector::cheatcodes::util::{ERROR_PREFIX, REVERT_PREFIX},
+        CHEATCODE_ADDRESS,
+    },
+    utils::u256_to_h256_be,
 };
 use bytes::Bytes;
 use ethers::{
-    abi::{AbiDecode, AbiEncode, RawLog},
+    abi::{AbiDecode, AbiEncode, HumanReadableParser, RawLog},
     types::{Address, H160, U256},
+    utils::id,
 };
 use revm::{return_ok, Bytecode, Database, EVMData, Return};
 use std::cmp::Ordering;
@@ -25,34 +31,128 @@ static DUMMY_CREATE_ADDRESS: Address =
 
 #[derive(Clone, Debug, Default)]
 pub struct ExpectedRevert {
-    /// The expected data returned by the revert
+    /// The expected data returned by the revert, or the expected selector if the revert is
+    /// matched by its origin
     pub reason: Bytes,
     /// The depth at which the revert is expected
     pub depth: u64,
+    /// The origin of the revert if it is matched against the reverts within the next call instead
+    /// of the data the next call returns
+    pub origin: Option<RevertOrigin>,
+    /// The reverted calls within the next call, innermost first, only recorded if the revert is
+    /// matched by its origin
+    pub chain: Vec<RevertFrame>,
 }
 
-fn expect_revert(state: &mut Cheatcodes, reason: Bytes, depth: u64) -> Result<Bytes, Bytes> {
+impl ExpectedRevert {
+    /// Records the revert of a call within the next call, `address` is `None` for creates
+    pub fn record(
+        &mut self,
+        address: Option<Address>,
+        depth: u64,
+        status: Return,
+        retdata: &Bytes,
+    ) {
+        if self.origin.is_none() || matches!(status, return_ok!()) || depth < self.depth {
+            return
+        }
+        // the next call of the test is at depth 1
+        let depth = depth - self.depth + 1;
+        self.chain.push(RevertFrame { address, depth, data: retdata.clone() });
+    }
+}
+
+/// Where a revert expected by `expectRevert(address,bytes4)` or `expectRevertAtDepth` originates
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RevertOrigin {
+    /// The contract that reverts
+    Reverter(Address),
+    /// The depth of the call that reverts, the next call of the test is at depth 1
+    Depth(u64),
+}
+
+/// A reverted call within the call that is expected to revert
+#[derive(Clone, Debug)]
+pub struct RevertFrame {
+    /// The address of the called contract, `None` for a create
+    pub address: Option<Address>,
+    /// The depth of the call, the next call of the test is at depth 1
+    pub depth: u64,
+    /// The data the call reverted with
+    pub data: Bytes,
+}
+
+impl RevertFrame {
+    fn matches(&self, origin: &RevertOrigin, selector: &[u8]) -> bool {
+        let origin_matches = match origin {
+            RevertOrigin::Reverter(address) => self.address == Some(*address),
+            RevertOrigin::Depth(depth) => self.depth == *depth,
+        };
+        origin_matches && self.data.starts_with(selector)
+    }
+}
+
+impl std::fmt::Display for RevertFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = decode::decode_revert(&self.data, None, None)
+            .unwrap_or_else(|_| format!("0x{}", hex::encode(&self.data)));
+        match self.address {
+            Some(address) => write!(f, "depth {}: {address:?} reverted with {reason}", self.depth),
+            None => write!(f, "depth {}: a create reverted with {reason}", self.depth),
+        }
+    }
+}
+
+fn expect_revert(
+    state: &mut Cheatcodes,
+    reason: Bytes,
+    depth: u64,
+    origin: Option<RevertOrigin>,
+) -> Result<Bytes, Bytes> {
     if state.expected_revert.is_some() {
         Err("You must call another function prior to expecting a second revert."
             .to_string()
             .encode()
             .into())
     } else {
-        state.expected_revert = Some(ExpectedRevert { reason, depth });
+        state.expected_revert = Some(ExpectedRevert { reason, depth, origin, chain: Vec::new() });
         Ok(Bytes::new())
     }
 }
 
 pub fn handle_expect_revert(
     is_create: bool,
-    expected_revert: &Bytes,
+    expected: &ExpectedRevert,
     status: Return,
     retdata: Bytes,
 ) -> Result<(Option<Address>, Bytes), Bytes> {
     if matches!(status, return_ok!()) {
-        return Err("Call did not revert as expected".to_string().encode().into())
+        let mut err = "Call did not revert as expected".to_string();
+        if !expected.chain.is_empty() {
+            err.push_str(&format!(", reverts caught within it:{}", format_chain(&expected.chain)));
+        }
+        return Err(err.encode().into())
     }
 
+    if let Some(origin) = &expected.origin {
+        if !expected.chain.iter().any(|frame| frame.matches(origin, &expected.reason)) {
+            let origin = match origin {
+                RevertOrigin::Reverter(address) => format!("{address:?}"),
+                RevertOrigin::Depth(depth) => format!("the call at depth {depth}"),
+            };
+            return Err(format!(
+                "Call reverted, but not from {origin} with selector 0x{}, revert chain:{}",
+                hex::encode(&expected.reason),
+                format_chain(&expected.chain)
+            )
+            .encode()
+            .into())
+        }
+        return Ok(dummy_output(is_create))
+    }
+
+    let expected_revert = &expected.reason;
+
     if !expected_revert.is_empty() && retdata.is_empty() {
         return Err("Call reverted as expected, but without data".to_string().encode().into())
     }
@@ -107,16 +207,26 @@ pub fn handle_expect_revert(
     };
 
     if actual_revert == expected_revert {
-        Ok(if is_create {
-            (Some(DUMMY_CREATE_ADDRESS), Bytes::new())
-        } else {
-            (None, DUMMY_CALL_OUTPUT.to_vec().into())
-        })
+        Ok(dummy_output(is_create))
     } else {
         Err(err)
     }
 }
 
+/// Returns the output of a call or create that reverted as expected, see [DUMMY_CALL_OUTPUT]
+fn dummy_output(is_create: bool) -> (Option<Address>, Bytes) {
+    if is_create {
+        (Some(DUMMY_CREATE_ADDRESS), Bytes::new())
+    } else {
+        (None, DUMMY_CALL_OUTPUT.to_vec().into())
+    }
+}
+
+/// Formats the reverts of the `chain` on one line each, innermost first
+fn format_chain(chain: &[RevertFrame]) -> String {
+    chain.iter().map(|frame| format!("\n  {frame}")).collect()
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct ExpectedEmit {
     /// The depth at which we expect this emit to have occurred
@@ -212,19 +322,194 @@ impl PartialOrd for MockCallDataContext {
     }
 }
 
+/// The scratch space and the free memory pointer, which are written to by any Solidity code
+const RESERVED_MEMORY: u64 = 0x60;
+
+/// The memory a contract is allowed to write to after calling `expectSafeMemory`
+#[derive(Clone, Debug, Default)]
+pub struct ExpectedSafeMemory {
+    /// The lowest memory offset that may be written
+    pub min: u64,
+    /// The highest memory offset that may be written
+    pub max: u64,
+    /// The depth of the contract whose memory writes are checked
+    pub depth: u64,
+    /// The first write outside of the allowed range, checked when the contract leaves its frame
+    /// or calls out of it, see [`ExpectedSafeMemory::check_exit()`]
+    pub violation: Option<UnsafeMemoryWrite>,
+}
+
+/// A memory write outside of the range allowed by `expectSafeMemory`
+#[derive(Clone, Debug)]
+pub struct UnsafeMemoryWrite {
+    /// The memory offset of the write
+    pub offset: U256,
+    /// The number of bytes written
+    pub size: u64,
+    /// The program counter of the write
+    pub pc: usize,
+}
+
+impl ExpectedSafeMemory {
+    /// Records the write of `size` bytes at the memory `offset`, made at `pc`, if it is the first
+    /// one outside of the allowed range.
+    ///
+    /// Writes to the scratch space and the free memory pointer below `0x60` are always allowed.
+    /// Solidity writes to them to hash values and to allocate memory, they never hold memory that
+    /// is allocated.
+    pub fn record_write(&mut self, offset: U256, size: u64, pc: usize) {
+        let end = offset.saturating_add(U256::from(size - 1));
+        if self.violation.is_some() ||
+            end < U256::from(RESERVED_MEMORY) ||
+            (offset >= U256::from(self.min) && end <= U256::from(self.max))
+        {
+            return
+        }
+        self.violation = Some(UnsafeMemoryWrite { offset, size, pc });
+    }
+
+    /// Checks the recorded violation when the contract leaves its frame or calls out of it.
+    ///
+    /// `stop_call_args` is the memory region of the calldata if the contract calls
+    /// `stopExpectSafeMemory`, a violation within it is dropped since encoding that call writes to
+    /// memory that is not allocated.
+    pub fn check_exit(&mut self, stop_call_args: Option<(U256, U256)>) -> Result<(), String> {
+        let violation = match self.violation.take() {
+            Some(violation) => violation,
+            None => return Ok(()),
+        };
+        if let Some((start, size)) = stop_call_args {
+            let end = violation.offset.saturating_add(U256::from(violation.size));
+            if violation.offset >= start && end <= start.saturating_add(size) {
+                return Ok(())
+            }
+        }
+        let UnsafeMemoryWrite { offset, size, pc } = violation;
+        Err(format!(
+            "Memory write of {size} bytes at offset {offset:#x} (pc {pc}) is outside of the allowed range [{:#x}, {:#x}]",
+            self.min, self.max
+        ))
+    }
+}
+
+/// Returns the calldata region of a call to `stopExpectSafeMemory` made with the topmost stack
+/// items of the `opcode`, `None` if it is not such a call
+pub fn stop_safe_memory_call_args(
+    opcode: u8,
+    stack: &revm::Stack,
+    memory: &revm::Memory,
+) -> Option<(U256, U256)> {
+    // the value of a `CALL` sits between the address and the calldata
+    let args = match opcode {
+        revm::opcode::CALL | revm::opcode::CALLCODE => 3,
+        revm::opcode::STATICCALL | revm::opcode::DELEGATECALL => 2,
+        _ => return None,
+    };
+    let address = Address::from(u256_to_h256_be(stack.peek(1).ok()?));
+    let (offset, size) = (stack.peek(args).ok()?, stack.peek(args + 1).ok()?);
+    if address != CHEATCODE_ADDRESS || size < U256::from(4) {
+        return None
+    }
+    if offset.saturating_add(U256::from(4)) > U256::from(memory.len()) {
+        return None
+    }
+    let selector = memory.get_slice(offset.as_usize(), 4);
+    (selector == id("stopExpectSafeMemory()")).then(|| (offset, size))
+}
+
+fn expect_safe_memory(
+    state: &mut Cheatcodes,
+    min: u64,
+    max: u64,
+    depth: u64,
+) -> Result<Bytes, Bytes> {
+    if min > max {
+        return Err(format!("Invalid memory range [{min:#x}, {max:#x}], min must not exceed max")
+            .encode()
+            .into())
+    }
+    state.expected_safe_memory = Some(ExpectedSafeMemory { min, max, depth, violation: None });
+    Ok(Bytes::new())
+}
+
+/// Mocks the calls to `address` whose calldata starts with `calldata` to return `returndata`
+fn mock_call<DB: Database>(
+    state: &mut Cheatcodes,
+    data: &mut EVMData<'_, DB>,
+    address: Address,
+    calldata: Bytes,
+    returndata: Bytes,
+) {
+    // TODO: Does this increase gas usage?
+    data.subroutine.load_account(address, data.db);
+
+    // Etches a single byte onto the account if it is empty to circumvent the `extcodesize`
+    // check Solidity might perform.
+    if data
+        .subroutine
+        .account(address)
+        .info
+        .code
+        .as_ref()
+        .map(|code| code.is_empty())
+        .unwrap_or(true)
+    {
+        let code = Bytecode::new_raw(Bytes::from_static(&[0u8])).to_checked();
+        data.subroutine.set_code(address, code);
+    }
+    state
+        .mocked_calls
+        .entry(address)
+        .or_default()
+        .insert(MockCallDataContext { calldata, value: None }, returndata);
+}
+
+/// Mocks all calls of the function with the signature `sig`, e.g. `transfer(address,uint256)`,
+/// on `address` to return `returndata`
+fn mock_return<DB: Database>(
+    state: &mut Cheatcodes,
+    data: &mut EVMData<'_, DB>,
+    address: Address,
+    sig: &str,
+    returndata: Vec<u8>,
+) -> Result<Bytes, Bytes> {
+    let func = HumanReadableParser::parse_function(sig).map_err(|err| {
+        Bytes::from(format!("Invalid function signature `{sig}`: {err}").encode())
+    })?;
+    mock_call(state, data, address, func.short_signature().to_vec().into(), returndata.into());
+    Ok(Bytes::new())
+}
+
 pub fn apply<DB: Database>(
     state: &mut Cheatcodes,
     data: &mut EVMData<'_, DB>,
     call: &HEVMCalls,
 ) -> Option<Result<Bytes, Bytes>> {
     Some(match call {
-        HEVMCalls::ExpectRevert0(_) => expect_revert(state, Bytes::new(), data.subroutine.depth()),
+        HEVMCalls::ExpectRevert0(_) => {
+            expect_revert(state, Bytes::new(), data.subroutine.depth(), None)
+        }
         HEVMCalls::ExpectRevert1(inner) => {
-            expect_revert(state, inner.0.to_vec().into(), data.subroutine.depth())
+            expect_revert(state, inner.0.to_vec().into(), data.subroutine.depth(), None)
         }
         HEVMCalls::ExpectRevert2(inner) => {
-            expect_revert(state, inner.0.to_vec().into(), data.subroutine.depth())
+            expect_revert(state, inner.0.to_vec().into(), data.subroutine.depth(), None)
         }
+        HEVMCalls::ExpectRevert3(inner) => expect_revert(
+            state,
+            inner.1.to_vec().into(),
+            data.subroutine.depth(),
+            Some(RevertOrigin::Reverter(inner.0)),
+        ),
+        HEVMCalls::ExpectRevertAtDepth(inner) if inner.0 == 0 => {
+            Err("The depth of the revert must be at least 1".to_string().encode().into())
+        }
+        HEVMCalls::ExpectRevertAtDepth(inner) => expect_revert(
+            state,
+            inner.1.to_vec().into(),
+            data.subroutine.depth(),
+            Some(RevertOrigin::Depth(inner.0)),
+        ),
         HEVMCalls::ExpectEmit0(inner) => {
             state.expected_emits.push(ExpectedEmit {
                 depth: data.subroutine.depth() - 1,
@@ -259,27 +544,7 @@ pub fn apply<DB: Database>(
             Ok(Bytes::new())
         }
         HEVMCalls::MockCall0(inner) => {
-            // TODO: Does this increase gas usage?
-            data.subroutine.load_account(inner.0, data.db);
-
-            // Etches a single byte onto the account if it is empty to circumvent the `extcodesize`
-            // check Solidity might perform.
-            if data
-                .subroutine
-                .account(inner.0)
-                .info
-                .code
-                .as_ref()
-                .map(|code| code.is_empty())
-                .unwrap_or(true)
-            {
-                let code = Bytecode::new_raw(Bytes::from_static(&[0u8])).to_checked();
-                data.subroutine.set_code(inner.0, code);
-            }
-            state.mocked_calls.entry(inner.0).or_default().insert(
-                MockCallDataContext { calldata: inner.1.to_vec().into(), value: None },
-                inner.2.to_vec().into(),
-            );
+            mock_call(state, data, inner.0, inner.1.to_vec().into(), inner.2.to_vec().into());
             Ok(Bytes::new())
         }
         HEVMCalls::MockCall1(inner) => {
@@ -289,10 +554,32 @@ pub fn apply<DB: Database>(
             );
             Ok(Bytes::new())
         }
+        HEVMCalls::MockReturn(inner) => {
+            mock_return(state, data, inner.0, &inner.1, inner.2.to_vec())
+        }
+        HEVMCalls::MockReturnUint(inner) => {
+            mock_return(state, data, inner.0, &inner.1, inner.2.encode())
+        }
+        HEVMCalls::MockReturnAddress(inner) => {
+            mock_return(state, data, inner.0, &inner.1, inner.2.encode())
+        }
+        HEVMCalls::MockReturnBool(inner) => {
+            mock_return(state, data, inner.0, &inner.1, inner.2.encode())
+        }
+        HEVMCalls::MockReturnBytes32(inner) => {
+            mock_return(state, data, inner.0, &inner.1, inner.2.encode())
+        }
         HEVMCalls::ClearMockedCalls(_) => {
             state.mocked_calls = Default::default();
             Ok(Bytes::new())
         }
+        HEVMCalls::ExpectSafeMemory(inner) => {
+            expect_safe_memory(state, inner.0, inner.1, data.subroutine.depth())
+        }
+        HEVMCalls::StopExpectSafeMemory(_) => {
+            state.expected_safe_memory = None;
+            Ok(Bytes::new())
+        }
         _ => return None,
     })
 }
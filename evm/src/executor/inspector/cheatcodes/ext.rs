@@ -10,11 +10,12 @@ use ethers::{
     utils::hex::FromHex,
 };
 use foundry_common::fs;
+use foundry_config::FsAccessKind;
 use serde::Deserialize;
 use std::{
     env,
-    io::{BufRead, BufReader, Write},
-    path::{Path, PathBuf},
+    io::{self, BufRead, BufReader, Write},
+    path::{Component, Path, PathBuf},
     process::Command,
     str::FromStr,
 };
@@ -74,6 +75,16 @@ impl ArtifactBytecode {
             }
         }
     }
+
+    fn into_deployed(self) -> Option<ethers::types::Bytes> {
+        match self {
+            ArtifactBytecode::Hardhat(inner) => inner.deployed_bytecode,
+            ArtifactBytecode::Forge(inner) => inner
+                .deployed_bytecode
+                .and_then(|deployed| deployed.bytecode)
+                .and_then(|bytecode| bytecode.object.into_bytes()),
+        }
+    }
 }
 
 /// A thin wrapper around a Hardhat-style artifact that only extracts the bytecode.
@@ -81,9 +92,14 @@ impl ArtifactBytecode {
 struct HardhatArtifact {
     #[serde(deserialize_with = "ethers::solc::artifacts::deserialize_bytes")]
     bytecode: ethers::types::Bytes,
+    #[serde(default, rename = "deployedBytecode")]
+    deployed_bytecode: Option<ethers::types::Bytes>,
 }
 
-fn get_code(path: &str) -> Result<Bytes, Bytes> {
+/// Reads the creation code, or the runtime code if `deployed` is set, of the artifact at `path`,
+/// which is either the path of the artifact file or `<file>` or `<file>:<contract name>` of a
+/// contract in the artifacts directory, e.g. `Vault.sol:Vault`
+pub(super) fn artifact_code(path: &str, deployed: bool) -> Result<Vec<u8>, Bytes> {
     let path = if path.ends_with(".json") {
         Path::new(&path).to_path_buf()
     } else {
@@ -98,13 +114,19 @@ fn get_code(path: &str) -> Result<Bytes, Bytes> {
     let data = fs::read_to_string(path).map_err(util::encode_error)?;
     let bytecode = serde_json::from_str::<ArtifactBytecode>(&data).map_err(util::encode_error)?;
 
-    if let Some(bin) = bytecode.into_inner() {
-        Ok(abi::encode(&[Token::Bytes(bin.to_vec())]).into())
+    let code = if deployed { bytecode.into_deployed() } else { bytecode.into_inner() };
+    if let Some(bin) = code {
+        Ok(bin.to_vec())
     } else {
         Err("No bytecode for contract. Is it abstract or unlinked?".to_string().encode().into())
     }
 }
 
+fn get_code(path: &str) -> Result<Bytes, Bytes> {
+    let code = artifact_code(path, false)?;
+    Ok(abi::encode(&[Token::Bytes(code)]).into())
+}
+
 fn set_env(key: &str, val: &str) -> Result<Bytes, Bytes> {
     // `std::env::set_var` may panic in the following situations
     // ref: https://doc.rust-lang.org/std/env/fn.set_var.html
@@ -187,7 +209,7 @@ fn full_path(state: &Cheatcodes, path: impl AsRef<Path>) -> PathBuf {
 
 fn read_file(state: &Cheatcodes, path: impl AsRef<Path>) -> Result<Bytes, Bytes> {
     let path = full_path(state, &path);
-    state.config.ensure_path_allowed(&path).map_err(util::encode_error)?;
+    state.config.ensure_path_allowed(&path, FsAccessKind::Read).map_err(util::encode_error)?;
 
     let data = fs::read_to_string(path).map_err(util::encode_error)?;
 
@@ -196,7 +218,7 @@ fn read_file(state: &Cheatcodes, path: impl AsRef<Path>) -> Result<Bytes, Bytes>
 
 fn read_line(state: &mut Cheatcodes, path: impl AsRef<Path>) -> Result<Bytes, Bytes> {
     let path = full_path(state, &path);
-    state.config.ensure_path_allowed(&path).map_err(util::encode_error)?;
+    state.config.ensure_path_allowed(&path, FsAccessKind::Read).map_err(util::encode_error)?;
 
     // Get reader for previously opened file to continue reading OR initialize new reader
     let reader = state
@@ -221,7 +243,7 @@ fn read_line(state: &mut Cheatcodes, path: impl AsRef<Path>) -> Result<Bytes, By
 
 fn write_file(state: &Cheatcodes, path: impl AsRef<Path>, data: &str) -> Result<Bytes, Bytes> {
     let path = full_path(state, &path);
-    state.config.ensure_path_allowed(&path).map_err(util::encode_error)?;
+    state.config.ensure_path_allowed(&path, FsAccessKind::Write).map_err(util::encode_error)?;
 
     fs::write(path, data).map_err(util::encode_error)?;
 
@@ -230,7 +252,7 @@ fn write_file(state: &Cheatcodes, path: impl AsRef<Path>, data: &str) -> Result<
 
 fn write_line(state: &Cheatcodes, path: impl AsRef<Path>, line: &str) -> Result<Bytes, Bytes> {
     let path = full_path(state, &path);
-    state.config.ensure_path_allowed(&path).map_err(util::encode_error)?;
+    state.config.ensure_path_allowed(&path, FsAccessKind::Write).map_err(util::encode_error)?;
 
     let mut file = std::fs::OpenOptions::new()
         .append(true)
@@ -245,7 +267,7 @@ fn write_line(state: &Cheatcodes, path: impl AsRef<Path>, line: &str) -> Result<
 
 fn close_file(state: &mut Cheatcodes, path: impl AsRef<Path>) -> Result<Bytes, Bytes> {
     let path = full_path(state, &path);
-    state.config.ensure_path_allowed(&path).map_err(util::encode_error)?;
+    state.config.ensure_path_allowed(&path, FsAccessKind::Read).map_err(util::encode_error)?;
 
     state.context.opened_read_files.remove(&path);
 
@@ -254,14 +276,62 @@ fn close_file(state: &mut Cheatcodes, path: impl AsRef<Path>) -> Result<Bytes, B
 
 fn remove_file(state: &mut Cheatcodes, path: impl AsRef<Path>) -> Result<Bytes, Bytes> {
     let path = full_path(state, &path);
-    state.config.ensure_path_allowed(&path).map_err(util::encode_error)?;
+    state.config.ensure_path_allowed(&path, FsAccessKind::Write).map_err(util::encode_error)?;
 
-    close_file(state, &path)?;
+    state.context.opened_read_files.remove(&path);
     fs::remove_file(&path).map_err(util::encode_error)?;
 
     Ok(Bytes::new())
 }
 
+/// Compares `value` with the snapshot `name`, which is stored hex encoded in
+/// `snapshots/<name>.snap` in the project root.
+///
+/// The snapshot is written instead if it does not exist yet or if snapshots are updated, see
+/// [`CheatsConfig::update_snapshots`](super::CheatsConfig::update_snapshots).
+fn assert_snapshot_eq(state: &Cheatcodes, value: &[u8], name: &str) -> Result<Bytes, Bytes> {
+    let relative = snapshot_path(name).map_err(util::encode_error)?;
+    let path = full_path(state, &relative);
+    let encoded = format!("0x{}", hex::encode(value));
+
+    if !state.config.update_snapshots {
+        match std::fs::read_to_string(&path) {
+            Ok(snapshot) if snapshot.trim() == encoded => return Ok(Bytes::new()),
+            Ok(snapshot) => {
+                return Err(util::encode_error(format!(
+                    "snapshot `{name}` does not match {}: expected {}, got {encoded}; run \
+                     `forge test --update-snapshots` to update it",
+                    relative.display(),
+                    snapshot.trim()
+                )))
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(util::encode_error(err)),
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(util::encode_error)?;
+    }
+    fs::write(&path, format!("{encoded}\n")).map_err(util::encode_error)?;
+
+    Ok(Bytes::new())
+}
+
+/// Returns the path of the snapshot `name` relative to the project root, names may contain
+/// directories but must stay within the `snapshots` directory
+fn snapshot_path(name: &str) -> Result<PathBuf, String> {
+    let is_valid = !name.is_empty() &&
+        Path::new(name).components().all(|component| matches!(component, Component::Normal(_)));
+    if !is_valid {
+        return Err(format!(
+            "invalid snapshot name `{name}`, it must be a relative path within the snapshots \
+             directory"
+        ))
+    }
+    Ok(Path::new("snapshots").join(format!("{name}.snap")))
+}
+
 pub fn apply(
     state: &mut Cheatcodes,
     ffi_enabled: bool,
@@ -299,6 +369,7 @@ pub fn apply(
         HEVMCalls::WriteLine(inner) => write_line(state, &inner.0, &inner.1),
         HEVMCalls::CloseFile(inner) => close_file(state, &inner.0),
         HEVMCalls::RemoveFile(inner) => remove_file(state, &inner.0),
+        HEVMCalls::AssertSnapshotEq(inner) => assert_snapshot_eq(state, &inner.0, &inner.1),
         _ => return None,
     })
 }
@@ -338,4 +409,31 @@ mod tests {
         let output = String::decode(&output).unwrap();
         assert_eq!(output, msg);
     }
+
+    #[test]
+    fn test_assert_snapshot_eq() {
+        let root = tempfile::tempdir().unwrap();
+        let config = CheatsConfig { root: root.path().to_path_buf(), ..Default::default() };
+        let mut cheats = Cheatcodes { config: Arc::new(config), ..Default::default() };
+
+        assert!(assert_snapshot_eq(&cheats, b"gm", "nested/greeting").is_ok());
+        let snapshot = root.path().join("snapshots/nested/greeting.snap");
+        assert_eq!(fs::read_to_string(&snapshot).unwrap(), "0x676d\n");
+        assert!(assert_snapshot_eq(&cheats, b"gm", "nested/greeting").is_ok());
+
+        let err = assert_snapshot_eq(&cheats, b"gn", "nested/greeting").unwrap_err();
+        let err = String::decode(&err[4..]).unwrap();
+        assert!(err.contains("expected 0x676d, got 0x676e"));
+
+        cheats.config = Arc::new(CheatsConfig {
+            root: root.path().to_path_buf(),
+            update_snapshots: true,
+            ..Default::default()
+        });
+        assert!(assert_snapshot_eq(&cheats, b"gn", "nested/greeting").is_ok());
+        assert_eq!(fs::read_to_string(&snapshot).unwrap(), "0x676e\n");
+
+        assert!(assert_snapshot_eq(&cheats, b"gm", "../greeting").is_err());
+        assert!(assert_snapshot_eq(&cheats, b"gm", "/tmp/greeting").is_err());
+    }
 }
@@ -1,14 +1,56 @@
 use crate::{abi::HEVMCalls, fuzz::ASSUME_MAGIC_RETURN_CODE};
 use bytes::Bytes;
+use ethers::{
+    abi::AbiEncode,
+    types::{I256, U256},
+};
 use revm::{Database, EVMData};
 
+/// Maps `x` into `[min, max]` as `min + x % (max - min + 1)`
+fn bound(x: U256, min: U256, max: U256) -> Result<Bytes, Bytes> {
+    if min > max {
+        return Err(format!("bound: min {min} is greater than max {max}").encode().into())
+    }
+    let result = match (max - min).checked_add(U256::one()) {
+        Some(size) => min + x % size,
+        // the range covers all values
+        None => x,
+    };
+    Ok(result.encode().into())
+}
+
+/// Maps `x` into `[min, max]` by its offset from `min` modulo the size of the range, so values
+/// that are already in the range are returned unchanged
+fn bound_int(x: I256, min: I256, max: I256) -> Result<Bytes, Bytes> {
+    if min > max {
+        return Err(format!("boundInt: min {min} is greater than max {max}").encode().into())
+    }
+    let (min_raw, max_raw) = (min.into_raw(), max.into_raw());
+    let result = match max_raw.overflowing_sub(min_raw).0.checked_add(U256::one()) {
+        Some(size) => {
+            let offset = x.into_raw().overflowing_sub(min_raw).0 % size;
+            I256::from_raw(min_raw.overflowing_add(offset).0)
+        }
+        // the range covers all values
+        None => x,
+    };
+    Ok(result.into_raw().encode().into())
+}
+
 pub fn apply<DB: Database>(
     _: &mut EVMData<'_, DB>,
     call: &HEVMCalls,
 ) -> Option<Result<Bytes, Bytes>> {
-    if let HEVMCalls::Assume(inner) = call {
-        Some(if inner.0 { Ok(Bytes::new()) } else { Err(ASSUME_MAGIC_RETURN_CODE.into()) })
-    } else {
-        None
-    }
+    Some(match call {
+        HEVMCalls::Assume(inner) => {
+            if inner.0 {
+                Ok(Bytes::new())
+            } else {
+                Err(ASSUME_MAGIC_RETURN_CODE.into())
+            }
+        }
+        HEVMCalls::Bound(inner) => bound(inner.0, inner.1, inner.2),
+        HEVMCalls::BoundInt(inner) => bound_int(inner.0, inner.1, inner.2),
+        _ => return None,
+    })
 }
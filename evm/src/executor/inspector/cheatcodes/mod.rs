@@ -1,6 +1,6 @@
 use self::{
     env::Broadcast,
-    expect::{handle_expect_emit, handle_expect_revert},
+    expect::{handle_expect_emit, handle_expect_revert, stop_safe_memory_call_args},
     util::process_create,
 };
 use crate::{
@@ -35,7 +35,10 @@ mod env;
 pub use env::{Prank, RecordAccess};
 /// Assertion helpers (such as `expectEmit`)
 mod expect;
-pub use expect::{ExpectedCallData, ExpectedEmit, ExpectedRevert, MockCallDataContext};
+pub use expect::{
+    ExpectedCallData, ExpectedEmit, ExpectedRevert, ExpectedSafeMemory, MockCallDataContext,
+    UnsafeMemoryWrite,
+};
 
 /// Cheatcodes that interact with the external environment (FFI etc.)
 mod ext;
@@ -98,6 +101,20 @@ pub struct Cheatcodes {
     /// Expected emits
     pub expected_emits: Vec<ExpectedEmit>,
 
+    /// The balances recorded by `expectBalanceChange`, by account
+    pub balance_snapshots: BTreeMap<Address, U256>,
+
+    /// The memory range writes are restricted to by `expectSafeMemory`
+    pub expected_safe_memory: Option<ExpectedSafeMemory>,
+
+    /// The error of the memory write that violated `expectSafeMemory`, reported when the call
+    /// that made it ends
+    pub unsafe_memory_write: Option<String>,
+
+    /// The gas the cheatcode that is being applied costs, e.g. per slot copied by `copyStorage`,
+    /// charged to the call of the cheatcode once it returns
+    pub cheatcode_gas: u64,
+
     /// Current broadcasting information
     pub broadcast: Option<Broadcast>,
 
@@ -107,6 +124,9 @@ pub struct Cheatcodes {
     /// Scripting based transactions
     pub broadcastable_transactions: VecDeque<TypedTransaction>,
 
+    /// Whether the calls of the test contract are executed as separate transactions
+    pub isolate: bool,
+
     /// Additional, user configurable context this Inspector has access to when inspecting a call
     pub config: Arc<CheatsConfig>,
 
@@ -133,6 +153,7 @@ impl Cheatcodes {
             corrected_nonce: false,
             block: Some(block),
             gas_price: Some(gas_price),
+            isolate: config.isolate,
             config: Arc::new(config),
             ..Default::default()
         }
@@ -181,7 +202,12 @@ where
         Return::Continue
     }
 
-    fn step(&mut self, interpreter: &mut Interpreter, _: &mut EVMData<'_, DB>, _: bool) -> Return {
+    fn step(
+        &mut self,
+        interpreter: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        _: bool,
+    ) -> Return {
         // Record writes and reads if `record` has been called
         if let Some(storage_accesses) = &mut self.accesses {
             match interpreter.contract.bytecode.bytecode()[interpreter.program_counter()] {
@@ -212,10 +238,53 @@ where
             }
         }
 
+        // Check memory writes if `expectSafeMemory` has been called
+        if let Some(safe_memory) = &mut self.expected_safe_memory {
+            if data.subroutine.depth() == safe_memory.depth {
+                let pc = interpreter.program_counter();
+                let op = interpreter.contract.bytecode.bytecode()[pc];
+                match op {
+                    opcode::MSTORE | opcode::MSTORE8 => {
+                        let offset = try_or_continue!(interpreter.stack().peek(0));
+                        let size = if op == opcode::MSTORE { 32 } else { 1 };
+                        safe_memory.record_write(offset, size, pc);
+                    }
+                    // the contract calls out of or leaves its frame
+                    opcode::CALL |
+                    opcode::CALLCODE |
+                    opcode::DELEGATECALL |
+                    opcode::STATICCALL |
+                    opcode::CREATE |
+                    opcode::CREATE2 |
+                    opcode::RETURN |
+                    opcode::REVERT |
+                    opcode::STOP |
+                    opcode::SELFDESTRUCT => {
+                        let stop_call_args = stop_safe_memory_call_args(
+                            op,
+                            interpreter.stack(),
+                            &interpreter.memory,
+                        );
+                        if let Err(err) = safe_memory.check_exit(stop_call_args) {
+                            self.unsafe_memory_write = Some(err);
+                            return Return::Revert
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
         Return::Continue
     }
 
-    fn log(&mut self, _: &mut EVMData<'_, DB>, address: &Address, topics: &[H256], data: &Bytes) {
+    fn log(
+        &mut self,
+        evm_data: &mut EVMData<'_, DB>,
+        address: &Address,
+        topics: &[H256],
+        data: &Bytes,
+    ) {
         // Match logs if `expectEmit` has been called
         if !self.expected_emits.is_empty() {
             handle_expect_emit(
@@ -227,9 +296,12 @@ where
 
         // Stores this log if `recordLogs` has been called
         if let Some(storage_recorded_logs) = &mut self.recorded_logs {
-            storage_recorded_logs
-                .entries
-                .push(RawLog { topics: topics.to_vec(), data: data.to_vec() });
+            storage_recorded_logs.record(
+                *address,
+                RawLog { topics: topics.to_vec(), data: data.to_vec() },
+                evm_data.subroutine.depth(),
+                self.config.recorded_logs_limit,
+            );
         }
     }
 
@@ -240,11 +312,18 @@ where
         is_static: bool,
     ) -> (Return, Gas, Bytes) {
         if call.contract == CHEATCODE_ADDRESS {
-            match self.apply_cheatcode(data, call.context.caller, call) {
-                Ok(retdata) => (Return::Return, Gas::new(call.gas_limit), retdata),
-                Err(err) => (Return::Revert, Gas::new(call.gas_limit), err),
+            let result = self.apply_cheatcode(data, call.context.caller, call);
+            let mut gas = Gas::new(call.gas_limit);
+            if !gas.record_cost(std::mem::take(&mut self.cheatcode_gas)) {
+                return (Return::OutOfGas, gas, Bytes::new())
+            }
+            match result {
+                Ok(retdata) => (Return::Return, gas, retdata),
+                Err(err) => (Return::Revert, gas, err),
             }
         } else if call.contract != HARDHAT_CONSOLE_ADDRESS {
+            let original_caller = call.context.caller;
+
             // Handle expected calls
             if let Some(expecteds) = self.expected_calls.get_mut(&call.contract) {
                 if let Some(found_match) = expecteds.iter().position(|expected| {
@@ -339,6 +418,19 @@ where
                 }
             }
 
+            // Execute the calls of the test contract as separate transactions
+            if self.isolate &&
+                !is_static &&
+                self.broadcast.is_none() &&
+                data.subroutine.depth() == 1
+            {
+                if let TransactTo::Call(test_contract) = data.env.tx.transact_to {
+                    if original_caller == test_contract {
+                        return env::isolated_call(data, call)
+                    }
+                }
+            }
+
             (Return::Continue, Gas::new(call.gas_limit), Bytes::new())
         } else {
             (Return::Continue, Gas::new(call.gas_limit), Bytes::new())
@@ -358,6 +450,11 @@ where
             return (status, remaining_gas, retdata)
         }
 
+        // Fail the call that was halted because of an unsafe memory write
+        if let Some(err) = self.unsafe_memory_write.take() {
+            return (Return::Revert, remaining_gas, err.encode().into())
+        }
+
         // Clean up pranks
         if let Some(prank) = &self.prank {
             if data.subroutine.depth() == prank.depth {
@@ -376,10 +473,11 @@ where
         }
 
         // Handle expected reverts
-        if let Some(expected_revert) = &self.expected_revert {
+        if let Some(expected_revert) = &mut self.expected_revert {
+            expected_revert.record(Some(call.contract), data.subroutine.depth(), status, &retdata);
             if data.subroutine.depth() <= expected_revert.depth {
                 let expected_revert = std::mem::take(&mut self.expected_revert).unwrap();
-                return match handle_expect_revert(false, &expected_revert.reason, status, retdata) {
+                return match handle_expect_revert(false, &expected_revert, status, retdata) {
                     Err(retdata) => (Return::Revert, remaining_gas, retdata),
                     Ok((_, retdata)) => (Return::Return, remaining_gas, retdata),
                 }
@@ -536,10 +634,11 @@ where
         }
 
         // Handle expected reverts
-        if let Some(expected_revert) = &self.expected_revert {
+        if let Some(expected_revert) = &mut self.expected_revert {
+            expected_revert.record(None, data.subroutine.depth(), status, &retdata);
             if data.subroutine.depth() <= expected_revert.depth {
                 let expected_revert = std::mem::take(&mut self.expected_revert).unwrap();
-                return match handle_expect_revert(true, &expected_revert.reason, status, retdata) {
+                return match handle_expect_revert(true, &expected_revert, status, retdata) {
                     Err(retdata) => (Return::Revert, None, remaining_gas, retdata),
                     Ok((address, retdata)) => (Return::Return, address, remaining_gas, retdata),
                 }
@@ -13,6 +13,12 @@ use revm::{
     Interpreter, Memory, Return,
 };
 
+/// The maximum number of steps recorded by the [Debugger], later steps are dropped.
+///
+/// Every step holds a copy of the stack and memory, so this bounds the memory used for very long
+/// executions.
+pub const MAX_DEBUG_STEPS: usize = 1_000_000;
+
 /// An inspector that collects debug nodes on every step of the interpreter.
 #[derive(Default, Debug)]
 pub struct Debugger {
@@ -37,6 +43,8 @@ pub struct Debugger {
     ///
     /// For more information on gas blocks, see [current_gas_block].
     pub previous_gas_block: u64,
+    /// The number of steps recorded so far
+    pub recorded_steps: usize,
 }
 
 impl Debugger {
@@ -101,6 +109,12 @@ where
         data: &mut EVMData<'_, DB>,
         _is_static: bool,
     ) -> Return {
+        if self.recorded_steps >= MAX_DEBUG_STEPS {
+            self.arena.truncated = true;
+            return Return::Continue
+        }
+        self.recorded_steps += 1;
+
         let pc = interpreter.program_counter();
         let op = interpreter.contract.bytecode.bytecode()[pc];
 
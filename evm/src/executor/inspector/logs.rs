@@ -31,8 +31,9 @@ impl LogCollector {
             }
         };
 
-        // Convert it to a DS-style `emit log(string)` event
-        self.logs.push(convert_hh_log_to_event(decoded));
+        // Convert it to a DS-style `emit log(string)` event, the arguments of a `logf` call that
+        // do not match its format are logged as the error
+        self.logs.push(convert_hh_log_to_event(format_hardhat_call(&decoded)));
 
         (Return::Continue, Bytes::new())
     }
@@ -66,8 +67,9 @@ where
     }
 }
 
-/// Converts a call to Hardhat's `console.log` to a DSTest `log(string)` event.
-fn convert_hh_log_to_event(call: HardhatConsoleCalls) -> Log {
+/// Converts the formatted `message` of a call to Hardhat's `console.log` to a DSTest
+/// `log(string)` event.
+fn convert_hh_log_to_event(message: String) -> Log {
     Log {
         // This is topic 0 of DSTest's `log(string)`
         topics: vec![H256::from_slice(
@@ -75,7 +77,7 @@ fn convert_hh_log_to_event(call: HardhatConsoleCalls) -> Log {
                 .unwrap(),
         )],
         // Convert the parameters of the call to their string representation for the log
-        data: ethers::abi::encode(&[Token::String(format_hardhat_call(&call))]).into(),
+        data: ethers::abi::encode(&[Token::String(message)]).into(),
         ..Default::default()
     }
 }
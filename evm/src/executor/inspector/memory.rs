@@ -0,0 +1,52 @@
+use revm::{opcode, Database, EVMData, Inspector, Interpreter, Return};
+use serde::{Deserialize, Serialize};
+
+/// Memory usage of an execution
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryStats {
+    /// The largest memory, in bytes, of any call frame of the execution
+    pub peak: usize,
+    /// The number of executed `MSTORE` and `MSTORE8` instructions
+    pub mstores: u64,
+    /// The number of executed `MLOAD` instructions
+    pub mloads: u64,
+}
+
+/// An inspector that collects the [MemoryStats] of an execution.
+#[derive(Default, Debug)]
+pub struct MemoryCollector {
+    pub stats: MemoryStats,
+}
+
+impl<DB> Inspector<DB> for MemoryCollector
+where
+    DB: Database,
+{
+    fn step(
+        &mut self,
+        interpreter: &mut Interpreter,
+        _: &mut EVMData<'_, DB>,
+        _is_static: bool,
+    ) -> Return {
+        match interpreter.contract.bytecode.bytecode()[interpreter.program_counter()] {
+            opcode::MSTORE | opcode::MSTORE8 => self.stats.mstores += 1,
+            opcode::MLOAD => self.stats.mloads += 1,
+            _ => {}
+        }
+
+        Return::Continue
+    }
+
+    fn step_end(
+        &mut self,
+        interpreter: &mut Interpreter,
+        _: &mut EVMData<'_, DB>,
+        _is_static: bool,
+        _status: Return,
+    ) -> Return {
+        // memory is only expanded by the executed instruction, so the peak is measured after it
+        self.stats.peak = self.stats.peak.max(interpreter.memory.len());
+
+        Return::Continue
+    }
+}
@@ -8,11 +8,17 @@ mod tracer;
 pub use tracer::Tracer;
 
 mod debugger;
-pub use debugger::Debugger;
+pub use debugger::{Debugger, MAX_DEBUG_STEPS};
 
 mod coverage;
 pub use coverage::CoverageCollector;
 
+mod memory;
+pub use memory::{MemoryCollector, MemoryStats};
+
+mod access_list;
+pub use access_list::AccessListTracer;
+
 mod stack;
 pub use stack::{InspectorData, InspectorStack};
 
@@ -49,6 +55,16 @@ pub struct InspectorStackConfig {
     pub fuzzer: Option<Fuzzer>,
     /// Whether or not coverage info should be collected
     pub coverage: bool,
+    /// Whether or not the memory usage should be collected
+    pub memory: bool,
+    /// Whether or not the tracer records the gas that the access list of the transaction saves
+    pub access_list_savings: bool,
+    /// Whether or not the tracer records the gas that the calls spend on expanding their memory
+    pub memory_gas: bool,
+    /// Whether or not the tracer records the gas of the instructions by the kind of instruction
+    pub gas_categories: bool,
+    /// Whether or not the tracer records the gas of the instructions by their program counter
+    pub gas_by_pc: bool,
 }
 
 impl InspectorStackConfig {
@@ -63,7 +79,13 @@ impl InspectorStackConfig {
         }
 
         if self.tracing {
-            stack.tracer = Some(Tracer::default());
+            stack.tracer = Some(Tracer {
+                record_access_list_savings: self.access_list_savings,
+                record_memory_gas: self.memory_gas,
+                record_gas_categories: self.gas_categories,
+                record_gas_by_pc: self.gas_by_pc,
+                ..Default::default()
+            });
         }
         if self.debugger {
             stack.debugger = Some(Debugger::default());
@@ -73,6 +95,9 @@ impl InspectorStackConfig {
         if self.coverage {
             stack.coverage = Some(CoverageCollector::default());
         }
+        if self.memory {
+            stack.memory = Some(MemoryCollector::default());
+        }
         stack
     }
 
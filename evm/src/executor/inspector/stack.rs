@@ -2,8 +2,11 @@ use super::{Cheatcodes, Debugger, Fuzzer, LogCollector, Tracer};
 use crate::{
     coverage::HitMaps,
     debug::DebugArena,
-    executor::{backend::DatabaseExt, inspector::CoverageCollector},
-    trace::CallTraceArena,
+    executor::{
+        backend::DatabaseExt,
+        inspector::{CoverageCollector, MemoryCollector, MemoryStats},
+    },
+    trace::{CallTraceArena, ForkContext},
 };
 use bytes::Bytes;
 use ethers::types::{Address, Log, H256};
@@ -28,6 +31,7 @@ pub struct InspectorData {
     pub traces: Option<CallTraceArena>,
     pub debug: Option<DebugArena>,
     pub coverage: Option<HitMaps>,
+    pub memory: Option<MemoryStats>,
     pub cheatcodes: Option<Cheatcodes>,
 }
 
@@ -43,9 +47,24 @@ pub struct InspectorStack {
     pub debugger: Option<Debugger>,
     pub fuzzer: Option<Fuzzer>,
     pub coverage: Option<CoverageCollector>,
+    pub memory: Option<MemoryCollector>,
 }
 
 impl InspectorStack {
+    /// Tells the tracer which fork the database is currently in, if any
+    fn update_fork<DB: DatabaseExt>(&mut self, data: &EVMData<'_, DB>) {
+        if let Some(tracer) = &mut self.tracer {
+            tracer.fork = if data.db.is_forked_mode() {
+                Some(ForkContext {
+                    chain_id: data.env.cfg.chain_id.as_u64(),
+                    block_number: data.env.block.number.as_u64(),
+                })
+            } else {
+                None
+            };
+        }
+    }
+
     pub fn collect_inspector_states(self) -> InspectorData {
         InspectorData {
             logs: self.logs.map(|logs| logs.logs).unwrap_or_default(),
@@ -57,6 +76,7 @@ impl InspectorStack {
             traces: self.tracer.map(|tracer| tracer.traces),
             debug: self.debugger.map(|debugger| debugger.arena),
             coverage: self.coverage.map(|coverage| coverage.maps),
+            memory: self.memory.map(|memory| memory.stats),
             cheatcodes: self.cheatcodes,
         }
     }
@@ -107,6 +127,7 @@ where
                 &mut self.debugger,
                 &mut self.tracer,
                 &mut self.coverage,
+                &mut self.memory,
                 &mut self.logs,
                 &mut self.cheatcodes
             ],
@@ -144,7 +165,13 @@ where
     ) -> Return {
         call_inspectors!(
             inspector,
-            [&mut self.debugger, &mut self.tracer, &mut self.logs, &mut self.cheatcodes],
+            [
+                &mut self.debugger,
+                &mut self.tracer,
+                &mut self.memory,
+                &mut self.logs,
+                &mut self.cheatcodes
+            ],
             {
                 let status = inspector.step_end(interpreter, data, is_static, status);
 
@@ -164,6 +191,7 @@ where
         call: &mut CallInputs,
         is_static: bool,
     ) -> (Return, Gas, Bytes) {
+        self.update_fork(data);
         call_inspectors!(
             inspector,
             [
@@ -233,6 +261,7 @@ where
         data: &mut EVMData<'_, DB>,
         call: &mut CreateInputs,
     ) -> (Return, Option<Address>, Gas, Bytes) {
+        self.update_fork(data);
         call_inspectors!(
             inspector,
             [
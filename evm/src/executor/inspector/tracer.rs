@@ -1,8 +1,8 @@
 use crate::{
-    executor::inspector::utils::{gas_used, get_create_address},
+    executor::inspector::utils::{gas_used, get_create_address, word_to_address},
     trace::{
-        CallTrace, CallTraceArena, LogCallOrder, RawOrDecodedCall, RawOrDecodedLog,
-        RawOrDecodedReturnData,
+        precompiles, CallTrace, CallTraceArena, ForkContext, LogCallOrder, RawOrDecodedCall,
+        RawOrDecodedLog, RawOrDecodedReturnData,
     },
     CallKind,
 };
@@ -12,14 +12,55 @@ use ethers::{
     types::{Address, H256, U256},
 };
 use revm::{
-    return_ok, CallInputs, CallScheme, CreateInputs, Database, EVMData, Gas, Inspector, Return,
+    opcode, return_ok, CallInputs, CallScheme, CreateInputs, Database, EVMData, Gas, Inspector,
+    Interpreter, Return, SpecId, TransactTo,
 };
+use std::collections::HashSet;
+
+/// The gas saved by a storage slot that is warm on its first access (EIP-2929)
+const PREWARMED_SLOT_SAVINGS: u64 = 2_100 - 100;
+
+/// The gas saved by an account that is warm on its first access (EIP-2929)
+const PREWARMED_ACCOUNT_SAVINGS: u64 = 2_600 - 100;
+
+/// The gas that calls which transfer value get on top of the gas forwarded by their caller
+const CALL_STIPEND: u64 = 2_300;
 
 /// An inspector that collects call traces.
 #[derive(Default, Debug)]
 pub struct Tracer {
     pub trace_stack: Vec<usize>,
     pub traces: CallTraceArena,
+    /// The fork that the next calls are executed on, recorded in their traces
+    pub fork: Option<ForkContext>,
+    /// The accounts and storage slots of the transaction's access list that were accessed
+    /// already, only their first access is cheaper because of the prewarming
+    pub prewarmed_accesses: HashSet<(Address, Option<U256>)>,
+    /// The memory size of every ongoing call, in bytes
+    pub memory_sizes: Vec<usize>,
+    /// The instruction that every ongoing call executes
+    pub pending_steps: Vec<Option<PendingStep>>,
+    /// The gas that the call instruction that is executed asks to forward to the next call
+    pub requested_call_gas: Option<U256>,
+    /// Whether the gas that the access list of the transaction saves is recorded in the traces
+    pub record_access_list_savings: bool,
+    /// Whether the gas that the calls spend on expanding their memory is recorded in the traces
+    pub record_memory_gas: bool,
+    /// Whether the gas of the instructions is recorded by the kind of instruction in the traces
+    pub record_gas_categories: bool,
+    /// Whether the gas of the instructions is recorded by their program counter in the traces
+    pub record_gas_by_pc: bool,
+}
+
+/// An instruction whose gas is settled once it was executed
+#[derive(Debug, Clone, Copy)]
+pub struct PendingStep {
+    pub op: u8,
+    pub pc: usize,
+    /// The gas the call had spent before the instruction
+    pub spent: u64,
+    /// The number of subcalls the call had made before the instruction
+    pub subcalls: usize,
 }
 
 impl Tracer {
@@ -32,6 +73,9 @@ impl Tracer {
         kind: CallKind,
         caller: Address,
     ) {
+        let precompile_gas_floor = precompiles::gas_floor(address, &data);
+        self.memory_sizes.push(0);
+        self.pending_steps.push(None);
         self.trace_stack.push(self.traces.push_trace(
             0,
             CallTrace {
@@ -42,6 +86,8 @@ impl Tracer {
                 value,
                 status: Return::Continue,
                 caller,
+                precompile_gas_floor,
+                fork: self.fork,
                 ..Default::default()
             },
         ));
@@ -54,6 +100,8 @@ impl Tracer {
         output: Vec<u8>,
         address: Option<Address>,
     ) {
+        self.memory_sizes.pop();
+        self.pending_steps.pop();
         let success = matches!(status, return_ok!());
         let trace = &mut self.traces.arena
             [self.trace_stack.pop().expect("more traces were filled than started")]
@@ -67,6 +115,14 @@ impl Tracer {
             trace.address = address;
         }
     }
+
+    /// Whether any of the recordings that inspect the executed instructions is enabled
+    fn records_steps(&self) -> bool {
+        self.record_access_list_savings ||
+            self.record_memory_gas ||
+            self.record_gas_categories ||
+            self.record_gas_by_pc
+    }
 }
 
 impl<DB> Inspector<DB> for Tracer
@@ -80,6 +136,129 @@ where
             .push(RawOrDecodedLog::Raw(RawLog { topics: topics.to_vec(), data: data.to_vec() }));
     }
 
+    fn step(
+        &mut self,
+        interpreter: &mut Interpreter,
+        data: &mut EVMData<'_, DB>,
+        _: bool,
+    ) -> Return {
+        let pc = interpreter.program_counter();
+        let op = interpreter.contract.bytecode.bytecode()[pc];
+        self.requested_call_gas = match op {
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => {
+                interpreter.stack().peek(0).ok()
+            }
+            _ => None,
+        };
+        if !self.records_steps() {
+            return Return::Continue
+        }
+
+        if self.record_gas_categories || self.record_gas_by_pc {
+            let subcalls =
+                self.trace_stack.last().map(|index| self.traces.arena[*index].children.len());
+            if let Some(pending) = self.pending_steps.last_mut() {
+                *pending = Some(PendingStep {
+                    op,
+                    pc,
+                    spent: interpreter.gas().spend(),
+                    subcalls: subcalls.unwrap_or_default(),
+                });
+            }
+        }
+
+        if !self.record_access_list_savings {
+            return Return::Continue
+        }
+        let access_list = &data.env.tx.access_list;
+        if access_list.is_empty() || !SpecId::enabled(data.env.cfg.spec_id, SpecId::BERLIN) {
+            return Return::Continue
+        }
+
+        let access = match op {
+            opcode::SLOAD | opcode::SSTORE => {
+                let slot = try_or_continue!(interpreter.stack().peek(0));
+                (interpreter.contract().address, Some(slot))
+            }
+            opcode::BALANCE | opcode::EXTCODESIZE | opcode::EXTCODECOPY | opcode::EXTCODEHASH => {
+                (word_to_address(try_or_continue!(interpreter.stack().peek(0))), None)
+            }
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => {
+                (word_to_address(try_or_continue!(interpreter.stack().peek(1))), None)
+            }
+            _ => return Return::Continue,
+        };
+
+        // the sender and the recipient of the transaction are always warm
+        let (address, slot) = access;
+        if slot.is_none() &&
+            (address == data.env.tx.caller ||
+                matches!(data.env.tx.transact_to, TransactTo::Call(to) if to == address))
+        {
+            return Return::Continue
+        }
+
+        let is_listed = access_list.iter().any(|(listed, slots)| {
+            *listed == address && slot.map_or(true, |slot| slots.contains(&slot))
+        });
+        if is_listed && self.prewarmed_accesses.insert(access) {
+            if let Some(index) = self.trace_stack.last() {
+                self.traces.arena[*index].trace.access_list_savings +=
+                    if slot.is_some() { PREWARMED_SLOT_SAVINGS } else { PREWARMED_ACCOUNT_SAVINGS };
+            }
+        }
+
+        Return::Continue
+    }
+
+    fn step_end(
+        &mut self,
+        interpreter: &mut Interpreter,
+        _: &mut EVMData<'_, DB>,
+        _: bool,
+        _: Return,
+    ) -> Return {
+        if !self.records_steps() {
+            return Return::Continue
+        }
+
+        // memory is only expanded by the executed instruction, so its growth is measured after it
+        if self.record_memory_gas {
+            let size = interpreter.memory.len();
+            if let Some(last_size) = self.memory_sizes.last_mut() {
+                if size > *last_size {
+                    let gas = memory_gas(size) - memory_gas(*last_size);
+                    *last_size = size;
+                    if let Some(index) = self.trace_stack.last() {
+                        self.traces.arena[*index].trace.memory_gas += gas;
+                    }
+                }
+            }
+        }
+
+        // the gas of call instructions is only settled once their subcall returned
+        if let Some(step) = self.pending_steps.last_mut().and_then(Option::take) {
+            let gas = interpreter.gas().spend().saturating_sub(step.spent);
+            if let Some(index) = self.trace_stack.last() {
+                let arena = &mut self.traces.arena;
+                if self.record_gas_by_pc {
+                    // the gas of the subcalls is attributed to the instructions of their own code
+                    let subcall_gas = arena[*index].children[step.subcalls..]
+                        .iter()
+                        .map(|child| arena[*child].trace.gas_cost)
+                        .sum::<u64>();
+                    *arena[*index].trace.pc_gas.entry(step.pc).or_default() +=
+                        gas.saturating_sub(subcall_gas);
+                }
+                if self.record_gas_categories {
+                    arena[*index].trace.gas_categories.record(step.op, gas);
+                }
+            }
+        }
+
+        Return::Continue
+    }
+
     fn call(
         &mut self,
         data: &mut EVMData<'_, DB>,
@@ -102,6 +281,24 @@ where
             from,
         );
 
+        // the gas of a call is capped at all but 1/64th of the gas the caller has left, so a call
+        // that gets less than it asked for is forwarded everything, only the stipend of value
+        // transfers comes on top
+        let stipend = if !call.transfer.value.is_zero() &&
+            matches!(call.context.scheme, CallScheme::Call | CallScheme::CallCode)
+        {
+            CALL_STIPEND
+        } else {
+            0
+        };
+        let forwards_all_gas = self
+            .requested_call_gas
+            .take()
+            .map(|requested| requested > U256::from(call.gas_limit.saturating_sub(stipend)));
+        if let Some(index) = self.trace_stack.last() {
+            self.traces.arena[*index].trace.forwards_all_gas = forwards_all_gas;
+        }
+
         (Return::Continue, Gas::new(call.gas_limit), Bytes::new())
     }
 
@@ -173,3 +370,9 @@ where
         (status, address, gas, retdata)
     }
 }
+
+/// Returns the gas that expanding the memory of a call to `size` bytes costs in total
+fn memory_gas(size: usize) -> u64 {
+    let words = (size as u64 + 31) / 32;
+    words * 3 + words * words / 512
+}
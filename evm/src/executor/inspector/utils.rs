@@ -1,5 +1,6 @@
+use crate::utils::u256_to_h256_be;
 use ethers::{
-    types::Address,
+    types::{Address, U256},
     utils::{get_contract_address, get_create2_address},
 };
 use revm::{CreateInputs, CreateScheme, SpecId};
@@ -29,6 +30,11 @@ pub fn get_create_address(call: &CreateInputs, nonce: u64) -> Address {
     }
 }
 
+/// Returns the address in the lower 20 bytes of a stack word
+pub fn word_to_address(word: U256) -> Address {
+    Address::from(u256_to_h256_be(word))
+}
+
 /// Get the gas used, accounting for refunds
 pub fn gas_used(spec: SpecId, spent: u64, refunded: u64) -> u64 {
     let refund_quotient = if SpecId::enabled(spec, SpecId::LONDON) { 5 } else { 2 };
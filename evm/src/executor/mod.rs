@@ -39,7 +39,7 @@ pub mod snapshot;
 
 use crate::{
     coverage::HitMaps,
-    executor::inspector::{InspectorStack, DEFAULT_CREATE2_DEPLOYER},
+    executor::inspector::{InspectorStack, MemoryStats, DEFAULT_CREATE2_DEPLOYER},
 };
 pub use builder::ExecutorBuilder;
 
@@ -215,6 +215,7 @@ impl Executor {
             labels,
             traces,
             coverage,
+            memory,
             debug,
             transactions,
             state_changeset,
@@ -231,6 +232,7 @@ impl Executor {
                     labels,
                     traces,
                     coverage,
+                    memory,
                     debug,
                     transactions,
                     state_changeset,
@@ -280,7 +282,7 @@ impl Executor {
             _ => Bytes::default(),
         };
 
-        let InspectorData { logs, labels, traces, coverage, debug, mut cheatcodes } =
+        let InspectorData { logs, labels, traces, coverage, memory, debug, mut cheatcodes } =
             inspector.collect_inspector_states();
 
         // Persist the changed block environment
@@ -314,6 +316,7 @@ impl Executor {
             logs,
             labels,
             coverage,
+            memory,
             traces,
             debug,
             transactions,
@@ -593,6 +596,8 @@ pub struct CallResult<D: Detokenize> {
     pub traces: Option<CallTraceArena>,
     /// The coverage info collected during the call
     pub coverage: Option<HitMaps>,
+    /// The memory usage of the call
+    pub memory: Option<MemoryStats>,
     /// The debug nodes of the call
     pub debug: Option<DebugArena>,
     /// Scripted transactions generated from this call
@@ -625,6 +630,8 @@ pub struct RawCallResult {
     pub traces: Option<CallTraceArena>,
     /// The coverage info collected during the call
     pub coverage: Option<HitMaps>,
+    /// The memory usage of the call
+    pub memory: Option<MemoryStats>,
     /// The debug nodes of the call
     pub debug: Option<DebugArena>,
     /// Scripted transactions generated from this call
@@ -648,6 +655,7 @@ impl Default for RawCallResult {
             labels: BTreeMap::new(),
             traces: None,
             coverage: None,
+            memory: None,
             debug: None,
             transactions: None,
             state_changeset: None,
@@ -684,7 +692,7 @@ fn convert_executed_call(
         _ => Bytes::default(),
     };
 
-    let InspectorData { logs, labels, traces, coverage, debug, cheatcodes } =
+    let InspectorData { logs, labels, traces, coverage, memory, debug, cheatcodes } =
         inspector.collect_inspector_states();
 
     let transactions = if let Some(cheats) = cheatcodes {
@@ -707,6 +715,7 @@ fn convert_executed_call(
         labels,
         traces,
         coverage,
+        memory,
         debug,
         transactions,
         state_changeset: Some(state_changeset),
@@ -728,6 +737,7 @@ fn convert_call_result<D: Detokenize>(
         labels,
         traces,
         coverage,
+        memory,
         debug,
         transactions,
         state_changeset,
@@ -745,6 +755,7 @@ fn convert_call_result<D: Detokenize>(
                 labels,
                 traces,
                 coverage,
+                memory,
                 debug,
                 transactions,
                 state_changeset,
@@ -1,7 +1,7 @@
 use super::{
     assert_invariants, BasicTxDetails, FuzzRunIdentifiedContracts, InvariantContract,
-    InvariantFuzzError, InvariantFuzzTestResult, InvariantTestOptions, RandomCallGenerator,
-    TargetedContracts,
+    InvariantFuzzError, InvariantFuzzTestResult, InvariantStats, InvariantTestOptions,
+    RandomCallGenerator, TargetedContracts,
 };
 use crate::{
     executor::{
@@ -18,7 +18,7 @@ use crate::{
 };
 use ethers::{
     abi::{Abi, Address, Detokenize, FixedBytes, Function, Tokenizable, TokenizableItem},
-    prelude::{ArtifactId, U256},
+    prelude::{ArtifactId, Bytes, U256},
 };
 use eyre::ContextCompat;
 use parking_lot::{Mutex, RwLock};
@@ -78,6 +78,9 @@ impl<'a> InvariantExecutor<'a> {
         let failures =
             RefCell::new(InvariantFailures::new(&invariant_contract.invariant_functions));
 
+        // Stores how often each targeted function was called.
+        let stats = RefCell::new(InvariantStats::default());
+
         let blank_executor = RefCell::new(&mut *self.executor);
 
         // Make sure invariants are sound even before starting to fuzz
@@ -131,6 +134,11 @@ impl<'a> InvariantExecutor<'a> {
                         .call_raw(*sender, *address, calldata.0.clone(), U256::zero())
                         .expect("could not make raw evm call");
 
+                    stats.borrow_mut().record_call(
+                        call_name(&targeted_contracts.lock(), address, calldata),
+                        call_result.reverted,
+                    );
+
                     // Collect data for fuzzing from the state changeset.
                     let state_changeset =
                         call_result.state_changeset.to_owned().expect("to have a state changeset.");
@@ -155,6 +163,7 @@ impl<'a> InvariantExecutor<'a> {
                         calldata: calldata.clone(),
                         gas: call_result.gas,
                         stipend: call_result.stipend,
+                        memory: call_result.memory,
                     });
 
                     if !can_continue(
@@ -186,15 +195,27 @@ impl<'a> InvariantExecutor<'a> {
                     }
                 }
 
+                {
+                    let mut stats = stats.borrow_mut();
+                    stats.max_depth = stats.max_depth.max(fuzz_runs.len());
+                }
                 fuzz_cases.borrow_mut().push(FuzzedCases::new(fuzz_runs));
 
                 Ok(())
             });
         }
 
-        let (reverts, invariants) = failures.into_inner().into_inner();
-
-        Ok(Some(InvariantFuzzTestResult { invariants, cases: fuzz_cases.into_inner(), reverts }))
+        let failures = failures.into_inner();
+        let mut stats = stats.into_inner();
+        stats.first_violation = failures.first_broken_invariant.clone();
+        let (reverts, invariants) = failures.into_inner();
+
+        Ok(Some(InvariantFuzzTestResult {
+            invariants,
+            cases: fuzz_cases.into_inner(),
+            reverts,
+            stats,
+        }))
     }
 
     /// Prepares certain structures to execute the invariant tests:
@@ -356,6 +377,20 @@ impl<'a> InvariantExecutor<'a> {
     }
 }
 
+/// Returns the name of the targeted function that `calldata` calls on `address` as
+/// `<contract>.<signature>`, or as `<address>.<selector>` if it is unknown
+fn call_name(targeted: &TargetedContracts, address: &Address, calldata: &Bytes) -> String {
+    let selector = &calldata[..calldata.len().min(4)];
+    targeted
+        .get(address)
+        .and_then(|(name, abi, _)| {
+            let func = abi.functions().find(|func| func.short_signature() == selector)?;
+            let name = name.rsplit(':').next().unwrap_or(name);
+            Some(format!("{name}.{}", func.signature()))
+        })
+        .unwrap_or_else(|| format!("{address:?}.0x{}", hex::encode(selector)))
+}
+
 /// Verifies that the invariant run execution can continue.
 fn can_continue(
     invariant_contract: &InvariantContract,
@@ -402,6 +437,8 @@ pub struct InvariantFailures {
     pub broken_invariants_count: usize,
     /// Maps a broken invariant to its specific error.
     pub failed_invariants: BTreeMap<String, Option<InvariantFuzzError>>,
+    /// The name of the invariant that was broken first.
+    pub first_broken_invariant: Option<String>,
 }
 
 impl InvariantFailures {
@@ -411,6 +448,7 @@ impl InvariantFailures {
             broken_invariants_count: 0,
             failed_invariants: invariants.iter().map(|f| (f.name.to_string(), None)).collect(),
             revert_reason: None,
+            first_broken_invariant: None,
         }
     }
 
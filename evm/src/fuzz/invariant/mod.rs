@@ -20,6 +20,7 @@ pub use executor::{InvariantExecutor, InvariantFailures};
 use parking_lot::Mutex;
 pub use proptest::test_runner::Config as FuzzConfig;
 use proptest::test_runner::TestError;
+use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, sync::Arc};
 
 pub type TargetedContracts = BTreeMap<Address, (String, Abi, Vec<Function>)>;
@@ -102,6 +103,9 @@ pub fn assert_invariants(
 
             // We only care about invariants which we haven't broken yet.
             if invariant_error.is_none() {
+                invariant_failures
+                    .first_broken_invariant
+                    .get_or_insert_with(|| broken_invariant.name.clone());
                 invariant_failures.failed_invariants.insert(
                     broken_invariant.name.clone(),
                     Some(InvariantFuzzError::new(
@@ -142,6 +146,38 @@ pub struct InvariantFuzzTestResult {
     pub cases: Vec<FuzzedCases>,
     /// Number of reverted fuzz calls
     pub reverts: usize,
+    /// The statistics of the fuzz calls
+    pub stats: InvariantStats,
+}
+
+/// The number of calls of a targeted function during an invariant test
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvariantCallStats {
+    pub calls: usize,
+    pub reverts: usize,
+}
+
+/// Statistics of the calls made by an invariant test, which help to tune its `runs` and `depth`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvariantStats {
+    /// The calls per targeted function, by `<contract>.<signature>`, or by `<address>.<selector>`
+    /// if the function is unknown
+    pub calls: BTreeMap<String, InvariantCallStats>,
+    /// The invariant that was broken first, if any
+    pub first_violation: Option<String>,
+    /// The largest number of calls made in a single run
+    pub max_depth: usize,
+}
+
+impl InvariantStats {
+    /// Records a call of the function `name`
+    pub fn record_call(&mut self, name: String, reverted: bool) {
+        let stats = self.calls.entry(name).or_default();
+        stats.calls += 1;
+        if reverted {
+            stats.reverts += 1;
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1,13 +1,13 @@
 use crate::{
     decode,
-    executor::{Executor, RawCallResult},
+    executor::{inspector::MemoryStats, Executor, RawCallResult},
     trace::CallTraceArena,
 };
 use ethers::{
     abi::{Abi, Function, Token},
-    types::{Address, Bytes, Log},
+    types::{Address, Bytes, Log, U256},
 };
-use foundry_common::calc;
+use foundry_common::calc::Stats;
 pub use proptest::test_runner::{Config as FuzzConfig, Reason};
 use proptest::test_runner::{TestCaseError, TestError, TestRunner};
 use serde::{Deserialize, Serialize};
@@ -34,12 +34,22 @@ pub struct FuzzedExecutor<'a> {
     runner: TestRunner,
     /// The account that calls tests
     sender: Address,
+    /// Calldata that is run before any fuzzed input
+    seeds: Vec<Bytes>,
 }
 
 impl<'a> FuzzedExecutor<'a> {
     /// Instantiates a fuzzed executor given a testrunner
     pub fn new(executor: &'a Executor, runner: TestRunner, sender: Address) -> Self {
-        Self { executor, runner, sender }
+        Self { executor, runner, sender, seeds: Vec::new() }
+    }
+
+    /// Runs the given calldata before the fuzzed inputs, a failing seed is reported as the
+    /// counterexample without fuzzing any further
+    #[must_use]
+    pub fn with_seeds(mut self, seeds: Vec<Bytes>) -> Self {
+        self.seeds = seeds;
+        self
     }
 
     /// Fuzzes the provided function, assuming it is available at the contract at `address`
@@ -73,8 +83,8 @@ impl<'a> FuzzedExecutor<'a> {
             (60, fuzz_calldata(func.clone())),
             (40, fuzz_calldata_from_state(func.clone(), state.clone())),
         ]);
-        tracing::debug!(func = ?func.name, should_fail, "fuzzing");
-        let run_result = self.runner.clone().run(&strat, |calldata| {
+        tracing::debug!(func = ?func.name, should_fail, seeds = self.seeds.len(), "fuzzing");
+        let run_case = |calldata: Bytes| {
             let call = self
                 .executor
                 .call_raw(self.sender, address, calldata.0.clone(), 0.into())
@@ -102,6 +112,7 @@ impl<'a> FuzzedExecutor<'a> {
                     calldata,
                     gas: call.gas,
                     stipend: call.stipend,
+                    memory: call.memory,
                 });
                 Ok(())
             } else {
@@ -122,7 +133,19 @@ impl<'a> FuzzedExecutor<'a> {
                     },
                 ))
             }
-        });
+        };
+
+        // the seeds run before the fuzzed inputs, a failing seed is the counterexample
+        let mut run_result: Result<(), TestError<Bytes>> = Ok(());
+        for seed in self.seeds.iter() {
+            if let Err(TestCaseError::Fail(reason)) = run_case(seed.clone()) {
+                run_result = Err(TestError::Fail(reason, seed.clone()));
+                break
+            }
+        }
+        if run_result.is_ok() {
+            run_result = self.runner.clone().run(&strat, &run_case);
+        }
 
         let (calldata, call) = counterexample.into_inner();
         let mut result = FuzzTestResult {
@@ -294,20 +317,27 @@ impl FuzzedCases {
 
     /// Returns the median gas of all test cases
     pub fn median_gas(&self, with_stipend: bool) -> u64 {
-        let mut values = self.gas_values(with_stipend);
-        values.sort_unstable();
-        calc::median_sorted(&values)
+        self.gas_stats(with_stipend).median.as_u64()
     }
 
     /// Returns the average gas use of all test cases
     pub fn mean_gas(&self, with_stipend: bool) -> u64 {
+        self.gas_stats(with_stipend).mean.as_u64()
+    }
+
+    /// Returns the statistics of the gas used by all test cases
+    pub fn gas_stats(&self, with_stipend: bool) -> Stats {
         let mut values = self.gas_values(with_stipend);
         values.sort_unstable();
-        calc::mean(&values).as_u64()
+        Stats::from_sorted(&values)
     }
 
-    fn gas_values(&self, with_stipend: bool) -> Vec<u64> {
-        self.cases.iter().map(|c| if with_stipend { c.gas } else { c.gas - c.stipend }).collect()
+    fn gas_values(&self, with_stipend: bool) -> Vec<U256> {
+        self.cases
+            .iter()
+            .map(|c| if with_stipend { c.gas } else { c.gas - c.stipend })
+            .map(U256::from)
+            .collect()
     }
 
     /// Returns the case with the highest gas usage
@@ -342,4 +372,7 @@ pub struct FuzzCase {
     pub gas: u64,
     /// The initial gas stipend for the transaction
     pub stipend: u64,
+    /// The memory usage, if it was collected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryStats>,
 }
@@ -191,7 +191,11 @@ impl CallTraceDecoder {
     /// Identify unknown addresses in the specified call trace using the specified identifier.
     ///
     /// Unknown contracts are contracts that either lack a label or an ABI.
-    pub fn identify(&mut self, trace: &CallTraceArena, identifier: &impl TraceIdentifier) {
+    pub fn identify(
+        &mut self,
+        trace: &CallTraceArena,
+        identifier: &(impl TraceIdentifier + ?Sized),
+    ) {
         let unidentified_addresses = trace
             .addresses()
             .into_iter()
@@ -0,0 +1,119 @@
+//! The gas spent by the instructions of a call, grouped by the kind of instruction
+
+use revm::opcode;
+use serde::{Deserialize, Serialize};
+use std::ops::AddAssign;
+
+/// The gas a call spent on the different kinds of instructions
+///
+/// The gas of a call instruction includes the gas that the called contract used, so the categories
+/// of a call sum to its total gas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasCategories {
+    /// `SLOAD` and `SSTORE`
+    pub storage: u64,
+    /// `MLOAD`, `MSTORE`, `MSTORE8` and `MSIZE`
+    pub memory: u64,
+    /// `CALL`, `CALLCODE`, `DELEGATECALL` and `STATICCALL`
+    pub calls: u64,
+    /// The arithmetic, comparison and bitwise instructions, `ADD` to `SIGNEXTEND` and `LT` to
+    /// `SAR`
+    pub arithmetic: u64,
+    /// `SHA3`
+    pub hashing: u64,
+    /// All other instructions
+    pub other: u64,
+}
+
+// === impl GasCategories ===
+
+impl GasCategories {
+    /// Adds the `gas` spent by an instruction with the opcode `op` to its category
+    pub fn record(&mut self, op: u8, gas: u64) {
+        let category = match op {
+            opcode::SLOAD | opcode::SSTORE => &mut self.storage,
+            opcode::MLOAD | opcode::MSTORE | opcode::MSTORE8 | opcode::MSIZE => &mut self.memory,
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => {
+                &mut self.calls
+            }
+            opcode::ADD..=opcode::SIGNEXTEND | opcode::LT..=opcode::SAR => &mut self.arithmetic,
+            opcode::SHA3 => &mut self.hashing,
+            _ => &mut self.other,
+        };
+        *category += gas;
+    }
+
+    /// Returns the gas of all categories
+    pub fn total(&self) -> u64 {
+        self.storage + self.memory + self.calls + self.arithmetic + self.hashing + self.other
+    }
+
+    /// Returns the categories with `other` set so that they sum to `total`
+    ///
+    /// The gas cost of a call also covers costs that no instruction spent, like the intrinsic gas
+    /// of a transaction, those are counted as `other`.
+    pub fn with_total(mut self, total: u64) -> Self {
+        let named = self.total() - self.other;
+        self.other = total.saturating_sub(named);
+        self
+    }
+
+    /// Returns `true` if no gas was recorded in any category
+    pub fn is_zero(&self) -> bool {
+        self.total() == 0
+    }
+
+    /// Returns the names and the gas of the categories, in the order they are displayed in
+    pub fn named(&self) -> [(&'static str, u64); 6] {
+        [
+            ("storage", self.storage),
+            ("memory", self.memory),
+            ("calls", self.calls),
+            ("arithmetic", self.arithmetic),
+            ("hashing", self.hashing),
+            ("other", self.other),
+        ]
+    }
+}
+
+impl AddAssign for GasCategories {
+    fn add_assign(&mut self, rhs: Self) {
+        self.storage += rhs.storage;
+        self.memory += rhs.memory;
+        self.calls += rhs.calls;
+        self.arithmetic += rhs.arithmetic;
+        self.hashing += rhs.hashing;
+        self.other += rhs.other;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn categories_sum_to_total() {
+        let mut categories = GasCategories::default();
+        categories.record(opcode::SLOAD, 2_100);
+        categories.record(opcode::MSTORE, 6);
+        categories.record(opcode::ADD, 3);
+        categories.record(opcode::SHR, 3);
+        categories.record(opcode::SHA3, 36);
+        categories.record(opcode::PUSH1, 3);
+        assert_eq!(
+            categories,
+            GasCategories {
+                storage: 2_100,
+                memory: 6,
+                arithmetic: 6,
+                hashing: 36,
+                other: 3,
+                ..Default::default()
+            }
+        );
+
+        let categories = categories.with_total(21_000 + 2_150);
+        assert_eq!(categories.other, 21_000 + 2_150 - 2_148);
+        assert_eq!(categories.total(), 21_000 + 2_150);
+    }
+}
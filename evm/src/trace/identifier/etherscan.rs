@@ -24,13 +24,19 @@ pub struct EtherscanIdentifier {
 impl EtherscanIdentifier {
     /// Creates a new Etherscan identifier.
     ///
-    /// The identifier is a noop if either `chain` or `etherscan_api_key` are `None`.
+    /// The identifier is a noop if either `chain` or `etherscan_api_key` are `None`, or in
+    /// `offline` mode.
     pub fn new(
         chain: Option<impl Into<Chain>>,
         etherscan_api_key: Option<String>,
         cache_path: Option<PathBuf>,
         ttl: Duration,
+        offline: bool,
     ) -> Self {
+        if offline {
+            return Self { client: None }
+        }
+
         if let Some(cache_path) = &cache_path {
             if let Err(err) = std::fs::create_dir_all(cache_path.join("sources")) {
                 warn!(target: "etherscanidentifier", "could not create etherscan cache dir: {:?}", err);
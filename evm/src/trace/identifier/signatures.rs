@@ -1,7 +1,11 @@
 use ethers::abi::{Event, Function};
 use foundry_utils::{decode_selector, get_event, get_func, selectors::SelectorType};
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, io::BufWriter, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
 use tracing::warn;
 
 /// An identifier that tries to identify functions and events using signatures found at
@@ -10,10 +14,15 @@ use tracing::warn;
 pub struct SignaturesIdentifier {
     cached: CachedSignatures,
     cached_path: Option<PathBuf>,
+    /// Whether only the cached signatures are used, without querying `sig.eth.samczsun.com`
+    offline: bool,
 }
 
 impl SignaturesIdentifier {
-    pub fn new(cache_path: Option<PathBuf>) -> eyre::Result<Self> {
+    /// Creates an identifier that caches the signatures in `cache_path`, if set.
+    ///
+    /// In `offline` mode unknown selectors are only looked up in the cache.
+    pub fn new(cache_path: Option<PathBuf>, offline: bool) -> eyre::Result<Self> {
         if let Some(cache_path) = cache_path {
             let path = cache_path.join("signatures");
             let cached = if path.is_file() {
@@ -24,9 +33,9 @@ impl SignaturesIdentifier {
                 }
                 CachedSignatures::default()
             };
-            return Ok(Self { cached, cached_path: Some(path) })
+            return Ok(Self { cached, cached_path: Some(path), offline })
         }
-        Ok(Self::default())
+        Ok(Self { offline, ..Default::default() })
     }
 
     pub fn save(&self) {
@@ -56,7 +65,7 @@ impl SignaturesIdentifier {
 
         let identifier = format!("0x{}", hex::encode(identifier));
 
-        if !map.contains_key(&identifier) {
+        if !self.offline && !map.contains_key(&identifier) {
             if let Ok(signatures) = decode_selector(&identifier, selector_type).await {
                 if let Some(signature) = signatures.into_iter().next() {
                     map.insert(identifier.to_string(), signature);
@@ -93,6 +102,18 @@ pub struct CachedSignatures {
     pub functions: BTreeMap<String, String>,
 }
 
+impl CachedSignatures {
+    /// Reads the signatures that a [`SignaturesIdentifier`] cached in `cache_path`, which are
+    /// empty if nothing was cached yet
+    pub fn read(cache_path: &Path) -> eyre::Result<Self> {
+        let path = cache_path.join("signatures");
+        if !path.is_file() {
+            return Ok(Self::default())
+        }
+        Ok(serde_json::from_reader(std::fs::File::open(&path)?)?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -101,7 +122,7 @@ mod tests {
     async fn can_query_signatures() {
         let tmp = tempfile::tempdir().unwrap();
         {
-            let mut sigs = SignaturesIdentifier::new(Some(tmp.path().into())).unwrap();
+            let mut sigs = SignaturesIdentifier::new(Some(tmp.path().into()), false).unwrap();
 
             assert!(sigs.cached.events.is_empty());
             assert!(sigs.cached.functions.is_empty());
@@ -121,8 +142,22 @@ mod tests {
             // dropping saves the cache
         }
 
-        let sigs = SignaturesIdentifier::new(Some(tmp.path().into())).unwrap();
+        let sigs = SignaturesIdentifier::new(Some(tmp.path().into()), false).unwrap();
         assert!(sigs.cached.events.len() == 1);
         assert!(sigs.cached.functions.len() == 1);
     }
+
+    #[tokio::test]
+    async fn only_uses_cache_offline() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut sigs = SignaturesIdentifier::new(Some(tmp.path().into()), true).unwrap();
+        sigs.cached
+            .functions
+            .insert("0x23b872dd".to_string(), "transferFrom(address,address,uint256)".to_string());
+
+        let func = sigs.identify_function(&[35, 184, 114, 221]).await.unwrap();
+        assert!(func == get_func("transferFrom(address,address,uint256)").unwrap());
+        // `balanceOf(address)` is not cached
+        assert!(sigs.identify_function(&[112, 160, 130, 49]).await.is_none());
+    }
 }
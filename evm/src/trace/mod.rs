@@ -4,12 +4,18 @@
 pub mod identifier;
 
 mod decoder;
+pub mod gas_categories;
 pub mod node;
+pub mod precompiles;
 mod utils;
 
 pub use decoder::{CallTraceDecoder, CallTraceDecoderBuilder};
 
-use crate::{abi::CHEATCODE_ADDRESS, trace::identifier::LocalTraceIdentifier, CallKind};
+use crate::{
+    abi::CHEATCODE_ADDRESS,
+    trace::{gas_categories::GasCategories, identifier::LocalTraceIdentifier},
+    CallKind,
+};
 use ethers::{
     abi::{Abi, Address, RawLog},
     prelude::ArtifactId,
@@ -71,6 +77,17 @@ impl CallTraceArena {
         }
     }
 
+    /// Returns a copy of the arena whose calls nested deeper than `max_depth` are left out when
+    /// it is displayed, where the entry call has depth 0
+    pub fn truncated(&self, max_depth: usize) -> CallTraceArena {
+        let mut arena = self.clone();
+        for node in arena.arena.iter_mut().filter(|node| node.trace.depth >= max_depth) {
+            node.children.clear();
+            node.ordering.retain(|order| matches!(order, LogCallOrder::Log(_)));
+        }
+        arena
+    }
+
     pub fn addresses(&self) -> HashSet<(&Address, Option<&Vec<u8>>)> {
         self.arena
             .iter()
@@ -320,12 +337,71 @@ pub struct CallTrace {
     pub output: RawOrDecodedReturnData,
     /// The gas cost of the call
     pub gas_cost: u64,
+    /// The gas the call costs according to the cost model of the standard precompile that it
+    /// calls, if any, see [`precompiles::gas_floor()`]
+    #[serde(default)]
+    pub precompile_gas_floor: Option<u64>,
+    /// The fork the call was executed on, if it was executed in fork mode
+    #[serde(default)]
+    pub fork: Option<ForkContext>,
+    /// The gas the instructions of this call itself saved because the accounts and storage slots
+    /// they accessed first were prewarmed by the access list of the transaction (EIP-2930), only
+    /// recorded if enabled with
+    /// [`ExecutorBuilder::set_access_list_savings()`](crate::executor::ExecutorBuilder::set_access_list_savings)
+    #[serde(default)]
+    pub access_list_savings: u64,
+    /// The gas the instructions of this call itself spent on expanding its memory, only recorded if
+    /// enabled with
+    /// [`ExecutorBuilder::set_memory_gas()`](crate::executor::ExecutorBuilder::set_memory_gas)
+    #[serde(default)]
+    pub memory_gas: u64,
+    /// The gas the instructions of this call itself spent, by the kind of instruction, the call
+    /// instructions include the gas used by their subcalls, only recorded if enabled with
+    /// [`ExecutorBuilder::set_gas_categories()`](crate::executor::ExecutorBuilder::set_gas_categories)
+    #[serde(default)]
+    pub gas_categories: GasCategories,
+    /// The gas used by the instructions of this call itself, excluding its subcalls, per source
+    /// line, empty if the source positions of the instructions are unknown
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub source_gas: Vec<SourceLineGas>,
+    /// The gas used by the instructions of this call itself, excluding its subcalls, by their
+    /// program counter, only recorded if enabled with
+    /// [`ExecutorBuilder::set_gas_by_pc()`](crate::executor::ExecutorBuilder::set_gas_by_pc)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pc_gas: BTreeMap<usize, u64>,
+    /// Whether the caller forwarded all of its remaining gas to this call, instead of a capped
+    /// amount, `None` if the gas the caller asked for is unknown, e.g. for the calls of the tests
+    #[serde(default)]
+    pub forwards_all_gas: Option<bool>,
     /// The status of the trace's call
     pub status: Return,
     /// call context of the runtime
     pub call_context: Option<CallContext>,
 }
 
+/// The gas used by the instructions of a call that map to a line of a source file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLineGas {
+    /// The path of the source file
+    pub file: String,
+    /// The 1-based line in the source file
+    pub line: usize,
+    pub gas: u64,
+}
+
+/// The chain and block of the fork that a call was executed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ForkContext {
+    pub chain_id: u64,
+    pub block_number: u64,
+}
+
+impl fmt::Display for ForkContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "chain {} @ block {}", self.chain_id, self.block_number)
+    }
+}
+
 // === impl CallTrace ===
 
 impl CallTrace {
@@ -349,6 +425,14 @@ impl Default for CallTrace {
             data: Default::default(),
             output: Default::default(),
             gas_cost: Default::default(),
+            precompile_gas_floor: Default::default(),
+            fork: Default::default(),
+            access_list_savings: Default::default(),
+            memory_gas: Default::default(),
+            gas_categories: Default::default(),
+            source_gas: Default::default(),
+            pc_gas: Default::default(),
+            forwards_all_gas: Default::default(),
             status: Return::Continue,
             call_context: Default::default(),
         }
@@ -418,6 +502,16 @@ pub enum TraceKind {
     Execution,
 }
 
+impl fmt::Display for TraceKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TraceKind::Deployment => f.write_str("deployment"),
+            TraceKind::Setup => f.write_str("setup"),
+            TraceKind::Execution => f.write_str("execution"),
+        }
+    }
+}
+
 /// Chooses the color of the trace depending on the destination address and status of the call.
 fn trace_color(trace: &CallTrace) -> Color {
     if trace.address == CHEATCODE_ADDRESS {
@@ -459,3 +553,24 @@ pub fn load_contracts(
         BTreeMap::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_nested_calls() {
+        let mut arena = CallTraceArena::default();
+        let mut entry = 0;
+        for depth in 0..4 {
+            entry = arena.push_trace(entry, CallTrace { depth, ..Default::default() });
+        }
+
+        let truncated = arena.truncated(1);
+        assert_eq!(truncated.arena[0].children, [1]);
+        assert!(truncated.arena[1].children.is_empty());
+        assert!(truncated.arena[1].ordering.is_empty());
+        assert_eq!(truncated.to_string().lines().count(), 4);
+        assert_eq!(arena.to_string().lines().count(), 8);
+    }
+}
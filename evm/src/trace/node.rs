@@ -131,7 +131,15 @@ impl CallTraceNode {
                 RawOrDecodedCall::Decoded(func.name.clone(), func.signature(), inputs);
 
             if let RawOrDecodedReturnData::Raw(bytes) = &self.trace.output {
-                if !bytes.is_empty() && self.trace.success {
+                let is_cheatcode = self.trace.address == CHEATCODE_ADDRESS;
+                let cheatcode_output = if is_cheatcode && self.trace.success {
+                    utils::decode_cheatcode_outputs(func, bytes)
+                } else {
+                    None
+                };
+                if let Some(output) = cheatcode_output {
+                    self.trace.output = RawOrDecodedReturnData::Decoded(output);
+                } else if !bytes.is_empty() && self.trace.success {
                     if let Some(tokens) =
                         funcs.iter().find_map(|func| func.decode_output(bytes).ok())
                     {
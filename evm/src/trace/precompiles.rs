@@ -0,0 +1,130 @@
+//! Gas cost models of the standard Ethereum precompiles
+
+use ethers::types::{Address, U256};
+
+/// Returns the gas that a call of the standard precompile at `address` with the given `input`
+/// costs according to the EIP that specifies it, or `None` if `address` is not a standard
+/// precompile.
+///
+/// The costs are the ones in effect since the Berlin hard fork.
+pub fn gas_floor(address: Address, input: &[u8]) -> Option<u64> {
+    let words = |len: usize| ((len + 31) / 32) as u64;
+    let gas = match precompile_number(address)? {
+        // ecrecover
+        1 => 3_000,
+        // sha256
+        2 => 60 + 12 * words(input.len()),
+        // ripemd160
+        3 => 600 + 120 * words(input.len()),
+        // identity
+        4 => 15 + 3 * words(input.len()),
+        // modexp, EIP-2565
+        5 => modexp_gas(input),
+        // ecAdd, ecMul and ecPairing, EIP-1108
+        6 => 150,
+        7 => 6_000,
+        8 => 45_000 + 34_000 * (input.len() / 192) as u64,
+        // blake2f, EIP-152: one gas per round
+        _ => input.get(..4).map(U256::from_big_endian).unwrap_or_default().as_u64(),
+    };
+    Some(gas)
+}
+
+/// Returns the number of the standard precompile at `address`, if any
+fn precompile_number(address: Address) -> Option<u8> {
+    let bytes = address.as_bytes();
+    let number = bytes[19];
+    if bytes[..19].iter().all(|byte| *byte == 0) && (1..=9).contains(&number) {
+        Some(number)
+    } else {
+        None
+    }
+}
+
+/// Returns `len` bytes of the `input` starting at `offset`, missing bytes are zero like they are
+/// for the precompiles
+fn padded(input: &[u8], offset: u64, len: u64) -> Vec<u8> {
+    (0..len)
+        .map(|i| {
+            offset
+                .checked_add(i)
+                .and_then(|idx| input.get(usize::try_from(idx).ok()?))
+                .copied()
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// The gas cost of the modexp precompile as specified by EIP-2565
+fn modexp_gas(input: &[u8]) -> u64 {
+    let length = |offset| {
+        let len = U256::from_big_endian(&padded(input, offset, 32));
+        // lengths that do not fit into 64 bits cannot be paid for anyway
+        if len > U256::from(u64::MAX) {
+            u64::MAX
+        } else {
+            len.as_u64()
+        }
+    };
+    let (base_len, exp_len, mod_len) = (length(0), length(32), length(64));
+
+    let words = (base_len.max(mod_len) as u128 + 7) / 8;
+    let complexity = words.saturating_mul(words);
+
+    let exp_head = U256::from_big_endian(&padded(
+        input,
+        base_len.saturating_add(96),
+        exp_len.min(32),
+    ));
+    let head_bits = (exp_head.bits() as u128).saturating_sub(1);
+    let iterations = if exp_len <= 32 {
+        head_bits
+    } else {
+        (exp_len as u128 - 32).saturating_mul(8).saturating_add(head_bits)
+    };
+
+    let gas = complexity.saturating_mul(iterations.max(1)) / 3;
+    gas.clamp(200, u64::MAX as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn precompile(number: u64) -> Address {
+        Address::from_low_u64_be(number)
+    }
+
+    #[test]
+    fn computes_precompile_floors() {
+        assert_eq!(gas_floor(precompile(10), &[]), None);
+        assert_eq!(gas_floor(precompile(1), &[0; 128]), Some(3_000));
+        assert_eq!(gas_floor(precompile(2), &[0; 64]), Some(84));
+        assert_eq!(gas_floor(precompile(4), &[0; 33]), Some(21));
+        assert_eq!(gas_floor(precompile(8), &[0; 384]), Some(113_000));
+        assert_eq!(gas_floor(precompile(9), &[0, 0, 0, 12]), Some(12));
+    }
+
+    #[test]
+    fn computes_modexp_floor() {
+        let modexp = |base_len: u64, exp: &[u8], mod_len: u64| {
+            let mut input = vec![0u8; 96];
+            U256::from(base_len).to_big_endian(&mut input[..32]);
+            U256::from(exp.len()).to_big_endian(&mut input[32..64]);
+            U256::from(mod_len).to_big_endian(&mut input[64..96]);
+            input.extend(vec![1u8; base_len as usize]);
+            input.extend(exp);
+            input.extend(vec![1u8; mod_len as usize]);
+            gas_floor(precompile(5), &input).unwrap()
+        };
+
+        // small inputs pay the minimum
+        assert_eq!(modexp(64, &[2], 64), 200);
+        // RSA-2048 verification with exponent 65537: 32 words squared times 16 iterations
+        assert_eq!(modexp(256, &[1, 0, 1], 256), 1024 * 16 / 3);
+        // an exponent longer than 32 bytes adds 8 iterations per additional byte
+        let mut exp = vec![0u8; 33];
+        exp[0] = 1;
+        assert_eq!(modexp(256, &exp, 256), 1024 * (8 + 248) / 3);
+    }
+}
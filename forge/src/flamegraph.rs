@@ -0,0 +1,173 @@
+use crate::{
+    executor::{CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS},
+    trace::{CallTrace, CallTraceArena, RawOrDecodedCall, TraceKind},
+};
+use std::{collections::BTreeMap, fmt};
+
+/// The gas used by the calls of traces in the folded stacks format of flamegraph tools.
+///
+/// Every stack is the `;` separated path of `Contract::function` frames from the outermost call
+/// to a call, its value is the gas that call used itself, excluding its subcalls, summed over all
+/// occurrences of the path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FoldedStacks {
+    /// stack -> gas
+    pub stacks: BTreeMap<String, u64>,
+}
+
+impl FoldedStacks {
+    /// Records the calls of all `traces`, the traces are expected to be decoded
+    pub fn analyze(&mut self, traces: &[(TraceKind, CallTraceArena)]) {
+        for (_, arena) in traces {
+            if !arena.arena.is_empty() {
+                self.analyze_node(arena, 0, String::new());
+            }
+        }
+    }
+
+    fn analyze_node(&mut self, arena: &CallTraceArena, node_index: usize, parent: String) {
+        let node = &arena.arena[node_index];
+        let trace = &node.trace;
+        if trace.address == CHEATCODE_ADDRESS || trace.address == HARDHAT_CONSOLE_ADDRESS {
+            return
+        }
+
+        let frame = frame_name(trace);
+        let stack = if parent.is_empty() { frame } else { format!("{parent};{frame}") };
+        let children_gas =
+            node.children.iter().map(|child| arena.arena[*child].trace.gas_cost).sum::<u64>();
+        *self.stacks.entry(stack.clone()).or_default() +=
+            trace.gas_cost.saturating_sub(children_gas);
+
+        for child in node.children.iter() {
+            self.analyze_node(arena, *child, stack.clone());
+        }
+    }
+
+    /// Returns by how much the gas of every stack changed from the `baseline` to these stacks,
+    /// e.g. to render a differential flamegraph of the call paths that got more expensive.
+    ///
+    /// Stacks whose gas did not change are omitted.
+    pub fn delta(&self, baseline: &FoldedStacks) -> FoldedStacksDelta {
+        let mut stacks = BTreeMap::new();
+        for stack in self.stacks.keys().chain(baseline.stacks.keys()) {
+            let current = self.stacks.get(stack).copied().unwrap_or_default() as i128;
+            let base = baseline.stacks.get(stack).copied().unwrap_or_default() as i128;
+            if current != base {
+                stacks.insert(stack.clone(), current - base);
+            }
+        }
+        FoldedStacksDelta { stacks }
+    }
+}
+
+impl fmt::Display for FoldedStacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (stack, gas) in self.stacks.iter() {
+            writeln!(f, "{stack} {gas}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The change of the gas of every stack between two [`FoldedStacks`], see
+/// [`FoldedStacks::delta()`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FoldedStacksDelta {
+    /// stack -> the gas of the stack in the current traces minus its gas in the baseline
+    pub stacks: BTreeMap<String, i128>,
+}
+
+impl fmt::Display for FoldedStacksDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (stack, delta) in self.stacks.iter() {
+            writeln!(f, "{stack} {delta}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the `Contract::function` frame of the call, the contract is named by its identified
+/// name, its label or its address, and the function by its name or selector
+fn frame_name(trace: &CallTrace) -> String {
+    let contract = trace
+        .contract
+        .as_deref()
+        .map(|id| id.rsplit(':').next().unwrap_or(id).to_string())
+        .or_else(|| trace.label.clone())
+        .unwrap_or_else(|| format!("{:?}", trace.address));
+    let function = match &trace.data {
+        RawOrDecodedCall::Decoded(func, _, _) => func.clone(),
+        RawOrDecodedCall::Raw(_) if trace.created() => "constructor".to_string(),
+        RawOrDecodedCall::Raw(bytes) if bytes.len() >= 4 => {
+            format!("0x{}", hex::encode(&bytes[..4]))
+        }
+        RawOrDecodedCall::Raw(bytes) if bytes.is_empty() => "receive".to_string(),
+        RawOrDecodedCall::Raw(_) => "fallback".to_string(),
+    };
+    format!("{contract}::{function}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::node::CallTraceNode;
+
+    fn call(contract: &str, func: &str, gas_cost: u64) -> CallTrace {
+        CallTrace {
+            contract: Some(format!("src/{contract}.sol:{contract}")),
+            data: RawOrDecodedCall::Decoded(func.to_string(), format!("{func}()"), vec![]),
+            gas_cost,
+            ..Default::default()
+        }
+    }
+
+    /// Builds an arena of a call to `Vault.deposit` that calls `Token.transferFrom` and
+    /// `Token.balanceOf`
+    fn deposit(deposit_gas: u64, transfer_gas: u64, balance_gas: u64) -> CallTraceArena {
+        let node = |idx, parent, children, trace| CallTraceNode {
+            parent,
+            children,
+            idx,
+            trace,
+            ..Default::default()
+        };
+        CallTraceArena {
+            arena: vec![
+                node(0, None, vec![1, 2], call("Vault", "deposit", deposit_gas)),
+                node(1, Some(0), vec![], call("Token", "transferFrom", transfer_gas)),
+                node(2, Some(0), vec![], call("Token", "balanceOf", balance_gas)),
+            ],
+        }
+    }
+
+    #[test]
+    fn folds_stacks_by_self_gas() {
+        let mut stacks = FoldedStacks::default();
+        stacks.analyze(&[
+            (TraceKind::Execution, deposit(50_000, 30_000, 2_000)),
+            (TraceKind::Execution, deposit(50_000, 30_000, 2_000)),
+        ]);
+        assert_eq!(
+            stacks.to_string(),
+            "Vault::deposit 36000\n\
+             Vault::deposit;Token::balanceOf 4000\n\
+             Vault::deposit;Token::transferFrom 60000\n"
+        );
+    }
+
+    #[test]
+    fn folds_gas_deltas() {
+        let mut baseline = FoldedStacks::default();
+        baseline.analyze(&[(TraceKind::Execution, deposit(50_000, 30_000, 2_000))]);
+        let mut current = FoldedStacks::default();
+        current.analyze(&[(TraceKind::Execution, deposit(54_000, 35_000, 2_000))]);
+
+        let delta = current.delta(&baseline);
+        assert_eq!(
+            delta.to_string(),
+            "Vault::deposit -1000\n\
+             Vault::deposit;Token::transferFrom 5000\n"
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use ethers::{
+    abi::{Function, ParamType, Token},
+    types::{Bytes, I256},
+};
+use eyre::{Context, Result};
+use foundry_common::fs;
+use serde_json::{Map, Value};
+use std::{collections::BTreeSet, path::Path};
+
+/// Known inputs of fuzz tests, read from a file with one JSON object per line that maps the
+/// parameter names of a fuzz test to the values of its arguments, e.g.
+/// `{"amount": "100", "to": "0x000000000000000000000000000000000000dEaD"}`.
+///
+/// The inputs of a line are run by every fuzz test whose parameter names are exactly the keys of
+/// the line, before the test is fuzzed.
+#[derive(Debug, Clone, Default)]
+pub struct FuzzInputs {
+    /// The inputs by their line number
+    inputs: Vec<(usize, Map<String, Value>)>,
+}
+
+impl FuzzInputs {
+    /// Reads the inputs from the NDJSON file at `path`, empty lines are skipped
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        let inputs = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                let input = serde_json::from_str(line).wrap_err_with(|| {
+                    format!("{}:{}: expected a JSON object", path.display(), idx + 1)
+                })?;
+                Ok((idx + 1, input))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { inputs })
+    }
+
+    /// Returns the calldata of all inputs that match the parameters of the fuzz test `func`
+    pub fn seeds_for(&self, func: &Function) -> Result<Vec<Bytes>> {
+        let names = func.inputs.iter().map(|param| param.name.as_str()).collect::<BTreeSet<_>>();
+        if names.len() != func.inputs.len() || names.contains("") {
+            // the inputs cannot be matched against unnamed parameters
+            return Ok(Vec::new())
+        }
+
+        self.inputs
+            .iter()
+            .filter(|(_, input)| input.keys().map(String::as_str).collect::<BTreeSet<_>>() == names)
+            .map(|(line, input)| {
+                encode_seed(func, input).wrap_err_with(|| {
+                    format!("Invalid fuzz input on line {line} for `{}`", func.signature())
+                })
+            })
+            .collect()
+    }
+}
+
+/// Encodes the arguments of the `input` as calldata of `func`
+fn encode_seed(func: &Function, input: &Map<String, Value>) -> Result<Bytes> {
+    let args = func
+        .inputs
+        .iter()
+        .map(|param| json_to_arg(&param.kind, &input[&param.name]))
+        .collect::<Result<Vec<_>>>()?;
+    let params = func.inputs.iter().map(|param| &param.kind);
+    let tokens = foundry_utils::parse_tokens(params.zip(args.iter().map(String::as_str)), true)?;
+    Ok(func.encode_input(&tokens)?.into())
+}
+
+/// Formats the arguments `args` of the fuzz test `func` as a line of a fuzz input file, or returns
+/// `None` if a parameter is unnamed
+pub fn to_line(func: &Function, args: &[Token]) -> Option<String> {
+    let mut input = Map::new();
+    for (param, arg) in func.inputs.iter().zip(args) {
+        if param.name.is_empty() {
+            return None
+        }
+        input.insert(param.name.clone(), token_to_json(arg));
+    }
+    Some(Value::Object(input).to_string())
+}
+
+/// Converts a JSON value into an argument string that can be tokenized as `kind`
+fn json_to_arg(kind: &ParamType, value: &Value) -> Result<String> {
+    let arg = match (kind, value) {
+        (ParamType::Array(inner) | ParamType::FixedArray(inner, _), Value::Array(items)) => {
+            let items = items
+                .iter()
+                .map(|item| {
+                    let item = json_to_arg(inner, item)?;
+                    // quoted so that commas do not split the string
+                    Ok(match **inner {
+                        ParamType::String => format!("{item:?}"),
+                        _ => item,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            format!("[{}]", items.join(","))
+        }
+        (ParamType::Tuple(params), Value::Array(items)) if params.len() == items.len() => {
+            let items = params
+                .iter()
+                .zip(items)
+                .map(|(param, item)| json_to_arg(param, item))
+                .collect::<Result<Vec<_>>>()?;
+            format!("({})", items.join(","))
+        }
+        (_, Value::String(value)) => value.clone(),
+        (_, Value::Number(_) | Value::Bool(_)) => value.to_string(),
+        _ => eyre::bail!("`{value}` is not a valid `{kind}`"),
+    };
+    Ok(arg)
+}
+
+/// Converts a token into its JSON value, numbers are strings so that they keep their precision
+fn token_to_json(token: &Token) -> Value {
+    match token {
+        Token::Address(addr) => Value::String(format!("{addr:?}")),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => {
+            Value::String(format!("0x{}", hex::encode(bytes)))
+        }
+        Token::Int(num) => Value::String(I256::from_raw(*num).to_string()),
+        Token::Uint(num) => Value::String(num.to_string()),
+        Token::Bool(b) => Value::Bool(*b),
+        Token::String(s) => Value::String(s.clone()),
+        Token::FixedArray(tokens) | Token::Array(tokens) | Token::Tuple(tokens) => {
+            Value::Array(tokens.iter().map(token_to_json).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::{
+        abi::{AbiParser, Address},
+        types::U256,
+    };
+
+    #[test]
+    fn roundtrips_fuzz_inputs() {
+        let func = AbiParser::default()
+            .parse_function("testFuzz(uint256 amount, int8 delta, address to, string[] names)")
+            .unwrap();
+        let args = vec![
+            Token::Uint(U256::from(100)),
+            Token::Int(I256::from(-1).into_raw()),
+            Token::Address(Address::from_low_u64_be(0xdead)),
+            Token::Array(vec![
+                Token::String("alice".to_string()),
+                Token::String("bob".to_string()),
+            ]),
+        ];
+        let line = to_line(&func, &args).unwrap();
+
+        let inputs = FuzzInputs {
+            inputs: vec![
+                (1, serde_json::from_str(&line).unwrap()),
+                (2, serde_json::from_str(r#"{"amount": 1}"#).unwrap()),
+            ],
+        };
+        let seeds = inputs.seeds_for(&func).unwrap();
+        assert_eq!(seeds.len(), 1);
+        assert_eq!(func.decode_input(&seeds[0][4..]).unwrap(), args);
+    }
+}
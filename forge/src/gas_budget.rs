@@ -0,0 +1,152 @@
+//! Gas budgets of functions declared in natspec comments
+//!
+//! A function declares the most gas its calls may use on average with a custom natspec tag in
+//! the doc comment right above it:
+//!
+//! ```solidity
+//! /// @custom:gas-budget 50000
+//! function deposit(uint256 amount) public {}
+//! ```
+//!
+//! The budgets are keyed like [`GasReport::function()`](crate::gas_report::GasReport::function)
+//! looks up functions, `<contract name>.<function name>`, or `<contract name>.<signature>` for
+//! overloaded functions, and checked against the gas report with
+//! [`GasReport::check_budgets()`](crate::gas_report::GasReport::check_budgets).
+
+use crate::natspec::documented_functions;
+use ethers::types::U256;
+use std::collections::BTreeMap;
+
+/// The natspec tag that declares the gas budget of a function
+pub const GAS_BUDGET_TAG: &str = "@custom:gas-budget";
+
+/// Parses the gas budgets declared in the solidity `source` into the `budgets`, functions without
+/// a budget are skipped.
+///
+/// An overloaded function is keyed by its signature, `<contract name>.<signature>`, so that every
+/// overload is checked against its own budget.
+///
+/// Returns a warning naming the function for every budget that can not be used, because it is not
+/// a number or because the function is overloaded and its signature can not be resolved.
+pub fn parse_gas_budgets(source: &str, budgets: &mut BTreeMap<String, U256>) -> Vec<String> {
+    let functions = documented_functions(source);
+    let mut warnings = Vec::new();
+
+    for function in &functions {
+        let budget = match function.docs.iter().find_map(|doc| doc.strip_prefix(GAS_BUDGET_TAG)) {
+            Some(budget) => budget.trim(),
+            None => continue,
+        };
+        let overloads = functions
+            .iter()
+            .filter(|other| other.contract == function.contract && other.name == function.name)
+            .count();
+        let key = if overloads > 1 {
+            match function.signature.as_ref() {
+                Some(signature) => format!("{}.{signature}", function.contract),
+                None => {
+                    warnings.push(format!(
+                        "ignoring the gas budget of `{}.{}`, the signature of the overloaded \
+                         function can not be resolved",
+                        function.contract, function.name
+                    ));
+                    continue
+                }
+            }
+        } else {
+            format!("{}.{}", function.contract, function.name)
+        };
+        match U256::from_dec_str(&budget.replace('_', "")) {
+            Ok(gas) => {
+                budgets.insert(key, gas);
+            }
+            Err(_) => {
+                warnings.push(format!("ignoring the invalid gas budget of `{key}`: `{budget}`"))
+            }
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_gas_budgets() {
+        let source = r#"
+contract Vault {
+    /// @notice Deposits the tokens
+    /// @custom:gas-budget 50000
+    function deposit(uint256 amount) public {}
+
+    /**
+     * @custom:gas-budget 1_000
+     */
+    function withdraw() public {}
+
+    function balance() public view returns (uint256) {}
+}
+
+library Math {
+    /** @custom:gas-budget 500 */
+    function max(uint256 a, uint256 b) internal pure returns (uint256) {}
+}
+"#;
+        let mut budgets = BTreeMap::new();
+        assert!(parse_gas_budgets(source, &mut budgets).is_empty());
+        assert_eq!(
+            budgets,
+            BTreeMap::from([
+                ("Math.max".to_string(), U256::from(500)),
+                ("Vault.deposit".to_string(), U256::from(50_000)),
+                ("Vault.withdraw".to_string(), U256::from(1_000)),
+            ])
+        );
+    }
+
+    #[test]
+    fn keys_overloads_by_signature() {
+        let source = r#"
+contract Vault {
+    /// @custom:gas-budget 50000
+    function deposit(uint amount) public {}
+
+    /// @custom:gas-budget 80000
+    function deposit(uint256 amount, address to) public {}
+
+    /// @custom:gas-budget 90000
+    function deposit(IERC20 token) public {}
+}
+"#;
+        let mut budgets = BTreeMap::new();
+        let warnings = parse_gas_budgets(source, &mut budgets);
+        assert_eq!(
+            budgets,
+            BTreeMap::from([
+                ("Vault.deposit(uint256)".to_string(), U256::from(50_000)),
+                ("Vault.deposit(uint256,address)".to_string(), U256::from(80_000)),
+            ])
+        );
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("Vault.deposit"));
+    }
+
+    #[test]
+    fn warns_about_invalid_budgets() {
+        let source = r#"
+contract Vault {
+    /// @custom:gas-budget lots
+    function deposit() public {}
+
+    /// @custom:gas-budget 1000
+    function withdraw() public {}
+}
+"#;
+        let mut budgets = BTreeMap::new();
+        let warnings = parse_gas_budgets(source, &mut budgets);
+        assert_eq!(warnings, ["ignoring the invalid gas budget of `Vault.deposit`: `lots`"]);
+        assert_eq!(budgets, BTreeMap::from([("Vault.withdraw".to_string(), U256::from(1000))]));
+    }
+}
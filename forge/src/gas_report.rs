@@ -1,157 +1,4006 @@
 use crate::{
-    executor::{CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS},
-    trace::{CallTraceArena, RawOrDecodedCall, TraceKind},
+    executor::{abi::HEVMCalls, CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS},
+    trace::{
+        gas_categories::GasCategories, identifier::TraceIdentifier, CallTrace, CallTraceArena,
+        CallTraceDecoder, RawOrDecodedCall, TraceKind,
+    },
 };
 use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, *};
-use ethers::types::U256;
-use foundry_common::{calc, TestFunctionExt};
-use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, fmt::Display};
+use ethers::{
+    abi::AbiDecode,
+    types::{Address, U256},
+};
+use eyre::Context;
+use foundry_common::{
+    calc::{self, Stats},
+    fs, TestFunctionExt,
+};
+use glob::Pattern;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::Display,
+    path::Path,
+};
+
+/// The context of the calls that were not executed on a fork
+const LOCAL_CONTEXT: &str = "local";
+
+/// The outcome of the calls that succeeded
+const SUCCESS_OUTCOME: &str = "success";
+
+/// The outcome of the calls that reverted
+const REVERT_OUTCOME: &str = "revert";
+
+/// Signatures of the functions that upgrade the implementation of UUPS and transparent proxies, or
+/// of a proxy managed by a `ProxyAdmin`
+const UPGRADE_SIGNATURES: &[&str] = &[
+    "upgradeTo(address)",
+    "upgradeToAndCall(address,bytes)",
+    "upgradeToAndCall(address,bytes,bool)",
+    "upgrade(address,address)",
+    "upgradeAndCall(address,address,bytes)",
+];
+
+/// The version of the serialized form of [`GasReport`], reports without a header are version 1
+pub const GAS_REPORT_SCHEMA_VERSION: u32 = 2;
+
+/// Describes how a [`GasReport`] was produced, so that consumers of serialized reports can tell
+/// whether they understand and can compare them.
+///
+/// The `report_for` contracts and the `fork_block` are top-level fields of the report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasReportHeader {
+    /// The version of the serialized form, later versions than
+    /// [`GAS_REPORT_SCHEMA_VERSION`] are rejected
+    #[serde(deserialize_with = "deserialize_schema_version")]
+    pub schema_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forge_version: Option<String>,
+    /// The config profile of the run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// The versions of solc that compiled the reported contracts
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub solc_versions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimizer: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimizer_runs: Option<usize>,
+    /// Whether the contracts were compiled through the IR pipeline
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub via_ir: Option<bool>,
+    /// When the report was taken, in seconds since the unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    /// The source paths whose contracts were left out, see [`GasReport::with_exclude_paths()`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_paths: Vec<String>,
+}
+
+impl Default for GasReportHeader {
+    fn default() -> Self {
+        Self {
+            schema_version: GAS_REPORT_SCHEMA_VERSION,
+            forge_version: None,
+            profile: None,
+            solc_versions: Vec::new(),
+            optimizer: None,
+            optimizer_runs: None,
+            via_ir: None,
+            timestamp: None,
+            exclude_paths: Vec::new(),
+        }
+    }
+}
+
+impl GasReportHeader {
+    /// The header of reports that were serialized before they had one
+    fn legacy() -> Self {
+        Self { schema_version: 1, ..Default::default() }
+    }
+
+    /// Returns why the compiler settings of the reports differ, if both recorded them
+    pub fn compiler_mismatch(&self, other: &GasReportHeader) -> Option<String> {
+        if !self.solc_versions.is_empty() &&
+            !other.solc_versions.is_empty() &&
+            self.solc_versions != other.solc_versions
+        {
+            return Some(format!(
+                "solc {} != solc {}",
+                self.solc_versions.join(","),
+                other.solc_versions.join(",")
+            ))
+        }
+        let optimizer = |header: &GasReportHeader| match (header.optimizer, header.optimizer_runs) {
+            (Some(true), Some(runs)) => Some(format!("optimizer with {runs} runs")),
+            (Some(true), None) => Some("optimizer".to_string()),
+            (Some(false), _) => Some("no optimizer".to_string()),
+            (None, _) => None,
+        };
+        if let (Some(this), Some(other)) = (optimizer(self), optimizer(other)) {
+            if this != other {
+                return Some(format!("{this} != {other}"))
+            }
+        }
+        let via_ir = |via_ir| if via_ir { "via-ir" } else { "no via-ir" };
+        match (self.via_ir, other.via_ir) {
+            (Some(this), Some(other)) if this != other => {
+                Some(format!("{} != {}", via_ir(this), via_ir(other)))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn deserialize_schema_version<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let version = u32::deserialize(deserializer)?;
+    if version > GAS_REPORT_SCHEMA_VERSION {
+        return Err(de::Error::custom(format!(
+            "unsupported gas report schema version {version}, the latest supported version is \
+             {GAS_REPORT_SCHEMA_VERSION}"
+        )))
+    }
+    Ok(version)
+}
 
 #[derive(Default, Debug, Serialize, Deserialize)]
 pub struct GasReport {
+    /// How the report was produced, reports that were serialized before they had a header are
+    /// read with schema version 1
+    #[serde(default = "GasReportHeader::legacy")]
+    pub header: GasReportHeader,
     pub report_for: Vec<String>,
+    /// The maximum depth in the call trace arena at which calls are recorded, if any.
+    ///
+    /// Depth 0 is the entry call invoked by the test.
+    pub max_depth: Option<usize>,
+    /// Arbitrary labels describing the run that produced this report, e.g. the git commit
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    /// Whether proxy upgrades are reported separately from the other functions
+    #[serde(default)]
+    pub report_upgrades: bool,
+    /// Whether only running statistics are kept per function instead of the gas of every call,
+    /// see [`StreamingStats`]
+    #[serde(default)]
+    pub streaming: bool,
+    /// The maximum number of calls whose gas is kept per function, beyond it a reservoir sample
+    /// of the calls is kept, see [`GasReport::with_max_samples_per_function()`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_samples_per_function: Option<usize>,
+    /// Whether the calls of a function that were executed on different forks are reported
+    /// together, instead of separately for every fork
+    #[serde(default)]
+    pub merge_fork_contexts: bool,
+    /// Whether the average gas of a function is colored by how it compares to the median
+    /// function of its contract, see [`GasInfo::relative_cost`]
+    #[serde(default)]
+    pub relative_coloring: bool,
+    /// Whether the function tables have a `memory_gas` column with the average gas per call that
+    /// was spent on expanding memory
+    #[serde(default)]
+    pub memory_gas_column: bool,
+    /// Whether every function is followed by a row with the average gas per call that its
+    /// instructions spent in every [`GasCategories`] category
+    #[serde(default)]
+    pub gas_categories: bool,
+    /// Whether the functions that forward all of their remaining gas to an address that is not a
+    /// known contract are flagged, see [`GasReport::with_flag_gas_griefing()`]
+    #[serde(default)]
+    pub flag_gas_griefing: bool,
+    /// The gas that is subtracted from every recorded call of a function, see
+    /// [`GasReport::with_noise_floor()`]
+    #[serde(default, skip_serializing_if = "U256::is_zero")]
+    pub noise_floor: U256,
+    /// The block the tests were forked from, `None` if they did not run on a fork.
+    ///
+    /// Only reports taken at the same block are comparable, see [`GasReportDiff`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork_block: Option<u64>,
+    /// The source paths whose contracts are not reported unless they are listed in `report_for`
+    #[serde(skip)]
+    pub exclude_paths: Vec<Pattern>,
+    /// Known function signatures by their selector, e.g. `0xa9059cbb -> transfer(address,uint256)`,
+    /// that the [`ContractInfo::unclassified`] calls are reported as once they are resolvable
+    #[serde(skip)]
+    pub signatures: BTreeMap<String, String>,
+    /// The contracts whose ABI is known by their identifier, their calls that match none of its
+    /// functions are reported as `fallback()` instead of as [`ContractInfo::unclassified`] calls
+    #[serde(skip)]
+    pub abi_contracts: HashSet<String>,
+    /// The addresses whose code was placed with `vm.etch` in the analyzed traces, the contracts at
+    /// them were not deployed, see [`ContractInfo::etched`]
+    #[serde(skip)]
+    pub etched: HashSet<Address>,
+    /// The deployment gas of the contracts created in the analyzed traces by their address, see
+    /// [`ContractInfo::implementations`]
+    #[serde(skip)]
+    pub deployments: HashMap<Address, u64>,
+    /// The traces analyzed with [`GasReport::analyze_labeled()`] by their label, e.g. the test
+    /// that produced them, see [`GasReport::receipt_for()`]
+    #[serde(skip)]
+    pub labeled: BTreeMap<String, Vec<(TraceKind, CallTraceArena)>>,
     pub contracts: BTreeMap<String, ContractInfo>,
+    /// The gas used by the reported contracts per source file and line, only recorded if the
+    /// traces carry source positions, see [`GasReport::line_annotations()`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub lines: BTreeMap<String, BTreeMap<usize, U256>>,
+}
+
+/// The gas used by the instructions on a line of a source file, summed over all calls
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineGas {
+    pub file: String,
+    /// The 1-based line in the source file
+    pub line: usize,
+    pub gas: U256,
+}
+
+/// A statistic of the calls of a function, see [`GasReport::extract_metric()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasColumn {
+    Min,
+    Mean,
+    Median,
+    Max,
+    /// The number of recorded calls
+    Calls,
+}
+
+/// The statistics of a function of a contract in a gas report, the flat form of the report that
+/// the columnar formats are built from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasReportRow {
+    pub contract: String,
+    pub signature: String,
+    /// The fork the calls were executed on, `None` if the row covers the calls of all forks
+    pub context: Option<String>,
+    pub calls: usize,
+    pub min: U256,
+    pub mean: U256,
+    /// The median gas of the calls, `None` in streaming mode
+    pub median: Option<U256>,
+    pub max: U256,
+}
+
+/// The column widths, in characters, of [`GasReport::to_fwf_with()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FwfWidths {
+    pub contract: usize,
+    pub signature: usize,
+    pub context: usize,
+    pub calls: usize,
+    /// The width of each of the min, mean, median and max columns
+    pub gas: usize,
+}
+
+impl Default for FwfWidths {
+    fn default() -> Self {
+        Self { contract: 40, signature: 60, context: 32, calls: 10, gas: 12 }
+    }
+}
+
+/// The gas used by a function signature in every contract that implements it, see
+/// [`GasReport::compare_signature()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComparisonTable {
+    pub signature: String,
+    /// One row per contract, ordered from the cheapest to the most expensive on average
+    pub rows: Vec<ComparisonRow>,
+}
+
+/// The gas used by the function of a single contract in a [`ComparisonTable`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComparisonRow {
+    pub contract: String,
+    pub calls: usize,
+    pub min: U256,
+    pub mean: U256,
+    pub max: U256,
+}
+
+/// The average gas of every function by the kind of trace its calls were recorded in, see
+/// [`GasReport::pivot_by_kind()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PivotReport {
+    /// The kinds of traces that calls were recorded in, in the order deployment, setup and
+    /// execution
+    pub kinds: Vec<String>,
+    pub rows: Vec<PivotRow>,
+}
+
+/// The gas used by a function of a contract in a [`PivotReport`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PivotRow {
+    pub contract: String,
+    pub signature: String,
+    /// The number of calls in traces of all kinds
+    pub calls: usize,
+    /// The average gas of the calls in traces of all kinds
+    pub mean: U256,
+    /// The average gas of the calls in the traces of each of the [`PivotReport::kinds`], `None`
+    /// if the function was not called in traces of that kind
+    pub by_kind: Vec<Option<U256>>,
+}
+
+/// The gas of the functions and deployments of the same contracts built with different compiler
+/// settings, see [`GasReport::compare_profiles()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileComparison {
+    /// The names of the compared profiles, in the order of the columns of every row
+    pub profiles: Vec<String>,
+    /// The deployment cost of the contracts deployed in every profile
+    pub deployments: Vec<ProfileRow>,
+    /// The average gas of the functions called in every profile
+    pub functions: Vec<ProfileRow>,
+    /// The `Contract.signature` of the functions that were only called in some of the profiles,
+    /// which are not compared
+    pub partial: Vec<String>,
+}
+
+/// The gas of a contract or of a function of it in every profile of a [`ProfileComparison`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileRow {
+    pub contract: String,
+    /// The signature of the function, `None` for the deployment of the contract
+    pub signature: Option<String>,
+    /// The gas in each of the [`ProfileComparison::profiles`]
+    pub gas: Vec<U256>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct ContractInfo {
     pub gas: U256,
-    pub size: U256,
+    /// The size of the deployed runtime bytecode, which counts against the EIP-170 limit
+    #[serde(default)]
+    pub deployed_size: U256,
+    /// The size of the init code, i.e. the creation bytecode and the constructor arguments.
+    ///
+    /// Reports of earlier versions recorded this as `size`.
+    #[serde(default, alias = "size")]
+    pub init_size: U256,
     pub functions: BTreeMap<String, BTreeMap<String, GasInfo>>,
+    /// The proxy upgrades performed by this contract, by function signature
+    #[serde(default)]
+    pub upgrades: BTreeMap<String, GasInfo>,
+    /// The deployment gas of the implementations that the upgrades of this contract switched to,
+    /// by their address, only known for implementations that were deployed in the analyzed traces
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub implementations: BTreeMap<Address, U256>,
+    /// The calls with a selector that could not be decoded of a contract without a known ABI,
+    /// e.g. low-level calls to assembly routers, by their selector
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub unclassified: BTreeMap<String, GasInfo>,
+    /// Whether the code of the contract was placed with `vm.etch` instead of being deployed,
+    /// which costs no gas, so it has no deployment cost unless it was also deployed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub etched: bool,
+}
+
+impl ContractInfo {
+    /// Returns whether any call of this contract was recorded
+    pub fn has_calls(&self) -> bool {
+        !self.functions.is_empty() || !self.upgrades.is_empty() || !self.unclassified.is_empty()
+    }
+
+    /// Returns the names of the functions whose most expensive call costs more gas than the
+    /// deployment of the contract, which is worth optimizing.
+    ///
+    /// This is empty if the deployment was not recorded. The report is expected to be finalized.
+    pub fn functions_exceeding_deploy(&self) -> Vec<&str> {
+        if self.gas.is_zero() {
+            return Vec::new()
+        }
+        self.functions
+            .iter()
+            .filter(|(_, sigs)| sigs.values().any(|function| function.max > self.gas))
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Returns the names of the functions that forwarded all of their remaining gas to an address
+    /// that is not a known contract, see [`GasReport::with_flag_gas_griefing()`]
+    pub fn griefing_vectors(&self) -> Vec<&str> {
+        self.functions
+            .iter()
+            .filter(|(_, sigs)| sigs.values().any(|function| function.griefing_vector))
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Returns a score between 0 and 1 that expresses how predictable the gas usage of this
+    /// contract's functions is, where 1 means every call of a function costs the same.
+    ///
+    /// The score is `1 / (1 + cv)`, where `cv` is the average coefficient of variation
+    /// (`stddev / mean`) of all functions, weighted by their number of calls. A `cv` of 1, i.e. a
+    /// standard deviation as large as the mean, results in a score of 0.5.
+    pub fn stability_score(&self) -> f64 {
+        let (weighted_cv, calls) = self
+            .functions
+            .values()
+            .flat_map(|sigs| sigs.values())
+            .filter(|function| function.call_count() > 0)
+            .fold((0f64, 0usize), |(weighted_cv, calls), function| {
+                let n = function.call_count();
+                (weighted_cv + function.coefficient_of_variation() * n as f64, calls + n)
+            });
+
+        if calls == 0 {
+            return 1.0
+        }
+        1.0 / (1.0 + weighted_cv / calls as f64)
+    }
+
+    /// Returns the `pct`-th percentile of the gas of all recorded calls of all functions of this
+    /// contract, e.g. the 95th percentile as the cost of a typical worst-case call into it.
+    ///
+    /// Zero in streaming mode, in which the gas of the calls is not kept.
+    pub fn aggregate_percentile(&self, pct: f64) -> U256 {
+        let mut calls = self
+            .functions
+            .values()
+            .flat_map(|sigs| sigs.values())
+            .flat_map(|function| function.calls.iter().copied())
+            .collect::<Vec<_>>();
+        calls.sort_unstable();
+        calc::percentile_sorted(&calls, pct)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct GasInfo {
     pub calls: Vec<U256>,
+    /// The number of recorded calls if `calls` only holds a reservoir sample of them, see
+    /// [`GasReport::with_max_samples_per_function()`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampled_from: Option<u64>,
+    /// The running statistics of all calls, recorded instead of `calls` in streaming mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<StreamingStats>,
     pub min: U256,
     pub mean: U256,
+    /// The median gas of all calls, always zero in streaming mode
     pub median: U256,
     pub max: U256,
+    /// The summed gas floors of the standard precompiles called by all recorded calls
+    #[serde(default, skip_serializing_if = "U256::is_zero")]
+    pub precompile_floor_total: U256,
+    /// The gas per call that the standard precompiles called by the function cost at least on
+    /// average, if it calls any.
+    ///
+    /// Everything the function uses on top of this is spent on the surrounding logic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precompile_floor: Option<U256>,
+    /// The summed gas that all recorded calls saved because the accounts and storage slots they
+    /// accessed were prewarmed by the access lists of their transactions (EIP-2930).
+    ///
+    /// Zero if the transactions had no access lists, then all first accesses are cold.
+    #[serde(default, skip_serializing_if = "U256::is_zero")]
+    pub access_list_savings: U256,
+    /// The summed gas that all recorded calls spent on expanding their memory, including the
+    /// memory of their subcalls.
+    ///
+    /// Zero if the calls were not traced with memory data.
+    #[serde(default, skip_serializing_if = "U256::is_zero")]
+    pub memory_gas: U256,
+    /// The summed gas of all recorded calls by the kind of instruction that spent it, the
+    /// categories of a call sum to its gas.
+    ///
+    /// Zero if the calls were traced without their instructions.
+    #[serde(default, skip_serializing_if = "GasCategories::is_zero")]
+    pub gas_categories: GasCategories,
+    /// The calls grouped by the fork they were executed on, e.g. `chain 1 @ block 15000000`, or
+    /// `local` if they were not executed on a fork.
+    ///
+    /// Only the running statistics of each context are kept, and only if the calls were executed
+    /// in more than one context.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub contexts: BTreeMap<String, StreamingStats>,
+    /// The running statistics of the calls grouped by the kind of trace they were recorded in,
+    /// e.g. `setup` or `execution`, see [`GasReport::pivot_by_kind()`]
+    ///
+    /// Only kept if the calls were recorded in traces of more than one kind.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub kinds: BTreeMap<String, StreamingStats>,
+    /// The kind of trace all calls were recorded in if they were recorded in traces of a single
+    /// kind, set when the report is finalized instead of keeping the [`GasInfo::kinds`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// The running statistics of the calls grouped by whether they succeeded or reverted, see
+    /// [`GasInfo::success_stats()`] and [`GasInfo::revert_stats()`].
+    ///
+    /// Only kept if some calls reverted, reverting calls often stop early and are much cheaper.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub outcomes: BTreeMap<String, StreamingStats>,
+    /// Whether these are the calls of the synthetic `receive()` or `fallback()` function, i.e.
+    /// calls without calldata or with calldata that matches no function of the contract
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub synthetic: bool,
+    /// Whether the most expensive call costs more gas than the deployment of the contract, see
+    /// [`ContractInfo::functions_exceeding_deploy()`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exceeds_deployment: bool,
+    /// Whether a call of this function forwarded all of its remaining gas to an address that is
+    /// not a known contract, a potential gas griefing vector, see
+    /// [`GasReport::with_flag_gas_griefing()`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub griefing_vector: bool,
+    /// The average gas of this function divided by the median of the average gas of all
+    /// functions of its contract, e.g. 2.0 if it costs twice as much as its peers.
+    ///
+    /// Only computed with relative coloring and for contracts with more than one called function.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative_cost: Option<f64>,
+    /// The numeric arguments of the decoded calls with their gas, see [`GasInfo::elasticity()`].
+    ///
+    /// Not recorded in streaming mode.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub samples: Vec<ArgumentSample>,
 }
 
-impl GasReport {
-    pub fn new(report_for: Vec<String>) -> Self {
-        Self { report_for, ..Default::default() }
+/// How the calls of a function are recorded
+#[derive(Debug, Clone, Copy)]
+struct Sampling {
+    /// Whether only running statistics are kept
+    streaming: bool,
+    /// The maximum number of calls that are kept
+    max_samples: Option<usize>,
+    /// The gas that is subtracted from every call
+    noise_floor: U256,
+}
+
+/// The gas used by a call together with its arguments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArgumentSample {
+    /// The value of every argument of the call, `None` if it is not a number
+    pub args: Vec<Option<f64>>,
+    pub gas: U256,
+}
+
+impl GasInfo {
+    /// Records the gas of the call `trace`, by the fork it was executed on, if any, by the `kind`
+    /// of trace it is part of and by whether it succeeded
+    fn record(&mut self, trace: &CallTrace, sampling: Sampling, kind: &TraceKind) {
+        let gas = U256::from(trace.gas_cost).saturating_sub(sampling.noise_floor);
+        self.push(gas, sampling);
+        let context =
+            trace.fork.map(|fork| fork.to_string()).unwrap_or_else(|| LOCAL_CONTEXT.into());
+        self.contexts.entry(context).or_default().push(gas);
+        self.kinds.entry(kind.to_string()).or_default().push(gas);
+        let outcome = if trace.success { SUCCESS_OUTCOME } else { REVERT_OUTCOME };
+        self.outcomes.entry(outcome.to_string()).or_default().push(gas);
     }
 
-    pub fn analyze(&mut self, traces: &[(TraceKind, CallTraceArena)]) {
-        let report_for_all = self.report_for.is_empty() || self.report_for.iter().any(|s| s == "*");
-        traces.iter().for_each(|(_, trace)| {
-            self.analyze_trace(trace, report_for_all);
-        });
+    /// Adds `gas` to the calls, either by storing it, by replacing a sampled call or by updating
+    /// the running statistics
+    fn push(&mut self, gas: U256, sampling: Sampling) {
+        if sampling.streaming {
+            self.stats.get_or_insert_with(Default::default).push(gas);
+            return
+        }
+        match sampling.max_samples {
+            Some(max_samples) if self.call_count() >= max_samples => {
+                // reservoir sampling: the n-th call replaces a random sample with probability
+                // `max_samples / n`, the index is derived from `n` so that reports are
+                // reproducible
+                let count = self.call_count() as u64 + 1;
+                self.sampled_from = Some(count);
+                let index = (mix64(count) % count) as usize;
+                if let Some(sample) = self.calls.get_mut(index) {
+                    *sample = gas;
+                }
+            }
+            _ => self.calls.push(gas),
+        }
     }
 
-    fn analyze_trace(&mut self, trace: &CallTraceArena, report_for_all: bool) {
-        self.analyze_node(0, trace, report_for_all);
+    /// Records the decoded arguments `args` of a call that used `gas`, only the first
+    /// `max_samples` calls are kept
+    fn record_args(&mut self, args: &[String], gas: U256, sampling: Sampling) {
+        if sampling.streaming ||
+            sampling.max_samples.map_or(false, |max_samples| self.samples.len() >= max_samples)
+        {
+            return
+        }
+        let args = args.iter().map(|arg| arg.parse::<f64>().ok()).collect();
+        self.samples.push(ArgumentSample { args, gas: gas.saturating_sub(sampling.noise_floor) });
     }
 
-    fn analyze_node(&mut self, node_index: usize, arena: &CallTraceArena, report_for_all: bool) {
-        let node = &arena.arena[node_index];
-        let trace = &node.trace;
+    /// Adds all calls recorded by `other` to this function, of which at most
+    /// `sampling.max_samples` are kept
+    fn merge(&mut self, other: GasInfo, sampling: Sampling) {
+        let (count, other_count) = (self.call_count(), other.call_count());
+        match sampling.max_samples {
+            Some(max_samples) if self.calls.len() + other.calls.len() > max_samples => {
+                // both hold samples of their calls, which keep a share of the merged samples
+                // proportional to the number of calls they were sampled from
+                let total = count + other_count;
+                let keep = (max_samples * count / total)
+                    .clamp(max_samples.saturating_sub(other.calls.len()), self.calls.len());
+                self.calls = spread_samples(&self.calls, keep);
+                self.calls.extend(spread_samples(&other.calls, max_samples - keep));
+                self.sampled_from = Some(total as u64);
+            }
+            _ => {
+                if self.sampled_from.is_some() || other.sampled_from.is_some() {
+                    self.sampled_from = Some((count + other_count) as u64);
+                }
+                self.calls.extend(other.calls);
+            }
+        }
+        let room =
+            sampling.max_samples.map_or(usize::MAX, |max| max.saturating_sub(self.samples.len()));
+        self.samples.extend(other.samples.into_iter().take(room));
+        self.precompile_floor_total += other.precompile_floor_total;
+        self.access_list_savings += other.access_list_savings;
+        self.memory_gas += other.memory_gas;
+        self.gas_categories += other.gas_categories;
+        self.synthetic |= other.synthetic;
+        self.griefing_vector |= other.griefing_vector;
+        if let Some(other) = other.stats {
+            self.stats.get_or_insert_with(Default::default).merge(&other);
+        }
+        for (context, other) in other.contexts {
+            self.contexts.entry(context).or_default().merge(&other);
+        }
+        for (kind, other) in other.kinds {
+            self.kinds.entry(kind).or_default().merge(&other);
+        }
+        for (outcome, other) in other.outcomes {
+            self.outcomes.entry(outcome).or_default().merge(&other);
+        }
+    }
+
+    /// Returns the number of recorded calls
+    pub fn call_count(&self) -> usize {
+        match (&self.stats, self.sampled_from) {
+            (Some(stats), _) => stats.count as usize,
+            (None, Some(count)) => count as usize,
+            (None, None) => self.calls.len(),
+        }
+    }
+
+    /// Sorts the recorded calls and computes the statistics over them
+    fn finalize(&mut self) {
+        if self.contexts.len() <= 1 {
+            self.contexts.clear();
+        }
+        if self.kinds.len() <= 1 {
+            self.kind = self.kinds.keys().next().cloned();
+            self.kinds.clear();
+        }
+        if !self.outcomes.contains_key(REVERT_OUTCOME) {
+            self.outcomes.clear();
+        }
+
+        let count = self.call_count();
+        if !self.precompile_floor_total.is_zero() && count > 0 {
+            self.precompile_floor = Some(self.precompile_floor_total / count);
+        }
 
-        if trace.address == CHEATCODE_ADDRESS || trace.address == HARDHAT_CONSOLE_ADDRESS {
+        if let Some(stats) = &self.stats {
+            self.min = stats.min;
+            self.max = stats.max;
+            self.mean = U256::from(stats.mean as u128);
             return
         }
+        self.calls.sort_unstable();
+        let stats = Stats::from_sorted(&self.calls);
+        self.min = stats.min;
+        self.max = stats.max;
+        self.mean = stats.mean;
+        self.median = stats.median;
+    }
 
-        if let Some(name) = &trace.contract {
-            // checking contract allowlist for reporting by extracting name out of identifier
-            let report_for = self
-                .report_for
-                .iter()
-                .any(|s| s == name.rsplit(':').next().unwrap_or(name.as_str()));
-            if report_for || report_for_all {
-                let mut contract_report =
-                    self.contracts.entry(name.to_string()).or_insert_with(Default::default);
+    /// Returns the statistics of the calls that succeeded, `None` if all of them reverted.
+    ///
+    /// Only complete once the report is finalized. If some calls reverted only the running
+    /// statistics are kept per outcome, so the median and percentiles are zero.
+    pub fn success_stats(&self) -> Option<Stats> {
+        if self.outcomes.is_empty() {
+            // no call reverted
+            return (self.call_count() > 0).then(|| self.stats())
+        }
+        self.outcomes.get(SUCCESS_OUTCOME).map(StreamingStats::summary)
+    }
 
-                match &trace.data {
-                    RawOrDecodedCall::Raw(bytes) if trace.created() => {
-                        contract_report.gas = trace.gas_cost.into();
-                        contract_report.size = bytes.len().into();
-                    }
-                    // TODO: More robust test contract filtering
-                    RawOrDecodedCall::Decoded(func, sig, _)
-                        if !func.is_test() && !func.is_setup() =>
-                    {
-                        let function_report = contract_report
-                            .functions
-                            .entry(func.clone())
-                            .or_default()
-                            .entry(sig.clone())
-                            .or_default();
-                        function_report.calls.push(trace.gas_cost.into());
-                    }
-                    _ => (),
-                }
+    /// Returns the running statistics of the calls that reverted, `None` if none of them did,
+    /// their median and percentiles are zero
+    pub fn revert_stats(&self) -> Option<Stats> {
+        self.outcomes.get(REVERT_OUTCOME).map(StreamingStats::summary)
+    }
+
+    /// Returns the statistics of the sorted calls, or of the running statistics in streaming mode
+    /// whose median and percentiles are zero
+    fn stats(&self) -> Stats {
+        match &self.stats {
+            Some(stats) => stats.summary(),
+            None => Stats { count: self.call_count(), ..Stats::from_sorted(&self.calls) },
+        }
+    }
+
+    /// Returns the median as displayed in the report, `-` if it is unknown in streaming mode
+    fn median_cell(&self) -> String {
+        if self.stats.is_some() {
+            "-".to_string()
+        } else {
+            self.median.to_string()
+        }
+    }
+
+    /// Returns the population standard deviation of the recorded calls divided by their mean
+    pub fn coefficient_of_variation(&self) -> f64 {
+        if let Some(stats) = &self.stats {
+            if stats.mean == 0.0 {
+                return 0.0
             }
+            return stats.variance().sqrt() / stats.mean
+        }
+        if self.calls.is_empty() {
+            return 0.0
+        }
+        let values: Vec<f64> = self.calls.iter().map(|gas| gas.as_u128() as f64).collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        if mean == 0.0 {
+            return 0.0
         }
+        let variance =
+            values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / values.len() as f64;
+        variance.sqrt() / mean
+    }
 
-        node.children.iter().for_each(|index| {
-            self.analyze_node(*index, arena, report_for_all);
-        });
+    /// Returns how the gas of the calls scales with their numeric argument at `arg_index`, e.g.
+    /// the batch size of a batch transfer, as the slope (gas per unit) and intercept (fixed
+    /// overhead) of a least squares fit of the gas against the argument.
+    ///
+    /// `None` if the calls were not decoded, the argument is not a number or it did not vary.
+    pub fn elasticity(&self, arg_index: usize) -> Option<(f64, f64)> {
+        let points = self
+            .samples
+            .iter()
+            .filter_map(|sample| Some((sample.args.get(arg_index).copied()??, sample.gas)))
+            .map(|(arg, gas)| (arg, gas.as_u128() as f64))
+            .collect::<Vec<_>>();
+        if points.len() < 2 {
+            return None
+        }
+        let count = points.len() as f64;
+        let mean_arg = points.iter().map(|(arg, _)| arg).sum::<f64>() / count;
+        let mean_gas = points.iter().map(|(_, gas)| gas).sum::<f64>() / count;
+        let variance = points.iter().map(|(arg, _)| (arg - mean_arg).powi(2)).sum::<f64>();
+        if variance == 0.0 {
+            return None
+        }
+        let covariance =
+            points.iter().map(|(arg, gas)| (arg - mean_arg) * (gas - mean_gas)).sum::<f64>();
+        let slope = covariance / variance;
+        Some((slope, mean_gas - slope * mean_arg))
+    }
+}
+
+/// Running count, mean and sum of squared deviations of the gas used by calls, updated with
+/// Welford's online algorithm.
+///
+/// Unlike the full list of calls this needs constant memory, but the median cannot be computed
+/// from it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub struct StreamingStats {
+    pub count: u64,
+    pub mean: f64,
+    /// The sum of squared deviations from the mean
+    pub m2: f64,
+    pub min: U256,
+    pub max: U256,
+}
+
+impl From<StreamingStats> for GasInfo {
+    fn from(stats: StreamingStats) -> Self {
+        Self {
+            min: stats.min,
+            mean: U256::from(stats.mean as u128),
+            max: stats.max,
+            stats: Some(stats),
+            ..Default::default()
+        }
+    }
+}
+
+impl StreamingStats {
+    /// Adds a call that used `gas` to the statistics
+    pub fn push(&mut self, gas: U256) {
+        if self.count == 0 || gas < self.min {
+            self.min = gas;
+        }
+        if gas > self.max {
+            self.max = gas;
+        }
+        self.count += 1;
+        let value = gas.as_u128() as f64;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// Combines the statistics of `other` into these, as if all of its calls were pushed
+    pub fn merge(&mut self, other: &StreamingStats) {
+        if other.count == 0 {
+            return
+        }
+        if self.count == 0 {
+            *self = *other;
+            return
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        self.mean += delta * other.count as f64 / count as f64;
+        self.m2 += other.m2 + delta * delta * (self.count * other.count) as f64 / count as f64;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.count = count;
+    }
+
+    /// Returns the statistics of all calls, whose median and percentiles are zero
+    pub fn summary(&self) -> Stats {
+        Stats {
+            count: self.count as usize,
+            min: self.min,
+            max: self.max,
+            mean: U256::from(self.mean as u128),
+            std_dev: U256::from(self.variance().sqrt() as u128),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the population variance of all calls
+    pub fn variance(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0
+        }
+        self.m2 / self.count as f64
+    }
+}
+
+impl GasReport {
+    pub fn new(report_for: Vec<String>) -> Self {
+        Self { report_for, ..Default::default() }
     }
 
+    /// Attaches the given `key = value` labels to the report
     #[must_use]
-    pub fn finalize(mut self) -> Self {
-        self.contracts.iter_mut().for_each(|(_, contract)| {
-            contract.functions.iter_mut().for_each(|(_, sigs)| {
-                sigs.iter_mut().for_each(|(_, func)| {
-                    func.calls.sort_unstable();
-                    func.min = func.calls.first().copied().unwrap_or_default();
-                    func.max = func.calls.last().copied().unwrap_or_default();
-                    func.mean = calc::mean(&func.calls);
-                    func.median = calc::median_sorted(&func.calls);
-                });
-            });
-        });
+    pub fn with_metadata(
+        mut self,
+        metadata: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) -> Self {
+        self.metadata
+            .extend(metadata.into_iter().map(|(key, value)| (key.into(), value.into())));
         self
     }
-}
 
-impl Display for GasReport {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        for (name, contract) in self.contracts.iter() {
-            if contract.functions.is_empty() {
-                continue
-            }
+    /// Describes how the report was produced in its serialized form
+    #[must_use]
+    pub fn with_header(mut self, header: GasReportHeader) -> Self {
+        self.header = header;
+        self
+    }
 
-            let mut table = Table::new();
-            table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
-            table.set_header(vec![Cell::new(format!("{name} contract"))
-                .add_attribute(Attribute::Bold)
-                .fg(Color::Green)]);
-            table.add_row(vec![
-                Cell::new("Deployment Cost").add_attribute(Attribute::Bold).fg(Color::Cyan),
-                Cell::new("Deployment Size").add_attribute(Attribute::Bold).fg(Color::Cyan),
-            ]);
-            table.add_row(vec![contract.gas.to_string(), contract.size.to_string()]);
+    /// Tags the report with the optimizer settings it was built with, `None` runs if the optimizer
+    /// was disabled, see [`GasReport::compare_profiles()`]
+    #[must_use]
+    pub fn with_build_profile(mut self, optimizer_runs: Option<usize>, via_ir: bool) -> Self {
+        self.header.optimizer = Some(optimizer_runs.is_some());
+        self.header.optimizer_runs = optimizer_runs;
+        self.header.via_ir = Some(via_ir);
+        self
+    }
 
-            table.add_row(vec![
-                Cell::new("Function Name").add_attribute(Attribute::Bold).fg(Color::Magenta),
-                Cell::new("min").add_attribute(Attribute::Bold).fg(Color::Green),
-                Cell::new("avg").add_attribute(Attribute::Bold).fg(Color::Yellow),
-                Cell::new("median").add_attribute(Attribute::Bold).fg(Color::Yellow),
-                Cell::new("max").add_attribute(Attribute::Bold).fg(Color::Red),
-                Cell::new("# calls").add_attribute(Attribute::Bold),
-            ]);
-            contract.functions.iter().for_each(|(fname, sigs)| {
-                sigs.iter().for_each(|(sig, function)| {
-                    // show function signature if overloaded else name
-                    let fn_display =
-                        if sigs.len() == 1 { fname.clone() } else { sig.replace(':', "") };
+    /// Only record calls at or above the given depth in the call trace arena
+    #[must_use]
+    pub fn with_max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
 
-                    table.add_row(vec![
-                        Cell::new(fn_display).add_attribute(Attribute::Bold),
-                        Cell::new(function.min.to_string()).fg(Color::Green),
-                        Cell::new(function.mean.to_string()).fg(Color::Yellow),
-                        Cell::new(function.median.to_string()).fg(Color::Yellow),
-                        Cell::new(function.max.to_string()).fg(Color::Red),
-                        Cell::new(function.calls.len().to_string()),
-                    ]);
-                })
-            });
-            writeln!(f, "{}", table)?
+    /// Report calls of the well-known proxy upgrade functions as upgrades instead of ordinary
+    /// functions
+    #[must_use]
+    pub fn with_report_upgrades(mut self, report_upgrades: bool) -> Self {
+        self.report_upgrades = report_upgrades;
+        self
+    }
+
+    /// Keep the gas of at most `max_samples` calls per function, beyond that a reservoir sample of
+    /// all calls is kept so that the memory of long fuzz and invariant runs is bounded.
+    ///
+    /// The statistics are computed over the sample, so min, mean, median and max are only
+    /// approximations once a function was called more often, the number of calls stays exact.
+    /// `None` keeps every call.
+    #[must_use]
+    pub fn with_max_samples_per_function(mut self, max_samples: Option<usize>) -> Self {
+        self.max_samples_per_function = max_samples;
+        self
+    }
+
+    /// Returns how the calls of the functions are recorded
+    fn sampling(&self) -> Sampling {
+        Sampling {
+            streaming: self.streaming,
+            max_samples: self.max_samples_per_function,
+            noise_floor: self.noise_floor,
+        }
+    }
+
+    /// Only keep running statistics per function instead of the gas used by every call.
+    ///
+    /// This bounds the memory used for long fuzz and invariant runs, but the report will not
+    /// include the median.
+    #[must_use]
+    pub fn with_streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
+    }
+
+    /// Leaves out the contracts whose source path matches one of the glob `patterns`, or is
+    /// within one of them if it is a directory, e.g. test helpers.
+    ///
+    /// The calls that these contracts make are still reported.
+    #[must_use]
+    pub fn with_exclude_paths(mut self, patterns: &[String]) -> Self {
+        self.exclude_paths = patterns
+            .iter()
+            .map(|pattern| {
+                Pattern::new(pattern).unwrap_or_else(|_| {
+                    Pattern::new(&Pattern::escape(pattern)).expect("escaped pattern is valid")
+                })
+            })
+            .collect();
+        self
+    }
+
+    /// Returns whether the contract with the identifier `<source>:<name>` is excluded
+    fn is_excluded(&self, contract: &str) -> bool {
+        let source = contract.rsplit_once(':').map_or(contract, |(source, _)| source);
+        self.exclude_paths.iter().any(|pattern| {
+            pattern.matches(source) || Path::new(source).starts_with(pattern.as_str())
+        })
+    }
+
+    /// Color the average gas of every function relative to the median function of its contract
+    /// instead of with a fixed color, so that functions that are expensive compared to their
+    /// peers stand out
+    #[must_use]
+    pub fn with_relative_coloring(mut self, relative_coloring: bool) -> Self {
+        self.relative_coloring = relative_coloring;
+        self
+    }
+
+    /// Adds a `memory_gas` column to the function tables with the average gas per call that was
+    /// spent on expanding memory, zero for calls that were traced without memory data
+    #[must_use]
+    pub fn with_memory_gas_column(mut self, memory_gas_column: bool) -> Self {
+        self.memory_gas_column = memory_gas_column;
+        self
+    }
+
+    /// Follows every function in the tables with a row that breaks its average gas per call down
+    /// into the [`GasCategories`] of the instructions that spent it
+    #[must_use]
+    pub fn with_gas_categories(mut self, gas_categories: bool) -> Self {
+        self.gas_categories = gas_categories;
+        self
+    }
+
+    /// Flags the functions that forward all of their remaining gas to an address that is not a
+    /// known contract of the project, since it can spend the gas or revert on purpose, a gas
+    /// griefing and denial of service vector.
+    ///
+    /// Calls are only flagged if the gas their caller asked to forward was traced, e.g. the calls
+    /// of the tests themselves are not.
+    #[must_use]
+    pub fn with_flag_gas_griefing(mut self, flag_gas_griefing: bool) -> Self {
+        self.flag_gas_griefing = flag_gas_griefing;
+        self
+    }
+
+    /// Subtracts the `floor` from the gas of every call of a function before the statistics are
+    /// computed, e.g. the constant overhead that the test harness adds to every measured call, so
+    /// the report shows the cost of the contracts only.
+    ///
+    /// Calls that used less gas than the floor are recorded as zero. Deployments are not
+    /// affected.
+    #[must_use]
+    pub fn with_noise_floor(mut self, floor: U256) -> Self {
+        self.noise_floor = floor;
+        self
+    }
+
+    /// Tags the report with the block the tests were forked from
+    #[must_use]
+    pub fn with_fork_block(mut self, fork_block: Option<u64>) -> Self {
+        self.fork_block = fork_block;
+        self
+    }
+
+    /// Report the calls of a function that were executed on different forks in a single row
+    /// instead of one row per fork
+    #[must_use]
+    pub fn with_merge_fork_contexts(mut self, merge_fork_contexts: bool) -> Self {
+        self.merge_fork_contexts = merge_fork_contexts;
+        self
+    }
+
+    /// Adds known function signatures by their selector, e.g. of the project ABIs and the cached
+    /// signature lookups, which the calls that could not be decoded are reported as
+    #[must_use]
+    pub fn with_signatures(
+        mut self,
+        signatures: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) -> Self {
+        self.signatures.extend(
+            signatures.into_iter().map(|(selector, sig)| (selector.into(), sig.into())),
+        );
+        self
+    }
+
+    /// Adds the identifiers of the contracts whose ABI is known, e.g. of the project artifacts
+    #[must_use]
+    pub fn with_abi_contracts(mut self, contracts: impl IntoIterator<Item = String>) -> Self {
+        self.abi_contracts.extend(contracts);
+        self
+    }
+
+    pub fn analyze(&mut self, traces: &[(TraceKind, CallTraceArena)]) {
+        let report_for_all = self.report_for.is_empty() || self.report_for.iter().any(|s| s == "*");
+        traces.iter().for_each(|(kind, trace)| {
+            self.analyze_trace(kind, trace, report_for_all);
+        });
+    }
+
+    /// Records the calls of the `traces` like [`GasReport::analyze()`] and also keeps the traces
+    /// under the `label`, e.g. the name of the test that produced them, so that a report of only
+    /// their calls can be built with [`GasReport::receipt_for()`]
+    pub fn analyze_labeled(
+        &mut self,
+        label: impl Into<String>,
+        traces: &[(TraceKind, CallTraceArena)],
+    ) {
+        self.analyze(traces);
+        self.labeled.entry(label.into()).or_default().extend(traces.iter().cloned());
+    }
+
+    /// Returns a finalized report of only the calls of the traces that were analyzed with the
+    /// `test_label`, with the settings of this report, `None` if no traces were analyzed with it
+    pub fn receipt_for(&self, test_label: &str) -> Option<GasReport> {
+        let traces = self.labeled.get(test_label)?;
+        let mut receipt = GasReport {
+            header: self.header.clone(),
+            report_for: self.report_for.clone(),
+            max_depth: self.max_depth,
+            metadata: self.metadata.clone(),
+            report_upgrades: self.report_upgrades,
+            streaming: self.streaming,
+            max_samples_per_function: self.max_samples_per_function,
+            merge_fork_contexts: self.merge_fork_contexts,
+            relative_coloring: self.relative_coloring,
+            memory_gas_column: self.memory_gas_column,
+            gas_categories: self.gas_categories,
+            flag_gas_griefing: self.flag_gas_griefing,
+            noise_floor: self.noise_floor,
+            fork_block: self.fork_block,
+            exclude_paths: self.exclude_paths.clone(),
+            signatures: self.signatures.clone(),
+            abi_contracts: self.abi_contracts.clone(),
+            ..Default::default()
+        };
+        receipt.analyze(traces);
+        Some(receipt.finalize())
+    }
+
+    /// Identifies the contracts in the `traces` with each of the `identifiers`, decodes the traces
+    /// and records their calls.
+    ///
+    /// This is the pipeline the traces of the tests go through, so the traces of any execution,
+    /// e.g. of replayed transactions, result in the same report.
+    pub async fn analyze_traces(
+        &mut self,
+        traces: &mut [(TraceKind, CallTraceArena)],
+        decoder: &mut CallTraceDecoder,
+        identifiers: &[&dyn TraceIdentifier],
+    ) {
+        for (_, trace) in traces.iter_mut() {
+            for identifier in identifiers {
+                decoder.identify(trace, *identifier);
+            }
+            decoder.decode(trace).await;
+        }
+        self.analyze(traces);
+    }
+
+    /// Reads the traces saved with [`GasReport::save_arenas()`], so that a report can be built
+    /// from them without executing the tests again
+    pub fn from_arenas_file(
+        path: impl AsRef<Path>,
+    ) -> eyre::Result<Vec<(TraceKind, CallTraceArena)>> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .wrap_err_with(|| format!("Failed to read the traces in {}", path.display()))
+    }
+
+    /// Saves the `traces` to the JSON file at `path`, see [`GasReport::from_arenas_file()`].
+    ///
+    /// The traces are expected to be decoded, the report is built from the decoded calls.
+    pub fn save_arenas(
+        path: impl AsRef<Path>,
+        traces: &[(TraceKind, CallTraceArena)],
+    ) -> eyre::Result<()> {
+        fs::write(path, serde_json::to_vec(traces)?)?;
+        Ok(())
+    }
+
+    fn analyze_trace(&mut self, kind: &TraceKind, trace: &CallTraceArena, report_for_all: bool) {
+        self.analyze_node(0, 0, kind, trace, report_for_all);
+    }
+
+    fn analyze_node(
+        &mut self,
+        node_index: usize,
+        depth: usize,
+        kind: &TraceKind,
+        arena: &CallTraceArena,
+        report_for_all: bool,
+    ) {
+        if self.max_depth.map(|max_depth| depth > max_depth).unwrap_or_default() {
+            return
+        }
+
+        let node = &arena.arena[node_index];
+        let trace = &node.trace;
+
+        if trace.address == CHEATCODE_ADDRESS {
+            if let Some(address) = etched_address(trace) {
+                self.etched.insert(address);
+            }
+            return
+        }
+        if trace.address == HARDHAT_CONSOLE_ADDRESS {
+            return
+        }
+        if trace.created() {
+            // a contract deployed to an address that was etched before
+            self.etched.remove(&trace.address);
+            self.deployments.insert(trace.address, trace.gas_cost);
+        }
+        let etched = self.etched.contains(&trace.address);
+
+        if let Some(name) = &trace.contract {
+            // checking contract allowlist for reporting by extracting name out of identifier
+            let report_for = self
+                .report_for
+                .iter()
+                .any(|s| s == name.rsplit(':').next().unwrap_or(name.as_str()));
+            if report_for || (report_for_all && !self.is_excluded(name)) {
+                for source_gas in &trace.source_gas {
+                    *self
+                        .lines
+                        .entry(source_gas.file.clone())
+                        .or_default()
+                        .entry(source_gas.line)
+                        .or_default() += U256::from(source_gas.gas);
+                }
+
+                let sampling = self.sampling();
+                let griefing_vector =
+                    self.flag_gas_griefing && forwards_all_gas_to_unknown(arena, node_index);
+                let mut contract_report =
+                    self.contracts.entry(name.to_string()).or_insert_with(Default::default);
+                contract_report.etched |= etched;
+
+                match &trace.data {
+                    RawOrDecodedCall::Raw(bytes) if trace.created() => {
+                        contract_report.gas = trace.gas_cost.into();
+                        contract_report.init_size = bytes.len().into();
+                        contract_report.deployed_size = trace.output.to_raw().len().into();
+                    }
+                    RawOrDecodedCall::Decoded(_, sig, args)
+                        if self.report_upgrades && UPGRADE_SIGNATURES.contains(&sig.as_str()) =>
+                    {
+                        if let Some(implementation) = upgrade_implementation(sig, args) {
+                            if let Some(gas) = self.deployments.get(&implementation) {
+                                contract_report
+                                    .implementations
+                                    .insert(implementation, U256::from(*gas));
+                            }
+                        }
+                        let upgrade_report =
+                            contract_report.upgrades.entry(sig.clone()).or_default();
+                        upgrade_report.record(trace, sampling, kind);
+                        upgrade_report.memory_gas += U256::from(memory_gas(arena, node_index));
+                        upgrade_report.gas_categories += gas_categories(trace);
+                    }
+                    // TODO: More robust test contract filtering
+                    RawOrDecodedCall::Decoded(func, sig, args)
+                        if !func.is_test() && !func.is_setup() =>
+                    {
+                        let function_report = contract_report
+                            .functions
+                            .entry(func.clone())
+                            .or_default()
+                            .entry(sig.clone())
+                            .or_default();
+                        function_report.record(trace, sampling, kind);
+                        function_report.record_args(args, trace.gas_cost.into(), sampling);
+                        function_report.griefing_vector |= griefing_vector;
+                        function_report.precompile_floor_total +=
+                            U256::from(precompile_floor(arena, node_index));
+                        function_report.access_list_savings +=
+                            U256::from(access_list_savings(arena, node_index));
+                        function_report.memory_gas += U256::from(memory_gas(arena, node_index));
+                        function_report.gas_categories += gas_categories(trace);
+                    }
+                    // calls with a selector that is not known yet, of contracts without an ABI
+                    RawOrDecodedCall::Raw(bytes)
+                        if bytes.len() >= 4 && !self.abi_contracts.contains(name) =>
+                    {
+                        let selector = format!("0x{}", hex::encode(&bytes[..4]));
+                        let function_report =
+                            contract_report.unclassified.entry(selector).or_default();
+                        function_report.record(trace, sampling, kind);
+                        function_report.griefing_vector |= griefing_vector;
+                        function_report.precompile_floor_total +=
+                            U256::from(precompile_floor(arena, node_index));
+                        function_report.access_list_savings +=
+                            U256::from(access_list_savings(arena, node_index));
+                        function_report.memory_gas += U256::from(memory_gas(arena, node_index));
+                        function_report.gas_categories += gas_categories(trace);
+                    }
+                    // plain transfers and calls that match none of the functions in the ABI
+                    RawOrDecodedCall::Raw(bytes) => {
+                        let name = if bytes.is_empty() { "receive" } else { "fallback" };
+                        let function_report = contract_report
+                            .functions
+                            .entry(name.to_string())
+                            .or_default()
+                            .entry(format!("{name}()"))
+                            .or_default();
+                        function_report.synthetic = true;
+                        function_report.record(trace, sampling, kind);
+                        function_report.griefing_vector |= griefing_vector;
+                        function_report.precompile_floor_total +=
+                            U256::from(precompile_floor(arena, node_index));
+                        function_report.access_list_savings +=
+                            U256::from(access_list_savings(arena, node_index));
+                        function_report.memory_gas += U256::from(memory_gas(arena, node_index));
+                        function_report.gas_categories += gas_categories(trace);
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        node.children.iter().for_each(|index| {
+            self.analyze_node(*index, depth + 1, kind, arena, report_for_all);
+        });
+    }
+
+    /// Merges the calls recorded for the function signature `from` of the `contract` into the
+    /// ones of `to`, or renames `from` to `to` if `to` has no calls yet.
+    ///
+    /// This consolidates a function that was recorded under different signatures, the statistics
+    /// are recomputed by the next [`GasReport::finalize()`].
+    pub fn rename_function(&mut self, contract: &str, from: &str, to: &str) {
+        if from == to {
+            return
+        }
+        let sampling = self.sampling();
+        let contract = match self.contracts.get_mut(contract) {
+            Some(contract) => contract,
+            None => return,
+        };
+        let from_name = match contract.functions.iter().find(|(_, sigs)| sigs.contains_key(from)) {
+            Some((name, _)) => name.clone(),
+            None => return,
+        };
+
+        let sigs = contract.functions.get_mut(&from_name).expect("function exists");
+        let info = sigs.remove(from).expect("signature exists");
+        if sigs.is_empty() {
+            contract.functions.remove(&from_name);
+        }
+
+        let to_name = to.split('(').next().unwrap_or(to).to_string();
+        contract
+            .functions
+            .entry(to_name)
+            .or_default()
+            .entry(to.to_string())
+            .or_default()
+            .merge(info, sampling);
+    }
+
+    /// Returns the recorded calls of the function `function`, given as `<contract>.<function>`
+    /// where the contract is its name or identifier and the function is its name or, if it is
+    /// overloaded, its signature, e.g. `Vault.withdraw` or `Vault.withdraw(uint256)`
+    pub fn function(&self, function: &str) -> Option<&GasInfo> {
+        // contract identifiers contain dots as well, the function name starts after the last one
+        let name_end = function.find('(').unwrap_or(function.len());
+        let dot = function[..name_end].rfind('.')?;
+        let (contract, function) = (&function[..dot], &function[dot + 1..]);
+        let (_, info) = self.contracts.iter().find(|(id, _)| {
+            id.as_str() == contract || id.rsplit(':').next() == Some(contract)
+        })?;
+        match info.functions.get(function) {
+            Some(sigs) if sigs.len() == 1 => sigs.values().next(),
+            Some(_) => None,
+            None => info.functions.values().find_map(|sigs| sigs.get(function)),
+        }
+    }
+
+    /// Checks that the average gas of the function `sig` of the `contract` is at most `max_mean`,
+    /// for gas assertions in tests.
+    ///
+    /// The function is looked up like [`GasReport::function()`] does, the report is expected to be
+    /// finalized.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the function, its observed average gas and the limit if it is
+    /// exceeded, or if no calls of the function were recorded.
+    pub fn assert_within(&self, contract: &str, sig: &str, max_mean: U256) -> Result<(), String> {
+        let function = self.function(&format!("{contract}.{sig}")).ok_or_else(|| {
+            format!("no calls of `{sig}` of the contract `{contract}` were recorded")
+        })?;
+        if function.mean > max_mean {
+            return Err(format!(
+                "`{sig}` of the contract `{contract}` exceeds its gas limit: observed an average \
+                 of {} gas, the limit is {max_mean} gas",
+                function.mean
+            ))
+        }
+        Ok(())
+    }
+
+    /// Returns a single statistic of the function `sig` of the `contract`, e.g. the mean gas of
+    /// one function per commit for a `git bisect run` script, or `None` if no calls of the
+    /// function were recorded.
+    ///
+    /// The function is looked up like [`GasReport::function()`] does, the report is expected to be
+    /// finalized.
+    pub fn extract_metric(&self, contract: &str, sig: &str, metric: GasColumn) -> Option<U256> {
+        let function = self.function(&format!("{contract}.{sig}"))?;
+        Some(match metric {
+            GasColumn::Min => function.min,
+            GasColumn::Mean => function.mean,
+            GasColumn::Median => function.median,
+            GasColumn::Max => function.max,
+            GasColumn::Calls => U256::from(function.call_count()),
+        })
+    }
+
+    /// Returns every reported function that has no entry in the `budgets`, as
+    /// `<contract name>.<function>` where the function is its name or, if it is overloaded, its
+    /// signature, e.g. `Vault.deposit` and `Vault.withdraw(uint256)`, sorted.
+    ///
+    /// Budgets are keyed like [`GasReport::function()`] looks up functions, so every overload
+    /// needs its own entry.
+    pub fn unbudgeted_functions(&self, budgets: &BTreeMap<String, U256>) -> Vec<String> {
+        let mut unbudgeted = Vec::new();
+        for (id, contract) in self.contracts.iter() {
+            let name = id.rsplit(':').next().unwrap_or(id);
+            for (function, sigs) in contract.functions.iter() {
+                for sig in sigs.keys() {
+                    let function = if sigs.len() == 1 { function } else { sig };
+                    let is_budgeted = |contract: &str| {
+                        budgets.contains_key(&format!("{contract}.{function}")) ||
+                            budgets.contains_key(&format!("{contract}.{sig}"))
+                    };
+                    if !is_budgeted(name) && !is_budgeted(id) {
+                        unbudgeted.push(format!("{name}.{function}"));
+                    }
+                }
+            }
+        }
+        unbudgeted.sort();
+        unbudgeted
+    }
+
+    /// Checks the average gas of every function that has an entry in the `budgets` against its
+    /// budget and returns the messages of the ones that exceed it, the report is expected to be
+    /// finalized.
+    ///
+    /// Budgets of functions that were not called are skipped.
+    pub fn check_budgets(&self, budgets: &BTreeMap<String, U256>) -> Vec<String> {
+        budgets
+            .iter()
+            .filter_map(|(function, budget)| {
+                let info = self.function(function)?;
+                (info.mean > *budget).then(|| {
+                    format!(
+                        "`{function}` exceeds its gas budget: observed an average of {} gas, the \
+                         budget is {budget} gas",
+                        info.mean
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Returns how much more gas a call of the `wrapped` function uses on average than a call of
+    /// the `base` function, e.g. the overhead of the modifiers that `wrapped` adds to `base`.
+    ///
+    /// Both functions are looked up like in [`GasReport::function()`], the result is `None` if
+    /// either one is missing or has no calls, and zero if `wrapped` is cheaper than `base`. The
+    /// report is expected to be finalized.
+    pub fn overhead_between(&self, base: &str, wrapped: &str) -> Option<U256> {
+        let base = self.function(base).filter(|info| info.call_count() > 0)?;
+        let wrapped = self.function(wrapped).filter(|info| info.call_count() > 0)?;
+        Some(wrapped.mean.saturating_sub(base.mean))
+    }
+
+    /// Returns the wei it costs at `gas_price_wei` to deploy every reported contract once and to
+    /// call every one of their functions once with its average gas, e.g. to estimate what running
+    /// the tested scenario costs on mainnet.
+    ///
+    /// Each signature of an overloaded function counts as a function, upgrades and unclassified
+    /// calls are not included. The sums saturate at `U256::MAX`. The report is expected to be
+    /// finalized.
+    pub fn total_cost(&self, gas_price_wei: U256) -> U256 {
+        let gas = self.contracts.values().fold(U256::zero(), |gas, contract| {
+            contract
+                .functions
+                .values()
+                .flat_map(|sigs| sigs.values())
+                .filter(|function| function.call_count() > 0)
+                .fold(gas.saturating_add(contract.gas), |gas, function| {
+                    gas.saturating_add(function.mean)
+                })
+        });
+        gas.saturating_mul(gas_price_wei)
+    }
+
+    /// Returns the gas used per source line by the calls of the reported contracts, excluding
+    /// the gas of their subcalls, ordered by file and line.
+    ///
+    /// This can be overlaid on the sources, e.g. as a gas heatmap in an editor. It is empty if the
+    /// analyzed traces do not carry source positions.
+    pub fn line_annotations(&self) -> Vec<LineGas> {
+        self.lines
+            .iter()
+            .flat_map(|(file, lines)| {
+                lines.iter().map(|(&line, &gas)| LineGas { file: file.clone(), line, gas })
+            })
+            .collect()
+    }
+
+    /// Removes the functions with fewer than `min_calls` recorded calls, and the contracts that
+    /// have no functions left afterwards.
+    ///
+    /// The statistics of a function that was called only once are trivially identical, so this
+    /// reduces the noise in the reports of large projects.
+    #[must_use]
+    pub fn filter_by_calls(mut self, min_calls: usize) -> Self {
+        self.contracts.retain(|_, contract| {
+            if !contract.has_calls() {
+                return true
+            }
+            contract.functions.retain(|_, sigs| {
+                sigs.retain(|_, info| info.call_count() >= min_calls);
+                !sigs.is_empty()
+            });
+            contract.upgrades.retain(|_, info| info.call_count() >= min_calls);
+            contract.unclassified.retain(|_, info| info.call_count() >= min_calls);
+            contract.has_calls()
+        });
+        self
+    }
+
+    /// Returns one row per function signature of every contract, or one per fork the function
+    /// was called on unless the fork contexts are merged, like the rows of the printed tables.
+    ///
+    /// The report is expected to be finalized.
+    pub fn rows(&self) -> Vec<GasReportRow> {
+        let mut rows = Vec::new();
+        for (contract, contract_info) in self.contracts.iter() {
+            for (signature, function) in contract_info.functions.values().flatten() {
+                let row = |context: Option<&String>, info: &GasInfo| GasReportRow {
+                    contract: contract.clone(),
+                    signature: signature.clone(),
+                    context: context.cloned(),
+                    calls: info.call_count(),
+                    min: info.min,
+                    mean: info.mean,
+                    median: info.stats.is_none().then(|| info.median),
+                    max: info.max,
+                };
+                if self.merge_fork_contexts || function.contexts.is_empty() {
+                    rows.push(row(None, function));
+                } else {
+                    rows.extend(
+                        function
+                            .contexts
+                            .iter()
+                            .map(|(context, stats)| row(Some(context), &GasInfo::from(*stats))),
+                    );
+                }
+            }
+        }
+        rows
+    }
+
+    /// Returns the JSON object the report serializes to, so that other fields, e.g. CI metadata
+    /// or links, can be inserted before it is written
+    pub fn to_json_map(&self) -> serde_json::Map<String, serde_json::Value> {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(map)) => map,
+            _ => unreachable!("gas reports serialize to JSON objects"),
+        }
+    }
+
+    /// Returns the [`GasReport::rows()`] as fixed-width columns with the [`FwfWidths::default()`]
+    pub fn to_fwf(&self) -> String {
+        self.to_fwf_with(&FwfWidths::default())
+    }
+
+    /// Returns the [`GasReport::rows()`] as lines of fixed-width columns without delimiters or a
+    /// header, for tools that cannot parse quoted formats.
+    ///
+    /// The columns are, in order: contract, function signature, fork context, number of calls,
+    /// min, mean, median and max gas. Every value is cut to the width of its column and padded
+    /// with spaces, text on the right and numbers on the left, so every line has the same
+    /// length. The context is empty if the calls of all forks are included and the median is `-`
+    /// in streaming mode.
+    pub fn to_fwf_with(&self, widths: &FwfWidths) -> String {
+        let mut fwf = String::new();
+        for row in self.rows() {
+            let median = row.median.map(|median| median.to_string()).unwrap_or_else(|| "-".into());
+            fwf.push_str(&fwf_left(&row.contract, widths.contract));
+            fwf.push_str(&fwf_left(&row.signature, widths.signature));
+            fwf.push_str(&fwf_left(row.context.as_deref().unwrap_or_default(), widths.context));
+            fwf.push_str(&fwf_right(&row.calls.to_string(), widths.calls));
+            for gas in [row.min.to_string(), row.mean.to_string(), median, row.max.to_string()] {
+                fwf.push_str(&fwf_right(&gas, widths.gas));
+            }
+            fwf.push('\n');
+        }
+        fwf
+    }
+
+    /// Compares the gas used by the function with the signature `sig`, e.g.
+    /// `transfer(address,uint256)`, of all contracts that implement it, such as the
+    /// implementations of an interface and their mocks.
+    ///
+    /// Contracts without calls of the function are omitted. The report is expected to be
+    /// finalized.
+    pub fn compare_signature(&self, sig: &str) -> ComparisonTable {
+        let name = sig.split('(').next().unwrap_or(sig);
+        let mut rows = self
+            .contracts
+            .iter()
+            .filter_map(|(contract, info)| {
+                let function = info.functions.get(name)?.get(sig)?;
+                Some(ComparisonRow {
+                    contract: contract.clone(),
+                    calls: function.call_count(),
+                    min: function.min,
+                    mean: function.mean,
+                    max: function.max,
+                })
+            })
+            .collect::<Vec<_>>();
+        rows.sort_by(|a, b| a.mean.cmp(&b.mean).then_with(|| a.contract.cmp(&b.contract)));
+        ComparisonTable { signature: sig.to_string(), rows }
+    }
+
+    /// Returns the average gas of every function of every contract by the kind of trace its calls
+    /// were recorded in, e.g. to tell how much more a function costs during the setup of a test
+    /// than in the test itself.
+    ///
+    /// The report is expected to be finalized.
+    pub fn pivot_by_kind(&self) -> PivotReport {
+        let order = |kind: &String| {
+            ["deployment", "setup", "execution"].iter().position(|known| *known == kind.as_str())
+        };
+        let mut kinds = self
+            .contracts
+            .values()
+            .flat_map(|contract| contract.functions.values().flatten())
+            .flat_map(|(_, function)| function.kinds.keys().chain(&function.kind).cloned())
+            .collect::<Vec<_>>();
+        kinds.sort_by(|a, b| order(a).cmp(&order(b)).then_with(|| a.cmp(b)));
+        kinds.dedup();
+
+        let mut rows = Vec::new();
+        for (contract, contract_info) in self.contracts.iter() {
+            for (signature, function) in contract_info.functions.values().flatten() {
+                rows.push(PivotRow {
+                    contract: contract.clone(),
+                    signature: signature.clone(),
+                    calls: function.call_count(),
+                    mean: function.mean,
+                    by_kind: kinds
+                        .iter()
+                        .map(|kind| match function.kinds.get(kind) {
+                            Some(stats) => Some(U256::from(stats.mean as u128)),
+                            None => (function.kind.as_ref() == Some(kind)).then(|| function.mean),
+                        })
+                        .collect(),
+                });
+            }
+        }
+        PivotReport { kinds, rows }
+    }
+
+    /// Compares the finalized `reports` of the same contracts built with different compiler
+    /// settings, e.g. optimizer runs, labeled by the name of their profile.
+    ///
+    /// The deployment cost and the average gas of every function are compared side by side, to
+    /// weigh cheaper calls against more expensive deployments. Functions that were not called in
+    /// all profiles can not be compared and are only listed.
+    pub fn compare_profiles(reports: &[(String, GasReport)]) -> ProfileComparison {
+        let profiles = reports.iter().map(|(name, _)| name.clone()).collect();
+
+        let mut deployments = Vec::new();
+        let mut functions = Vec::new();
+        let mut partial = BTreeSet::new();
+        let contracts = reports
+            .iter()
+            .flat_map(|(_, report)| report.contracts.keys())
+            .collect::<BTreeSet<_>>();
+        for contract in contracts {
+            let infos = reports
+                .iter()
+                .map(|(_, report)| report.contracts.get(contract))
+                .collect::<Vec<_>>();
+            if infos.iter().all(|info| info.map_or(false, |info| !info.gas.is_zero())) {
+                deployments.push(ProfileRow {
+                    contract: contract.clone(),
+                    signature: None,
+                    gas: infos.iter().flatten().map(|info| info.gas).collect(),
+                });
+            }
+
+            let signatures = infos
+                .iter()
+                .flatten()
+                .flat_map(|info| info.functions.values().flat_map(|sigs| sigs.keys()))
+                .collect::<BTreeSet<_>>();
+            for signature in signatures {
+                let name = signature.split('(').next().unwrap_or(signature);
+                let gas = infos
+                    .iter()
+                    .map(|info| Some(info?.functions.get(name)?.get(signature)?.mean))
+                    .collect::<Option<Vec<_>>>();
+                match gas {
+                    Some(gas) => functions.push(ProfileRow {
+                        contract: contract.clone(),
+                        signature: Some(signature.clone()),
+                        gas,
+                    }),
+                    None => {
+                        partial.insert(format!("{contract}.{signature}"));
+                    }
+                }
+            }
+        }
+        let partial = partial.into_iter().collect();
+        ProfileComparison { profiles, deployments, functions, partial }
+    }
+
+    /// Renders the functions of all contracts grouped into tiers by their mean gas.
+    ///
+    /// The `thresholds` separate the tiers, a function whose mean is at least a threshold belongs
+    /// to a tier above it, e.g. `[50_000, 200_000]` results in a cheap, a moderate and an
+    /// expensive tier. Every non-empty tier is rendered as a labeled table of its functions, the
+    /// most expensive first. Deployments are not tiered.
+    ///
+    /// The report is expected to be finalized.
+    pub fn render_tiered(&self, thresholds: &[U256]) -> String {
+        let mut thresholds = thresholds.to_vec();
+        thresholds.sort();
+        thresholds.dedup();
+
+        let mut tiers = vec![Vec::new(); thresholds.len() + 1];
+        for row in self.rows() {
+            let tier = thresholds.iter().filter(|threshold| row.mean >= **threshold).count();
+            tiers[tier].push(row);
+        }
+
+        let mut out = String::new();
+        for (idx, mut rows) in tiers.into_iter().enumerate() {
+            if rows.is_empty() {
+                continue
+            }
+            rows.sort_by(|a, b| b.mean.cmp(&a.mean).then_with(|| a.contract.cmp(&b.contract)));
+
+            let name = match (thresholds.len(), idx) {
+                (2, 0) => "Cheap".to_string(),
+                (2, 1) => "Moderate".to_string(),
+                (2, 2) => "Expensive".to_string(),
+                _ => format!("Tier {}", idx + 1),
+            };
+            let lower = idx.checked_sub(1).map(|idx| thresholds[idx]);
+            let range = match (lower, thresholds.get(idx)) {
+                (None, Some(upper)) => format!("< {upper} gas"),
+                (Some(lower), Some(upper)) => format!("{lower} - {upper} gas"),
+                (Some(lower), None) => format!(">= {lower} gas"),
+                (None, None) => "all functions".to_string(),
+            };
+
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
+            table.set_header(vec![
+                Cell::new(format!("{name} ({range})")).add_attribute(Attribute::Bold),
+                Cell::new("Function Name").add_attribute(Attribute::Bold).fg(Color::Magenta),
+                Cell::new("avg").add_attribute(Attribute::Bold).fg(Color::Yellow),
+                Cell::new("# calls").add_attribute(Attribute::Bold),
+            ]);
+            for row in rows {
+                let signature = match &row.context {
+                    Some(context) => format!("{} [{context}]", row.signature),
+                    None => row.signature,
+                };
+                table.add_row(vec![
+                    Cell::new(row.contract).add_attribute(Attribute::Bold),
+                    Cell::new(signature),
+                    Cell::new(row.mean.to_string()).fg(Color::Yellow),
+                    Cell::new(row.calls.to_string()),
+                ]);
+            }
+            out.push_str(&format!("{table}\n"));
+        }
+        out
+    }
+
+    /// Computes the statistics of all recorded calls.
+    ///
+    /// The unclassified calls whose selector is one of the known [`GasReport::signatures`] are
+    /// reported under that signature, so reports stay stable across runs with the same signatures.
+    #[must_use]
+    pub fn finalize(mut self) -> Self {
+        let sampling = self.sampling();
+        let signatures = &self.signatures;
+        self.contracts.iter_mut().for_each(|(_, contract)| {
+            let unclassified = std::mem::take(&mut contract.unclassified);
+            for (selector, info) in unclassified {
+                match signatures.get(&selector) {
+                    Some(sig) => {
+                        let name = sig.split('(').next().unwrap_or(sig).to_string();
+                        contract
+                            .functions
+                            .entry(name)
+                            .or_default()
+                            .entry(sig.clone())
+                            .or_default()
+                            .merge(info, sampling);
+                    }
+                    None => {
+                        contract.unclassified.insert(selector, info);
+                    }
+                }
+            }
+            contract.unclassified.values_mut().for_each(GasInfo::finalize);
+
+            let deployment = contract.gas;
+            contract.functions.iter_mut().for_each(|(_, sigs)| {
+                sigs.iter_mut().for_each(|(_, func)| {
+                    func.finalize();
+                    func.exceeds_deployment = !deployment.is_zero() && func.max > deployment;
+                });
+            });
+            contract.upgrades.iter_mut().for_each(|(_, upgrade)| upgrade.finalize());
+        });
+        if self.relative_coloring {
+            self.contracts.values_mut().for_each(set_relative_costs);
+        }
+        self
+    }
+}
+
+/// The change of the average gas used by a function between two reports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionGasDiff {
+    /// The contract identifier, `<source>:<name>`
+    pub contract: String,
+    pub name: String,
+    pub signature: String,
+    /// The average gas in the baseline report
+    pub before: U256,
+    /// The average gas in the current report
+    pub after: U256,
+}
+
+impl FunctionGasDiff {
+    /// Returns the change relative to the baseline in percent, a function that used no gas before
+    /// counts as a 100% change
+    pub fn pct_change(&self) -> f64 {
+        let before = self.before.as_u128() as f64;
+        let after = self.after.as_u128() as f64;
+        if before == 0.0 {
+            return if after == 0.0 { 0.0 } else { 100.0 }
+        }
+        (after - before) / before * 100.0
+    }
+
+    /// Returns the function as `<contract name>.<function name>`
+    pub fn display_name(&self) -> String {
+        let contract = self.contract.rsplit(':').next().unwrap_or(&self.contract);
+        format!("{contract}.{}", self.name)
+    }
+}
+
+/// The change of the deployed bytecode size of a contract between two reports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeploymentSizeDiff {
+    /// The contract identifier, `<source>:<name>`
+    pub contract: String,
+    pub before: U256,
+    pub after: U256,
+}
+
+/// The changes of the average gas of all functions that a report has in common with a baseline
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GasReportDiff {
+    pub functions: Vec<FunctionGasDiff>,
+    /// The deployed sizes of the contracts that both reports recorded, baselines of earlier
+    /// versions only recorded the init code size and are not compared
+    pub deployments: Vec<DeploymentSizeDiff>,
+    /// The fork blocks of the baseline and the current report if they differ, the gas of the
+    /// functions is not comparable then
+    pub fork_block_mismatch: Option<(Option<u64>, Option<u64>)>,
+}
+
+impl GasReportDiff {
+    /// Compares the functions of the `current` report with the ones of the `baseline`, both
+    /// reports are expected to be finalized
+    pub fn new(baseline: &GasReport, current: &GasReport) -> Self {
+        let mut functions = Vec::new();
+        let mut deployments = Vec::new();
+        for (contract, info) in current.contracts.iter() {
+            let baseline = match baseline.contracts.get(contract) {
+                Some(baseline) => baseline,
+                None => continue,
+            };
+            if !baseline.deployed_size.is_zero() && !info.deployed_size.is_zero() {
+                deployments.push(DeploymentSizeDiff {
+                    contract: contract.clone(),
+                    before: baseline.deployed_size,
+                    after: info.deployed_size,
+                });
+            }
+            for (name, sigs) in info.functions.iter() {
+                let baseline_sigs = match baseline.functions.get(name) {
+                    Some(baseline_sigs) => baseline_sigs,
+                    None => continue,
+                };
+                for (sig, function) in sigs.iter() {
+                    if let Some(before) = baseline_sigs.get(sig) {
+                        functions.push(FunctionGasDiff {
+                            contract: contract.clone(),
+                            name: name.clone(),
+                            signature: sig.clone(),
+                            before: before.mean,
+                            after: function.mean,
+                        });
+                    }
+                }
+            }
+        }
+        let fork_block_mismatch = if baseline.fork_block == current.fork_block {
+            None
+        } else {
+            Some((baseline.fork_block, current.fork_block))
+        };
+        Self { functions, deployments, fork_block_mismatch }
+    }
+
+    /// Compares the reports like [`GasReportDiff::new()`], but fails if they were compiled with
+    /// different solc versions or optimizer settings, unless `force` is set.
+    ///
+    /// The gas of such reports differs because of the compiler, not because of the code.
+    pub fn try_new(baseline: &GasReport, current: &GasReport, force: bool) -> eyre::Result<Self> {
+        if !force {
+            if let Some(mismatch) = baseline.header.compiler_mismatch(&current.header) {
+                eyre::bail!(
+                    "The reports were compiled with different settings ({mismatch}), pass \
+                     `--force` to compare them anyway"
+                )
+            }
+        }
+        Ok(Self::new(baseline, current))
+    }
+
+    /// Returns a warning if the reports were taken at different fork blocks
+    pub fn fork_block_warning(&self) -> Option<String> {
+        let (baseline, current) = self.fork_block_mismatch?;
+        let block = |block: Option<u64>| match block {
+            Some(block) => format!("fork block {block}"),
+            None => "no fork".to_string(),
+        };
+        Some(format!(
+            "the baseline has {} but the current report has {}, the gas is not comparable",
+            block(baseline),
+            block(current)
+        ))
+    }
+
+    /// Returns all functions that use more gas than before
+    pub fn regressions(&self) -> impl Iterator<Item = &FunctionGasDiff> {
+        self.functions.iter().filter(|function| function.after > function.before)
+    }
+
+    /// Returns all functions that use less gas than before
+    pub fn improvements(&self) -> impl Iterator<Item = &FunctionGasDiff> {
+        self.functions.iter().filter(|function| function.after < function.before)
+    }
+
+    /// Returns a one-line summary that fits into a CI status, e.g.
+    /// `gas: 3 ↑ (max +4.2% Foo.bar), 5 ↓ (max -1.3% Foo.baz), 392 unchanged`.
+    ///
+    /// Only the worst regression and the biggest improvement are named.
+    pub fn to_status_line(&self) -> String {
+        let summarize = |functions: Vec<&FunctionGasDiff>, arrow: &str| {
+            let extreme = functions.iter().max_by(|a, b| {
+                a.pct_change().abs().partial_cmp(&b.pct_change().abs()).unwrap_or(Ordering::Equal)
+            });
+            match extreme {
+                Some(extreme) => format!(
+                    "{} {arrow} (max {:+.1}% {})",
+                    functions.len(),
+                    extreme.pct_change(),
+                    extreme.display_name()
+                ),
+                None => format!("0 {arrow}"),
+            }
+        };
+        let unchanged =
+            self.functions.iter().filter(|function| function.after == function.before).count();
+        let status = format!(
+            "gas: {}, {}, {unchanged} unchanged",
+            summarize(self.regressions().collect(), "↑"),
+            summarize(self.improvements().collect(), "↓")
+        );
+        match self.fork_block_warning() {
+            Some(warning) => format!("WARNING: {warning}; {status}"),
+            None => status,
+        }
+    }
+}
+
+#[cfg(feature = "otel")]
+impl GasReport {
+    /// Records the deployment cost of every contract and the mean and max gas of every function as
+    /// observable gauges of the given `meter`, attributed by contract and function signature.
+    ///
+    /// The report should be finalized before it is exported.
+    pub fn export_otel(
+        &self,
+        meter: &opentelemetry::metrics::Meter,
+    ) -> opentelemetry::metrics::Result<()> {
+        use opentelemetry::KeyValue;
+
+        let mut deployments = Vec::new();
+        let mut means = Vec::new();
+        let mut maxes = Vec::new();
+        for (name, contract) in self.contracts.iter() {
+            if !contract.gas.is_zero() || !contract.etched {
+                deployments
+                    .push((contract.gas.as_u64(), vec![KeyValue::new("contract", name.clone())]));
+            }
+            for (sig, function) in contract.functions.values().flatten() {
+                let attributes = vec![
+                    KeyValue::new("contract", name.clone()),
+                    KeyValue::new("signature", sig.clone()),
+                ];
+                means.push((function.mean.as_u64(), attributes.clone()));
+                maxes.push((function.max.as_u64(), attributes));
+            }
+        }
+
+        let deployment_gauge = meter
+            .u64_observable_gauge("forge.gas.deployment")
+            .with_description("Deployment cost of the contract")
+            .init();
+        let mean_gauge = meter
+            .u64_observable_gauge("forge.gas.mean")
+            .with_description("Mean gas used by calls to the function")
+            .init();
+        let max_gauge = meter
+            .u64_observable_gauge("forge.gas.max")
+            .with_description("Maximum gas used by a call to the function")
+            .init();
+
+        meter.register_callback(move |cx| {
+            for (value, attributes) in deployments.iter() {
+                deployment_gauge.observe(cx, *value, attributes);
+            }
+            for (value, attributes) in means.iter() {
+                mean_gauge.observe(cx, *value, attributes);
+            }
+            for (value, attributes) in maxes.iter() {
+                max_gauge.observe(cx, *value, attributes);
+            }
+        })
+    }
+}
+
+impl Display for GasReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        let mut metadata =
+            self.metadata.iter().map(|(key, value)| format!("{key}={value}")).collect::<Vec<_>>();
+        if let Some(fork_block) = self.fork_block {
+            metadata.push(format!("fork_block={fork_block}"));
+        }
+        if !metadata.is_empty() {
+            writeln!(f, "# {}", metadata.join(" "))?;
+        }
+        for (name, contract) in self.contracts.iter() {
+            if !contract.has_calls() {
+                continue
+            }
+
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
+            let mut header = vec![
+                Cell::new(format!("{name} contract"))
+                    .add_attribute(Attribute::Bold)
+                    .fg(Color::Green),
+                Cell::new(format!("stability {:.2}", contract.stability_score())),
+            ];
+            let p95 = contract.aggregate_percentile(95.0);
+            if !p95.is_zero() {
+                header.push(Cell::new(format!("p95 of all calls {p95}")));
+            }
+            table.set_header(header);
+            table.add_row(vec![
+                Cell::new("Deployment Cost").add_attribute(Attribute::Bold).fg(Color::Cyan),
+                Cell::new("Deployment Size (deployed/init)")
+                    .add_attribute(Attribute::Bold)
+                    .fg(Color::Cyan),
+            ]);
+            if contract.etched && contract.gas.is_zero() {
+                table.add_row(vec![Cell::new("etched").add_attribute(Attribute::Dim)]);
+            } else {
+                table.add_row(vec![
+                    contract.gas.to_string(),
+                    format!("{}/{}", contract.deployed_size, contract.init_size),
+                ]);
+            }
+
+            // abbreviate function names that would not fit into the terminal
+            let name_width =
+                table.width().map(|width| name_column_width(contract, width as usize));
+            let abbreviate = |sig: &str| match name_width {
+                Some(max) => abbreviate_signature(sig, max),
+                None => sig.to_string(),
+            };
+
+            table.add_row(header_row("Function Name", self.memory_gas_column));
+            // one row per fork context, unless they are merged
+            let add_rows = |table: &mut Table, name: String, info: &GasInfo| {
+                if self.merge_fork_contexts || info.contexts.is_empty() {
+                    table.add_row(stats_row(
+                        name,
+                        info,
+                        self.relative_coloring,
+                        self.memory_gas_column,
+                    ));
+                    // the calls that succeeded and reverted below, if some reverted
+                    for (outcome, stats) in info.outcomes.iter() {
+                        let name = format!("  {outcome}");
+                        let info = GasInfo::from(*stats);
+                        table.add_row(stats_row(name, &info, false, self.memory_gas_column));
+                    }
+                    if self.gas_categories {
+                        if let Some(row) = gas_categories_row(info) {
+                            table.add_row(row);
+                        }
+                    }
+                } else {
+                    for (context, stats) in info.contexts.iter() {
+                        let name = format!("{name} [{context}]");
+                        table.add_row(stats_row(
+                            name,
+                            &GasInfo::from(*stats),
+                            self.relative_coloring,
+                            self.memory_gas_column,
+                        ));
+                    }
+                }
+            };
+            contract.functions.iter().for_each(|(fname, sigs)| {
+                sigs.iter().for_each(|(sig, function)| {
+                    // show function signature if overloaded else name
+                    let fn_display =
+                        if sigs.len() == 1 { fname.clone() } else { sig.replace(':', "") };
+                    add_rows(&mut table, abbreviate(&fn_display), function);
+                })
+            });
+
+            if !contract.upgrades.is_empty() {
+                table.add_row(header_row("Upgrade", self.memory_gas_column));
+                contract.upgrades.iter().for_each(|(sig, upgrade)| {
+                    add_rows(&mut table, abbreviate(sig), upgrade);
+                });
+            }
+
+            if !contract.unclassified.is_empty() {
+                table.add_row(header_row("Unclassified", self.memory_gas_column));
+                contract.unclassified.iter().for_each(|(selector, info)| {
+                    add_rows(&mut table, selector.clone(), info);
+                });
+            }
+            writeln!(f, "{}", table)?;
+
+            let exceeding = contract.functions_exceeding_deploy();
+            if !exceeding.is_empty() {
+                writeln!(
+                    f,
+                    "Warning: {} of {name} cost more gas than its deployment: {}",
+                    if exceeding.len() > 1 { "functions" } else { "function" },
+                    exceeding.join(", ")
+                )?;
+            }
+
+            if !contract.implementations.is_empty() {
+                writeln!(f, "Implementations deployed for the upgrades of {name}:")?;
+                for (address, gas) in &contract.implementations {
+                    writeln!(f, "  {address:?}: {gas} gas")?;
+                }
+            }
+
+            let griefing_vectors = contract.griefing_vectors();
+            if !griefing_vectors.is_empty() {
+                writeln!(
+                    f,
+                    "Warning: {} of {name} forward all remaining gas to unknown addresses, which \
+                     can grief them: {}",
+                    if griefing_vectors.len() > 1 { "functions" } else { "function" },
+                    griefing_vectors.join(", ")
+                )?;
+            }
+
+            let precompile_notes = contract
+                .functions
+                .values()
+                .flat_map(|sigs| sigs.iter())
+                .filter_map(|(sig, function)| Some((sig, function, function.precompile_floor?)))
+                .map(|(sig, function, floor)| {
+                    let overhead = function.mean.saturating_sub(floor);
+                    format!(
+                        "  {sig}: precompile floor: {floor}, observed: {} (+{overhead})",
+                        function.mean
+                    )
+                })
+                .collect::<Vec<_>>();
+            if !precompile_notes.is_empty() {
+                writeln!(f, "Precompile gas of {name}:")?;
+                for note in precompile_notes {
+                    writeln!(f, "{note}")?;
+                }
+            }
+
+            let prewarmed = contract
+                .functions
+                .values()
+                .flat_map(|sigs| sigs.iter())
+                .filter(|(_, function)| !function.access_list_savings.is_zero())
+                .map(|(sig, function)| {
+                    let calls = U256::from(function.call_count().max(1));
+                    format!("  {sig}: {} gas per call", function.access_list_savings / calls)
+                })
+                .collect::<Vec<_>>();
+            if !prewarmed.is_empty() {
+                writeln!(f, "Access list prewarming saved {name}:")?;
+                for note in prewarmed {
+                    writeln!(f, "{note}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for ComparisonTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
+        table.set_header(vec![
+            Cell::new(format!("{} comparison", self.signature))
+                .add_attribute(Attribute::Bold)
+                .fg(Color::Green),
+            Cell::new("min").add_attribute(Attribute::Bold).fg(Color::Green),
+            Cell::new("avg").add_attribute(Attribute::Bold).fg(Color::Yellow),
+            Cell::new("max").add_attribute(Attribute::Bold).fg(Color::Red),
+            Cell::new("# calls").add_attribute(Attribute::Bold),
+        ]);
+        for row in self.rows.iter() {
+            table.add_row(vec![
+                Cell::new(&row.contract).add_attribute(Attribute::Bold),
+                Cell::new(row.min.to_string()).fg(Color::Green),
+                Cell::new(row.mean.to_string()).fg(Color::Yellow),
+                Cell::new(row.max.to_string()).fg(Color::Red),
+                Cell::new(row.calls.to_string()),
+            ]);
+        }
+        write!(f, "{table}")
+    }
+}
+
+impl Display for PivotReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
+        let mut header = vec![
+            Cell::new("Contract").add_attribute(Attribute::Bold).fg(Color::Green),
+            Cell::new("Function Name").add_attribute(Attribute::Bold).fg(Color::Magenta),
+        ];
+        // a single kind has nothing to compare against, so only the average is shown
+        let by_kind = self.kinds.len() > 1;
+        if by_kind {
+            header.extend(self.kinds.iter().map(|kind| {
+                Cell::new(format!("avg ({kind})")).add_attribute(Attribute::Bold).fg(Color::Yellow)
+            }));
+        } else {
+            header.push(Cell::new("avg").add_attribute(Attribute::Bold).fg(Color::Yellow));
+        }
+        header.push(Cell::new("# calls").add_attribute(Attribute::Bold));
+        table.set_header(header);
+
+        for row in self.rows.iter() {
+            let mut cells = vec![
+                Cell::new(&row.contract),
+                Cell::new(&row.signature).add_attribute(Attribute::Bold),
+            ];
+            if by_kind {
+                cells.extend(row.by_kind.iter().map(|mean| match mean {
+                    Some(mean) => Cell::new(mean.to_string()).fg(Color::Yellow),
+                    None => Cell::new("-"),
+                }));
+            } else {
+                cells.push(Cell::new(row.mean.to_string()).fg(Color::Yellow));
+            }
+            cells.push(Cell::new(row.calls.to_string()));
+            table.add_row(cells);
+        }
+        write!(f, "{table}")
+    }
+}
+
+impl Display for ProfileComparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
+        let mut header = vec![
+            Cell::new("Contract").add_attribute(Attribute::Bold).fg(Color::Green),
+            Cell::new("Function Name").add_attribute(Attribute::Bold).fg(Color::Magenta),
+        ];
+        header.extend(
+            self.profiles
+                .iter()
+                .map(|profile| Cell::new(profile).add_attribute(Attribute::Bold).fg(Color::Yellow)),
+        );
+        table.set_header(header);
+
+        for row in self.deployments.iter().chain(self.functions.iter()) {
+            let mut cells = vec![
+                Cell::new(&row.contract),
+                match &row.signature {
+                    Some(signature) => Cell::new(signature).add_attribute(Attribute::Bold),
+                    None => {
+                        Cell::new("Deployment Cost").add_attribute(Attribute::Bold).fg(Color::Cyan)
+                    }
+                },
+            ];
+            // the cheapest profile of every row is highlighted
+            let min = row.gas.iter().min();
+            cells.extend(row.gas.iter().map(|gas| {
+                let cell = Cell::new(gas.to_string());
+                if Some(gas) == min {
+                    cell.fg(Color::Green)
+                } else {
+                    cell
+                }
+            }));
+            table.add_row(cells);
+        }
+        write!(f, "{table}")?;
+        if !self.partial.is_empty() {
+            write!(f, "\nNot called in all profiles: {}", self.partial.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Sets the [`GasInfo::relative_cost`] of all called functions of the finalized `contract`,
+/// contracts with a single called function have nothing to compare against
+fn set_relative_costs(contract: &mut ContractInfo) {
+    let mut means = contract
+        .functions
+        .values()
+        .flat_map(|sigs| sigs.values())
+        .filter(|function| function.call_count() > 0)
+        .map(|function| function.mean)
+        .collect::<Vec<_>>();
+    if means.len() < 2 {
+        return
+    }
+    means.sort_unstable();
+    let median = Stats::from_sorted(&means).median;
+    if median.is_zero() {
+        return
+    }
+    let median = median.as_u128() as f64;
+    contract
+        .functions
+        .values_mut()
+        .flat_map(|sigs| sigs.values_mut())
+        .filter(|function| function.call_count() > 0)
+        .for_each(|function| {
+            function.relative_cost = Some(function.mean.as_u128() as f64 / median);
+        });
+}
+
+/// Returns the color of an average gas that is `relative_cost` times the median of its contract,
+/// a gradient from yellow at the median to red at four times the median, or green if it is at
+/// most the median
+fn relative_cost_color(relative_cost: f64) -> Color {
+    if relative_cost <= 1.0 {
+        return Color::Green
+    }
+    let redness = (relative_cost.log2() / 2.0).min(1.0);
+    Color::Rgb { r: 255, g: (255.0 * (1.0 - redness)) as u8, b: 0 }
+}
+
+/// Returns the row of the statistics of the function or upgrade `name`, with the average colored
+/// by its [`GasInfo::relative_cost`] if `relative_coloring` is set
+fn stats_row(
+    name: String,
+    info: &GasInfo,
+    relative_coloring: bool,
+    memory_gas_column: bool,
+) -> Vec<Cell> {
+    let name = Cell::new(name).add_attribute(Attribute::Bold);
+    let mean = Cell::new(info.mean.to_string());
+    let mean = match (relative_coloring, info.relative_cost) {
+        (false, _) => mean.fg(Color::Yellow),
+        (true, Some(relative_cost)) => mean.fg(relative_cost_color(relative_cost)),
+        // nothing to compare against
+        (true, None) => mean,
+    };
+    let mut row = vec![
+        if info.exceeds_deployment { name.fg(Color::Red) } else { name },
+        Cell::new(info.min.to_string()).fg(Color::Green),
+        mean,
+        Cell::new(info.median_cell()).fg(Color::Yellow),
+        Cell::new(info.max.to_string()).fg(Color::Red),
+        Cell::new(info.call_count().to_string()),
+    ];
+    if memory_gas_column {
+        let calls = U256::from(info.call_count().max(1));
+        row.push(Cell::new((info.memory_gas / calls).to_string()));
+    }
+    row
+}
+
+/// Returns the header row of a section of a contract table whose first column is `label`
+fn header_row(label: &str, memory_gas_column: bool) -> Vec<Cell> {
+    let mut row = vec![
+        Cell::new(label).add_attribute(Attribute::Bold).fg(Color::Magenta),
+        Cell::new("min").add_attribute(Attribute::Bold).fg(Color::Green),
+        Cell::new("avg").add_attribute(Attribute::Bold).fg(Color::Yellow),
+        Cell::new("median").add_attribute(Attribute::Bold).fg(Color::Yellow),
+        Cell::new("max").add_attribute(Attribute::Bold).fg(Color::Red),
+        Cell::new("# calls").add_attribute(Attribute::Bold),
+    ];
+    if memory_gas_column {
+        row.push(Cell::new("memory_gas").add_attribute(Attribute::Bold));
+    }
+    row
+}
+
+/// Returns the row below the statistics of a function with the average gas per call of every
+/// non-empty category, `None` if the calls were traced without their instructions
+fn gas_categories_row(info: &GasInfo) -> Option<Vec<Cell>> {
+    if info.gas_categories.is_zero() {
+        return None
+    }
+    let calls = info.call_count().max(1) as u64;
+    let breakdown = info
+        .gas_categories
+        .named()
+        .iter()
+        .filter(|(_, gas)| *gas > 0)
+        .map(|(category, gas)| format!("  {category}: {}", gas / calls))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(vec![Cell::new(breakdown).add_attribute(Attribute::Dim)])
+}
+
+/// Returns the address whose code the cheatcode call `trace` replaced with `vm.etch`, if it is one
+fn etched_address(trace: &CallTrace) -> Option<Address> {
+    match &trace.data {
+        RawOrDecodedCall::Raw(bytes) => match HEVMCalls::decode(bytes).ok()? {
+            HEVMCalls::Etch(inner) => Some(inner.0),
+            _ => None,
+        },
+        RawOrDecodedCall::Decoded(_, sig, args) if sig == "etch(address,bytes)" => {
+            // the address is displayed either by itself or labeled as `label: [address]`
+            let arg = args.first()?;
+            let start = arg.rfind("0x")? + 2;
+            arg.get(start..start + 40)?.parse().ok()
+        }
+        _ => None,
+    }
+}
+
+/// Scrambles the bits of `value` (SplitMix64), used as a reproducible source of randomness
+fn mix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Returns the summed gas floors of all standard precompiles called from within the node, see
+/// [`crate::trace::precompiles::gas_floor()`]
+fn precompile_floor(arena: &CallTraceArena, node_index: usize) -> u64 {
+    arena.arena[node_index]
+        .children
+        .iter()
+        .map(|child| {
+            let trace = &arena.arena[*child].trace;
+            trace.precompile_gas_floor.unwrap_or_else(|| precompile_floor(arena, *child))
+        })
+        .sum()
+}
+
+/// Returns the address of the implementation that the call of the upgrade function `sig` with the
+/// decoded `args` switches to, the arguments are addresses that may be labeled like
+/// `VaultV2: [0x..]`
+fn upgrade_implementation(sig: &str, args: &[String]) -> Option<Address> {
+    // the upgrade functions of a `ProxyAdmin` take the proxy first
+    let index =
+        if sig.starts_with("upgrade(") || sig.starts_with("upgradeAndCall(") { 1 } else { 0 };
+    let arg = args.get(index)?;
+    let start = arg.rfind("0x")? + 2;
+    arg.get(start..start + 40)?.parse().ok()
+}
+
+/// Returns the gas saved by access list prewarming within the node, including its subcalls
+fn access_list_savings(arena: &CallTraceArena, node_index: usize) -> u64 {
+    let node = &arena.arena[node_index];
+    node.trace.access_list_savings +
+        node.children.iter().map(|child| access_list_savings(arena, *child)).sum::<u64>()
+}
+
+/// Returns the gas categories of the call with `other` covering the part of its gas that no
+/// instruction spent, zero if its instructions were not traced
+fn gas_categories(trace: &CallTrace) -> GasCategories {
+    if trace.gas_categories.is_zero() {
+        return GasCategories::default()
+    }
+    trace.gas_categories.with_total(trace.gas_cost)
+}
+
+/// Returns `keep` of the sampled `calls`, evenly spaced so that they stay representative if the
+/// calls are sorted
+fn spread_samples(calls: &[U256], keep: usize) -> Vec<U256> {
+    (0..keep).map(|i| calls[i * calls.len() / keep]).collect()
+}
+
+/// Returns the gas spent on memory expansion within the node, including its subcalls
+fn memory_gas(arena: &CallTraceArena, node_index: usize) -> u64 {
+    let node = &arena.arena[node_index];
+    node.trace.memory_gas +
+        node.children.iter().map(|child| memory_gas(arena, *child)).sum::<u64>()
+}
+
+/// Returns whether the node made a call that was forwarded all of its remaining gas to an address
+/// that is neither a known contract nor a precompile, cheatcode or console address
+fn forwards_all_gas_to_unknown(arena: &CallTraceArena, node_index: usize) -> bool {
+    arena.arena[node_index].children.iter().any(|child| {
+        let trace = &arena.arena[*child].trace;
+        trace.forwards_all_gas == Some(true) &&
+            trace.contract.is_none() &&
+            trace.precompile_gas_floor.is_none() &&
+            trace.address != CHEATCODE_ADDRESS &&
+            trace.address != HARDHAT_CONSOLE_ADDRESS
+    })
+}
+
+/// Cuts the `value` to `width` characters and pads it with spaces on the right
+fn fwf_left(value: &str, width: usize) -> String {
+    let value = value.chars().take(width).collect::<String>();
+    format!("{value:<width$}")
+}
+
+/// Cuts the `value` to `width` characters and pads it with spaces on the left
+fn fwf_right(value: &str, width: usize) -> String {
+    let value = value.chars().take(width).collect::<String>();
+    format!("{value:>width$}")
+}
+
+/// Returns how many characters of a table with the given total `width` are left for the function
+/// names of the `contract`, next to the statistics columns
+fn name_column_width(contract: &ContractInfo, width: usize) -> usize {
+    let mut columns = ["min".len(), "avg".len(), "median".len(), "max".len(), "# calls".len()];
+    let infos = contract
+        .functions
+        .values()
+        .flat_map(|sigs| sigs.values())
+        .chain(contract.upgrades.values())
+        .chain(contract.unclassified.values())
+        .collect::<Vec<_>>();
+    let contexts = infos
+        .iter()
+        .flat_map(|info| info.contexts.values())
+        .map(|stats| GasInfo::from(*stats))
+        .collect::<Vec<_>>();
+    for info in infos.into_iter().chain(&contexts) {
+        let values = [
+            info.min.to_string().len(),
+            info.mean.to_string().len(),
+            info.median_cell().len(),
+            info.max.to_string().len(),
+            info.call_count().to_string().len(),
+        ];
+        for (column, value) in columns.iter_mut().zip(values) {
+            *column = (*column).max(value);
+        }
+    }
+    // every column is padded with a space on both sides and followed by a border
+    let stats: usize = columns.iter().map(|column| column + 3).sum();
+    width.saturating_sub(stats + 4)
+}
+
+/// Abbreviates the function signature `sig` to at most `max` characters, keeping the function name
+/// readable for as long as possible.
+///
+/// The parameter types are shortened first, `transfer(address,uint256)` becomes
+/// `transfer(addr,uint)`, then they are replaced by their number, `transfer(…2 args)`, and only
+/// if that is still too long the name itself is cut off.
+pub fn abbreviate_signature(sig: &str, max: usize) -> String {
+    let fits = |s: &str| s.chars().count() <= max;
+    if fits(sig) {
+        return sig.to_string()
+    }
+
+    let (name, params) = match sig.split_once('(') {
+        Some((name, params)) => (name, params.strip_suffix(')').unwrap_or(params)),
+        None => (sig, ""),
+    };
+
+    if !params.is_empty() {
+        let short = format!("{name}({})", abbreviate_param_types(params));
+        if fits(&short) {
+            return short
+        }
+
+        let args = count_params(params);
+        let counted = format!("{name}(…{args} {})", if args == 1 { "arg" } else { "args" });
+        if fits(&counted) {
+            return counted
+        }
+    }
+
+    if fits(name) {
+        return name.to_string()
+    }
+    match max {
+        0 => String::new(),
+        max => format!("{}…", name.chars().take(max - 1).collect::<String>()),
+    }
+}
+
+/// Replaces the common elementary types in a parameter list with their short forms
+fn abbreviate_param_types(params: &str) -> String {
+    let abbreviate = |ty: &str| match ty {
+        "address" => "addr",
+        "uint256" => "uint",
+        "int256" => "int",
+        "string" => "str",
+        ty => ty,
+    }
+    .to_string();
+
+    let mut out = String::with_capacity(params.len());
+    let mut rest = params;
+    while let Some((pos, separator)) = rest.char_indices().find(|(_, c)| !c.is_alphanumeric()) {
+        out.push_str(&abbreviate(&rest[..pos]));
+        out.push(separator);
+        rest = &rest[pos + separator.len_utf8()..];
+    }
+    out.push_str(&abbreviate(rest));
+    out
+}
+
+/// Returns the number of top level parameters in a parameter list
+fn count_params(params: &str) -> usize {
+    let mut depth = 0usize;
+    let mut count = 1;
+    for c in params.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => count += 1,
+            _ => {}
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        executor::abi::EtchCall,
+        trace::{
+            node::CallTraceNode, CallTrace, ForkContext, RawOrDecodedReturnData, SourceLineGas,
+        },
+        CallKind,
+    };
+    use ethers::abi::AbiEncode;
+
+    fn call(contract: &str, func: &str, depth: usize, gas_cost: u64) -> CallTrace {
+        CallTrace {
+            depth,
+            success: true,
+            contract: Some(format!("src/{contract}.sol:{contract}")),
+            data: RawOrDecodedCall::Decoded(func.to_string(), format!("{func}()"), vec![]),
+            gas_cost,
+            ..Default::default()
+        }
+    }
+
+    /// Builds an arena in which every trace is the child of the previous one
+    fn nested_arena(traces: Vec<CallTrace>) -> CallTraceArena {
+        let len = traces.len();
+        let arena = traces
+            .into_iter()
+            .enumerate()
+            .map(|(idx, trace)| CallTraceNode {
+                parent: idx.checked_sub(1),
+                children: if idx + 1 < len { vec![idx + 1] } else { vec![] },
+                idx,
+                trace,
+                ..Default::default()
+            })
+            .collect();
+        CallTraceArena { arena }
+    }
+
+    #[test]
+    fn excludes_contracts_by_path() {
+        let mut handler = call("Handler", "deposit", 1, 80_000);
+        handler.contract = Some("test/handlers/Handler.sol:Handler".to_string());
+        let traces = [(
+            TraceKind::Execution,
+            nested_arena(vec![
+                call("VaultInvariants", "invariant_solvent", 0, 100_000),
+                handler,
+                call("Vault", "deposit", 2, 50_000),
+            ]),
+        )];
+
+        let mut report = GasReport::new(vec![]).with_exclude_paths(&["test/**".to_string()]);
+        report.analyze(&traces);
+        assert!(!report.contracts.contains_key("test/handlers/Handler.sol:Handler"));
+        assert!(report.contracts.contains_key("src/Vault.sol:Vault"));
+
+        // directories are excluded without a glob, and listed contracts are always reported
+        let mut report = GasReport::new(vec!["Handler".to_string(), "Vault".to_string()])
+            .with_exclude_paths(&["test".to_string()]);
+        report.analyze(&traces);
+        assert!(report.contracts.contains_key("test/handlers/Handler.sol:Handler"));
+        let mut report = GasReport::new(vec![]).with_exclude_paths(&["test".to_string()]);
+        report.analyze(&traces);
+        assert!(!report.contracts.contains_key("test/handlers/Handler.sol:Handler"));
+        assert!(report.contracts["src/Vault.sol:Vault"].functions.contains_key("deposit"));
+    }
+
+    #[test]
+    fn respects_max_depth() {
+        let arena = nested_arena(vec![
+            call("CounterTest", "testIncrement", 0, 50_000),
+            call("Counter", "increment", 1, 30_000),
+            call("Inner", "bump", 2, 10_000),
+        ]);
+        let traces = [(TraceKind::Execution, arena)];
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        assert!(report.contracts.contains_key("src/Inner.sol:Inner"));
+
+        let mut report = GasReport::new(vec![]).with_max_depth(Some(1));
+        report.analyze(&traces);
+        assert!(report.contracts.contains_key("src/Counter.sol:Counter"));
+        assert!(!report.contracts.contains_key("src/Inner.sol:Inner"));
+    }
+
+    #[test]
+    fn stability_score() {
+        let function = |calls: Vec<u64>| GasInfo {
+            calls: calls.into_iter().map(U256::from).collect(),
+            ..Default::default()
+        };
+        let mut contract = ContractInfo::default();
+        assert_eq!(contract.stability_score(), 1.0);
+
+        contract
+            .functions
+            .entry("constant".to_string())
+            .or_default()
+            .insert("constant()".to_string(), function(vec![100, 100, 100]));
+        assert_eq!(contract.stability_score(), 1.0);
+
+        // stddev 100 and mean 100 => cv of 1 for two out of five calls
+        contract
+            .functions
+            .entry("variable".to_string())
+            .or_default()
+            .insert("variable(uint256)".to_string(), function(vec![0, 200]));
+        let score = contract.stability_score();
+        assert!((score - 1.0 / (1.0 + 2.0 / 5.0)).abs() < 1e-9, "{score}");
+    }
+
+    #[test]
+    fn pools_calls_into_aggregate_percentile() {
+        let traces = (1..=10)
+            .map(|i| {
+                let func = if i % 2 == 0 { "mint" } else { "burn" };
+                (TraceKind::Execution, nested_arena(vec![call("Token", func, 1, i * 1_000)]))
+            })
+            .collect::<Vec<_>>();
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        let token = &report.contracts["src/Token.sol:Token"];
+        assert_eq!(token.aggregate_percentile(95.0), U256::from(10_000));
+        assert_eq!(token.aggregate_percentile(50.0), U256::from(5_000));
+        assert_eq!(token.aggregate_percentile(0.0), U256::from(1_000));
+        assert!(report.to_string().contains("p95 of all calls 10000"));
+        assert!(ContractInfo::default().aggregate_percentile(95.0).is_zero());
+    }
+
+    #[test]
+    fn reports_upgrades_separately() {
+        let upgrade = |gas_cost| CallTrace {
+            data: RawOrDecodedCall::Decoded(
+                "upgradeTo".to_string(),
+                "upgradeTo(address)".to_string(),
+                vec![],
+            ),
+            ..call("Proxy", "upgradeTo", 1, gas_cost)
+        };
+        let traces = [
+            (TraceKind::Execution, nested_arena(vec![upgrade(40_000)])),
+            // same name, but not the upgrade signature
+            (TraceKind::Execution, nested_arena(vec![call("Proxy", "upgradeTo", 1, 1_000)])),
+        ];
+        let proxy = "src/Proxy.sol:Proxy";
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        assert!(report.contracts[proxy].upgrades.is_empty());
+        assert_eq!(report.contracts[proxy].functions["upgradeTo"].len(), 2);
+
+        let mut report = GasReport::new(vec![]).with_report_upgrades(true);
+        report.analyze(&traces);
+        let report = report.finalize();
+        let contract = &report.contracts[proxy];
+        assert_eq!(contract.upgrades["upgradeTo(address)"].max, U256::from(40_000));
+        assert_eq!(contract.functions["upgradeTo"].keys().collect::<Vec<_>>(), ["upgradeTo()"]);
+        assert!(report.to_string().contains("Upgrade"));
+    }
+
+    #[test]
+    fn attributes_implementation_deployments_to_upgrades() {
+        let implementation = Address::repeat_byte(0x22);
+        let mut deploy = call("VaultV2", "", 1, 300_000);
+        deploy.kind = CallKind::Create;
+        deploy.address = implementation;
+        deploy.data = RawOrDecodedCall::Raw(vec![0; 100]);
+        let upgrade = CallTrace {
+            data: RawOrDecodedCall::Decoded(
+                "upgradeTo".to_string(),
+                "upgradeTo(address)".to_string(),
+                vec![format!("VaultV2: [{implementation:?}]")],
+            ),
+            ..call("Proxy", "upgradeTo", 1, 40_000)
+        };
+
+        let mut report = GasReport::new(vec![]).with_report_upgrades(true);
+        report.analyze(&[
+            (TraceKind::Deployment, nested_arena(vec![deploy])),
+            (TraceKind::Execution, nested_arena(vec![upgrade])),
+        ]);
+        let report = report.finalize();
+        let proxy = &report.contracts["src/Proxy.sol:Proxy"];
+        assert_eq!(proxy.implementations[&implementation], U256::from(300_000));
+        assert_eq!(proxy.upgrades["upgradeTo(address)"].max, U256::from(40_000));
+        assert!(report.to_string().contains(&format!("{implementation:?}: 300000 gas")));
+    }
+
+    #[test]
+    fn reports_access_list_savings() {
+        let prewarmed = |contract: &str, func: &str, depth, gas_cost, access_list_savings| {
+            CallTrace { access_list_savings, ..call(contract, func, depth, gas_cost) }
+        };
+        let traces = [
+            (
+                TraceKind::Execution,
+                nested_arena(vec![
+                    prewarmed("Vault", "deposit", 1, 40_000, 2_000),
+                    prewarmed("Token", "transferFrom", 2, 20_000, 2_500),
+                ]),
+            ),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 44_500)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "withdraw", 1, 30_000)])),
+        ];
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+        let deposit = report.function("Vault.deposit").unwrap();
+        assert_eq!(deposit.access_list_savings, U256::from(4_500));
+        assert!(report.function("Vault.withdraw").unwrap().access_list_savings.is_zero());
+        assert!(report.to_string().contains("deposit(): 2250 gas per call"));
+    }
+
+    #[test]
+    fn asserts_mean_within_limit() {
+        let traces = [
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 40_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 50_000)])),
+        ];
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+        assert_eq!(report.assert_within("Vault", "deposit", U256::from(45_000)), Ok(()));
+        assert_eq!(
+            report.assert_within("Vault", "deposit", U256::from(44_999)),
+            Err("`deposit` of the contract `Vault` exceeds its gas limit: observed an average of \
+                 45000 gas, the limit is 44999 gas"
+                .to_string())
+        );
+        assert!(report.assert_within("Vault", "withdraw", U256::from(45_000)).is_err());
+    }
+
+    #[test]
+    fn extracts_single_metric() {
+        let traces = [
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 40_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 50_000)])),
+        ];
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+        let metric = |column| report.extract_metric("Vault", "deposit", column);
+        assert_eq!(metric(GasColumn::Min), Some(U256::from(40_000)));
+        assert_eq!(metric(GasColumn::Mean), Some(U256::from(45_000)));
+        assert_eq!(metric(GasColumn::Max), Some(U256::from(50_000)));
+        assert_eq!(metric(GasColumn::Calls), Some(U256::from(2)));
+        assert_eq!(report.extract_metric("Vault", "withdraw", GasColumn::Mean), None);
+    }
+
+    #[test]
+    fn reports_memory_gas() {
+        let expanding = |contract: &str, func: &str, depth, gas_cost, memory_gas| CallTrace {
+            memory_gas,
+            ..call(contract, func, depth, gas_cost)
+        };
+        let traces = [
+            (
+                TraceKind::Execution,
+                nested_arena(vec![
+                    expanding("Vault", "deposit", 1, 40_000, 300),
+                    expanding("Token", "transferFrom", 2, 20_000, 100),
+                ]),
+            ),
+            (
+                TraceKind::Execution,
+                nested_arena(vec![expanding("Vault", "deposit", 1, 40_000, 200)]),
+            ),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "withdraw", 1, 30_000)])),
+        ];
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+        assert_eq!(report.function("Vault.deposit").unwrap().memory_gas, U256::from(600));
+        assert!(report.function("Vault.withdraw").unwrap().memory_gas.is_zero());
+        assert!(!report.to_string().contains("memory_gas"));
+
+        let table = report.with_memory_gas_column(true).to_string();
+        let last_cell = |name: &str| {
+            let row = table.lines().find(|line| line.contains(&format!(" {name} "))).unwrap();
+            row.trim_end_matches(|c| c == '│' || c == ' ').rsplit(' ').next().unwrap().to_string()
+        };
+        assert!(table.contains("memory_gas"));
+        assert_eq!(last_cell("deposit"), "300");
+        assert_eq!(last_cell("withdraw"), "0");
+    }
+
+    #[test]
+    fn reports_gas_categories() {
+        let categorized = |gas_cost, storage, calls, arithmetic| CallTrace {
+            gas_categories: GasCategories { storage, calls, arithmetic, ..Default::default() },
+            ..call("Vault", "deposit", 1, gas_cost)
+        };
+        let traces = [
+            (TraceKind::Execution, nested_arena(vec![categorized(30_000, 22_100, 5_000, 100)])),
+            (TraceKind::Execution, nested_arena(vec![categorized(10_000, 2_900, 5_000, 100)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "withdraw", 1, 30_000)])),
+        ];
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+        let deposit = report.function("Vault.deposit").unwrap().gas_categories;
+        assert_eq!(
+            deposit,
+            GasCategories {
+                storage: 25_000,
+                calls: 10_000,
+                arithmetic: 200,
+                other: 4_800,
+                ..Default::default()
+            }
+        );
+        assert_eq!(deposit.total(), 40_000);
+        assert!(report.function("Vault.withdraw").unwrap().gas_categories.is_zero());
+        assert!(!report.to_string().contains("storage: "));
+
+        let table = report.with_gas_categories(true).to_string();
+        assert!(table.contains("storage: 12500"));
+        assert!(table.contains("calls: 5000"));
+        assert!(table.contains("arithmetic: 100"));
+        assert!(table.contains("other: 2400"));
+        assert!(!table.contains("hashing: "));
+    }
+
+    #[test]
+    fn reports_precompile_floor() {
+        let pairing = CallTrace {
+            precompile_gas_floor: Some(113_000),
+            gas_cost: 113_000,
+            ..Default::default()
+        };
+        let traces = [
+            (
+                TraceKind::Execution,
+                nested_arena(vec![call("Verifier", "verify", 1, 150_000), pairing]),
+            ),
+            (TraceKind::Execution, nested_arena(vec![call("Verifier", "reset", 1, 5_000)])),
+        ];
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+        let functions = &report.contracts["src/Verifier.sol:Verifier"].functions;
+        assert_eq!(functions["verify"]["verify()"].precompile_floor, Some(113_000.into()));
+        assert_eq!(functions["reset"]["reset()"].precompile_floor, None);
+        assert!(report
+            .to_string()
+            .contains("verify(): precompile floor: 113000, observed: 150000 (+37000)"));
+    }
+
+    #[test]
+    fn summarizes_diff_in_status_line() {
+        let report = |functions: Vec<(&str, u64)>| {
+            let traces: Vec<_> = functions
+                .into_iter()
+                .map(|(func, gas)| {
+                    (TraceKind::Execution, nested_arena(vec![call("Foo", func, 1, gas)]))
+                })
+                .collect();
+            let mut report = GasReport::new(vec![]);
+            report.analyze(&traces);
+            report.finalize()
+        };
+        let baseline =
+            report(vec![("bar", 1_000), ("baz", 1_000), ("qux", 2_000), ("same", 500), ("old", 1)]);
+        let current =
+            report(vec![("bar", 1_042), ("baz", 1_010), ("qux", 1_900), ("same", 500), ("new", 1)]);
+
+        let diff = GasReportDiff::new(&baseline, &current);
+        assert_eq!(diff.functions.len(), 4);
+        assert_eq!(
+            diff.to_status_line(),
+            "gas: 2 ↑ (max +4.2% Foo.bar), 1 ↓ (max -5.0% Foo.qux), 1 unchanged"
+        );
+        assert_eq!(
+            GasReportDiff::new(&baseline, &baseline).to_status_line(),
+            "gas: 0 ↑, 0 ↓, 5 unchanged"
+        );
+    }
+
+    #[test]
+    fn records_deployed_and_init_size() {
+        let mut deploy = call("Token", "", 1, 50_000);
+        deploy.kind = CallKind::Create;
+        deploy.data = RawOrDecodedCall::Raw(vec![0; 300]);
+        deploy.output = RawOrDecodedReturnData::Raw(vec![0; 200]);
+        let traces = [(TraceKind::Deployment, nested_arena(vec![deploy]))];
+        let token = "src/Token.sol:Token";
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+        assert_eq!(report.contracts[token].deployed_size, U256::from(200));
+        assert_eq!(report.contracts[token].init_size, U256::from(300));
+
+        // the init code size was recorded as `size` before
+        let baseline: GasReport = serde_json::from_value(serde_json::json!({
+            "report_for": [],
+            "contracts": {
+                "src/Token.sol:Token": { "gas": "0x1", "size": "0x12c", "functions": {} }
+            }
+        }))
+        .unwrap();
+        assert_eq!(baseline.contracts[token].init_size, U256::from(300));
+        assert!(GasReportDiff::new(&baseline, &report).deployments.is_empty());
+
+        let diff = GasReportDiff::new(&report, &report);
+        assert_eq!(
+            diff.deployments,
+            [DeploymentSizeDiff {
+                contract: token.to_string(),
+                before: U256::from(200),
+                after: U256::from(200)
+            }]
+        );
+    }
+
+    #[test]
+    fn tags_etched_contracts() {
+        let target = Address::from_low_u64_be(0xe7c4);
+        let etch = |data| {
+            let etch =
+                CallTrace { address: CHEATCODE_ADDRESS, data, ..call("VaultTest", "", 1, 0) };
+            (TraceKind::Setup, nested_arena(vec![call("VaultTest", "setUp", 0, 0), etch]))
+        };
+        let decoded = || {
+            RawOrDecodedCall::Decoded(
+                "etch".to_string(),
+                "etch(address,bytes)".to_string(),
+                vec![format!("vault: [{target:?}]"), "0x00".to_string()],
+            )
+        };
+        let raw = HEVMCalls::Etch(EtchCall(target, vec![0u8].into())).encode();
+        let raw = RawOrDecodedCall::Raw(raw);
+        let deposit = CallTrace { address: target, ..call("Vault", "deposit", 1, 40_000) };
+        let deposit =
+            (TraceKind::Execution, nested_arena(vec![call("VaultTest", "test", 0, 0), deposit]));
+        let vault = "src/Vault.sol:Vault";
+
+        for data in [decoded(), raw] {
+            let mut report = GasReport::new(vec![]);
+            report.analyze(&[etch(data), deposit.clone()]);
+            let report = report.finalize();
+            assert!(report.contracts[vault].etched);
+            assert!(report.to_string().contains("etched"));
+        }
+
+        // a real deployment to the address is reported as usual
+        let mut deploy = CallTrace { address: target, ..call("Vault", "", 1, 90_000) };
+        deploy.kind = CallKind::Create;
+        deploy.data = RawOrDecodedCall::Raw(vec![0; 100]);
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&[
+            etch(decoded()),
+            (TraceKind::Deployment, nested_arena(vec![deploy])),
+            deposit,
+        ]);
+        assert!(!report.contracts[vault].etched);
+        assert_eq!(report.contracts[vault].gas, U256::from(90_000));
+    }
+
+    #[test]
+    fn flags_functions_exceeding_deployment() {
+        let mut deploy = call("Vault", "", 1, 40_000);
+        deploy.kind = CallKind::Create;
+        deploy.data = RawOrDecodedCall::Raw(vec![0; 100]);
+        let traces = [
+            (TraceKind::Deployment, nested_arena(vec![deploy])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "migrate", 1, 90_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 30_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Token", "mint", 1, 90_000)])),
+        ];
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        let vault = &report.contracts["src/Vault.sol:Vault"];
+        assert_eq!(vault.functions_exceeding_deploy(), ["migrate"]);
+        assert!(vault.functions["migrate"]["migrate()"].exceeds_deployment);
+        assert!(!vault.functions["deposit"]["deposit()"].exceeds_deployment);
+        assert!(report.to_string().contains("function of src/Vault.sol:Vault cost more gas"));
+
+        // the deployment of the token was not recorded
+        let token = &report.contracts["src/Token.sol:Token"];
+        assert!(token.functions_exceeding_deploy().is_empty());
+        assert!(!token.functions["mint"]["mint()"].exceeds_deployment);
+    }
+
+    #[test]
+    fn flags_gas_griefing_vectors() {
+        let forward = |forwards_all_gas| CallTrace {
+            depth: 2,
+            contract: None,
+            data: RawOrDecodedCall::Raw(vec![]),
+            forwards_all_gas,
+            ..Default::default()
+        };
+        let traces = [
+            (
+                TraceKind::Execution,
+                nested_arena(vec![call("Vault", "withdraw", 1, 30_000), forward(Some(true))]),
+            ),
+            (
+                TraceKind::Execution,
+                nested_arena(vec![call("Vault", "refund", 1, 30_000), forward(Some(false))]),
+            ),
+            (
+                TraceKind::Execution,
+                nested_arena(vec![call("Vault", "sweep", 1, 30_000), forward(None)]),
+            ),
+        ];
+        let mut report = GasReport::new(vec![]).with_flag_gas_griefing(true);
+        report.analyze(&traces);
+        let report = report.finalize();
+        let vault = &report.contracts["src/Vault.sol:Vault"];
+        assert_eq!(vault.griefing_vectors(), ["withdraw"]);
+        assert!(report.to_string().contains("function of src/Vault.sol:Vault forward all"));
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+        assert!(report.contracts["src/Vault.sol:Vault"].griefing_vectors().is_empty());
+    }
+
+    #[test]
+    fn lists_unbudgeted_functions() {
+        let mut withdraw = call("Vault", "withdraw", 1, 10_000);
+        withdraw.data = RawOrDecodedCall::Decoded(
+            "withdraw".to_string(),
+            "withdraw(uint256)".to_string(),
+            vec![],
+        );
+        let traces = [
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 10_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "withdraw", 1, 10_000)])),
+            (TraceKind::Execution, nested_arena(vec![withdraw])),
+            (TraceKind::Execution, nested_arena(vec![call("Token", "mint", 1, 50_000)])),
+        ];
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        let budgets = BTreeMap::from([
+            ("Vault.deposit".to_string(), U256::from(20_000)),
+            ("Vault.withdraw(uint256)".to_string(), U256::from(20_000)),
+            ("src/Token.sol:Token.mint".to_string(), U256::from(60_000)),
+        ]);
+        assert_eq!(report.unbudgeted_functions(&budgets), ["Vault.withdraw()"]);
+        assert_eq!(report.unbudgeted_functions(&BTreeMap::new()).len(), 4);
+    }
+
+    #[test]
+    fn builds_receipts_of_labeled_traces() {
+        let mut report = GasReport::new(vec![]).with_streaming(true);
+        report.analyze_labeled(
+            "VaultTest::testDeposit",
+            &[(TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 40_000)]))],
+        );
+        report.analyze_labeled(
+            "VaultTest::testMint",
+            &[(TraceKind::Execution, nested_arena(vec![call("Token", "mint", 1, 50_000)]))],
+        );
+        report.analyze(&[(
+            TraceKind::Execution,
+            nested_arena(vec![call("Vault", "deposit", 1, 60_000)]),
+        )]);
+
+        let receipt = report.receipt_for("VaultTest::testDeposit").unwrap();
+        assert!(receipt.streaming);
+        assert_eq!(receipt.contracts.keys().collect::<Vec<_>>(), ["src/Vault.sol:Vault"]);
+        let deposit = receipt.function("Vault.deposit").unwrap();
+        assert_eq!(deposit.call_count(), 1);
+        assert_eq!(deposit.mean, U256::from(40_000));
+        assert!(report.receipt_for("VaultTest::testWithdraw").is_none());
+
+        let report = report.finalize();
+        assert_eq!(report.function("Vault.deposit").unwrap().call_count(), 2);
+    }
+
+    #[test]
+    fn checks_budgets() {
+        let traces = [
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 40_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 60_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Token", "mint", 1, 50_000)])),
+        ];
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        let budgets = BTreeMap::from([
+            ("Vault.deposit".to_string(), U256::from(45_000)),
+            ("Vault.withdraw".to_string(), U256::from(1)),
+            ("Token.mint".to_string(), U256::from(50_000)),
+        ]);
+        assert_eq!(
+            report.check_budgets(&budgets),
+            ["`Vault.deposit` exceeds its gas budget: observed an average of 50000 gas, the \
+              budget is 45000 gas"]
+        );
+    }
+
+    #[test]
+    fn filters_functions_by_calls() {
+        let traces = [
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 10_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 12_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "withdraw", 1, 10_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Token", "mint", 1, 50_000)])),
+        ];
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize().filter_by_calls(2);
+
+        assert_eq!(report.function("Vault.deposit").unwrap().call_count(), 2);
+        assert!(report.function("Vault.withdraw").is_none());
+        assert!(!report.contracts.keys().any(|id| id.ends_with(":Token")));
+    }
+
+    #[test]
+    fn compares_signature_across_contracts() {
+        let traces = [
+            (TraceKind::Execution, nested_arena(vec![call("Token", "transfer", 1, 30_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Token", "transfer", 1, 34_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("MockToken", "transfer", 1, 20_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 40_000)])),
+        ];
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        let table = report.compare_signature("transfer()");
+        let contracts = table.rows.iter().map(|row| row.contract.as_str()).collect::<Vec<_>>();
+        assert_eq!(contracts, ["src/MockToken.sol:MockToken", "src/Token.sol:Token"]);
+        assert_eq!(table.rows[1].mean, U256::from(32_000));
+        assert_eq!(table.rows[1].calls, 2);
+        assert!(report.compare_signature("deposit(uint256)").rows.is_empty());
+    }
+
+    #[test]
+    fn pivots_by_trace_kind() {
+        let traces = [
+            (TraceKind::Setup, nested_arena(vec![call("Token", "mint", 1, 50_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Token", "mint", 1, 30_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Token", "mint", 1, 34_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Token", "transfer", 1, 25_000)])),
+        ];
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        let pivot = report.pivot_by_kind();
+        assert_eq!(pivot.kinds, ["setup", "execution"]);
+        let mint = pivot.rows.iter().find(|row| row.signature == "mint()").unwrap();
+        assert_eq!(mint.calls, 3);
+        assert_eq!(mint.by_kind, [Some(U256::from(50_000)), Some(U256::from(32_000))]);
+        let transfer = pivot.rows.iter().find(|row| row.signature == "transfer()").unwrap();
+        assert_eq!(transfer.by_kind, [None, Some(U256::from(25_000))]);
+
+        // the calls of a single kind are not kept twice
+        let info = report.function("Token.transfer").unwrap();
+        assert!(info.kinds.is_empty());
+        assert_eq!(info.kind.as_deref(), Some("execution"));
+        assert!(!serde_json::to_string(info).unwrap().contains("\"kinds\""));
+        let table = pivot.to_string();
+        assert!(table.contains("avg (setup)"));
+        assert!(table.contains("avg (execution)"));
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces[1..]);
+        let pivot = report.finalize().pivot_by_kind();
+        assert_eq!(pivot.kinds, ["execution"]);
+        let table = pivot.to_string();
+        assert!(table.contains("avg"));
+        assert!(!table.contains("avg (execution)"));
+    }
+
+    #[test]
+    fn compares_build_profiles() {
+        let report = |deploy_gas, mint_gas, extra: Option<&str>| {
+            let mut deploy = call("Token", "", 1, deploy_gas);
+            deploy.kind = CallKind::Create;
+            deploy.data = RawOrDecodedCall::Raw(vec![0; 100]);
+            let mut traces = vec![
+                (TraceKind::Deployment, nested_arena(vec![deploy])),
+                (TraceKind::Execution, nested_arena(vec![call("Token", "mint", 1, mint_gas)])),
+            ];
+            if let Some(func) = extra {
+                traces.push((TraceKind::Execution, nested_arena(vec![call("Token", func, 1, 1)])));
+            }
+            let mut report = GasReport::new(vec![]);
+            report.analyze(&traces);
+            report.finalize()
+        };
+        let runs_200 = report(100_000, 30_000, Some("burn")).with_build_profile(Some(200), false);
+        assert_eq!(runs_200.header.optimizer, Some(true));
+        assert_eq!(runs_200.header.via_ir, Some(false));
+        let via_ir = report(120_000, 25_000, None).with_build_profile(Some(200), true);
+        assert!(runs_200.header.compiler_mismatch(&via_ir.header).unwrap().contains("via-ir"));
+
+        let comparison = GasReport::compare_profiles(&[
+            ("runs=200".to_string(), runs_200),
+            ("via-ir".to_string(), via_ir),
+        ]);
+        assert_eq!(comparison.profiles, ["runs=200", "via-ir"]);
+        assert_eq!(comparison.deployments.len(), 1);
+        assert_eq!(comparison.deployments[0].gas, [U256::from(100_000), U256::from(120_000)]);
+        assert_eq!(comparison.functions.len(), 1);
+        assert_eq!(comparison.functions[0].signature.as_deref(), Some("mint()"));
+        assert_eq!(comparison.functions[0].gas, [U256::from(30_000), U256::from(25_000)]);
+        assert_eq!(comparison.partial, ["src/Token.sol:Token.burn()"]);
+        let table = comparison.to_string();
+        assert!(table.contains("Deployment Cost"));
+        assert!(table.contains("Not called in all profiles: src/Token.sol:Token.burn()"));
+    }
+
+    #[test]
+    fn renders_tiers_by_mean() {
+        let traces = [
+            (TraceKind::Execution, nested_arena(vec![call("Token", "approve", 1, 30_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Token", "transfer", 1, 60_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Token", "transfer", 1, 80_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 250_000)])),
+        ];
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        let rendered =
+            report.render_tiered(&[U256::from(200_000), U256::from(50_000), U256::from(50_000)]);
+        let cheap = rendered.find("Cheap (< 50000 gas)").unwrap();
+        let moderate = rendered.find("Moderate (50000 - 200000 gas)").unwrap();
+        let expensive = rendered.find("Expensive (>= 200000 gas)").unwrap();
+        assert!(cheap < moderate && moderate < expensive);
+        assert!(rendered[cheap..moderate].contains("approve()"));
+        assert!(rendered[moderate..expensive].contains("transfer()"));
+        assert!(rendered[expensive..].contains("deposit()"));
+        assert!(!rendered.contains("Deployment"));
+
+        let rendered = report.render_tiered(&[U256::from(100_000)]);
+        assert!(rendered.contains("Tier 1 (< 100000 gas)"));
+        assert!(rendered.contains("Tier 2 (>= 100000 gas)"));
+    }
+
+    #[test]
+    fn fits_gas_against_argument() {
+        let batch = |count: u64| {
+            let mut batch = call("Token", "batchTransfer", 1, 1_000 + 500 * count);
+            batch.data = RawOrDecodedCall::Decoded(
+                "batchTransfer".to_string(),
+                "batchTransfer(uint256,address)".to_string(),
+                vec![count.to_string(), "0x000000000000000000000000000000000000dEaD".to_string()],
+            );
+            (TraceKind::Execution, nested_arena(vec![batch]))
+        };
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&[batch(1), batch(2), batch(4)]);
+        let report = report.finalize();
+
+        let info = report.function("Token.batchTransfer").unwrap();
+        let (slope, intercept) = info.elasticity(0).unwrap();
+        assert!((slope - 500.0).abs() < 1e-6);
+        assert!((intercept - 1_000.0).abs() < 1e-6);
+        // the address is not a number and there is no third argument
+        assert_eq!(info.elasticity(1), None);
+        assert_eq!(info.elasticity(2), None);
+    }
+
+    #[test]
+    fn formats_fixed_width_columns() {
+        let traces = [
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 10_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 30_000)])),
+        ];
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        let widths = FwfWidths { contract: 12, signature: 8, context: 4, calls: 3, gas: 6 };
+        let fwf = report.to_fwf_with(&widths);
+        let lines = fwf.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1);
+        let line = lines[0];
+        assert_eq!(line.len(), 12 + 8 + 4 + 3 + 4 * 6);
+        // the contract and signature are cut, the numbers are right-aligned
+        assert_eq!(&line[..12], "src/Vault.so");
+        assert_eq!(&line[12..20], "deposit(");
+        assert_eq!(&line[20..24], "    ");
+        assert_eq!(&line[24..27], "  2");
+
+        let info = &report.contracts["src/Vault.sol:Vault"].functions["deposit"]["deposit()"];
+        let gas = |idx: usize| line[27 + idx * 6..27 + (idx + 1) * 6].trim_start();
+        assert_eq!(gas(0), info.min.to_string());
+        assert_eq!(gas(1), info.mean.to_string());
+        assert_eq!(gas(2), info.median.to_string());
+        assert_eq!(gas(3), info.max.to_string());
+    }
+
+    #[test]
+    fn colors_functions_relative_to_their_contract() {
+        let traces = [
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 10_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "withdraw", 1, 10_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "migrate", 1, 50_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Token", "mint", 1, 50_000)])),
+        ];
+        let mut report = GasReport::new(vec![]).with_relative_coloring(true);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        let vault = &report.contracts["src/Vault.sol:Vault"];
+        assert_eq!(vault.functions["deposit"]["deposit()"].relative_cost, Some(1.0));
+        assert_eq!(vault.functions["migrate"]["migrate()"].relative_cost, Some(5.0));
+        assert_eq!(relative_cost_color(1.0), Color::Green);
+        assert_eq!(relative_cost_color(2.0), Color::Rgb { r: 255, g: 127, b: 0 });
+        assert_eq!(relative_cost_color(5.0), Color::Rgb { r: 255, g: 0, b: 0 });
+
+        // a single function has no peers
+        let token = &report.contracts["src/Token.sol:Token"];
+        assert_eq!(token.functions["mint"]["mint()"].relative_cost, None);
+    }
+
+    #[test]
+    fn records_receive_and_fallback() {
+        let raw = |calldata: Vec<u8>, gas_cost| CallTrace {
+            data: RawOrDecodedCall::Raw(calldata),
+            ..call("Splitter", "", 1, gas_cost)
+        };
+        let traces = [
+            (TraceKind::Execution, nested_arena(vec![raw(vec![], 2_100)])),
+            (TraceKind::Execution, nested_arena(vec![raw(vec![], 2_300)])),
+            (TraceKind::Execution, nested_arena(vec![raw(vec![0xde, 0xad], 5_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Splitter", "release", 1, 30_000)])),
+        ];
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        let receive = report.function("Splitter.receive").unwrap();
+        assert!(receive.synthetic);
+        assert_eq!(receive.call_count(), 2);
+        assert_eq!(receive.mean, U256::from(2_200));
+        let fallback = report.function("Splitter.fallback()").unwrap();
+        assert!(fallback.synthetic);
+        assert_eq!(fallback.call_count(), 1);
+        assert!(!report.function("Splitter.release").unwrap().synthetic);
+
+        let json = serde_json::to_value(&report).unwrap();
+        let functions = &json["contracts"]["src/Splitter.sol:Splitter"]["functions"];
+        assert_eq!(functions["receive"]["receive()"]["synthetic"], true);
+        assert!(functions["release"]["release()"].get("synthetic").is_none());
+    }
+
+    #[test]
+    fn converts_to_json_map() {
+        let traces =
+            [(TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 40_000)]))];
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        let mut map = report.to_json_map();
+        assert_eq!(serde_json::Value::Object(map.clone()), serde_json::to_value(&report).unwrap());
+        map.insert("ci".to_string(), serde_json::json!({ "run": 42 }));
+        let json = serde_json::to_string(&map).unwrap();
+        let report: GasReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(report.function("Vault.deposit").unwrap().mean, U256::from(40_000));
+    }
+
+    #[test]
+    fn reports_unclassified_calls() {
+        let raw = |calldata: Vec<u8>, gas_cost| {
+            let trace = CallTrace {
+                data: RawOrDecodedCall::Raw(calldata),
+                ..call("Router", "", 1, gas_cost)
+            };
+            (TraceKind::Execution, nested_arena(vec![trace]))
+        };
+        let traces = [
+            raw(vec![0xa9, 0x05, 0x9c, 0xbb, 1], 30_000),
+            raw(vec![0x12, 0x34, 0x56, 0x78], 8_000),
+            raw(vec![0x12, 0x34, 0x56, 0x78], 9_000),
+        ];
+        let mut report = GasReport::new(vec![])
+            .with_signatures([("0xa9059cbb", "transfer(address,uint256)")]);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        let transfer = report.function("Router.transfer").unwrap();
+        assert_eq!(transfer.call_count(), 1);
+        let contract = &report.contracts["src/Router.sol:Router"];
+        assert_eq!(contract.unclassified.len(), 1);
+        assert_eq!(contract.unclassified["0x12345678"].mean, U256::from(8_500));
+
+        let json = serde_json::to_value(&report).unwrap();
+        let unclassified = &json["contracts"]["src/Router.sol:Router"]["unclassified"];
+        assert_eq!(unclassified["0x12345678"]["calls"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn reports_unknown_selectors_of_known_abis_as_fallback() {
+        let raw = |contract, calldata: Vec<u8>, gas_cost| {
+            let trace = CallTrace {
+                data: RawOrDecodedCall::Raw(calldata),
+                ..call(contract, "", 1, gas_cost)
+            };
+            (TraceKind::Execution, nested_arena(vec![trace]))
+        };
+        let traces = [
+            raw("Router", vec![0x12, 0x34, 0x56, 0x78], 8_000),
+            raw("Router", vec![0x87, 0x65, 0x43, 0x21, 1], 9_000),
+            raw("Relay", vec![0x12, 0x34, 0x56, 0x78], 7_000),
+        ];
+        let mut report =
+            GasReport::new(vec![]).with_abi_contracts(["src/Router.sol:Router".to_string()]);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        let router = &report.contracts["src/Router.sol:Router"];
+        assert!(router.unclassified.is_empty());
+        let fallback = report.function("Router.fallback()").unwrap();
+        assert!(fallback.synthetic);
+        assert_eq!(fallback.call_count(), 2);
+        let relay = &report.contracts["src/Relay.sol:Relay"];
+        assert_eq!(relay.unclassified["0x12345678"].mean, U256::from(7_000));
+        assert!(report.function("Relay.fallback()").is_none());
+    }
+
+    #[test]
+    fn analyzes_saved_arenas() {
+        let traces = vec![
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 30_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 50_000)])),
+        ];
+        let path = std::env::temp_dir()
+            .join(format!("forge-gas-report-traces-{}.json", std::process::id()));
+        GasReport::save_arenas(&path, &traces).unwrap();
+
+        let saved = GasReport::from_arenas_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(saved.len(), 2);
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&saved);
+        let report = report.finalize();
+        let deposit = report.function("Vault.deposit").unwrap();
+        assert_eq!(deposit.call_count(), 2);
+        assert_eq!(deposit.mean, U256::from(40_000));
+    }
+
+    #[test]
+    fn versions_serialized_reports() {
+        let header = GasReportHeader {
+            solc_versions: vec!["0.8.17".to_string()],
+            optimizer: Some(true),
+            optimizer_runs: Some(200),
+            ..Default::default()
+        };
+        let report = GasReport::new(vec![]).with_header(header.clone());
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["header"]["schema_version"], GAS_REPORT_SCHEMA_VERSION);
+        let report: GasReport = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(report.header, header);
+
+        // reports without a header are the first version
+        let legacy: GasReport =
+            serde_json::from_value(serde_json::json!({ "report_for": [], "contracts": {} }))
+                .unwrap();
+        assert_eq!(legacy.header.schema_version, 1);
+        assert!(GasReportDiff::try_new(&legacy, &report, false).is_ok());
+
+        let mut future = json;
+        future["header"]["schema_version"] = (GAS_REPORT_SCHEMA_VERSION + 1).into();
+        let err = serde_json::from_value::<GasReport>(future).unwrap_err();
+        assert!(err.to_string().contains("unsupported gas report schema version"));
+    }
+
+    #[test]
+    fn refuses_to_compare_different_compiler_settings() {
+        let report = |version: &str, runs| {
+            GasReport::new(vec![]).with_header(GasReportHeader {
+                solc_versions: vec![version.to_string()],
+                optimizer: Some(true),
+                optimizer_runs: Some(runs),
+                ..Default::default()
+            })
+        };
+        let baseline = report("0.8.17", 200);
+        assert!(GasReportDiff::try_new(&baseline, &report("0.8.17", 200), false).is_ok());
+
+        let err = GasReportDiff::try_new(&baseline, &report("0.8.17", 10_000), false).unwrap_err();
+        assert!(err.to_string().contains("optimizer with 200 runs != optimizer with 10000 runs"));
+        assert!(GasReportDiff::try_new(&baseline, &report("0.8.16", 200), false).is_err());
+        assert!(GasReportDiff::try_new(&baseline, &report("0.8.16", 200), true).is_ok());
+    }
+
+    #[test]
+    fn requires_matching_fork_blocks() {
+        let forked = GasReport::new(vec![]).with_fork_block(Some(15_000_000));
+        let json = serde_json::to_string(&forked).unwrap();
+        let forked: GasReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(forked.fork_block, Some(15_000_000));
+        assert!(forked.to_string().starts_with("# fork_block=15000000\n"));
+
+        let local = GasReport::default();
+        assert!(!serde_json::to_string(&local).unwrap().contains("fork_block"));
+        assert_eq!(GasReportDiff::new(&forked, &forked).fork_block_warning(), None);
+
+        let diff = GasReportDiff::new(&local, &forked);
+        assert_eq!(diff.fork_block_mismatch, Some((None, Some(15_000_000))));
+        assert_eq!(
+            diff.to_status_line(),
+            "WARNING: the baseline has no fork but the current report has fork block 15000000, \
+             the gas is not comparable; gas: 0 ↑, 0 ↓, 0 unchanged"
+        );
+    }
+
+    #[test]
+    fn streaming_stats() {
+        let traces: Vec<_> = [100u64, 200, 600]
+            .into_iter()
+            .map(|gas| (TraceKind::Execution, nested_arena(vec![call("Counter", "bump", 1, gas)])))
+            .collect();
+        let counter = "src/Counter.sol:Counter";
+
+        let mut full = GasReport::new(vec![]);
+        full.analyze(&traces);
+        let full = full.finalize();
+        let mut streaming = GasReport::new(vec![]).with_streaming(true);
+        streaming.analyze(&traces);
+        let streaming = streaming.finalize();
+
+        let full = &full.contracts[counter].functions["bump"]["bump()"];
+        let streaming = &streaming.contracts[counter].functions["bump"]["bump()"];
+        assert!(streaming.calls.is_empty());
+        assert_eq!(streaming.call_count(), 3);
+        assert_eq!((streaming.min, streaming.max), (full.min, full.max));
+        assert_eq!(streaming.mean, full.mean);
+        assert_eq!(streaming.median_cell(), "-");
+        let (a, b) = (streaming.coefficient_of_variation(), full.coefficient_of_variation());
+        assert!((a - b).abs() < 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn samples_calls_beyond_limit() {
+        let traces: Vec<_> = (1..=1_000u64)
+            .map(|i| call("Counter", "bump", 1, i * 100))
+            .map(|trace| (TraceKind::Execution, nested_arena(vec![trace])))
+            .collect();
+
+        let mut report = GasReport::new(vec![]).with_max_samples_per_function(Some(100));
+        report.analyze(&traces);
+        let report = report.finalize();
+        let bump = report.function("Counter.bump").unwrap();
+        assert_eq!(bump.calls.len(), 100);
+        assert_eq!(bump.samples.len(), 100);
+        assert_eq!(bump.call_count(), 1_000);
+        assert_eq!(bump.sampled_from, Some(1_000));
+        // the sample covers all calls, not only the first ones
+        assert!(bump.max > U256::from(90_000));
+        assert!(bump.mean > U256::from(35_000) && bump.mean < U256::from(65_000));
+
+        let mut exact = GasReport::new(vec![]).with_max_samples_per_function(None);
+        exact.analyze(&traces);
+        let exact = exact.finalize();
+        let bump = exact.function("Counter.bump").unwrap();
+        assert_eq!(bump.calls.len(), 1_000);
+        assert_eq!(bump.sampled_from, None);
+        assert_eq!(bump.mean, U256::from(50_050));
+    }
+
+    #[test]
+    fn keeps_sample_limit_when_merging() {
+        let counter = "src/Counter.sol:Counter";
+        let bump = |sig: &str, gas_cost| {
+            let trace = CallTrace {
+                data: RawOrDecodedCall::Decoded("bump".to_string(), sig.to_string(), vec![]),
+                ..call("Counter", "bump", 1, gas_cost)
+            };
+            (TraceKind::Execution, nested_arena(vec![trace]))
+        };
+        let traces: Vec<_> = (1..=1_000u64)
+            .map(|i| bump("bump()", i * 100))
+            .chain((1..=1_000u64).map(|i| bump("bump(uint256)", 100_000 + i * 100)))
+            .collect();
+
+        let mut report = GasReport::new(vec![]).with_max_samples_per_function(Some(100));
+        report.analyze(&traces);
+        report.rename_function(counter, "bump(uint256)", "bump()");
+        let report = report.finalize();
+        let bump = &report.contracts[counter].functions["bump"]["bump()"];
+        assert_eq!(bump.calls.len(), 100);
+        assert_eq!(bump.samples.len(), 100);
+        assert_eq!(bump.call_count(), 2_000);
+        // both signatures are represented by half of the samples
+        assert_eq!(bump.calls.iter().filter(|gas| **gas > U256::from(100_000)).count(), 50);
+        // the calls per context and kind are only counted
+        assert!(bump.contexts.is_empty());
+        assert_eq!(bump.kind.as_deref(), Some("execution"));
+    }
+
+    #[test]
+    fn subtracts_noise_floor() {
+        let traces = [
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 40_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 50_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "balance", 1, 300)])),
+        ];
+
+        let mut report = GasReport::new(vec![]).with_noise_floor(U256::from(1_000));
+        report.analyze(&traces);
+        let report = report.finalize();
+        let deposit = report.function("Vault.deposit").unwrap();
+        assert_eq!(deposit.min, U256::from(39_000));
+        assert_eq!(deposit.mean, U256::from(44_000));
+        assert_eq!(deposit.max, U256::from(49_000));
+        assert!(report.function("Vault.balance").unwrap().max.is_zero());
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+        assert_eq!(report.function("Vault.deposit").unwrap().mean, U256::from(45_000));
+    }
+
+    #[test]
+    fn renames_functions() {
+        let traces = [
+            (TraceKind::Execution, nested_arena(vec![call("Token", "transfer", 1, 300)])),
+            (TraceKind::Execution, nested_arena(vec![call("Token", "transfer", 1, 100)])),
+            (TraceKind::Execution, nested_arena(vec![call("Token", "send", 1, 200)])),
+        ];
+        let token = "src/Token.sol:Token";
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        report.rename_function(token, "send()", "transfer()");
+        let report = report.finalize();
+
+        let functions = &report.contracts[token].functions;
+        assert!(!functions.contains_key("send"));
+        let transfer = &functions["transfer"]["transfer()"];
+        assert_eq!(transfer.calls, [100u64, 200, 300].map(U256::from));
+        assert_eq!(transfer.median, U256::from(200));
+
+        // renames if the target does not exist yet
+        let mut report = GasReport::new(vec![]).with_streaming(true);
+        report.analyze(&traces);
+        report.rename_function(token, "transfer()", "transfer(address,uint256)");
+        let report = report.finalize();
+        let functions = &report.contracts[token].functions["transfer"];
+        assert_eq!(functions.keys().collect::<Vec<_>>(), ["transfer(address,uint256)"]);
+        let transfer = &functions["transfer(address,uint256)"];
+        assert_eq!(transfer.call_count(), 2);
+        assert_eq!(transfer.mean, U256::from(200));
+    }
+
+    #[test]
+    fn groups_calls_by_fork() {
+        let forked = |block_number: u64, gas_cost: u64| {
+            let mut trace = call("Pool", "swap", 1, gas_cost);
+            trace.fork = Some(ForkContext { chain_id: 1, block_number });
+            (TraceKind::Execution, nested_arena(vec![trace]))
+        };
+        let pool = "src/Pool.sol:Pool";
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&[forked(100, 30_000), forked(100, 32_000), forked(200, 90_000)]);
+        let report = report.finalize();
+        let swap = &report.contracts[pool].functions["swap"]["swap()"];
+        assert_eq!(swap.call_count(), 3);
+        let contexts = swap.contexts.keys().collect::<Vec<_>>();
+        assert_eq!(contexts, ["chain 1 @ block 100", "chain 1 @ block 200"]);
+        assert_eq!(swap.contexts["chain 1 @ block 100"].mean, 31_000.0);
+        assert!(report.to_string().contains("swap [chain 1 @ block 200]"));
+
+        let mut merged = GasReport::new(vec![]).with_merge_fork_contexts(true);
+        merged.analyze(&[forked(100, 30_000), forked(200, 90_000)]);
+        assert!(!merged.finalize().to_string().contains("[chain 1"));
+
+        // a single context is not reported separately
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&[forked(100, 30_000), forked(100, 32_000)]);
+        assert!(report.finalize().contracts[pool].functions["swap"]["swap()"].contexts.is_empty());
+    }
+
+    #[test]
+    fn splits_calls_by_outcome() {
+        let withdraw = |success: bool, gas_cost: u64| {
+            let trace = CallTrace { success, ..call("Vault", "withdraw", 1, gas_cost) };
+            (TraceKind::Execution, nested_arena(vec![trace]))
+        };
+
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&[withdraw(true, 40_000), withdraw(true, 44_000), withdraw(false, 3_000)]);
+        let report = report.finalize();
+        let info = report.function("Vault.withdraw").unwrap();
+        let success = info.success_stats().unwrap();
+        assert_eq!((success.count, success.min, success.max), (2, 40_000.into(), 44_000.into()));
+        assert_eq!((success.mean, success.median), (42_000.into(), 0.into()));
+        let revert = info.revert_stats().unwrap();
+        assert_eq!((revert.count, revert.mean, revert.max), (1, 3_000.into(), 3_000.into()));
+        assert!(report.to_string().contains("  revert"));
+        // the calls are not kept per outcome
+        let json = serde_json::to_value(info).unwrap();
+        assert!(json["outcomes"]["revert"].get("calls").is_none());
+        assert_eq!(json["outcomes"]["revert"]["count"], 1);
+
+        // the split survives serialization
+        let json = serde_json::to_string(&report).unwrap();
+        let report: GasReport = serde_json::from_str(&json).unwrap();
+        let info = report.function("Vault.withdraw").unwrap();
+        assert_eq!(info.revert_stats().unwrap().max, U256::from(3_000));
+        assert_eq!(info.success_stats().unwrap().mean, U256::from(42_000));
+
+        // without reverts all calls are successful
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&[withdraw(true, 40_000)]);
+        let report = report.finalize();
+        let info = report.function("Vault.withdraw").unwrap();
+        assert!(info.outcomes.is_empty() && info.revert_stats().is_none());
+        assert_eq!(info.success_stats().unwrap().mean, U256::from(40_000));
+    }
+
+    #[test]
+    fn computes_overhead_between_functions() {
+        let traces: Vec<_> =
+            [("withdraw", 30_000), ("withdraw", 32_000), ("withdrawGuarded", 33_400)]
+                .into_iter()
+                .map(|(func, gas)| {
+                    (TraceKind::Execution, nested_arena(vec![call("Vault", func, 1, gas)]))
+                })
+                .collect();
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        let overhead = report.overhead_between("Vault.withdraw", "Vault.withdrawGuarded");
+        assert_eq!(overhead, Some(U256::from(2_400)));
+        assert_eq!(
+            report.overhead_between("src/Vault.sol:Vault.withdraw()", "Vault.withdrawGuarded"),
+            Some(U256::from(2_400))
+        );
+        // cheaper wrappers have no overhead
+        assert_eq!(
+            report.overhead_between("Vault.withdrawGuarded", "Vault.withdraw"),
+            Some(U256::zero())
+        );
+        assert_eq!(report.overhead_between("Vault.withdraw", "Vault.missing"), None);
+        assert_eq!(report.overhead_between("Other.withdraw", "Vault.withdraw"), None);
+    }
+
+    #[test]
+    fn computes_total_cost() {
+        let mut deploy = call("Vault", "", 1, 200_000);
+        deploy.kind = CallKind::Create;
+        deploy.data = RawOrDecodedCall::Raw(vec![0; 100]);
+        let traces = [
+            (TraceKind::Deployment, nested_arena(vec![deploy])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 40_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "deposit", 1, 44_000)])),
+            (TraceKind::Execution, nested_arena(vec![call("Vault", "withdraw", 1, 30_000)])),
+        ];
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+        let report = report.finalize();
+
+        // 200_000 + 42_000 + 30_000 gas at 10 gwei
+        let gwei = U256::exp10(9);
+        assert_eq!(report.total_cost(gwei * 10), U256::from(272_000) * gwei * 10);
+        assert_eq!(report.total_cost(U256::MAX), U256::MAX);
+        assert!(GasReport::new(vec![]).total_cost(gwei).is_zero());
+    }
+
+    #[test]
+    fn aggregates_gas_per_source_line() {
+        let line = |line, gas| SourceLineGas { file: "src/Vault.sol".to_string(), line, gas };
+        let mut withdraw = call("Vault", "withdraw", 1, 5_000);
+        withdraw.source_gas = vec![line(10, 100), line(12, 300)];
+        let mut deposit = call("Vault", "deposit", 1, 3_000);
+        deposit.source_gas = vec![line(12, 200), line(3, 50)];
+        let traces = vec![
+            (TraceKind::Execution, nested_arena(vec![withdraw])),
+            (TraceKind::Execution, nested_arena(vec![deposit, call("Token", "transfer", 2, 0)])),
+        ];
+        let mut report = GasReport::new(vec![]);
+        report.analyze(&traces);
+
+        let lines = report
+            .line_annotations()
+            .into_iter()
+            .map(|gas| (gas.file, gas.line, gas.gas.as_u64()))
+            .collect::<Vec<_>>();
+        let file = "src/Vault.sol".to_string();
+        assert_eq!(lines, [(file.clone(), 3, 50), (file.clone(), 10, 100), (file, 12, 500)]);
+
+        // traces without source positions have no annotations
+        let mut report = GasReport::new(vec![]);
+        let arena = nested_arena(vec![call("Vault", "withdraw", 1, 1)]);
+        report.analyze(&[(TraceKind::Execution, arena)]);
+        assert!(report.line_annotations().is_empty());
+    }
+
+    #[test]
+    fn merges_streaming_stats() {
+        let push_all = |values: &[u64]| {
+            let mut stats = StreamingStats::default();
+            values.iter().for_each(|value| stats.push(U256::from(*value)));
+            stats
+        };
+        let mut merged = push_all(&[10, 20]);
+        merged.merge(&push_all(&[30, 70, 5]));
+        let all = push_all(&[10, 20, 30, 70, 5]);
+        assert_eq!(merged.count, all.count);
+        assert_eq!((merged.min, merged.max), (all.min, all.max));
+        assert!((merged.mean - all.mean).abs() < 1e-9);
+        assert!((merged.variance() - all.variance()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn abbreviates_signatures() {
+        let sig = "transfer(address,uint256)";
+        assert_eq!(abbreviate_signature(sig, 100), sig);
+        assert_eq!(abbreviate_signature(sig, 20), "transfer(addr,uint)");
+        assert_eq!(abbreviate_signature(sig, 17), "transfer(…2 args)");
+        assert_eq!(abbreviate_signature(sig, 10), "transfer");
+        assert_eq!(abbreviate_signature(sig, 5), "tran…");
+        assert_eq!(
+            abbreviate_signature("swap((address,uint256)[],bytes)", 25),
+            "swap((addr,uint)[],bytes)"
+        );
+        assert_eq!(abbreviate_signature("swap((address,uint256)[],bytes)", 20), "swap(…2 args)");
+    }
+
+    #[test]
+    fn carries_metadata() {
+        let report = GasReport::default().with_metadata([("commit", "abc123"), ("solc", "0.8.17")]);
+        assert!(report.to_string().starts_with("# commit=abc123 solc=0.8.17\n"));
+
+        let json = serde_json::to_string(&report).unwrap();
+        let report: GasReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(report.metadata.get("commit").map(String::as_str), Some("abc123"));
+    }
+
+    #[test]
+    fn renders_metadata_of_deserialized_reports_above_contracts() {
+        let mut report = GasReport::new(vec![]).with_metadata([("commit", "abc123")]);
+        report.analyze(&[(
+            TraceKind::Execution,
+            nested_arena(vec![call("Vault", "deposit", 1, 50_000)]),
+        )]);
+        let report = report.finalize();
+
+        let json = serde_json::to_string(&report).unwrap();
+        let from_json: GasReport = serde_json::from_str(&json).unwrap();
+        let toml = toml::to_string(&toml::Value::try_from(&report).unwrap()).unwrap();
+        let from_toml: GasReport = toml::from_str(&toml).unwrap();
+        for report in [from_json, from_toml] {
+            assert_eq!(report.metadata["commit"], "abc123");
+            let out = report.to_string();
+            let metadata = out.find("# commit=abc123").unwrap();
+            let contract = out.find("src/Vault.sol:Vault").unwrap();
+            assert!(metadata < contract, "{out}");
+            assert!(out.contains("deposit"));
         }
-        Ok(())
     }
 }
@@ -0,0 +1,326 @@
+//! Attribution of the gas of instructions to the lines of the sources
+//!
+//! The tracer records the gas of the instructions of every call by their program counter if
+//! [`ExecutorBuilder::set_gas_by_pc()`](crate::executor::ExecutorBuilder::set_gas_by_pc) is
+//! enabled. The compiler's source map of the runtime code of a contract maps every instruction to
+//! the range of the source it was compiled from, which resolves the program counters to lines.
+
+use crate::{
+    revm::SpecId,
+    trace::{CallTraceArena, RawOrDecodedCall, SourceLineGas, TraceKind},
+    utils::build_pc_ic_map,
+};
+use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, *};
+use ethers::{
+    prelude::{artifacts::CompactBytecode, ProjectCompileOutput},
+    solc::{artifacts::contract::CompactContractBytecode, sourcemap::SourceMap},
+    types::Address,
+};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::Path,
+};
+
+/// The number of the most expensive lines of a function that are highlighted in its listing
+pub const HIGHLIGHTED_LINES: usize = 5;
+
+/// Resolves the program counters of the contracts to the lines of their sources and sums up the
+/// gas of the lines per function, see [`GasSourceMap::render_top_functions()`]
+#[derive(Debug, Default)]
+pub struct GasSourceMap {
+    /// The path of every source, relative to the project root, and its lines
+    files: Vec<(String, Vec<String>)>,
+    /// contract identifier -> program counter of the runtime code -> index of the file and line
+    lines: HashMap<String, BTreeMap<usize, (usize, usize)>>,
+    /// The contracts whose functions are not listed, e.g. the test contracts
+    skipped: HashSet<String>,
+    /// `<contract name>.<function signature>` -> the lines of its calls
+    functions: BTreeMap<String, FunctionLines>,
+}
+
+/// The gas used by the calls of a function per line
+#[derive(Debug, Default)]
+struct FunctionLines {
+    calls: usize,
+    /// index of the file and line -> gas
+    gas: BTreeMap<(usize, usize), u64>,
+}
+
+impl FunctionLines {
+    fn total(&self) -> u64 {
+        self.gas.values().sum()
+    }
+}
+
+impl GasSourceMap {
+    /// Reads the sources and the runtime source maps of all contracts of the compiler `output`
+    ///
+    /// Contracts without a source map, e.g. artifacts of an earlier compilation, are skipped.
+    pub fn new(output: ProjectCompileOutput, root: &Path) -> Self {
+        let (artifacts, sources) = output.into_artifacts_with_sources();
+        let mut map = Self::default();
+
+        // the index of the file and the offsets at which its lines start, by compiler version and
+        // source id
+        let mut line_starts: HashMap<_, HashMap<u32, (usize, Vec<usize>)>> = HashMap::new();
+        for (path, source_file, version) in sources.into_sources_with_version() {
+            let content = match std::fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(_) => continue,
+            };
+            let starts = std::iter::once(0)
+                .chain(content.match_indices('\n').map(|(idx, _)| idx + 1))
+                .collect();
+            line_starts
+                .entry(version)
+                .or_default()
+                .insert(source_file.id, (map.files.len(), starts));
+            let path = Path::new(&path)
+                .strip_prefix(root)
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or(path);
+            map.files.push((path, content.lines().map(str::to_string).collect()));
+        }
+
+        for (id, artifact) in artifacts {
+            let bytecode = match CompactContractBytecode::from(artifact)
+                .deployed_bytecode
+                .and_then(|bytecode| bytecode.bytecode)
+            {
+                Some(bytecode) => bytecode,
+                None => continue,
+            };
+            let source_map = match bytecode.source_map() {
+                Some(Ok(source_map)) => source_map,
+                _ => continue,
+            };
+            let (code, sources) = match (dummy_link(bytecode), line_starts.get(&id.version)) {
+                (Some(code), Some(sources)) => (code, sources),
+                _ => continue,
+            };
+            map.lines.insert(id.identifier(), instruction_lines(&code, &source_map, sources));
+        }
+        map
+    }
+
+    /// Leaves the functions of the contracts with the `identifiers` out of the listings, their
+    /// calls are still attributed
+    #[must_use]
+    pub fn with_skipped_contracts(mut self, identifiers: impl IntoIterator<Item = String>) -> Self {
+        self.skipped.extend(identifiers);
+        self
+    }
+
+    /// Fills the [`CallTrace::source_gas`](crate::trace::CallTrace::source_gas) of the calls of
+    /// the `traces` from the gas they recorded by program counter and adds it to the lines of
+    /// their functions, the traces are expected to be decoded
+    ///
+    /// The calls of contracts without a source map and contract creations are left out.
+    pub fn analyze(&mut self, traces: &mut [(TraceKind, CallTraceArena)]) {
+        for (_, arena) in traces {
+            for node in arena.arena.iter_mut() {
+                let trace = &mut node.trace;
+                let contract = match &trace.contract {
+                    Some(contract) if !trace.created() => contract,
+                    _ => continue,
+                };
+                let lines = match self.lines.get(contract) {
+                    Some(lines) => lines,
+                    None => continue,
+                };
+
+                let mut gas: BTreeMap<(usize, usize), u64> = BTreeMap::new();
+                for (pc, pc_gas) in trace.pc_gas.iter() {
+                    if let Some(line) = lines.get(pc) {
+                        *gas.entry(*line).or_default() += pc_gas;
+                    }
+                }
+                trace.source_gas = gas
+                    .iter()
+                    .map(|(&(file, line), &gas)| SourceLineGas {
+                        file: self.files[file].0.clone(),
+                        line,
+                        gas,
+                    })
+                    .collect();
+
+                if let RawOrDecodedCall::Decoded(_, signature, _) = &trace.data {
+                    if self.skipped.contains(contract) {
+                        continue
+                    }
+                    let name = contract.rsplit(':').next().unwrap_or(contract);
+                    let function =
+                        self.functions.entry(format!("{name}.{signature}")).or_default();
+                    function.calls += 1;
+                    for (line, gas) in gas {
+                        *function.gas.entry(line).or_default() += gas;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Renders the lines of the `n` functions that used the most gas in total with the gas every
+    /// line used, the [`HIGHLIGHTED_LINES`] most expensive lines of a function are red
+    pub fn render_top_functions(&self, n: usize) -> String {
+        let mut functions = self.functions.iter().collect::<Vec<_>>();
+        functions.sort_by_key(|(_, function)| std::cmp::Reverse(function.total()));
+
+        let mut out = String::new();
+        for (name, function) in functions.into_iter().take(n) {
+            let highlighted = expensive_lines(&function.gas, HIGHLIGHTED_LINES);
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
+            table.set_header(vec![
+                Cell::new(format!(
+                    "{name} ({} gas in {} calls)",
+                    function.total(),
+                    function.calls
+                ))
+                .add_attribute(Attribute::Bold),
+                Cell::new("Line").add_attribute(Attribute::Bold),
+                Cell::new("Gas").add_attribute(Attribute::Bold).fg(Color::Yellow),
+            ]);
+            for (&(file, line), gas) in function.gas.iter() {
+                let (path, lines) = &self.files[file];
+                let code = lines.get(line - 1).map(|code| code.trim()).unwrap_or_default();
+                let row = vec![
+                    Cell::new(code),
+                    Cell::new(format!("{path}:{line}")),
+                    Cell::new(gas.to_string()),
+                ];
+                if highlighted.contains(&(file, line)) {
+                    table.add_row(row.into_iter().map(|cell| cell.fg(Color::Red)));
+                } else {
+                    table.add_row(row);
+                }
+            }
+            out.push_str(&format!("{table}\n"));
+        }
+        out
+    }
+}
+
+/// Returns the `n` lines that used the most gas, ties are broken by the position of the lines
+fn expensive_lines(gas: &BTreeMap<(usize, usize), u64>, n: usize) -> HashSet<(usize, usize)> {
+    let mut lines = gas.iter().collect::<Vec<_>>();
+    lines.sort_by_key(|(_, gas)| std::cmp::Reverse(**gas));
+    lines.into_iter().take(n).map(|(line, _)| *line).collect()
+}
+
+/// Resolves the program counters of the runtime `code` to the file index and line of the source
+/// range the `source_map` maps their instruction to, `sources` are the file indices and the line
+/// offsets by source id
+///
+/// Instructions that the compiler generated without a source range are left out.
+fn instruction_lines(
+    code: &[u8],
+    source_map: &SourceMap,
+    sources: &HashMap<u32, (usize, Vec<usize>)>,
+) -> BTreeMap<usize, (usize, usize)> {
+    build_pc_ic_map(SpecId::LATEST, code)
+        .into_iter()
+        .filter_map(|(pc, ic)| {
+            let element = source_map.get(ic)?;
+            let (file, starts) = sources.get(&element.index?)?;
+            Some((pc, (*file, starts.partition_point(|start| *start <= element.offset))))
+        })
+        .collect()
+}
+
+/// Links the library references of the bytecode to the zero address, which does not change the
+/// program counters of its instructions
+fn dummy_link(mut bytecode: CompactBytecode) -> Option<Vec<u8>> {
+    let link_references = std::mem::take(&mut bytecode.link_references);
+    for (file, libraries) in link_references {
+        for library in libraries.keys() {
+            bytecode.link(&file, library, Address::zero());
+        }
+    }
+    bytecode.object.resolve();
+    bytecode.object.into_bytes().map(|bytes| bytes.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::{node::CallTraceNode, CallTrace};
+    use ethers::solc::sourcemap;
+
+    const SOURCE: &str = "contract Vault {
+    uint256 total;
+    function deposit() public {}
+}";
+
+    #[test]
+    fn resolves_instructions_to_lines() {
+        // PUSH1 0x80 PUSH1 0x40 MSTORE STOP
+        let code = [0x60, 0x80, 0x60, 0x40, 0x52, 0x00];
+        let source_map = sourcemap::parse("0:16:0:-;21:14:0:-;40:28:0:-;0:0:-1:-").unwrap();
+        let starts = std::iter::once(0)
+            .chain(SOURCE.match_indices('\n').map(|(idx, _)| idx + 1))
+            .collect();
+        let sources = HashMap::from([(0, (7, starts))]);
+        assert_eq!(
+            instruction_lines(&code, &source_map, &sources),
+            BTreeMap::from([(0, (7, 1)), (2, (7, 2)), (4, (7, 3))])
+        );
+    }
+
+    #[test]
+    fn attributes_gas_to_lines_of_functions() {
+        let contract = "src/Vault.sol:Vault".to_string();
+        let mut map = GasSourceMap {
+            files: vec![(
+                "src/Vault.sol".to_string(),
+                SOURCE.lines().map(str::to_string).collect(),
+            )],
+            lines: HashMap::from([(
+                contract.clone(),
+                BTreeMap::from([(0, (0, 1)), (2, (0, 2)), (4, (0, 3)), (5, (0, 3))]),
+            )]),
+            ..Default::default()
+        };
+        let call = |pc_gas: BTreeMap<usize, u64>| CallTraceArena {
+            arena: vec![CallTraceNode {
+                trace: CallTrace {
+                    contract: Some(contract.clone()),
+                    data: RawOrDecodedCall::Decoded(
+                        "deposit".to_string(),
+                        "deposit()".to_string(),
+                        vec![],
+                    ),
+                    pc_gas,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }],
+        };
+        let mut traces = vec![
+            (TraceKind::Execution, call(BTreeMap::from([(0, 3), (4, 20_000), (5, 100)]))),
+            (TraceKind::Execution, call(BTreeMap::from([(2, 2_100), (9, 1)]))),
+        ];
+        map.analyze(&mut traces);
+
+        let source_gas = &traces[0].1.arena[0].trace.source_gas;
+        assert_eq!(
+            source_gas.iter().map(|line| (line.line, line.gas)).collect::<Vec<_>>(),
+            [(1, 3), (3, 20_100)]
+        );
+        let function = &map.functions["Vault.deposit()"];
+        assert_eq!(function.calls, 2);
+        assert_eq!(function.total(), 22_203);
+        assert_eq!(expensive_lines(&function.gas, 2), HashSet::from([(0, 3), (0, 2)]));
+
+        let listing = map.render_top_functions(1);
+        assert!(listing.contains("Vault.deposit() (22203 gas in 2 calls)"));
+        let row = listing.lines().find(|line| line.contains("src/Vault.sol:3")).unwrap();
+        assert!(row.contains("function deposit() public {}"));
+        assert!(row.contains("20100"));
+
+        let mut map = map.with_skipped_contracts([contract]);
+        map.functions.clear();
+        map.analyze(&mut traces);
+        assert!(map.render_top_functions(1).is_empty());
+    }
+}
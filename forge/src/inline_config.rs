@@ -0,0 +1,337 @@
+//! Per-test configuration overrides declared in natspec comments
+//!
+//! A test function can override some settings of the active profile by placing
+//! `forge-config: <profile>.<key> = <value>` lines in the doc comment right above it:
+//!
+//! ```solidity
+//! /// forge-config: default.verbosity = 4
+//! /// forge-config: default.gas_report = false
+//! /// forge-config: default.evm_version = "berlin"
+//! /// forge-config: default.isolate = true
+//! function testSomething() public {}
+//! ```
+//!
+//! Only overrides for the currently selected profile are applied.
+
+use crate::natspec::documented_functions;
+use ethers::solc::EvmVersion;
+use foundry_evm::executor::SpecId;
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+/// The marker that starts an inline config item in a natspec comment
+pub const INLINE_CONFIG_PREFIX: &str = "forge-config:";
+
+/// All keys that can be configured inline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlineConfigKey {
+    /// The verbosity the test's logs and traces are displayed with
+    Verbosity,
+    /// Whether the test's calls are included in the gas report
+    GasReport,
+    /// The EVM version the test is executed with
+    EvmVersion,
+    /// Whether the test's calls are executed as separate transactions
+    Isolate,
+}
+
+impl InlineConfigKey {
+    pub const ALL: [InlineConfigKey; 4] = [
+        InlineConfigKey::Verbosity,
+        InlineConfigKey::GasReport,
+        InlineConfigKey::EvmVersion,
+        InlineConfigKey::Isolate,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InlineConfigKey::Verbosity => "verbosity",
+            InlineConfigKey::GasReport => "gas_report",
+            InlineConfigKey::EvmVersion => "evm_version",
+            InlineConfigKey::Isolate => "isolate",
+        }
+    }
+}
+
+impl FromStr for InlineConfigKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        InlineConfigKey::ALL.into_iter().find(|key| key.as_str() == s).ok_or_else(|| {
+            let keys = InlineConfigKey::ALL.map(|key| key.as_str()).join(", ");
+            format!("unknown key, expected one of: {keys}")
+        })
+    }
+}
+
+/// The overrides configured for a single test function
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InlineTestConfig {
+    pub verbosity: Option<u8>,
+    pub gas_report: Option<bool>,
+    pub evm_spec: Option<SpecId>,
+    pub isolate: Option<bool>,
+}
+
+impl InlineTestConfig {
+    /// Validates and applies the given `key = value` item
+    fn set(&mut self, key: InlineConfigKey, value: &str) -> Result<(), String> {
+        let value = value.trim().trim_matches('"');
+        match key {
+            InlineConfigKey::Verbosity => {
+                let verbosity =
+                    value.parse::<u8>().ok().filter(|verbosity| *verbosity <= 5).ok_or_else(
+                        || format!("expected a verbosity between 0 and 5, got `{value}`"),
+                    )?;
+                self.verbosity = Some(verbosity);
+            }
+            InlineConfigKey::GasReport => {
+                let gas_report = value
+                    .parse::<bool>()
+                    .map_err(|_| format!("expected `true` or `false`, got `{value}`"))?;
+                self.gas_report = Some(gas_report);
+            }
+            InlineConfigKey::EvmVersion => {
+                let spec = match value {
+                    // the versions after london are not known to solc yet, the merge is the
+                    // latest hardfork the EVM implements
+                    "paris" | "merge" => SpecId::MERGE,
+                    "shanghai" => SpecId::LATEST,
+                    value => match value.parse::<EvmVersion>()? {
+                        EvmVersion::Homestead => SpecId::HOMESTEAD,
+                        EvmVersion::TangerineWhistle => SpecId::TANGERINE,
+                        EvmVersion::SpuriousDragon => SpecId::SPURIOUS_DRAGON,
+                        EvmVersion::Byzantium => SpecId::BYZANTIUM,
+                        EvmVersion::Constantinople => SpecId::CONSTANTINOPLE,
+                        EvmVersion::Petersburg => SpecId::PETERSBURG,
+                        EvmVersion::Istanbul => SpecId::ISTANBUL,
+                        EvmVersion::Berlin => SpecId::BERLIN,
+                        EvmVersion::London => SpecId::LONDON,
+                    },
+                };
+                self.evm_spec = Some(spec);
+            }
+            InlineConfigKey::Isolate => {
+                let isolate = value
+                    .parse::<bool>()
+                    .map_err(|_| format!("expected `true` or `false`, got `{value}`"))?;
+                self.isolate = Some(isolate);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An invalid inline config item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineConfigError {
+    /// The test the item belongs to in the form of `<contract>::<function>`
+    pub test: String,
+    /// The key of the invalid item, as written in the source
+    pub key: String,
+    /// Why the item is invalid
+    pub reason: String,
+}
+
+impl fmt::Display for InlineConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid inline config for `{}`, key `{}`: {}", self.test, self.key, self.reason)
+    }
+}
+
+impl std::error::Error for InlineConfigError {}
+
+/// The inline config overrides of all tests, `path:Contract -> (function name -> overrides)`
+#[derive(Debug, Clone, Default)]
+pub struct InlineConfig {
+    contracts: BTreeMap<String, BTreeMap<String, InlineTestConfig>>,
+}
+
+impl InlineConfig {
+    /// Returns the overrides of all tests in the contract with the `identifier`, `path:Contract`
+    pub fn contract(&self, identifier: &str) -> Option<&BTreeMap<String, InlineTestConfig>> {
+        self.contracts.get(identifier)
+    }
+
+    /// Returns the overrides of the given test, which can be passed either as function name or
+    /// signature
+    pub fn get(&self, identifier: &str, test: &str) -> Option<&InlineTestConfig> {
+        self.contract(identifier)?.get(function_name(test))
+    }
+
+    /// Parses all inline config items of the solidity `source` at `path` that apply to
+    /// `profile`
+    pub fn parse_source(
+        &mut self,
+        path: &str,
+        source: &str,
+        profile: &str,
+    ) -> Result<(), InlineConfigError> {
+        for function in documented_functions(source) {
+            let test = format!("{}::{}", function.contract, function.name);
+            let mut config = InlineTestConfig::default();
+            for doc in function.docs.iter() {
+                if let Some(item) = doc.strip_prefix(INLINE_CONFIG_PREFIX) {
+                    parse_item(&mut config, item.trim(), profile, &test)?;
+                }
+            }
+            if config != InlineTestConfig::default() {
+                self.contracts
+                    .entry(format!("{path}:{}", function.contract))
+                    .or_default()
+                    .insert(function.name.to_string(), config);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses a single `<profile>.<key> = <value>` item into the `config`, if it applies to `profile`
+fn parse_item(
+    config: &mut InlineTestConfig,
+    item: &str,
+    profile: &str,
+    test: &str,
+) -> Result<(), InlineConfigError> {
+    let err = |key: &str, reason: String| InlineConfigError {
+        test: test.to_string(),
+        key: key.to_string(),
+        reason,
+    };
+
+    let (key, value) = item
+        .split_once('=')
+        .ok_or_else(|| err(item, "expected `<profile>.<key> = <value>`".into()))?;
+    let key = key.trim();
+    let (item_profile, name) =
+        key.split_once('.').ok_or_else(|| err(key, "expected `<profile>.<key>`".into()))?;
+    let name = name.parse::<InlineConfigKey>().map_err(|reason| err(key, reason))?;
+
+    if item_profile != profile {
+        return Ok(())
+    }
+    config.set(name, value).map_err(|reason| err(key, reason))
+}
+
+/// Strips the parameters of a function signature
+fn function_name(signature: &str) -> &str {
+    signature.split('(').next().unwrap_or(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+contract InlineTest {
+    /// forge-config: default.verbosity = 4
+    /// forge-config: default.gas_report = false
+    /// forge-config: ci.verbosity = 1
+    function testFlaky() public {}
+
+    /**
+     * forge-config: default.evm_version = "berlin"
+     * forge-config: default.isolate = true
+     */
+    function testBerlin() public {}
+
+    function testPlain() public {}
+}
+"#;
+
+    #[test]
+    fn parses_inline_config() {
+        let mut config = InlineConfig::default();
+        config.parse_source("test/Inline.t.sol", SOURCE, "default").unwrap();
+
+        let flaky = config.get("test/Inline.t.sol:InlineTest", "testFlaky()").unwrap();
+        assert_eq!(flaky.verbosity, Some(4));
+        assert_eq!(flaky.gas_report, Some(false));
+        assert_eq!(flaky.evm_spec, None);
+
+        let berlin = config.get("test/Inline.t.sol:InlineTest", "testBerlin").unwrap();
+        assert_eq!(berlin.evm_spec, Some(SpecId::BERLIN));
+        assert_eq!(berlin.isolate, Some(true));
+
+        assert!(config.get("test/Inline.t.sol:InlineTest", "testPlain").is_none());
+    }
+
+    #[test]
+    fn keys_overrides_by_source() {
+        let mut config = InlineConfig::default();
+        config.parse_source("test/Inline.t.sol", SOURCE, "default").unwrap();
+        let other = r#"
+contract InlineTest {
+    /** forge-config: default.verbosity = 5 */
+    function testFlaky() public {}
+}
+"#;
+        config.parse_source("test/Other.t.sol", other, "default").unwrap();
+
+        let flaky = config.get("test/Inline.t.sol:InlineTest", "testFlaky").unwrap();
+        assert_eq!(flaky.verbosity, Some(4));
+        let flaky = config.get("test/Other.t.sol:InlineTest", "testFlaky").unwrap();
+        assert_eq!(flaky.verbosity, Some(5));
+        assert_eq!(flaky.gas_report, None);
+        assert!(config.get("test/Other.t.sol:InlineTest", "testBerlin").is_none());
+    }
+
+    #[test]
+    fn only_applies_selected_profile() {
+        let mut config = InlineConfig::default();
+        config.parse_source("test/Inline.t.sol", SOURCE, "ci").unwrap();
+
+        let flaky = config.get("test/Inline.t.sol:InlineTest", "testFlaky").unwrap();
+        assert_eq!(flaky.verbosity, Some(1));
+        assert_eq!(flaky.gas_report, None);
+        assert!(config.get("test/Inline.t.sol:InlineTest", "testBerlin").is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_items() {
+        let source = r#"
+contract InlineTest {
+    /// forge-config: default.gas_reprt = false
+    function testTypo() public {}
+}
+"#;
+        let err = InlineConfig::default()
+            .parse_source("test/Inline.t.sol", source, "default")
+            .unwrap_err();
+        assert_eq!(err.test, "InlineTest::testTypo");
+        assert_eq!(err.key, "default.gas_reprt");
+
+        let source = r#"
+contract InlineTest {
+    /// forge-config: default.verbosity = loud
+    function testVerbosity() public {}
+}
+"#;
+        let err = InlineConfig::default()
+            .parse_source("test/Inline.t.sol", source, "default")
+            .unwrap_err();
+        assert_eq!(err.key, "default.verbosity");
+    }
+
+    #[test]
+    fn accepts_all_evm_versions() {
+        let versions = [
+            ("homestead", SpecId::HOMESTEAD),
+            ("tangerineWhistle", SpecId::TANGERINE),
+            ("spuriousDragon", SpecId::SPURIOUS_DRAGON),
+            ("byzantium", SpecId::BYZANTIUM),
+            ("constantinople", SpecId::CONSTANTINOPLE),
+            ("petersburg", SpecId::PETERSBURG),
+            ("istanbul", SpecId::ISTANBUL),
+            ("berlin", SpecId::BERLIN),
+            ("london", SpecId::LONDON),
+            ("paris", SpecId::MERGE),
+            ("shanghai", SpecId::LATEST),
+        ];
+        for (version, spec) in versions {
+            let mut config = InlineTestConfig::default();
+            config.set(InlineConfigKey::EvmVersion, &format!("\"{version}\"")).unwrap();
+            assert_eq!(config.evm_spec, Some(spec), "{version}");
+        }
+        assert!(InlineTestConfig::default().set(InlineConfigKey::EvmVersion, "cancun").is_err());
+    }
+}
@@ -7,6 +7,36 @@ pub mod gas_report;
 /// Coverage reports
 pub mod coverage;
 
+/// Memory usage reports
+pub mod memory_report;
+
+/// Per-test config overrides
+pub mod inline_config;
+
+/// Gas budgets declared in natspec comments
+pub mod gas_budget;
+
+/// Scanning of the natspec comments of functions
+mod natspec;
+
+/// Mutations of sources for mutation testing
+pub mod mutation;
+
+/// Known inputs of fuzz tests
+pub mod fuzz_inputs;
+
+/// Detection of public functions that are never called by the tests
+pub mod untested;
+
+/// Folded call stacks of the gas used by traces, for flamegraphs
+pub mod flamegraph;
+
+/// Attribution of the gas of instructions to source lines
+pub mod gas_source_map;
+
+/// The outcomes of previous test runs, to run the failed suites first
+pub mod test_history;
+
 /// The Forge test runner
 mod runner;
 use ethers::types::U256;
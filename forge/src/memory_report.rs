@@ -0,0 +1,138 @@
+use crate::{executor::inspector::MemoryStats, result::TestResult};
+use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, *};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt};
+
+/// The memory usage of a test, aggregated over all of its runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TestMemory {
+    /// The number of runs, `1` unless the test is fuzzed
+    pub runs: usize,
+    /// The lowest peak memory, in bytes, of all runs
+    pub min_peak: usize,
+    /// The mean peak memory, in bytes, of all runs
+    pub mean_peak: usize,
+    /// The highest peak memory, in bytes, of all runs
+    pub max_peak: usize,
+    /// The mean number of `MSTORE` and `MSTORE8` instructions per run
+    pub mstores: u64,
+    /// The mean number of `MLOAD` instructions per run
+    pub mloads: u64,
+}
+
+impl TestMemory {
+    /// Aggregates the memory usage of the runs of a test, returns `None` if there are no runs
+    pub fn new(runs: &[MemoryStats]) -> Option<Self> {
+        let min_peak = runs.iter().map(|stats| stats.peak).min()?;
+        let max_peak = runs.iter().map(|stats| stats.peak).max()?;
+        let count = runs.len();
+        Some(Self {
+            runs: count,
+            min_peak,
+            mean_peak: runs.iter().map(|stats| stats.peak).sum::<usize>() / count,
+            max_peak,
+            mstores: runs.iter().map(|stats| stats.mstores).sum::<u64>() / count as u64,
+            mloads: runs.iter().map(|stats| stats.mloads).sum::<u64>() / count as u64,
+        })
+    }
+}
+
+impl fmt::Display for TestMemory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.runs == 1 {
+            write!(f, "(memory: {} bytes", self.max_peak)?;
+        } else {
+            write!(
+                f,
+                "(memory min: {} bytes, μ: {} bytes, max: {} bytes",
+                self.min_peak, self.mean_peak, self.max_peak
+            )?;
+        }
+        write!(f, ", mstores: {}, mloads: {})", self.mstores, self.mloads)
+    }
+}
+
+/// The memory usage of all tests that collected it, by contract and test name
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct MemoryReport {
+    pub contracts: BTreeMap<String, BTreeMap<String, TestMemory>>,
+}
+
+impl MemoryReport {
+    /// Adds the memory usage of the test `name` of the `contract`, if it was collected
+    pub fn add_test(&mut self, contract: &str, name: &str, result: &TestResult) {
+        if let Some(memory) = TestMemory::new(&result.memory) {
+            self.contracts
+                .entry(contract.to_string())
+                .or_default()
+                .insert(name.to_string(), memory);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contracts.is_empty()
+    }
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, tests) in self.contracts.iter() {
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
+            table.set_header(vec![
+                Cell::new(format!("{name} memory")).add_attribute(Attribute::Bold).fg(Color::Green),
+                Cell::new("runs").add_attribute(Attribute::Bold).fg(Color::Cyan),
+                Cell::new("min").add_attribute(Attribute::Bold).fg(Color::Green),
+                Cell::new("avg").add_attribute(Attribute::Bold).fg(Color::Yellow),
+                Cell::new("max").add_attribute(Attribute::Bold).fg(Color::Red),
+                Cell::new("mstores").add_attribute(Attribute::Bold).fg(Color::Cyan),
+                Cell::new("mloads").add_attribute(Attribute::Bold).fg(Color::Cyan),
+            ]);
+            for (test, memory) in tests.iter() {
+                table.add_row(vec![
+                    Cell::new(test).add_attribute(Attribute::Bold),
+                    Cell::new(memory.runs.to_string()),
+                    Cell::new(memory.min_peak.to_string()).fg(Color::Green),
+                    Cell::new(memory.mean_peak.to_string()).fg(Color::Yellow),
+                    Cell::new(memory.max_peak.to_string()).fg(Color::Red),
+                    Cell::new(memory.mstores.to_string()),
+                    Cell::new(memory.mloads.to_string()),
+                ]);
+            }
+            writeln!(f, "{}", table)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_fuzz_runs() {
+        let runs = [
+            MemoryStats { peak: 64, mstores: 2, mloads: 1 },
+            MemoryStats { peak: 128, mstores: 4, mloads: 3 },
+            MemoryStats { peak: 288, mstores: 6, mloads: 5 },
+        ];
+        let memory = TestMemory::new(&runs).unwrap();
+        assert_eq!(
+            memory,
+            TestMemory {
+                runs: 3,
+                min_peak: 64,
+                mean_peak: 160,
+                max_peak: 288,
+                mstores: 4,
+                mloads: 3
+            }
+        );
+        assert_eq!(
+            memory.to_string(),
+            "(memory min: 64 bytes, μ: 160 bytes, max: 288 bytes, mstores: 4, mloads: 3)"
+        );
+
+        assert_eq!(TestMemory::new(&[]), None);
+    }
+}
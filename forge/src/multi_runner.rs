@@ -1,4 +1,7 @@
-use crate::{result::SuiteResult, ContractRunner, TestFilter, TestOptions};
+use crate::{
+    fuzz_inputs::FuzzInputs, inline_config::InlineConfig, result::SuiteResult,
+    test_history::TestHistory, ContractRunner, TestFilter, TestOptions,
+};
 use ethers::{
     abi::Abi,
     prelude::{artifacts::CompactContractBytecode, ArtifactId, ArtifactOutput},
@@ -7,16 +10,24 @@ use ethers::{
 };
 use eyre::Result;
 use foundry_common::TestFunctionExt;
+use foundry_config::Config;
 use foundry_evm::{
     executor::{
-        backend::Backend, fork::CreateFork, inspector::CheatsConfig, opts::EvmOpts, Executor,
-        ExecutorBuilder, SpecId,
+        backend::Backend,
+        fork::{CreateFork, ForkAccessLog},
+        inspector::CheatsConfig,
+        opts::EvmOpts,
+        Executor, ExecutorBuilder, SpecId,
     },
     revm,
 };
 use foundry_utils::PostLinkInput;
 use rayon::prelude::*;
-use std::{collections::BTreeMap, path::Path, sync::mpsc::Sender};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::mpsc::Sender,
+};
 
 pub type DeployableContracts = BTreeMap<ArtifactId, (Abi, Bytes, Vec<Bytes>)>;
 
@@ -46,8 +57,28 @@ pub struct MultiContractRunner {
     pub cheats_config: CheatsConfig,
     /// Whether to collect coverage info
     pub coverage: bool,
+    /// Whether to collect the memory usage of the tests
+    pub memory_report: bool,
+    /// Whether to record the gas that the access list of the transaction saves in the traces
+    pub access_list_savings: bool,
+    /// Whether to record the gas that the calls spend on expanding their memory in the traces
+    pub memory_gas: bool,
+    /// Whether to record the gas of the instructions by the kind of instruction in the traces
+    pub gas_categories: bool,
+    /// Whether to record the gas of the instructions by their program counter in the traces
+    pub gas_by_pc: bool,
     /// Settings related to fuzz and/or invariant tests
     pub test_options: TestOptions,
+    /// Per-test overrides declared in natspec comments
+    pub inline_config: InlineConfig,
+    /// Known inputs that are run before fuzzing a test
+    pub fuzz_inputs: Option<FuzzInputs>,
+    /// The file with the accounts and storage slots of the fork accessed by the previous run,
+    /// which are prefetched, and that the accesses of this run are written to
+    pub fork_prefetch: Option<PathBuf>,
+    /// The outcomes of previous runs, which the suites are ordered by if set, see
+    /// [`TestHistory::schedule()`]
+    pub history: Option<TestHistory>,
 }
 
 impl MultiContractRunner {
@@ -113,7 +144,9 @@ impl MultiContractRunner {
     /// This will create the runtime based on the configured `evm` ops and create the `Backend`
     /// before executing all contracts and their tests in _parallel_.
     ///
-    /// Each Executor gets its own instance of the `Backend`.
+    /// Each Executor gets its own instance of the `Backend`. If the runner has a
+    /// [`TestHistory`], the suites that failed in their last run are started before the others,
+    /// both ordered by their average duration, shortest first.
     pub fn test(
         &mut self,
         filter: &impl TestFilter,
@@ -121,68 +154,94 @@ impl MultiContractRunner {
         test_options: TestOptions,
     ) -> Result<BTreeMap<String, SuiteResult>> {
         let db = Backend::spawn(self.fork.take());
+        let fork_db = db.active_fork_db().map(|fork| fork.db.clone());
+        if let (Some(fork_db), Some(path)) = (&fork_db, &self.fork_prefetch) {
+            if path.is_file() {
+                fork_db.prefetch(ForkAccessLog::read(path)?)?;
+            }
+        }
+
+        let mut suites = self
+            .contracts
+            .iter()
+            .filter(|(id, _)| {
+                filter.matches_path(id.source.to_string_lossy()) &&
+                    filter.matches_contract(&id.name)
+            })
+            .filter(|(_, (abi, _, _))| abi.functions().any(|func| filter.matches_test(&func.name)))
+            .collect::<Vec<_>>();
+        if let Some(history) = &self.history {
+            suites =
+                history.schedule(suites.into_iter().map(|suite| (suite.0.identifier(), suite)));
+        }
+
+        // the suites are started in their scheduled order
+        let results = suites
+            .into_iter()
+            .par_bridge()
+            .map(|(id, (abi, deploy_code, libs))| {
+                let identifier = id.identifier();
+                let inline_config = self.inline_config.contract(&identifier);
+                // tests with an increased inline verbosity need traces as well
+                let enable_tracing = self.evm_opts.verbosity >= 3 ||
+                    inline_config
+                        .map(|tests| {
+                            tests.values().any(|test| test.verbosity.unwrap_or_default() >= 3)
+                        })
+                        .unwrap_or_default();
+                let executor = ExecutorBuilder::default()
+                    .with_cheatcodes(self.cheats_config.clone())
+                    .with_config(self.env.clone())
+                    .with_spec(self.evm_spec)
+                    .with_gas_limit(self.evm_opts.gas_limit())
+                    .set_tracing(enable_tracing)
+                    .set_coverage(self.coverage)
+                    .set_memory_report(self.memory_report)
+                    .set_access_list_savings(self.access_list_savings)
+                    .set_memory_gas(self.memory_gas)
+                    .set_gas_categories(self.gas_categories)
+                    .set_gas_by_pc(self.gas_by_pc)
+                    .build(db.clone());
+                tracing::trace!(contract= ?identifier, "start executing all tests in contract");
+
+                let result = self.run_tests(
+                    &identifier,
+                    abi,
+                    executor,
+                    deploy_code.clone(),
+                    libs,
+                    (filter, test_options),
+                )?;
+
+                tracing::trace!(contract= ?identifier, "executed all tests in contract");
+                Ok((identifier, result))
+            })
+            .filter_map(Result::<_>::ok)
+            .filter(|(_, results)| !results.is_empty())
+            .map_with(stream_result, |stream_result, (name, result)| {
+                if let Some(stream_result) = stream_result.as_ref() {
+                    stream_result.send((name.clone(), result.clone())).unwrap();
+                }
+                (name, result)
+            })
+            .collect::<BTreeMap<_, _>>();
 
-        let results =
-            // the db backend that serves all the data, each contract gets its own instance
-
-             self
-                .contracts
-                .par_iter()
-                .filter(|(id, _)| {
-                    filter.matches_path(id.source.to_string_lossy()) &&
-                        filter.matches_contract(&id.name)
-                })
-                .filter(|(_, (abi, _, _))| {
-                    abi.functions().any(|func| filter.matches_test(&func.name))
-                })
-                .map(|(id, (abi, deploy_code, libs))| {
-                    let executor = ExecutorBuilder::default()
-                        .with_cheatcodes(self.cheats_config.clone())
-                        .with_config(self.env.clone())
-                        .with_spec(self.evm_spec)
-                        .with_gas_limit(self.evm_opts.gas_limit())
-                        .set_tracing(self.evm_opts.verbosity >= 3)
-                        .set_coverage(self.coverage)
-                        .build(db.clone());
-                    let identifier = id.identifier();
-                    tracing::trace!(contract= ?identifier, "start executing all tests in contract");
-
-                    let result = self.run_tests(
-                        &identifier,
-                        abi,
-                        executor,
-                        deploy_code.clone(),
-                        libs,
-                        (filter, test_options),
-                    )?;
-
-                    tracing::trace!(contract= ?identifier, "executed all tests in contract");
-                    Ok((identifier, result))
-                })
-                .filter_map(Result::<_>::ok)
-                .filter(|(_, results)| !results.is_empty())
-                .map_with(stream_result, |stream_result, (name, result)| {
-                    if let Some(stream_result) = stream_result.as_ref() {
-                        stream_result.send((name.clone(), result.clone())).unwrap();
-                    }
-                    (name, result)
-                })
-                .collect::<BTreeMap<_, _>>()
-        ;
+        if let (Some(fork_db), Some(path)) = (&fork_db, &self.fork_prefetch) {
+            fork_db.access_log().write(path)?;
+        }
 
         Ok(results)
     }
 
-    // The _name field is unused because we only want it for tracing
     #[tracing::instrument(
         name = "contract",
         skip_all,
         err,
-        fields(name = %_name)
+        fields(name = %name)
     )]
     fn run_tests(
         &self,
-        _name: &str,
+        name: &str,
         contract: &Abi,
         executor: Executor,
         deploy_code: Bytes,
@@ -197,6 +256,8 @@ impl MultiContractRunner {
             self.sender,
             self.errors.as_ref(),
             libs,
+            self.inline_config.contract(name),
+            self.fuzz_inputs.as_ref(),
         );
         runner.run_tests(filter, test_options, Some(&self.known_contracts))
     }
@@ -218,8 +279,27 @@ pub struct MultiContractRunnerBuilder {
     pub cheats_config: Option<CheatsConfig>,
     /// Whether or not to collect coverage info
     pub coverage: bool,
+    /// Whether or not to collect the memory usage of the tests
+    pub memory_report: bool,
+    /// Whether or not to record the gas that the access list of the transaction saves in the
+    /// traces
+    pub access_list_savings: bool,
+    /// Whether or not to record the gas that the calls spend on expanding their memory in the
+    /// traces
+    pub memory_gas: bool,
+    /// Whether or not to record the gas of the instructions by the kind of instruction in the
+    /// traces
+    pub gas_categories: bool,
+    /// Whether or not to record the gas of the instructions by their program counter in the traces
+    pub gas_by_pc: bool,
     /// Settings related to fuzz and/or invariant tests
     pub test_options: Option<TestOptions>,
+    /// Known inputs that are run before fuzzing a test
+    pub fuzz_inputs: Option<FuzzInputs>,
+    /// The access log of the fork to prefetch and update
+    pub fork_prefetch: Option<PathBuf>,
+    /// The outcomes of previous runs to order the suites by
+    pub history: Option<TestHistory>,
 }
 
 impl MultiContractRunnerBuilder {
@@ -235,6 +315,7 @@ impl MultiContractRunnerBuilder {
     where
         A: ArtifactOutput,
     {
+        let root = root.as_ref();
         // This is just the contracts compiled, but we need to merge this with the read cached
         // artifacts
         let contracts = output
@@ -305,6 +386,17 @@ impl MultiContractRunnerBuilder {
             },
         )?;
 
+        // parse the inline config of all test contracts
+        let mut inline_config = InlineConfig::default();
+        let profile = Config::selected_profile();
+        let test_sources: BTreeSet<_> = deployable_contracts.keys().map(|id| &id.source).collect();
+        for source in test_sources {
+            if let Ok(content) = std::fs::read_to_string(root.join(source)) {
+                let path = source.to_string_lossy();
+                inline_config.parse_source(&path, &content, profile.as_str().as_str())?;
+            }
+        }
+
         let execution_info = foundry_utils::flatten_known_contracts(&known_contracts);
         Ok(MultiContractRunner {
             contracts: deployable_contracts,
@@ -318,7 +410,16 @@ impl MultiContractRunnerBuilder {
             fork: self.fork,
             cheats_config: self.cheats_config.unwrap_or_default(),
             coverage: self.coverage,
+            memory_report: self.memory_report,
+            access_list_savings: self.access_list_savings,
+            memory_gas: self.memory_gas,
+            gas_categories: self.gas_categories,
+            gas_by_pc: self.gas_by_pc,
             test_options: self.test_options.unwrap_or_default(),
+            inline_config,
+            fuzz_inputs: self.fuzz_inputs,
+            fork_prefetch: self.fork_prefetch,
+            history: self.history,
         })
     }
 
@@ -346,6 +447,22 @@ impl MultiContractRunnerBuilder {
         self
     }
 
+    /// Prefetches the accounts and storage slots of the fork listed in the access log at `path`,
+    /// if it exists, and writes the accesses of the run to it
+    #[must_use]
+    pub fn with_fork_prefetch(mut self, path: Option<PathBuf>) -> Self {
+        self.fork_prefetch = path;
+        self
+    }
+
+    /// Runs the suites that failed in their last run first, each group ordered by their average
+    /// duration, see [`TestHistory::schedule()`]
+    #[must_use]
+    pub fn with_history(mut self, history: Option<TestHistory>) -> Self {
+        self.history = history;
+        self
+    }
+
     #[must_use]
     pub fn with_cheats_config(mut self, cheats_config: CheatsConfig) -> Self {
         self.cheats_config = Some(cheats_config);
@@ -358,9 +475,45 @@ impl MultiContractRunnerBuilder {
         self
     }
 
+    #[must_use]
+    pub fn with_fuzz_inputs(mut self, fuzz_inputs: Option<FuzzInputs>) -> Self {
+        self.fuzz_inputs = fuzz_inputs;
+        self
+    }
+
     #[must_use]
     pub fn set_coverage(mut self, enable: bool) -> Self {
         self.coverage = enable;
         self
     }
+
+    #[must_use]
+    pub fn set_memory_report(mut self, enable: bool) -> Self {
+        self.memory_report = enable;
+        self
+    }
+
+    #[must_use]
+    pub fn set_access_list_savings(mut self, enable: bool) -> Self {
+        self.access_list_savings = enable;
+        self
+    }
+
+    #[must_use]
+    pub fn set_memory_gas(mut self, enable: bool) -> Self {
+        self.memory_gas = enable;
+        self
+    }
+
+    #[must_use]
+    pub fn set_gas_categories(mut self, enable: bool) -> Self {
+        self.gas_categories = enable;
+        self
+    }
+
+    #[must_use]
+    pub fn set_gas_by_pc(mut self, enable: bool) -> Self {
+        self.gas_by_pc = enable;
+        self
+    }
 }
@@ -0,0 +1,268 @@
+//! Mutations of solidity sources for mutation testing
+//!
+//! A mutant is a copy of a source with a single small change, like `>` replaced with `>=`. Tests
+//! that still pass on a mutant do not notice the change, the mutant survived them.
+
+use foundry_config::MutationOperator;
+use std::{fmt, ops::Range};
+
+/// A single change of a source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mutant {
+    /// The kind of the change
+    pub operator: MutationOperator,
+    /// The byte range of the source that is replaced
+    pub span: Range<usize>,
+    /// The code of the span in the original source
+    pub original: String,
+    /// The code the span is replaced with, empty if it is removed
+    pub replacement: String,
+    /// The line of the change, starting at 1
+    pub line: usize,
+}
+
+impl Mutant {
+    /// Returns the `source` with this change applied
+    pub fn apply(&self, source: &str) -> String {
+        format!("{}{}{}", &source[..self.span.start], self.replacement, &source[self.span.end..])
+    }
+}
+
+impl fmt::Display for Mutant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let original = self.original.split_whitespace().collect::<Vec<_>>().join(" ");
+        if self.replacement.is_empty() {
+            write!(f, "removed `{original}`")
+        } else {
+            write!(f, "replaced `{original}` with `{}`", self.replacement)
+        }
+    }
+}
+
+/// Returns all mutants of the `source` that the `operators` produce, in the order of their
+/// position in the source
+///
+/// Comments, string literals and `pragma` and `import` directives are never mutated.
+pub fn mutants(source: &str, operators: &[MutationOperator]) -> Vec<Mutant> {
+    let code = code_only(source);
+    let mut mutants = Vec::new();
+    for operator in operators {
+        let found = match operator {
+            MutationOperator::Arithmetic => arithmetic(&code),
+            MutationOperator::Boundary => boundary(&code),
+            MutationOperator::RequireRemoval => require_removal(&code),
+        };
+        mutants.extend(found.into_iter().map(|(span, replacement)| Mutant {
+            operator: *operator,
+            original: source[span.clone()].to_string(),
+            line: source[..span.start].matches('\n').count() + 1,
+            span,
+            replacement: replacement.to_string(),
+        }));
+    }
+    mutants.sort_by_key(|mutant| mutant.span.start);
+    mutants
+}
+
+/// Returns the replacements of arithmetic operators, `+` with `-`, `*` with `/` and the other way
+/// around
+fn arithmetic(code: &[u8]) -> Vec<(Range<usize>, &'static str)> {
+    let mut found = Vec::new();
+    for (i, byte) in code.iter().copied().enumerate() {
+        let replacement = match byte {
+            b'+' => "-",
+            b'-' => "+",
+            b'*' => "/",
+            b'/' => "*",
+            _ => continue,
+        };
+        let prev = i.checked_sub(1).map(|prev| code[prev]);
+        let next = code.get(i + 1).copied();
+        // skips compound operators like `++`, `+=`, `**` and `->`
+        if matches!(next, Some(b'=' | b'+' | b'-' | b'*' | b'/' | b'>')) ||
+            matches!(prev, Some(b'+' | b'-' | b'*' | b'/'))
+        {
+            continue
+        }
+        // skips unary operators, a binary operator follows an operand
+        let operand = code[..i].iter().rev().copied().find(|byte| !byte.is_ascii_whitespace());
+        if !matches!(operand, Some(byte) if is_ident(byte) || byte == b')' || byte == b']') {
+            continue
+        }
+        found.push((i..i + 1, replacement));
+    }
+    found
+}
+
+/// Returns the replacements of comparisons, `<` with `<=`, `>` with `>=` and the other way around
+fn boundary(code: &[u8]) -> Vec<(Range<usize>, &'static str)> {
+    let mut found = Vec::new();
+    for (i, byte) in code.iter().copied().enumerate() {
+        if !matches!(byte, b'<' | b'>') {
+            continue
+        }
+        let prev = i.checked_sub(1).map(|prev| code[prev]);
+        let next = code.get(i + 1).copied();
+        // skips shifts, `=>` and `->`
+        if matches!(prev, Some(b'<' | b'>' | b'=' | b'-')) || matches!(next, Some(b'<' | b'>')) {
+            continue
+        }
+        let (span, replacement) = match (byte, next) {
+            (b'<', Some(b'=')) => (i..i + 2, "<"),
+            (b'>', Some(b'=')) => (i..i + 2, ">"),
+            (b'<', _) => (i..i + 1, "<="),
+            _ => (i..i + 1, ">="),
+        };
+        found.push((span, replacement));
+    }
+    found
+}
+
+/// Returns the removals of `require(..);` statements
+fn require_removal(code: &[u8]) -> Vec<(Range<usize>, &'static str)> {
+    const REQUIRE: &[u8] = b"require";
+    let mut found = Vec::new();
+    let mut start = 0;
+    while let Some(offset) = find(&code[start..], REQUIRE) {
+        let i = start + offset;
+        start = i + REQUIRE.len();
+        let is_word = (i == 0 || !is_ident(code[i - 1])) &&
+            code.get(start).map(|byte| !is_ident(*byte)).unwrap_or(true);
+        if !is_word {
+            continue
+        }
+        let open = match code[start..].iter().position(|byte| !byte.is_ascii_whitespace()) {
+            Some(open) if code[start + open] == b'(' => start + open,
+            _ => continue,
+        };
+        let mut depth = 0;
+        let close = code[open..].iter().position(|byte| {
+            match byte {
+                b'(' => depth += 1,
+                b')' => depth -= 1,
+                _ => {}
+            }
+            depth == 0
+        });
+        let end = match close.map(|close| open + close + 1) {
+            Some(end) => end,
+            None => continue,
+        };
+        match code[end..].iter().position(|byte| !byte.is_ascii_whitespace()) {
+            Some(semicolon) if code[end + semicolon] == b';' => {
+                found.push((i..end + semicolon + 1, ""));
+                start = end + semicolon + 1;
+            }
+            _ => continue,
+        }
+    }
+    found
+}
+
+/// Returns the `source` with the comments, string literals and `pragma` and `import` directives
+/// replaced by spaces, so that only code is left at the original positions
+fn code_only(source: &str) -> Vec<u8> {
+    let mut code = source.as_bytes().to_vec();
+    let mut i = 0;
+    while i < code.len() {
+        let rest = &code[i..];
+        let is_directive = (i == 0 || !is_ident(code[i - 1])) &&
+            (rest.starts_with(b"pragma ") || rest.starts_with(b"import "));
+        let end = if rest.starts_with(b"//") {
+            find(rest, b"\n").map(|end| i + end).unwrap_or(code.len())
+        } else if rest.starts_with(b"/*") {
+            find(&rest[2..], b"*/").map(|end| i + end + 4).unwrap_or(code.len())
+        } else if is_directive {
+            find(rest, b";").map(|end| i + end + 1).unwrap_or(code.len())
+        } else if let quote @ (b'"' | b'\'') = rest[0] {
+            let mut end = i + 1;
+            while end < code.len() && code[end] != quote {
+                end += if code[end] == b'\\' { 2 } else { 1 };
+            }
+            (end + 1).min(code.len())
+        } else {
+            i += 1;
+            continue
+        };
+        code[i..end].iter_mut().filter(|byte| **byte != b'\n').for_each(|byte| *byte = b' ');
+        i = end;
+    }
+    code
+}
+
+/// Returns the position of the first occurrence of `needle` in `haystack`
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Whether the byte can be part of an identifier or a number
+fn is_ident(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"// SPDX-License-Identifier: UNLICENSED
+pragma solidity >=0.8.0;
+import "./Token.sol";
+
+contract Vault {
+    mapping(address => uint256) balances;
+
+    /// deposits a - b
+    function withdraw(uint256 amount) public {
+        require(balances[msg.sender] >= amount, "x > y");
+        balances[msg.sender] = balances[msg.sender] - amount * 2;
+        for (uint256 i = 0; i < 10; i++) {
+            amount += i << 1;
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn mutates_arithmetic_operators() {
+        let mutants = mutants(SOURCE, &[MutationOperator::Arithmetic]);
+        let changes =
+            mutants.iter().map(|mutant| (mutant.line, mutant.to_string())).collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            [(11, "replaced `-` with `+`".to_string()), (11, "replaced `*` with `/`".to_string())]
+        );
+        assert!(mutants[0].apply(SOURCE).contains("balances[msg.sender] + amount * 2;"));
+    }
+
+    #[test]
+    fn mutates_boundaries() {
+        let mutants = mutants(SOURCE, &[MutationOperator::Boundary]);
+        let changes =
+            mutants.iter().map(|mutant| (mutant.line, mutant.to_string())).collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            [(10, "replaced `>=` with `>`".to_string()), (12, "replaced `<` with `<=`".to_string())]
+        );
+        assert!(mutants[1].apply(SOURCE).contains("i <= 10;"));
+    }
+
+    #[test]
+    fn removes_requires() {
+        let mutants = mutants(SOURCE, &[MutationOperator::RequireRemoval]);
+        assert_eq!(mutants.len(), 1);
+        assert_eq!(mutants[0].line, 10);
+        assert_eq!(
+            mutants[0].to_string(),
+            r#"removed `require(balances[msg.sender] >= amount, "x > y");`"#
+        );
+        assert!(!mutants[0].apply(SOURCE).contains("require"));
+    }
+
+    #[test]
+    fn orders_mutants_by_position() {
+        let mutants = mutants(SOURCE, &MutationOperator::ALL);
+        assert_eq!(mutants.len(), 5);
+        assert!(mutants.windows(2).all(|pair| pair[0].span.start < pair[1].span.start));
+        assert_eq!(mutants[0].operator, MutationOperator::RequireRemoval);
+    }
+}
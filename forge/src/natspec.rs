@@ -0,0 +1,179 @@
+//! Scanning of the natspec comments above the functions of solidity sources
+
+/// A function declared in a solidity source with the natspec comment right above it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DocumentedFunction<'a> {
+    /// The name of the contract, library or interface that declares the function
+    pub contract: &'a str,
+    /// The name of the function
+    pub name: &'a str,
+    /// The canonical signature of the function, e.g. `deposit(uint256)`, `None` if the type of a
+    /// parameter is user-defined and can not be resolved from the source alone
+    pub signature: Option<String>,
+    /// The lines of the doc comment without their comment markers, empty lines are left out
+    pub docs: Vec<&'a str>,
+}
+
+/// Returns all functions declared in the `source` with their doc comments, in the order of their
+/// declarations.
+///
+/// Doc comments are `///` lines and `/** */` blocks, including single-line blocks like
+/// `/** @custom:gas-budget 50000 */`. A comment documents the declaration right after it, any other
+/// line in between discards it.
+pub(crate) fn documented_functions(source: &str) -> Vec<DocumentedFunction<'_>> {
+    let mut functions = Vec::new();
+    let mut contract = "";
+    let mut docs = Vec::new();
+    // the parameters of the last declared function, collected until they are closed
+    let mut params: Option<String> = None;
+
+    for line in source.lines() {
+        let line = line.trim();
+        if let Some(collected) = params.as_mut() {
+            collected.push(' ');
+            collected.push_str(line);
+            close_params(&mut functions, &mut params);
+            continue
+        }
+
+        if line.starts_with("*/") {
+            continue
+        }
+        let comment = line
+            .strip_prefix("///")
+            .or_else(|| line.strip_prefix("/**"))
+            .or_else(|| line.strip_prefix('*'));
+        if let Some(comment) = comment {
+            let comment = comment.trim_end().strip_suffix("*/").unwrap_or(comment).trim();
+            if !comment.is_empty() {
+                docs.push(comment);
+            }
+            continue
+        }
+
+        if let Some(name) = ["contract", "library", "interface"]
+            .into_iter()
+            .find_map(|kind| declared_name(line, kind))
+        {
+            contract = name;
+        } else if let Some(name) = declared_name(line, "function") {
+            functions.push(DocumentedFunction {
+                contract,
+                name,
+                signature: None,
+                docs: std::mem::take(&mut docs),
+            });
+            params = line.split_once('(').map(|(_, params)| params.to_string());
+            close_params(&mut functions, &mut params);
+        }
+
+        if !line.is_empty() {
+            docs.clear();
+        }
+    }
+
+    functions
+}
+
+/// Sets the signature of the last of the `functions` once its collected `params` are closed
+fn close_params(functions: &mut [DocumentedFunction<'_>], params: &mut Option<String>) {
+    let end = match params.as_ref().and_then(|params| params.find(')')) {
+        Some(end) => end,
+        None => return,
+    };
+    let params = params.take().expect("params are collected");
+    if let Some(function) = functions.last_mut() {
+        let types = params[..end]
+            .split(',')
+            .map(str::trim)
+            .filter(|param| !param.is_empty())
+            .map(canonical_type)
+            .collect::<Option<Vec<_>>>();
+        function.signature = types.map(|types| format!("{}({})", function.name, types.join(",")));
+    }
+}
+
+/// Returns the canonical ABI type of a parameter declaration, e.g. `uint256[]` for
+/// `uint[] memory amounts`, `None` for user-defined types
+fn canonical_type(param: &str) -> Option<String> {
+    let ty = param.split_whitespace().next()?;
+    let (base, array) = ty.find('[').map_or((ty, ""), |idx| ty.split_at(idx));
+    let base = match base {
+        "uint" => "uint256",
+        "int" => "int256",
+        "byte" => "bytes1",
+        "address" | "bool" | "string" | "bytes" => base,
+        _ => {
+            let bits = base
+                .strip_prefix("uint")
+                .or_else(|| base.strip_prefix("int"))
+                .or_else(|| base.strip_prefix("bytes"))?;
+            bits.parse::<u16>().ok()?;
+            base
+        }
+    };
+    Some(format!("{base}{array}"))
+}
+
+/// Returns the name of the `kind` (e.g. `contract` or `function`) declared on this line, if any
+fn declared_name<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+    let line = line.strip_prefix("abstract ").unwrap_or(line);
+    let name = line.strip_prefix(kind)?.strip_prefix(' ')?.trim_start();
+    let end =
+        name.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$')).unwrap_or(name.len());
+    if end == 0 {
+        return None
+    }
+    Some(&name[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scans_documented_functions() {
+        let source = r#"
+library Math {
+    /** @custom:gas-budget 500 */
+    function max(uint a, uint b) internal pure returns (uint) {}
+}
+
+abstract contract Vault {
+    /// @notice Deposits the tokens
+    /// @custom:gas-budget 50000
+    function deposit(uint256 amount) public {}
+
+    /**
+     * @custom:gas-budget 1_000
+     */
+    function deposit(
+        uint256[] memory amounts,
+        address payable to
+    ) public {}
+
+    // not a doc comment
+    function withdraw(IERC20 token) public {}
+}
+"#;
+        let functions = documented_functions(source);
+        let summary = functions
+            .iter()
+            .map(|func| (func.contract, func.signature.as_deref(), func.docs.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                ("Math", Some("max(uint256,uint256)"), vec!["@custom:gas-budget 500"]),
+                (
+                    "Vault",
+                    Some("deposit(uint256)"),
+                    vec!["@notice Deposits the tokens", "@custom:gas-budget 50000"]
+                ),
+                ("Vault", Some("deposit(uint256[],address)"), vec!["@custom:gas-budget 1_000"]),
+                ("Vault", None, vec![]),
+            ]
+        );
+        assert_eq!(functions[3].name, "withdraw");
+    }
+}
@@ -4,7 +4,8 @@ use crate::Address;
 use ethers::prelude::Log;
 use foundry_evm::{
     coverage::HitMaps,
-    fuzz::{CounterExample, FuzzedCases},
+    executor::inspector::MemoryStats,
+    fuzz::{invariant::InvariantStats, CounterExample, FuzzedCases},
     trace::{CallTraceArena, TraceKind},
 };
 use serde::{Deserialize, Serialize};
@@ -85,6 +86,10 @@ pub struct TestResult {
     #[serde(skip)]
     pub coverage: Option<HitMaps>,
 
+    /// The memory usage of every run of the test, if it was collected
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memory: Vec<MemoryStats>,
+
     /// Labeled addresses
     pub labeled_addresses: BTreeMap<Address, String>,
 }
@@ -142,8 +147,9 @@ pub enum TestKind {
     Standard(u64),
     /// A solidity fuzz test, that stores all test cases
     Fuzz(FuzzedCases),
-    /// A solidity invariant test, that stores all test cases
-    Invariant(Vec<FuzzedCases>, usize),
+    /// A solidity invariant test, that stores all test cases, the number of reverted calls and the
+    /// statistics of the calls
+    Invariant(Vec<FuzzedCases>, usize, InvariantStats),
 }
 
 impl TestKind {
@@ -156,7 +162,7 @@ impl TestKind {
                 median_gas: fuzzed.median_gas(false),
                 mean_gas: fuzzed.mean_gas(false),
             },
-            TestKind::Invariant(fuzzed, reverts) => TestKindReport::Invariant {
+            TestKind::Invariant(fuzzed, reverts, _) => TestKindReport::Invariant {
                 runs: fuzzed.len(),
                 calls: fuzzed.iter().map(|sequence| sequence.cases().len()).sum(),
                 reverts: *reverts,
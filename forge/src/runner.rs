@@ -1,4 +1,6 @@
 use crate::{
+    fuzz_inputs::FuzzInputs,
+    inline_config::InlineTestConfig,
     result::{SuiteResult, TestKind, TestResult, TestSetup},
     TestFilter, TestOptions,
 };
@@ -15,7 +17,7 @@ use foundry_evm::{
         invariant::{
             InvariantContract, InvariantExecutor, InvariantFuzzTestResult, InvariantTestOptions,
         },
-        FuzzedExecutor,
+        FuzzedCases, FuzzedExecutor,
     },
     trace::{load_contracts, TraceKind},
     CALLER,
@@ -44,6 +46,10 @@ pub struct ContractRunner<'a> {
     pub initial_balance: U256,
     /// The address which will be used as the `from` field in all EVM calls
     pub sender: Address,
+    /// Per-test overrides declared in natspec comments, `function name -> overrides`
+    pub inline_config: Option<&'a BTreeMap<String, InlineTestConfig>>,
+    /// Known inputs that are run before fuzzing a test
+    pub fuzz_inputs: Option<&'a FuzzInputs>,
 }
 
 impl<'a> ContractRunner<'a> {
@@ -56,6 +62,8 @@ impl<'a> ContractRunner<'a> {
         sender: Option<Address>,
         errors: Option<&'a Abi>,
         predeploy_libs: &'a [Bytes],
+        inline_config: Option<&'a BTreeMap<String, InlineTestConfig>>,
+        fuzz_inputs: Option<&'a FuzzInputs>,
     ) -> Self {
         Self {
             executor,
@@ -65,8 +73,27 @@ impl<'a> ContractRunner<'a> {
             sender: sender.unwrap_or_default(),
             errors,
             predeploy_libs,
+            inline_config,
+            fuzz_inputs,
         }
     }
+
+    /// Returns a runner for the given test function with its inline config overrides applied to
+    /// the executor
+    fn with_inline_config(&self, func: &Function) -> Self {
+        let mut runner = self.clone();
+        if let Some(config) = self.inline_config.and_then(|tests| tests.get(&func.name)) {
+            if let Some(spec) = config.evm_spec {
+                runner.executor.env_mut().cfg.spec_id = spec;
+            }
+            if let Some(isolate) = config.isolate {
+                if let Some(cheats) = runner.executor.inspector_config_mut().cheatcodes.as_mut() {
+                    cheats.isolate = isolate;
+                }
+            }
+        }
+        runner
+    }
 }
 
 impl<'a> ContractRunner<'a> {
@@ -217,6 +244,7 @@ impl<'a> ContractRunner<'a> {
                         kind: TestKind::Standard(0),
                         traces: vec![],
                         coverage: None,
+                        memory: Vec::new(),
                         labeled_addresses: BTreeMap::new(),
                     },
                 )]
@@ -249,6 +277,7 @@ impl<'a> ContractRunner<'a> {
                         kind: TestKind::Standard(0),
                         traces: setup.traces,
                         coverage: None,
+                        memory: Vec::new(),
                         labeled_addresses: setup.labeled_addresses,
                     },
                 )]
@@ -272,15 +301,16 @@ impl<'a> ContractRunner<'a> {
                 tests
                     .par_iter()
                     .flat_map(|(func, should_fail)| {
+                        let runner = self.with_inline_config(func);
                         if func.is_fuzz_test() {
-                            self.run_fuzz_test(
+                            runner.run_fuzz_test(
                                 func,
                                 *should_fail,
                                 test_options.fuzzer(),
                                 setup.clone(),
                             )
                         } else {
-                            self.clone().run_test(func, *should_fail, setup.clone())
+                            runner.run_test(func, *should_fail, setup.clone())
                         }
                         .map(|result| Ok((func.signature(), result)))
                     })
@@ -310,7 +340,7 @@ impl<'a> ContractRunner<'a> {
 
             results.into_iter().zip(functions.iter()).for_each(|(result, function)| {
                 match result.kind {
-                    TestKind::Invariant(ref _cases, _) => {
+                    TestKind::Invariant(ref _cases, _, _) => {
                         test_results.insert(function.name.clone(), result);
                     }
                     _ => unreachable!(),
@@ -349,7 +379,7 @@ impl<'a> ContractRunner<'a> {
 
         // Run unit test
         let start = Instant::now();
-        let (reverted, reason, gas, stipend, execution_traces, coverage, state_changeset) =
+        let (reverted, reason, gas, stipend, execution_traces, coverage, memory, state_changeset) =
             match self.executor.execute_test::<(), _, _>(
                 self.sender,
                 address,
@@ -365,13 +395,23 @@ impl<'a> ContractRunner<'a> {
                     logs: execution_logs,
                     traces: execution_trace,
                     coverage,
+                    memory,
                     labels: new_labels,
                     state_changeset,
                     ..
                 }) => {
                     labeled_addresses.extend(new_labels);
                     logs.extend(execution_logs);
-                    (reverted, None, gas, stipend, execution_trace, coverage, state_changeset)
+                    (
+                        reverted,
+                        None,
+                        gas,
+                        stipend,
+                        execution_trace,
+                        coverage,
+                        memory,
+                        state_changeset,
+                    )
                 }
                 Err(EvmError::Execution {
                     reverted,
@@ -386,7 +426,16 @@ impl<'a> ContractRunner<'a> {
                 }) => {
                     labeled_addresses.extend(new_labels);
                     logs.extend(execution_logs);
-                    (reverted, Some(reason), gas, stipend, execution_trace, None, state_changeset)
+                    (
+                        reverted,
+                        Some(reason),
+                        gas,
+                        stipend,
+                        execution_trace,
+                        None,
+                        None,
+                        state_changeset,
+                    )
                 }
                 Err(err) => {
                     error!(?err);
@@ -417,6 +466,7 @@ impl<'a> ContractRunner<'a> {
             kind: TestKind::Standard(gas.overflowing_sub(stipend).0),
             traces,
             coverage,
+            memory: memory.into_iter().collect(),
             labeled_addresses,
         })
     }
@@ -446,14 +496,15 @@ impl<'a> ContractRunner<'a> {
         let invariant_contract =
             InvariantContract { address, invariant_functions: functions, abi: self.contract };
 
-        if let Some(InvariantFuzzTestResult { invariants, cases, reverts }) = evm.invariant_fuzz(
-            invariant_contract,
-            InvariantTestOptions {
-                depth: test_options.invariant_depth,
-                fail_on_revert: test_options.invariant_fail_on_revert,
-                call_override: test_options.invariant_call_override,
-            },
-        )? {
+        let invariant_options = InvariantTestOptions {
+            depth: test_options.invariant_depth,
+            fail_on_revert: test_options.invariant_fail_on_revert,
+            call_override: test_options.invariant_call_override,
+        };
+
+        if let Some(InvariantFuzzTestResult { invariants, cases, reverts, stats }) =
+            evm.invariant_fuzz(invariant_contract, invariant_options)?
+        {
             let results = invariants
                 .iter()
                 .map(|(_, test_error)| {
@@ -480,8 +531,9 @@ impl<'a> ContractRunner<'a> {
                         }),
                         counterexample,
                         logs,
-                        kind: TestKind::Invariant(cases.clone(), reverts),
+                        kind: TestKind::Invariant(cases.clone(), reverts, stats.clone()),
                         coverage: None, // todo?
+                        memory: Vec::new(),
                         traces,
                         labeled_addresses: labeled_addresses.clone(),
                     }
@@ -504,14 +556,28 @@ impl<'a> ContractRunner<'a> {
     ) -> Result<TestResult> {
         let TestSetup { address, mut logs, mut traces, mut labeled_addresses, .. } = setup;
 
+        let seeds = match self.fuzz_inputs.map(|inputs| inputs.seeds_for(func)).transpose() {
+            Ok(seeds) => seeds.unwrap_or_default(),
+            Err(err) => {
+                return Ok(TestResult {
+                    success: false,
+                    reason: Some(format!("{err:#}")),
+                    counterexample: None,
+                    logs,
+                    kind: TestKind::Fuzz(FuzzedCases::new(Vec::new())),
+                    traces,
+                    coverage: None,
+                    memory: Vec::new(),
+                    labeled_addresses,
+                })
+            }
+        };
+
         // Run fuzz test
         let start = Instant::now();
-        let mut result = FuzzedExecutor::new(&self.executor, runner, self.sender).fuzz(
-            func,
-            address,
-            should_fail,
-            self.errors,
-        );
+        let mut result = FuzzedExecutor::new(&self.executor, runner, self.sender)
+            .with_seeds(seeds)
+            .fuzz(func, address, should_fail, self.errors);
 
         // Record logs, labels and traces
         logs.append(&mut result.logs);
@@ -524,6 +590,7 @@ impl<'a> ContractRunner<'a> {
             success = %result.success
         );
 
+        let memory = result.cases.cases().iter().filter_map(|case| case.memory).collect();
         Ok(TestResult {
             success: result.success,
             reason: result.reason,
@@ -533,6 +600,7 @@ impl<'a> ContractRunner<'a> {
             traces,
             // TODO: Maybe support coverage for fuzz tests
             coverage: None,
+            memory,
             labeled_addresses,
         })
     }
@@ -0,0 +1,161 @@
+use crate::result::SuiteResult;
+use eyre::Result;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+/// The file in the cache directory with the tests that failed in the last run of their suite
+pub const LAST_FAILED_FILE: &str = "last-failed.json";
+
+/// The file in the cache directory with the average durations of the test suites
+pub const DURATIONS_FILE: &str = "test-durations.json";
+
+/// The outcomes of previous runs of the test suites, kept in the cache directory to run the
+/// suites that are most likely to fail first.
+///
+/// Suites are identified by their `path:Contract` identifier.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestHistory {
+    /// The tests that failed in the last run of their suite
+    pub last_failed: BTreeMap<String, BTreeSet<String>>,
+    /// The average duration of every suite that has run
+    pub durations: BTreeMap<String, SuiteDuration>,
+}
+
+/// The average duration of the runs of a test suite
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuiteDuration {
+    /// The average duration in milliseconds
+    pub mean_ms: u64,
+    /// The number of runs the average is taken over
+    pub runs: u64,
+}
+
+impl TestHistory {
+    /// Reads the history from the `cache_dir`, files that do not exist yet are read as empty
+    pub fn read(cache_dir: impl AsRef<Path>) -> Result<Self> {
+        let cache_dir = cache_dir.as_ref();
+        Ok(Self {
+            last_failed: read_json(&cache_dir.join(LAST_FAILED_FILE))?,
+            durations: read_json(&cache_dir.join(DURATIONS_FILE))?,
+        })
+    }
+
+    /// Writes the history to the `cache_dir`
+    pub fn write(&self, cache_dir: impl AsRef<Path>) -> Result<()> {
+        let cache_dir = cache_dir.as_ref();
+        fs::create_dir_all(cache_dir)?;
+        write_json(&cache_dir.join(LAST_FAILED_FILE), &self.last_failed)?;
+        write_json(&cache_dir.join(DURATIONS_FILE), &self.durations)
+    }
+
+    /// Records the outcomes of the suites that ran, suites that did not run keep their history
+    pub fn record(&mut self, results: &BTreeMap<String, SuiteResult>) {
+        for (suite, result) in results {
+            let failed = result.failures().map(|(name, _)| name.clone()).collect::<BTreeSet<_>>();
+            if failed.is_empty() {
+                self.last_failed.remove(suite);
+            } else {
+                self.last_failed.insert(suite.clone(), failed);
+            }
+
+            let duration = self.durations.entry(suite.clone()).or_default();
+            let total = duration.mean_ms as u128 * duration.runs as u128 +
+                result.duration.as_millis();
+            duration.runs += 1;
+            duration.mean_ms = (total / duration.runs as u128) as u64;
+        }
+    }
+
+    /// Whether the position of the `test` of the `suite` in the order of
+    /// [`TestHistory::schedule()`] is based on its history, because it failed in the last run of
+    /// the suite or the duration of the suite is known
+    pub fn is_known(&self, suite: &str, test: &str) -> bool {
+        self.last_failed.get(suite).map_or(false, |failed| failed.contains(test)) ||
+            self.durations.contains_key(suite)
+    }
+
+    /// Orders the `suites` to run the ones with a test that failed in their last run first,
+    /// followed by the others.
+    ///
+    /// Both are ordered by the average duration of the suites, shortest first, followed by the
+    /// suites that have not run before in their given order.
+    pub fn schedule<T>(&self, suites: impl IntoIterator<Item = (String, T)>) -> Vec<T> {
+        let mut suites = suites.into_iter().collect::<Vec<_>>();
+        suites.sort_by_key(|(suite, _)| {
+            let mean = self.durations.get(suite).map_or(u64::MAX, |duration| duration.mean_ms);
+            (!self.last_failed.contains_key(suite), mean)
+        });
+        suites.into_iter().map(|(_, suite)| suite).collect()
+    }
+}
+
+fn read_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+    if !path.is_file() {
+        return Ok(T::default())
+    }
+    Ok(serde_json::from_reader(BufReader::new(fs::File::open(path)?))?)
+}
+
+fn write_json(path: &Path, value: &impl Serialize) -> Result<()> {
+    Ok(serde_json::to_writer_pretty(BufWriter::new(fs::File::create(path)?), value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::{TestKind, TestResult};
+    use std::time::Duration;
+
+    fn suite(millis: u64, failed: &[&str]) -> SuiteResult {
+        let tests = ["testA()", "testB()"]
+            .into_iter()
+            .map(|name| {
+                let result = TestResult {
+                    success: !failed.contains(&name),
+                    reason: None,
+                    counterexample: None,
+                    logs: vec![],
+                    kind: TestKind::Standard(0),
+                    traces: vec![],
+                    coverage: None,
+                    memory: Vec::new(),
+                    labeled_addresses: BTreeMap::new(),
+                };
+                (name.to_string(), result)
+            })
+            .collect();
+        SuiteResult::new(Duration::from_millis(millis), tests, vec![])
+    }
+
+    #[test]
+    fn runs_failed_and_short_suites_first() {
+        let mut history = TestHistory::default();
+        history.record(&BTreeMap::from([
+            ("src/A.t.sol:A".to_string(), suite(300, &[])),
+            ("src/B.t.sol:B".to_string(), suite(100, &[])),
+            ("src/C.t.sol:C".to_string(), suite(500, &["testB()"])),
+            ("src/D.t.sol:D".to_string(), suite(200, &["testA()"])),
+        ]));
+        history.record(&BTreeMap::from([("src/A.t.sol:A".to_string(), suite(100, &[]))]));
+        assert_eq!(history.durations["src/A.t.sol:A"], SuiteDuration { mean_ms: 200, runs: 2 });
+        assert_eq!(history.last_failed["src/C.t.sol:C"], BTreeSet::from(["testB()".to_string()]));
+
+        let suites = ["src/A.t.sol:A", "src/B.t.sol:B", "src/C.t.sol:C", "src/D.t.sol:D", "new"];
+        assert_eq!(
+            history.schedule(suites.map(|suite| (suite.to_string(), suite))),
+            ["src/D.t.sol:D", "src/C.t.sol:C", "src/B.t.sol:B", "src/A.t.sol:A", "new"]
+        );
+        assert!(history.is_known("src/C.t.sol:C", "testB()"));
+        assert!(!history.is_known("new", "testA()"));
+
+        // fixed suites are no longer run first
+        history.record(&BTreeMap::from([("src/D.t.sol:D".to_string(), suite(200, &[]))]));
+        assert!(!history.last_failed.contains_key("src/D.t.sol:D"));
+        assert!(history.is_known("src/D.t.sol:D", "testA()"));
+    }
+}
@@ -0,0 +1,149 @@
+use crate::trace::{CallTraceArena, RawOrDecodedCall, TraceKind};
+use ethers::{
+    abi::{Abi, StateMutability},
+    utils::id,
+};
+use foundry_common::TestFunctionExt;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// A public or external function that is never called by any test
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UntestedFunction {
+    /// The contract identifier, `<source>:<name>`
+    pub contract: String,
+    pub name: String,
+    pub signature: String,
+    /// The `0x` prefixed function selector
+    pub selector: String,
+}
+
+impl fmt::Display for UntestedFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} {}", self.contract, self.signature, self.selector)
+    }
+}
+
+/// Records the functions that are called in call traces, per contract
+#[derive(Debug, Default)]
+pub struct CalledFunctions {
+    /// contract identifier -> selectors of the called functions
+    called: BTreeMap<String, BTreeSet<[u8; 4]>>,
+}
+
+impl CalledFunctions {
+    /// Records every call of the `traces`, calls are attributed to the contract the trace was
+    /// identified as
+    pub fn analyze(&mut self, traces: &[(TraceKind, CallTraceArena)]) {
+        for node in traces.iter().flat_map(|(_, arena)| arena.arena.iter()) {
+            let trace = &node.trace;
+            let contract = match &trace.contract {
+                Some(contract) => contract,
+                None => continue,
+            };
+            let selector = match &trace.data {
+                RawOrDecodedCall::Decoded(_, sig, _) if !sig.is_empty() => Some(id(sig)),
+                RawOrDecodedCall::Raw(bytes) if !trace.created() => {
+                    bytes.get(..4).and_then(|selector| selector.try_into().ok())
+                }
+                _ => None,
+            };
+            if let Some(selector) = selector {
+                self.called.entry(contract.clone()).or_default().insert(selector);
+            }
+        }
+    }
+
+    /// Returns `true` if the function with the `selector` was called on the `contract`
+    pub fn is_called(&self, contract: &str, selector: [u8; 4]) -> bool {
+        self.called.get(contract).map(|called| called.contains(&selector)).unwrap_or_default()
+    }
+
+    /// Returns all functions of the given `(identifier, abi)` contracts that were never called.
+    ///
+    /// Test contracts are skipped, `view` and `pure` functions are only included if `include_view`
+    /// is set.
+    pub fn untested<'a>(
+        &self,
+        contracts: impl IntoIterator<Item = (String, &'a Abi)>,
+        include_view: bool,
+    ) -> Vec<UntestedFunction> {
+        contracts
+            .into_iter()
+            .filter(|(_, abi)| !is_test_contract(abi))
+            .flat_map(|(contract, abi)| {
+                abi.functions()
+                    .filter(|func| {
+                        include_view ||
+                            !matches!(
+                                func.state_mutability,
+                                StateMutability::View | StateMutability::Pure
+                            )
+                    })
+                    .filter(|func| !self.is_called(&contract, func.short_signature()))
+                    .map(|func| UntestedFunction {
+                        contract: contract.clone(),
+                        name: func.name.clone(),
+                        signature: func.signature(),
+                        selector: format!("0x{}", hex::encode(func.short_signature())),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+/// Returns `true` if the contract has test functions or is marked as a test
+fn is_test_contract(abi: &Abi) -> bool {
+    abi.functions().any(|func| func.is_test() || func.is_setup() || func.name == "IS_TEST")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::{node::CallTraceNode, CallTrace};
+
+    const ABI: &str = r#"[
+        {"type":"function","name":"increment","inputs":[],"outputs":[],"stateMutability":"nonpayable"},
+        {"type":"function","name":"reset","inputs":[],"outputs":[],"stateMutability":"nonpayable"},
+        {"type":"function","name":"number","inputs":[],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view"}
+    ]"#;
+
+    fn traces(data: RawOrDecodedCall) -> Vec<(TraceKind, CallTraceArena)> {
+        let trace = CallTrace {
+            contract: Some("src/Counter.sol:Counter".to_string()),
+            data,
+            ..Default::default()
+        };
+        let arena = CallTraceArena { arena: vec![CallTraceNode { trace, ..Default::default() }] };
+        vec![(TraceKind::Execution, arena)]
+    }
+
+    #[test]
+    fn lists_uncalled_functions() {
+        let abi: Abi = serde_json::from_str(ABI).unwrap();
+        let contract = "src/Counter.sol:Counter".to_string();
+
+        let mut called = CalledFunctions::default();
+        called.analyze(&traces(RawOrDecodedCall::Decoded(
+            "increment".to_string(),
+            "increment()".to_string(),
+            vec![],
+        )));
+
+        let untested = called.untested([(contract.clone(), &abi)], false);
+        assert_eq!(untested.len(), 1);
+        assert_eq!(untested[0].signature, "reset()");
+        assert_eq!(untested[0].selector, format!("0x{}", hex::encode(id("reset()"))));
+
+        let untested = called.untested([(contract.clone(), &abi)], true);
+        assert_eq!(untested.len(), 2);
+
+        // undecoded calls are matched by their selector
+        called.analyze(&traces(RawOrDecodedCall::Raw(id("reset()").to_vec())));
+        assert!(called.untested([(contract, &abi)], false).is_empty());
+    }
+}
@@ -2,7 +2,9 @@
 
 use crate::test_helpers::{COMPILED, COMPILED_WITH_LIBS, EVM_OPTS, LIBS_PROJECT, PROJECT};
 use forge::{result::SuiteResult, MultiContractRunner, MultiContractRunnerBuilder, TestOptions};
-use foundry_config::{Config, RpcEndpoint, RpcEndpoints};
+use foundry_config::{
+    Config, FsAccessPermission, FsPermissions, PathPermission, RpcEndpoint, RpcEndpoints,
+};
 use foundry_evm::{decode::decode_console_logs, executor::inspector::CheatsConfig};
 use std::collections::BTreeMap;
 
@@ -26,6 +28,10 @@ pub fn base_runner() -> MultiContractRunnerBuilder {
 pub fn runner() -> MultiContractRunner {
     let mut config = Config::with_root(PROJECT.root());
     config.rpc_endpoints = rpc_endpoints();
+    config.fs_permissions = FsPermissions::new([
+        PathPermission::new(FsAccessPermission::None, "./fixtures/File/denied"),
+        PathPermission::new(FsAccessPermission::ReadWrite, "./"),
+    ]);
 
     base_runner()
         .with_cheats_config(CheatsConfig::new(&config, &EVM_OPTS))
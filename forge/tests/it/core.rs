@@ -625,6 +625,27 @@ fn test_logs() {
                         Some(vec!["constructor".into(), "formatted log test 1".into()]),
                         None,
                     ),
+                    (
+                        "testConsoleLogf()",
+                        true,
+                        None,
+                        Some(vec![
+                            "constructor".into(),
+                            "logf str=test int=-31337 hex=0xff exp=1.5e18 addr=0x0000000000000000000000000000000000000001"
+                                .into(),
+                        ]),
+                        None,
+                    ),
+                    (
+                        "testConsoleLogfMismatch()",
+                        true,
+                        None,
+                        Some(vec![
+                            "constructor".into(),
+                            "logf: the arguments do not match `logf %d %d`: Invalid data".into(),
+                        ]),
+                        None,
+                    ),
                 ],
             ),
         ]),
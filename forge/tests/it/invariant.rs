@@ -1,6 +1,7 @@
 //! Tests for invariants
 
 use crate::{config::*, test_helpers::filter::Filter};
+use forge::result::TestKind;
 use std::collections::BTreeMap;
 
 #[test]
@@ -47,3 +48,28 @@ fn test_invariant() {
         ]),
     );
 }
+
+#[test]
+fn test_invariant_stats() {
+    let mut runner = runner();
+    let filter =
+        Filter::new(".*", ".*", ".*fuzz/invariant/(target/TargetSelectors|InvariantTest1)");
+    let results = runner.test(&filter, None, TEST_OPTS).unwrap();
+
+    let stats = |contract: &str, test: &str| match &results[contract].test_results[test].kind {
+        TestKind::Invariant(_, _, stats) => stats.clone(),
+        kind => panic!("{test} is not an invariant test: {kind:?}"),
+    };
+
+    // only the targeted selector is called
+    let selectors =
+        stats("fuzz/invariant/target/TargetSelectors.t.sol:TargetSelectors", "invariantTrueWorld");
+    assert_eq!(selectors.calls.keys().collect::<Vec<_>>(), ["Hello.change()"]);
+    assert_eq!(selectors.calls["Hello.change()"].reverts, 0);
+    assert_eq!(selectors.first_violation, None);
+    assert_eq!(selectors.max_depth, TEST_OPTS.invariant_depth as usize);
+
+    let broken = stats("fuzz/invariant/InvariantTest1.t.sol:InvariantTest", "invariant_neverFalse");
+    assert_eq!(broken.first_violation.as_deref(), Some("invariant_neverFalse"));
+    assert!(broken.max_depth > 0);
+}
@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+contract AssertSnapshotTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+
+    function testAssertSnapshotEq() public {
+        cheats.assertSnapshotEq(abi.encode(uint256(42), "forty-two"), "assertSnapshotEq");
+    }
+
+    function testFailAssertSnapshotEqChangedValue() public {
+        cheats.assertSnapshotEq(abi.encode(uint256(43), "forty-two"), "assertSnapshotEq");
+    }
+
+    function testRevertsOnSnapshotOutsideOfSnapshots() public {
+        cheats.expectRevert(
+            "invalid snapshot name `../escape`, it must be a relative path within the snapshots directory"
+        );
+        cheats.assertSnapshotEq(hex"00", "../escape");
+    }
+}
@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+contract Token {
+    string public symbol = "TKN";
+    mapping(address => uint256) public balanceOf;
+    mapping(address => mapping(address => uint256)) public allowance;
+
+    function mint(address to, uint256 amount) public {
+        balanceOf[to] += amount;
+    }
+
+    function approve(address spender, uint256 amount) public {
+        allowance[msg.sender][spender] = amount;
+    }
+}
+
+contract AssertTokenTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+    Token token;
+    address alice = address(0xa11ce);
+
+    function setUp() public {
+        token = new Token();
+        cheats.label(alice, "alice");
+    }
+
+    function testAssertTokenBalance() public {
+        token.mint(alice, 100);
+        cheats.assertTokenBalance(address(token), alice, 100, "balance");
+    }
+
+    function testAssertTokenBalanceMessage() public {
+        token.mint(alice, 1);
+        try cheats.assertTokenBalance(address(token), alice, 2, "after mint") {
+            fail();
+        } catch (bytes memory reason) {
+            string memory expected = string(
+                abi.encodePacked(
+                    "after mint: expected alice (", cheats.toString(alice), ") to hold 2 TKN, but it holds 1"
+                )
+            );
+            assertEq(keccak256(reason), keccak256(abi.encode(expected)));
+        }
+    }
+
+    function testFailAssertTokenBalanceOfNonToken() public {
+        cheats.assertTokenBalance(address(this), alice, 0, "not a token");
+    }
+
+    function testAssertTokenAllowance() public {
+        token.approve(alice, 5);
+        cheats.assertTokenAllowance(address(token), address(this), alice, 5, "allowance");
+    }
+
+    function testFailAssertTokenAllowance() public {
+        token.approve(alice, 5);
+        cheats.assertTokenAllowance(address(token), address(this), alice, 6, "allowance");
+    }
+}
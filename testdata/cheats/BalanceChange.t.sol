@@ -0,0 +1,86 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+contract BalanceChangeTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+    address alice = address(0xa11ce);
+
+    function setUp() public {
+        cheats.deal(address(this), 10 ether);
+        cheats.label(alice, "alice");
+    }
+
+    function testAssertBalanceIncrease() public {
+        cheats.expectBalanceChange(alice, 1 ether);
+        payable(alice).transfer(1 ether);
+        cheats.assertBalanceChange(alice, 1 ether);
+    }
+
+    function testAssertBalanceDecrease() public {
+        cheats.expectBalanceChange(address(this), -2 ether);
+        payable(alice).transfer(2 ether);
+        cheats.assertBalanceChange(address(this), -2 ether);
+    }
+
+    function testAssertBalanceUnchanged() public {
+        cheats.expectBalanceChange(alice, 0);
+        cheats.assertBalanceChange(alice, 0);
+    }
+
+    function testAssertBalanceChangeMessage() public {
+        cheats.expectBalanceChange(alice, 2);
+        payable(alice).transfer(1);
+        try cheats.assertBalanceChange(alice, 2) {
+            fail();
+        } catch (bytes memory reason) {
+            string memory expected = string(
+                abi.encodePacked(
+                    "expected the balance of alice (",
+                    cheats.toString(alice),
+                    ") to increase by 2, but it increased by 1"
+                )
+            );
+            assertEq(keccak256(reason), keccak256(abi.encode(expected)));
+        }
+    }
+
+    function testAssertBalanceChangeOfMaxBalance() public {
+        cheats.deal(alice, type(uint256).max);
+        cheats.expectBalanceChange(alice, -1 ether);
+        cheats.deal(alice, type(uint256).max - 1 ether);
+        cheats.assertBalanceChange(alice, -1 ether);
+    }
+
+    function testAssertBalanceChangeOutOfRange() public {
+        cheats.deal(alice, 0);
+        cheats.expectBalanceChange(alice, 0);
+        cheats.deal(alice, type(uint256).max);
+        try cheats.assertBalanceChange(alice, 0) {
+            fail();
+        } catch (bytes memory reason) {
+            string memory expected = string(
+                abi.encodePacked(
+                    "expected the balance of alice (",
+                    cheats.toString(alice),
+                    ") to not change, but it increased by ",
+                    cheats.toString(type(uint256).max),
+                    ", which is out of int256 range"
+                )
+            );
+            assertEq(keccak256(reason), keccak256(abi.encode(expected)));
+        }
+    }
+
+    function testFailAssertBalanceChangeWrongDirection() public {
+        cheats.expectBalanceChange(alice, -1);
+        payable(alice).transfer(1);
+        cheats.assertBalanceChange(alice, -1);
+    }
+
+    function testFailAssertBalanceChangeWithoutExpect() public {
+        cheats.assertBalanceChange(alice, 0);
+    }
+}
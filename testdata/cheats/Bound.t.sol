@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+contract BoundTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+
+    function testBound() public {
+        assertEq(cheats.bound(5, 0, 10), 5);
+        assertEq(cheats.bound(12, 3, 10), 7);
+        assertEq(cheats.bound(42, 7, 7), 7);
+        assertEq(cheats.bound(type(uint256).max, 0, type(uint256).max), type(uint256).max);
+    }
+
+    function testFuzzBound(uint256 x, uint256 min, uint256 max) public {
+        if (min > max) (min, max) = (max, min);
+        uint256 result = cheats.bound(x, min, max);
+        assertTrue(result >= min && result <= max, "result out of range");
+    }
+
+    function testBoundInt() public {
+        assertEq(cheats.boundInt(-3, -10, 10), -3);
+        assertEq(cheats.boundInt(11, -10, 10), -10);
+        assertEq(cheats.boundInt(10, -10, 10), 10);
+        assertEq(cheats.boundInt(type(int256).min, type(int256).min, type(int256).max), type(int256).min);
+    }
+
+    function testFuzzBoundInt(int256 x, int256 min, int256 max) public {
+        if (min > max) (min, max) = (max, min);
+        int256 result = cheats.boundInt(x, min, max);
+        assertTrue(result >= min && result <= max, "result out of range");
+    }
+
+    function testFailBoundMinAboveMax() public {
+        cheats.bound(5, 10, 0);
+    }
+
+    function testFailBoundIntMinAboveMax() public {
+        cheats.boundInt(5, 10, -10);
+    }
+}
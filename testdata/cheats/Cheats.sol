@@ -4,6 +4,8 @@ pragma solidity >=0.8.0;
 interface Cheats {
     // This allows us to getRecordedLogs()
     struct Log {bytes32[] topics; bytes data;}
+    // This allows us to getRecordedLogs(address) and getRecordedLogs(bytes32)
+    struct RecordedLog {bytes32[] topics; bytes data; address emitter; uint256 depth; uint256 index;}
     // Set block.timestamp (newTimestamp)
     function warp(uint256) external;
     // Set block.height (newHeight)
@@ -16,6 +18,13 @@ interface Cheats {
     function load(address,bytes32) external returns (bytes32);
     // Stores a value to an address' storage slot, (who, slot, value)
     function store(address,bytes32,bytes32) external;
+    // Stores a value into `size` bytes at byte `offset` (from the low-order end) of a storage slot
+    // without changing the rest of the slot, (who, slot, offset, size, value)
+    function storePacked(address,bytes32,uint256,uint256,bytes32) external;
+    // Loads `size` bytes at byte `offset` of a storage slot, (who, slot, offset, size) => (value)
+    function loadPacked(address,bytes32,uint256,uint256) external returns (bytes32);
+    // Copies all non-zero storage slots of an address to another address, (from, to)
+    function copyStorage(address,address) external;
     // Signs data, (privateKey, digest) => (v, r, s)
     function sign(uint256,bytes32) external returns (uint8,bytes32,bytes32);
     // Gets address for a given private key, (privateKey) => (address)
@@ -58,10 +67,23 @@ interface Cheats {
     function deal(address, uint256) external;
     // Sets an address' code, (who, newCode)
     function etch(address, bytes calldata) external;
+    // Sets an address' code to the runtime code of a contract artifact, given like for `getCode`,
+    // (who, artifact)
+    function etchContract(address, string calldata) external;
+    // Runs the constructor of a contract artifact with the ABI encoded arguments and sets an
+    // address' code to the runtime code it returns and its storage to the storage the constructor
+    // wrote, (who, artifact, constructorArgs)
+    function etchWithConstructor(address, string calldata, bytes calldata) external;
     // Expects an error on next call
     function expectRevert() external;
     function expectRevert(bytes calldata) external;
     function expectRevert(bytes4) external;
+    // Expects a revert of the reverter with data that starts with the selector somewhere within the
+    // next call, which must revert as well, (reverter, selector)
+    function expectRevert(address,bytes4) external;
+    // Expects a revert with data that starts with the selector at the call depth within the next
+    // call, which is at depth 1 and must revert as well, (depth, selector)
+    function expectRevertAtDepth(uint64,bytes4) external;
     // Record all storage reads and writes
     function record() external;
     // Gets all accessed reads and write slot from a recording session, for a given address
@@ -70,6 +92,12 @@ interface Cheats {
     function recordLogs() external;
     // Gets all the recorded logs
     function getRecordedLogs() external returns (Log[] memory);
+    // Gets the recorded logs emitted by the address, without clearing them
+    function getRecordedLogs(address) external returns (RecordedLog[] memory);
+    // Gets the recorded logs with the topic0, without clearing them
+    function getRecordedLogs(bytes32) external returns (RecordedLog[] memory);
+    // Clears the recorded logs
+    function clearRecordedLogs() external;
     // Prepare an expected log with (bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData).
     // Call this function, then emit an event, then call a function. Internally after the call, we check if
     // logs were emitted in the expected order with the expected topics and data (as specified by the booleans).
@@ -84,6 +112,14 @@ interface Cheats {
     // Mocks a call to an address with a specific msg.value, returning specified data.
     // Calldata match takes precedence over msg.value in case of ambiguity.
     function mockCall(address,uint256,bytes calldata,bytes calldata) external;
+    // Mocks all calls of the function with the signature to an address, returning specified data,
+    // (target, signature, returnData)
+    function mockReturn(address,string calldata,bytes calldata) external;
+    // Same as `mockReturn` with an encoded uint256, address, bool or bytes32 return value
+    function mockReturnUint(address,string calldata,uint256) external;
+    function mockReturnAddress(address,string calldata,address) external;
+    function mockReturnBool(address,string calldata,bool) external;
+    function mockReturnBytes32(address,string calldata,bytes32) external;
     // Clears all mocked calls
     function clearMockedCalls() external;
     // Expect a call to an address with the specified calldata.
@@ -97,6 +133,10 @@ interface Cheats {
     function label(address, string calldata) external;
     // If the condition is false, discard this run's fuzz inputs and generate new ones
     function assume(bool) external;
+    // Maps x into [min, max] without discarding the run, reverts if min > max (x, min, max)
+    function bound(uint256,uint256,uint256) external returns (uint256);
+    // Maps x into [min, max] by its offset from min, reverts if min > max (x, min, max)
+    function boundInt(int256,int256,int256) external returns (int256);
     // Set nonce for an account
     function setNonce(address,uint64) external;
     // Get nonce for an account
@@ -180,4 +220,29 @@ interface Cheats {
     function rpcUrl(string calldata) external returns(string memory);
     /// Returns all rpc urls and their aliases `[alias, url][]`
     function rpcUrls() external returns(string[2][] memory);
+    // Executes a call to `target` with `data` as a new top-level transaction and commits its state
+    // changes, (target, data) => (returnData)
+    function transact(address,bytes calldata) external returns (bytes memory);
+    // Same as `transact`, but also transfers `value` to `target`, (target, value, data) => (returnData)
+    function transact(address,uint256,bytes calldata) external returns (bytes memory);
+    // Only allows memory writes to the range [min, max] (and the scratch space below 0x60) in the
+    // current call until `stopExpectSafeMemory` is called, other writes fail the call
+    function expectSafeMemory(uint64 min, uint64 max) external;
+    // Stops restricting memory writes
+    function stopExpectSafeMemory() external;
+    // Fails if the `balanceOf` the account of the token is not the expected one, with a message
+    // naming the label, the token symbol and the account, (token, account, expected, label)
+    function assertTokenBalance(address,address,uint256,string calldata) external;
+    // Same as `assertTokenBalance` for the `allowance` of a spender,
+    // (token, owner, spender, expected, label)
+    function assertTokenAllowance(address,address,address,uint256,string calldata) external;
+    // Records the current balance of the account, which `assertBalanceChange` compares with later,
+    // the delta is only checked by `assertBalanceChange`, (account, expectedDelta)
+    function expectBalanceChange(address,int256) external;
+    // Fails if the balance of the account did not change by the delta since it was recorded by
+    // `expectBalanceChange`, (account, expectedDelta)
+    function assertBalanceChange(address,int256) external;
+    // Compares the value with the snapshot `snapshots/<name>.snap` in the project root, which is
+    // written if it does not exist yet or if `--update-snapshots` is set, (value, name)
+    function assertSnapshotEq(bytes calldata,string calldata) external;
 }
@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+contract Counter {
+    uint256 public a;
+    uint256 public b;
+    address public owner;
+
+    constructor(uint256 _a, uint256 _b) {
+        a = _a;
+        b = _b;
+        owner = msg.sender;
+    }
+
+    function setB(uint256 _b) public {
+        b = _b;
+    }
+}
+
+contract CopyStorageTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+    Counter proxy;
+    Counter implementation;
+
+    function setUp() public {
+        proxy = new Counter(1, 2);
+        implementation = new Counter(0, 0);
+    }
+
+    function testCopyStorage() public {
+        cheats.copyStorage(address(proxy), address(implementation));
+        assertEq(implementation.a(), 1, "copyStorage failed to copy a");
+        assertEq(implementation.b(), 2, "copyStorage failed to copy b");
+        assertEq(implementation.owner(), address(this), "copyStorage failed to copy owner");
+    }
+
+    function testCopyStorageChangedInTest() public {
+        proxy.setB(42);
+        cheats.copyStorage(address(proxy), address(implementation));
+        assertEq(implementation.b(), 42, "copyStorage did not copy the changed slot");
+    }
+
+    function testCopyStorageChargesPerSlot() public {
+        uint256 gasBefore = gasleft();
+        cheats.copyStorage(address(proxy), address(implementation));
+        uint256 used = gasBefore - gasleft();
+        // three non-zero slots are copied
+        assertGe(used, 3 * 22100, "copyStorage did not charge for the copied slots");
+    }
+}
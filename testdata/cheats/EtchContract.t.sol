@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+interface Secret {
+    function secret() external view returns (uint256);
+}
+
+contract EtchContractTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+    string constant ARTIFACT = "../testdata/fixtures/GetCode/WorkingContract.json";
+
+    function testEtchContract() public {
+        address target = address(10);
+        cheats.etchContract(target, ARTIFACT);
+        assertEq(Secret(target).secret(), 42);
+    }
+
+    function testEtchContractHardhatArtifact() public {
+        address target = address(11);
+        cheats.etchContract(target, "../testdata/fixtures/GetCode/HardhatWorkingContract.json");
+        assertEq(Secret(target).secret(), 42);
+    }
+
+    function testEtchWithConstructor() public {
+        address target = address(12);
+        cheats.etchWithConstructor(target, ARTIFACT, "");
+        assertEq(Secret(target).secret(), 42);
+
+        address etched = address(13);
+        cheats.etchContract(etched, ARTIFACT);
+        assertEq(keccak256(target.code), keccak256(etched.code));
+    }
+
+    function testFailEtchUnlinkedContract() public {
+        cheats.etchContract(address(10), "UnlinkedContract.sol");
+    }
+}
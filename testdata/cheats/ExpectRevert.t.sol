@@ -35,6 +35,20 @@ contract Reverter {
     }
 }
 
+contract Rethrower {
+    error WrappedError(bytes reason);
+
+    function rethrow(Reverter inner) public {
+        try inner.revertWithCustomError() {} catch (bytes memory reason) {
+            revert WrappedError(reason);
+        }
+    }
+
+    function swallow(Reverter inner) public {
+        try inner.revertWithCustomError() {} catch {}
+    }
+}
+
 contract ConstructorReverter {
     constructor(string memory message) {
         require(false, message);
@@ -136,4 +150,34 @@ contract ExpectRevertTest is DSTest {
     function testFailExpectRevertDangling() public {
         cheats.expectRevert("dangling");
     }
+
+    function testExpectRevertFromReverter() public {
+        Reverter inner = new Reverter();
+        Rethrower rethrower = new Rethrower();
+        cheats.expectRevert(address(inner), Reverter.CustomError.selector);
+        rethrower.rethrow(inner);
+    }
+
+    function testExpectRevertAtDepth() public {
+        Reverter inner = new Reverter();
+        Rethrower rethrower = new Rethrower();
+        cheats.expectRevertAtDepth(2, Reverter.CustomError.selector);
+        rethrower.rethrow(inner);
+        cheats.expectRevertAtDepth(1, Rethrower.WrappedError.selector);
+        rethrower.rethrow(inner);
+    }
+
+    function testFailExpectRevertFromOtherReverter() public {
+        Reverter inner = new Reverter();
+        Rethrower rethrower = new Rethrower();
+        cheats.expectRevert(address(rethrower), Reverter.CustomError.selector);
+        rethrower.rethrow(inner);
+    }
+
+    function testFailExpectRevertFromReverterCaught() public {
+        Reverter inner = new Reverter();
+        Rethrower rethrower = new Rethrower();
+        cheats.expectRevert(address(inner), Reverter.CustomError.selector);
+        rethrower.swallow(inner);
+    }
 }
@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+contract ExpectSafeMemoryTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+
+    function freeMemoryPointer() internal pure returns (uint64 ptr) {
+        assembly {
+            ptr := mload(0x40)
+        }
+    }
+
+    function testExpectSafeMemory() public {
+        uint64 ptr = freeMemoryPointer();
+        cheats.expectSafeMemory(ptr, ptr + 0x3f);
+        assembly {
+            mstore(ptr, 1)
+            mstore8(add(ptr, 0x3f), 2)
+            mstore(0x40, add(ptr, 0x40))
+        }
+        cheats.stopExpectSafeMemory();
+    }
+
+    function testFailExpectSafeMemory() public {
+        uint64 ptr = freeMemoryPointer();
+        cheats.expectSafeMemory(ptr, ptr + 0x1f);
+        assembly {
+            mstore(add(ptr, 0x20), 1)
+        }
+        cheats.stopExpectSafeMemory();
+    }
+
+    function testFailExpectSafeMemoryStopSelector() public {
+        uint64 ptr = freeMemoryPointer();
+        bytes4 selector = Cheats.stopExpectSafeMemory.selector;
+        cheats.expectSafeMemory(ptr, ptr + 0x1f);
+        assembly {
+            mstore(add(ptr, 0x20), selector)
+        }
+        cheats.stopExpectSafeMemory();
+    }
+
+    function testStopExpectSafeMemory() public {
+        uint64 ptr = freeMemoryPointer();
+        cheats.expectSafeMemory(ptr, ptr + 0x1f);
+        cheats.stopExpectSafeMemory();
+        assembly {
+            mstore(add(ptr, 0x20), 1)
+        }
+    }
+}
@@ -12,7 +12,11 @@ contract FileTest is DSTest {
 
         assertEq(cheats.readFile(path), "hello readable world\nthis is the second line!");
 
-        cheats.expectRevert("Path is not allowed.");
+        cheats.expectRevert(
+            bytes(
+                "read access to `/etc/hosts` is denied, no `fs_permissions` entry matches, the nearest is { access = \"read-write\", path = \"./\" }"
+            )
+        );
         cheats.readFile("/etc/hosts");
     }
 
@@ -23,7 +27,11 @@ contract FileTest is DSTest {
         assertEq(cheats.readLine(path), "this is the second line!");
         assertEq(cheats.readLine(path), "");
 
-        cheats.expectRevert("Path is not allowed.");
+        cheats.expectRevert(
+            bytes(
+                "read access to `/etc/hosts` is denied, no `fs_permissions` entry matches, the nearest is { access = \"read-write\", path = \"./\" }"
+            )
+        );
         cheats.readLine("/etc/hosts");
     }
 
@@ -36,7 +44,11 @@ contract FileTest is DSTest {
 
         cheats.removeFile(path);
 
-        cheats.expectRevert("Path is not allowed.");
+        cheats.expectRevert(
+            bytes(
+                "write access to `/etc/hosts` is denied, no `fs_permissions` entry matches, the nearest is { access = \"read-write\", path = \"./\" }"
+            )
+        );
         cheats.writeFile("/etc/hosts", "malicious stuff");
     }
 
@@ -53,7 +65,11 @@ contract FileTest is DSTest {
 
         cheats.removeFile(path);
 
-        cheats.expectRevert("Path is not allowed.");
+        cheats.expectRevert(
+            bytes(
+                "write access to `/etc/hosts` is denied, no `fs_permissions` entry matches, the nearest is { access = \"read-write\", path = \"./\" }"
+            )
+        );
         cheats.writeLine("/etc/hosts", "malicious stuff");
     }
 
@@ -78,7 +94,29 @@ contract FileTest is DSTest {
 
         cheats.removeFile(path);
 
-        cheats.expectRevert("Path is not allowed.");
+        cheats.expectRevert(
+            bytes(
+                "write access to `/etc/hosts` is denied, no `fs_permissions` entry matches, the nearest is { access = \"read-write\", path = \"./\" }"
+            )
+        );
         cheats.removeFile("/etc/hosts");
     }
+
+    function testNoneEntryOverridesBroaderGrant() public {
+        string memory path = "../testdata/fixtures/File/denied/secret.txt";
+
+        cheats.expectRevert(
+            bytes(
+                "write access to `fixtures/File/denied/secret.txt` is denied by `fs_permissions` entry { access = \"none\", path = \"./fixtures/File/denied\" }"
+            )
+        );
+        cheats.writeFile(path, "secret");
+
+        cheats.expectRevert(
+            bytes(
+                "read access to `fixtures/File/denied/secret.txt` is denied by `fs_permissions` entry { access = \"none\", path = \"./fixtures/File/denied\" }"
+            )
+        );
+        cheats.readFile(path);
+    }
 }
@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+contract Counter {
+    uint256 public count;
+
+    function increment() public {
+        count++;
+    }
+
+    function incrementAndRevert() public {
+        count++;
+        revert("reverted");
+    }
+}
+
+contract IsolateTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+    Counter counter;
+
+    function setUp() public {
+        counter = new Counter();
+    }
+
+    /// forge-config: default.isolate = true
+    function testIsolatedCallsIncrementNonce() public {
+        uint64 nonce = cheats.getNonce(address(this));
+        counter.increment();
+        counter.increment();
+        assertEq(cheats.getNonce(address(this)), nonce + 2, "nonce was not incremented per call");
+        assertEq(counter.count(), 2);
+    }
+
+    /// forge-config: default.isolate = true
+    function testIsolatedCallsChargeIntrinsicGas() public {
+        uint256 gasBefore = gasleft();
+        counter.increment();
+        uint256 used = gasBefore - gasleft();
+        assertGe(used, 21000, "intrinsic gas was not charged");
+    }
+
+    /// forge-config: default.isolate = true
+    function testIsolatedRevertKeepsNonce() public {
+        uint64 nonce = cheats.getNonce(address(this));
+        try counter.incrementAndRevert() {} catch {}
+        assertEq(cheats.getNonce(address(this)), nonce + 1, "nonce of reverted call was not kept");
+        assertEq(counter.count(), 0, "state of reverted call was kept");
+    }
+
+    function testCallsAreNotIsolatedByDefault() public {
+        uint64 nonce = cheats.getNonce(address(this));
+        counter.increment();
+        assertEq(cheats.getNonce(address(this)), nonce);
+    }
+}
@@ -42,6 +42,14 @@ contract NestedMock {
     }
 }
 
+interface Getters {
+    function owner() external view returns (address);
+
+    function paused() external view returns (bool);
+
+    function root() external view returns (bytes32);
+}
+
 contract MockCallTest is DSTest {
     Cheats constant cheats = Cheats(HEVM_ADDRESS);
 
@@ -214,4 +222,32 @@ contract MockCallTest is DSTest {
         assertEq(mock.add(1, 2), 10);
         mock.noReturnValue();
     }
+
+    function testMockReturn() public {
+        Mock target = new Mock();
+
+        cheats.mockReturn(address(target), "add(uint256,uint256)", abi.encode(10));
+        assertEq(target.add(1, 2), 10);
+        assertEq(target.add(2, 3), 10);
+        assertEq(target.numberA(), 1);
+    }
+
+    function testMockReturnTyped() public {
+        Mock target = new Mock();
+        Getters getters = Getters(address(100));
+
+        cheats.mockReturnUint(address(target), "numberB()", 20);
+        cheats.mockReturnAddress(address(getters), "owner()", address(0xa11ce));
+        cheats.mockReturnBool(address(getters), "paused()", true);
+        cheats.mockReturnBytes32(address(getters), "root()", bytes32(uint256(1)));
+
+        assertEq(target.numberB(), 20);
+        assertEq(getters.owner(), address(0xa11ce));
+        assertTrue(getters.paused());
+        assertEq(getters.root(), bytes32(uint256(1)));
+    }
+
+    function testFailMockReturnInvalidSignature() public {
+        cheats.mockReturnUint(address(100), "numberA(uint257", 1);
+    }
 }
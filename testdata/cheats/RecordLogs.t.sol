@@ -69,7 +69,7 @@ contract Emitter {
 }
 
 contract Emitterv2 {
-    Emitter emitter  = new Emitter();
+    Emitter public emitter = new Emitter();
 
     function emitEvent(
         uint256 topic1,
@@ -254,4 +254,61 @@ contract RecordLogsTest is DSTest {
         assertEq(entries.length, 1);
         assertEq(entries[0].topics.length, 4);
     }
+
+    function testFilterRecordedLogsByEmitter() public {
+        Emitterv2 emitterv2 = new Emitterv2();
+
+        cheats.recordLogs();
+        emitter.emitEvent(1, generateTestData(8));
+        emitterv2.emitEvent(2, 3, 4, generateTestData(8));
+        emitter.emitEvent(5, generateTestData(8));
+
+        Cheats.RecordedLog[] memory entries = cheats.getRecordedLogs(address(emitter));
+        assertEq(entries.length, 2);
+        assertEq(entries[0].emitter, address(emitter));
+        assertEq(entries[0].topics[1], bytes32(uint256(1)));
+        assertEq(entries[1].topics[1], bytes32(uint256(5)));
+        assertEq(entries[0].index, 0);
+        assertEq(entries[1].index, 2);
+
+        // the inner emitter of `Emitterv2` emits one call deeper
+        Cheats.RecordedLog[] memory nested = cheats.getRecordedLogs(address(emitterv2.emitter()));
+        assertEq(nested.length, 1);
+        assertEq(nested[0].index, 1);
+        assertEq(nested[0].depth, entries[0].depth + 1);
+
+        // filtering does not clear the recorded logs
+        assertEq(cheats.getRecordedLogs().length, 3);
+    }
+
+    function testFilterRecordedLogsByTopic0() public {
+        cheats.recordLogs();
+        emitter.emitEvent(1, generateTestData(8));
+        emitter.emitEvent(1, 2, generateTestData(8));
+        emitter.emitEvent(3, generateTestData(8));
+        emitter.emitAnonymousEvent(generateTestData(8));
+
+        Cheats.RecordedLog[] memory entries =
+            cheats.getRecordedLogs(keccak256("LogTopic1(uint256,bytes)"));
+        assertEq(entries.length, 2);
+        assertEq(entries[0].topics[1], bytes32(uint256(1)));
+        assertEq(entries[1].topics[1], bytes32(uint256(3)));
+        assertEq(entries[1].index, 2);
+
+        assertEq(cheats.getRecordedLogs(keccak256("LogTopic0(bytes)")).length, 0);
+    }
+
+    function testClearRecordedLogs() public {
+        cheats.recordLogs();
+        emitter.emitEvent(1, generateTestData(8));
+        cheats.clearRecordedLogs();
+        assertEq(cheats.getRecordedLogs(address(emitter)).length, 0);
+
+        // recording continues after clearing
+        emitter.emitEvent(2, generateTestData(8));
+        Cheats.RecordedLog[] memory entries = cheats.getRecordedLogs(address(emitter));
+        assertEq(entries.length, 1);
+        assertEq(entries[0].topics[1], bytes32(uint256(2)));
+        assertEq(entries[0].index, 1);
+    }
 }
@@ -19,6 +19,14 @@ contract RpcUrlTest is DSTest {
         string memory url = cheats.rpcUrl("rpcEnvAlias");
     }
 
+    // returns an error naming the known aliases if the alias does not exist
+    function testRevertsOnUnknownAlias() public {
+        cheats.expectRevert(
+            "invalid rpc url `unknownAlias`, it is neither a URL nor one of the `[rpc_endpoints]` aliases: rpcAlias, rpcEnvAlias"
+        );
+        string memory url = cheats.rpcUrl("unknownAlias");
+    }
+
     // can set env and return correct url
     function testCanSetAndGetURLAndAllUrls() public {
         // this will fail because alias is not set
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+contract PackedStorage {
+    uint64 public a = 1;
+    uint128 public b = 2;
+    uint64 public c = 3;
+    uint256 public d = 4;
+}
+
+contract StorePackedTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+    PackedStorage store;
+
+    function setUp() public {
+        store = new PackedStorage();
+    }
+
+    function testStorePacked() public {
+        // `b` occupies the 16 bytes after the 8 bytes of `a` in slot 0
+        cheats.storePacked(address(store), bytes32(0), 8, 16, bytes32(uint256(42)));
+        assertEq(store.a(), 1, "storePacked corrupted a");
+        assertEq(store.b(), 42, "storePacked failed");
+        assertEq(store.c(), 3, "storePacked corrupted c");
+        assertEq(store.d(), 4, "storePacked corrupted d");
+    }
+
+    function testStorePackedFuzzed(uint64 a, uint128 b, uint64 c) public {
+        cheats.storePacked(address(store), bytes32(0), 0, 8, bytes32(uint256(a)));
+        cheats.storePacked(address(store), bytes32(0), 8, 16, bytes32(uint256(b)));
+        cheats.storePacked(address(store), bytes32(0), 24, 8, bytes32(uint256(c)));
+        assertEq(store.a(), a, "storePacked failed for a");
+        assertEq(store.b(), b, "storePacked failed for b");
+        assertEq(store.c(), c, "storePacked failed for c");
+        assertEq(store.d(), 4, "storePacked corrupted d");
+    }
+
+    function testStorePackedFullSlot() public {
+        cheats.storePacked(address(store), bytes32(uint256(1)), 0, 32, bytes32(type(uint256).max));
+        assertEq(store.d(), type(uint256).max, "storePacked failed");
+    }
+
+    function testLoadPacked() public {
+        assertEq(uint256(cheats.loadPacked(address(store), bytes32(0), 0, 8)), 1, "loadPacked failed for a");
+        assertEq(uint256(cheats.loadPacked(address(store), bytes32(0), 8, 16)), 2, "loadPacked failed for b");
+        assertEq(uint256(cheats.loadPacked(address(store), bytes32(0), 24, 8)), 3, "loadPacked failed for c");
+        assertEq(uint256(cheats.loadPacked(address(store), bytes32(uint256(1)), 0, 32)), 4, "loadPacked failed for d");
+    }
+
+    function testFailStorePackedValueTooLarge() public {
+        cheats.storePacked(address(store), bytes32(0), 0, 8, bytes32(uint256(type(uint64).max) + 1));
+    }
+
+    function testFailStorePackedOutOfSlot() public {
+        cheats.storePacked(address(store), bytes32(0), 24, 16, bytes32(uint256(1)));
+    }
+
+    function testFailLoadPackedEmpty() public {
+        cheats.loadPacked(address(store), bytes32(0), 0, 0);
+    }
+}
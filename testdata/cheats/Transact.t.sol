@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: Unlicense
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+import "./Cheats.sol";
+
+contract Oracle {
+    uint256 public price;
+
+    function update(uint256 newPrice) public payable returns (uint256) {
+        price = newPrice;
+        return newPrice;
+    }
+
+    function fail() public pure {
+        revert("oracle failure");
+    }
+}
+
+contract TransactTest is DSTest {
+    Cheats constant cheats = Cheats(HEVM_ADDRESS);
+    Oracle oracle;
+
+    function setUp() public {
+        oracle = new Oracle();
+    }
+
+    function testTransact() public {
+        bytes memory ret = cheats.transact(address(oracle), abi.encodeWithSelector(Oracle.update.selector, 42));
+        assertEq(abi.decode(ret, (uint256)), 42);
+        assertEq(oracle.price(), 42);
+    }
+
+    function testTransactWithValue() public {
+        cheats.deal(tx.origin, 1 ether);
+        cheats.transact(address(oracle), 1 ether, abi.encodeWithSelector(Oracle.update.selector, 7));
+        assertEq(oracle.price(), 7);
+        assertEq(address(oracle).balance, 1 ether);
+    }
+
+    function testTransactIncrementsOriginNonce() public {
+        uint64 nonce = cheats.getNonce(tx.origin);
+        cheats.transact(address(oracle), abi.encodeWithSelector(Oracle.update.selector, 1));
+        assertEq(cheats.getNonce(tx.origin), nonce + 1);
+    }
+
+    function transactAndRevert() external {
+        cheats.transact(address(oracle), 1 ether, abi.encodeWithSelector(Oracle.update.selector, 9));
+        revert("calling frame reverted");
+    }
+
+    function testTransactRolledBackWithCallingFrame() public {
+        cheats.deal(tx.origin, 1 ether);
+        try this.transactAndRevert() {
+            fail();
+        } catch Error(string memory reason) {
+            assertEq(reason, "calling frame reverted");
+        }
+        assertEq(oracle.price(), 0);
+        assertEq(address(oracle).balance, 0);
+        assertEq(tx.origin.balance, 1 ether);
+    }
+
+    function testTransactRevert() public {
+        try cheats.transact(address(oracle), abi.encodeWithSelector(Oracle.fail.selector)) {
+            fail();
+        } catch Error(string memory reason) {
+            assertEq(reason, "oracle failure");
+        }
+    }
+
+    function testTransactRevertIncrementsOriginNonce() public {
+        uint64 nonce = cheats.getNonce(tx.origin);
+        try cheats.transact(address(oracle), abi.encodeWithSelector(Oracle.fail.selector)) {
+            fail();
+        } catch {}
+        assertEq(cheats.getNonce(tx.origin), nonce + 1);
+    }
+}
@@ -235,4 +235,15 @@ contract HardhatLogsTest {
     function testConsoleLogFormatSpill() public view {
         console.log("formatted log %s", testStr, testUint);
     }
+
+    function testConsoleLogf() public view {
+        console.logf(
+            "logf str=%s int=%d hex=%x exp=%e addr=%a",
+            abi.encode(testStr, testInt, uint256(255), uint256(1.5e18), testAddr)
+        );
+    }
+
+    function testConsoleLogfMismatch() public view {
+        console.logf("logf %d %d", abi.encode(uint256(1)));
+    }
 }
@@ -41,6 +41,10 @@ library console {
         _sendLogPayload(abi.encodeWithSignature("log(bytes)", p0));
     }
 
+    function logf(string memory format, bytes memory args) internal view {
+        _sendLogPayload(abi.encodeWithSignature("logf(string,bytes)", format, args));
+    }
+
     function logBytes1(bytes1 p0) internal view {
         _sendLogPayload(abi.encodeWithSignature("log(bytes1)", p0));
     }
@@ -0,0 +1,360 @@
+//! A navigable journal of a recorded execution
+//!
+//! The debugger records a snapshot of the stack and memory before every executed instruction. The
+//! journal additionally resolves the source line of every instruction via the source maps, so the
+//! execution can be searched forwards and backwards across call boundaries, by opcode, source line
+//! or breakpoint.
+
+use ethers::{
+    solc::{artifacts::ContractBytecodeSome, sourcemap::SourceMap},
+    types::{Address, U256},
+};
+use forge::{
+    debug::{DebugStep, Instruction},
+    utils::PCICMap,
+    CallKind,
+};
+use revm::opcode;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    path::PathBuf,
+    str::FromStr,
+};
+
+/// A position in the journal: the index of the call and the index of the step in that call
+pub type Position = (usize, usize);
+
+/// A line of a source file, identified by the source index used in the source maps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SourceLocation {
+    pub source: u32,
+    /// The line number, starting at 1
+    pub line: usize,
+}
+
+/// A breakpoint on a line of a source file, `<file>:<line>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakpoint {
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+impl FromStr for Breakpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (file, line) =
+            s.rsplit_once(':').ok_or_else(|| format!("expected `<file>:<line>`, got `{s}`"))?;
+        let line = line
+            .parse::<usize>()
+            .ok()
+            .filter(|line| *line > 0)
+            .ok_or_else(|| format!("invalid line number `{line}`"))?;
+        Ok(Self { file: file.into(), line })
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file.display(), self.line)
+    }
+}
+
+/// The recorded calls of an execution together with the source location of every step
+#[derive(Default)]
+pub struct Journal {
+    calls: Vec<(Address, Vec<DebugStep>, CallKind)>,
+    locations: Vec<Vec<Option<SourceLocation>>>,
+    breakpoints: BTreeSet<SourceLocation>,
+}
+
+impl Journal {
+    pub fn new(
+        calls: Vec<(Address, Vec<DebugStep>, CallKind)>,
+        identified_contracts: &HashMap<Address, String>,
+        known_contracts: &HashMap<String, ContractBytecodeSome>,
+        pc_ic_maps: &BTreeMap<String, (PCICMap, PCICMap)>,
+        source_code: &BTreeMap<u32, String>,
+    ) -> Self {
+        let line_starts = line_starts(source_code);
+        let mut source_maps: HashMap<(&str, bool), Option<SourceMap>> = HashMap::new();
+
+        let locations = calls
+            .iter()
+            .map(|(address, steps, kind)| {
+                let contract_name = match identified_contracts.get(address) {
+                    Some(name) => name.as_str(),
+                    None => return vec![None; steps.len()],
+                };
+                let is_create = matches!(kind, CallKind::Create);
+                let source_map = source_maps
+                    .entry((contract_name, is_create))
+                    .or_insert_with(|| source_map(known_contracts.get(contract_name)?, is_create));
+                let pc_ic_map = pc_ic_maps
+                    .get(contract_name)
+                    .map(|(create, runtime)| if is_create { create } else { runtime });
+
+                steps
+                    .iter()
+                    .map(|step| {
+                        let ic = pc_ic_map?.get(&step.pc)?;
+                        let element = source_map.as_ref()?.get(*ic)?;
+                        let source = element.index?;
+                        let line = line_starts
+                            .get(&source)?
+                            .partition_point(|start| *start <= element.offset);
+                        Some(SourceLocation { source, line })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self { calls, locations, breakpoints: BTreeSet::new() }
+    }
+
+    /// Returns all recorded calls
+    pub fn calls(&self) -> &[(Address, Vec<DebugStep>, CallKind)] {
+        &self.calls
+    }
+
+    /// Returns the source location of the instruction at `pos`, if it could be resolved
+    pub fn location(&self, (call, step): Position) -> Option<SourceLocation> {
+        self.locations.get(call)?.get(step).copied().flatten()
+    }
+
+    /// Sets the source lines that [Journal::next_breakpoint] and [Journal::prev_breakpoint] stop at
+    pub fn set_breakpoints(&mut self, breakpoints: impl IntoIterator<Item = SourceLocation>) {
+        self.breakpoints = breakpoints.into_iter().collect();
+    }
+
+    /// Returns the position of the next instruction that belongs to a different source line
+    pub fn next_line(&self, from: Position) -> Option<Position> {
+        let current = self.location(from);
+        self.find_next(from, |pos| {
+            let location = self.location(pos);
+            location.is_some() && location != current
+        })
+    }
+
+    /// Returns the position of the first instruction of the previous source line
+    pub fn prev_line(&self, from: Position) -> Option<Position> {
+        let current = self.location(from);
+        let (call, mut step) = self.find_prev(from, |pos| {
+            let location = self.location(pos);
+            location.is_some() && location != current
+        })?;
+        let line = self.location((call, step));
+        while step > 0 && self.location((call, step - 1)) == line {
+            step -= 1;
+        }
+        Some((call, step))
+    }
+
+    /// Returns the position of the next execution of the opcode `op`
+    pub fn next_op(&self, from: Position, op: u8) -> Option<Position> {
+        self.find_next(from, |pos| self.is_op(pos, op))
+    }
+
+    /// Returns the position of the previous execution of the opcode `op`
+    pub fn prev_op(&self, from: Position, op: u8) -> Option<Position> {
+        self.find_prev(from, |pos| self.is_op(pos, op))
+    }
+
+    /// Returns the position of the next breakpoint hit
+    pub fn next_breakpoint(&self, from: Position) -> Option<Position> {
+        self.find_next(from, |pos| self.is_breakpoint_hit(pos))
+    }
+
+    /// Returns the position of the previous breakpoint hit
+    pub fn prev_breakpoint(&self, from: Position) -> Option<Position> {
+        self.find_prev(from, |pos| self.is_breakpoint_hit(pos))
+    }
+
+    /// Returns all storage slots written by the code at the address of the call at `pos` up to,
+    /// but not including, the instruction at `pos`.
+    ///
+    /// Writes of calls that reverted later on are included.
+    pub fn storage_writes(&self, (call, step): Position) -> BTreeMap<U256, U256> {
+        let mut storage = BTreeMap::new();
+        let address = match self.calls.get(call) {
+            Some((address, _, _)) => *address,
+            None => return storage,
+        };
+        for (idx, (_, steps, _)) in
+            self.calls.iter().enumerate().take(call + 1).filter(|(_, (a, _, _))| *a == address)
+        {
+            let steps = if idx == call { &steps[..step.min(steps.len())] } else { &steps[..] };
+            for step in steps {
+                if let (Instruction::OpCode(opcode::SSTORE), [.., value, key]) =
+                    (step.instruction, &step.stack[..])
+                {
+                    storage.insert(*key, *value);
+                }
+            }
+        }
+        storage
+    }
+
+    /// Returns `true` if the instruction at `pos` is the first one of a line with a breakpoint
+    fn is_breakpoint_hit(&self, (call, step): Position) -> bool {
+        match self.location((call, step)) {
+            Some(location) => {
+                self.breakpoints.contains(&location) &&
+                    (step == 0 || self.location((call, step - 1)) != Some(location))
+            }
+            None => false,
+        }
+    }
+
+    fn is_op(&self, (call, step): Position, op: u8) -> bool {
+        matches!(
+            self.calls.get(call).and_then(|(_, steps, _)| steps.get(step)),
+            Some(DebugStep { instruction: Instruction::OpCode(o), .. }) if *o == op
+        )
+    }
+
+    /// Returns the first position after `from` that satisfies `f`
+    fn find_next(&self, (call, step): Position, f: impl Fn(Position) -> bool) -> Option<Position> {
+        (call..self.calls.len())
+            .flat_map(|idx| {
+                let start = if idx == call { step + 1 } else { 0 };
+                (start..self.calls[idx].1.len()).map(move |step| (idx, step))
+            })
+            .find(|pos| f(*pos))
+    }
+
+    /// Returns the last position before `from` that satisfies `f`
+    fn find_prev(&self, (call, step): Position, f: impl Fn(Position) -> bool) -> Option<Position> {
+        (0..=call.min(self.calls.len().checked_sub(1)?))
+            .rev()
+            .flat_map(|idx| {
+                let end = if idx == call { step } else { self.calls[idx].1.len() };
+                (0..end).rev().map(move |step| (idx, step))
+            })
+            .find(|pos| f(*pos))
+    }
+}
+
+/// Returns all source lines that instructions of the `known_contracts` map to.
+///
+/// This is used to check breakpoints before the debugger is started, a breakpoint on a line that
+/// is not part of this set can never be hit.
+pub fn source_lines(
+    known_contracts: &HashMap<String, ContractBytecodeSome>,
+    source_code: &BTreeMap<u32, String>,
+) -> BTreeSet<SourceLocation> {
+    let line_starts = line_starts(source_code);
+    known_contracts
+        .values()
+        .flat_map(|contract| [source_map(contract, true), source_map(contract, false)])
+        .flatten()
+        .flatten()
+        .filter_map(|element| {
+            let source = element.index?;
+            let line = line_starts.get(&source)?.partition_point(|start| *start <= element.offset);
+            Some(SourceLocation { source, line })
+        })
+        .collect()
+}
+
+/// Returns the parsed creation or runtime source map of the contract
+fn source_map(contract: &ContractBytecodeSome, is_create: bool) -> Option<SourceMap> {
+    let source_map = if is_create {
+        contract.bytecode.source_map()
+    } else {
+        contract.deployed_bytecode.bytecode.as_ref()?.source_map()
+    };
+    source_map?.ok()
+}
+
+/// Returns the byte offsets at which the lines of every source start
+fn line_starts(source_code: &BTreeMap<u32, String>) -> HashMap<u32, Vec<usize>> {
+    source_code
+        .iter()
+        .map(|(id, source)| {
+            let starts =
+                std::iter::once(0).chain(source.match_indices('\n').map(|(idx, _)| idx + 1));
+            (*id, starts.collect())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(op: u8, stack: Vec<u64>) -> DebugStep {
+        DebugStep {
+            stack: stack.into_iter().map(U256::from).collect(),
+            instruction: Instruction::OpCode(op),
+            ..Default::default()
+        }
+    }
+
+    fn journal() -> Journal {
+        let address = Address::repeat_byte(1);
+        let calls = vec![
+            (
+                address,
+                vec![step(opcode::PUSH1, vec![]), step(opcode::SSTORE, vec![7, 1])],
+                CallKind::Call,
+            ),
+            (Address::repeat_byte(2), vec![step(opcode::JUMPDEST, vec![])], CallKind::Call),
+            (
+                address,
+                vec![step(opcode::JUMPDEST, vec![]), step(opcode::SSTORE, vec![8, 1])],
+                CallKind::Call,
+            ),
+        ];
+        let mut journal = Journal { calls, ..Default::default() };
+        let line = |line| Some(SourceLocation { source: 0, line });
+        journal.locations = vec![vec![line(1), line(1)], vec![None], vec![line(2), line(3)]];
+        journal
+    }
+
+    #[test]
+    fn parses_breakpoints() {
+        let breakpoint: Breakpoint = "src/Counter.sol:12".parse().unwrap();
+        assert_eq!(breakpoint, Breakpoint { file: "src/Counter.sol".into(), line: 12 });
+        assert_eq!(breakpoint.to_string(), "src/Counter.sol:12");
+        assert!("src/Counter.sol".parse::<Breakpoint>().is_err());
+        assert!("src/Counter.sol:0".parse::<Breakpoint>().is_err());
+    }
+
+    #[test]
+    fn navigates_by_opcode_across_calls() {
+        let journal = journal();
+        assert_eq!(journal.next_op((0, 0), opcode::JUMPDEST), Some((1, 0)));
+        assert_eq!(journal.next_op((1, 0), opcode::JUMPDEST), Some((2, 0)));
+        assert_eq!(journal.prev_op((2, 1), opcode::SSTORE), Some((0, 1)));
+        assert_eq!(journal.prev_op((0, 1), opcode::SSTORE), None);
+    }
+
+    #[test]
+    fn navigates_by_source_line() {
+        let journal = journal();
+        assert_eq!(journal.next_line((0, 0)), Some((2, 0)));
+        assert_eq!(journal.next_line((2, 0)), Some((2, 1)));
+        assert_eq!(journal.prev_line((2, 1)), Some((2, 0)));
+        // lands on the first instruction of the line
+        assert_eq!(journal.prev_line((2, 0)), Some((0, 0)));
+    }
+
+    #[test]
+    fn stops_at_breakpoints() {
+        let mut journal = journal();
+        journal.set_breakpoints([SourceLocation { source: 0, line: 1 }]);
+        assert_eq!(journal.next_breakpoint((0, 0)), None);
+        assert_eq!(journal.prev_breakpoint((2, 1)), Some((0, 0)));
+    }
+
+    #[test]
+    fn replays_storage_writes() {
+        let journal = journal();
+        assert!(journal.storage_writes((0, 1)).is_empty());
+        assert_eq!(journal.storage_writes((2, 0)).get(&U256::from(1)), Some(&U256::from(7)));
+        assert_eq!(journal.storage_writes((2, 1)).get(&U256::from(1)), Some(&U256::from(7)));
+        assert!(journal.storage_writes((1, 0)).is_empty());
+    }
+}
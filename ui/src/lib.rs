@@ -6,7 +6,10 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use ethers::{solc::artifacts::ContractBytecodeSome, types::Address};
+use ethers::{
+    solc::artifacts::ContractBytecodeSome,
+    types::{Address, U256},
+};
 use eyre::Result;
 use forge::{
     debug::{DebugStep, Instruction},
@@ -44,11 +47,15 @@ pub enum TUIExitReason {
     CharExit,
 }
 
+mod journal;
+pub use journal::{source_lines, Breakpoint, Journal, Position, SourceLocation};
+
 mod op_effects;
 use op_effects::stack_indices_affected;
 
 pub struct Tui {
-    debug_arena: Vec<(Address, Vec<DebugStep>, CallKind)>,
+    /// The recorded execution
+    journal: Journal,
     terminal: Terminal<CrosstermBackend<io::Stdout>>,
     /// Buffer for keys prior to execution, i.e. '10' + 'k' => move up 10 operations
     key_buffer: String,
@@ -101,8 +108,15 @@ impl Tui {
                 ))
             })
             .collect();
-        Ok(Tui {
+        let journal = Journal::new(
             debug_arena,
+            &identified_contracts,
+            &known_contracts,
+            &pc_ic_maps,
+            &source_code,
+        );
+        Ok(Tui {
+            journal,
             terminal,
             key_buffer: String::new(),
             current_step,
@@ -113,6 +127,26 @@ impl Tui {
         })
     }
 
+    /// Sets the source lines at which `b` and `B` stop
+    #[must_use]
+    pub fn with_breakpoints(
+        mut self,
+        breakpoints: impl IntoIterator<Item = SourceLocation>,
+    ) -> Self {
+        self.journal.set_breakpoints(breakpoints);
+        self
+    }
+
+    /// Moves to the given position, resetting the scroll state if it is in a different call
+    fn goto(draw_memory: &mut DrawMemory, current_step: &mut usize, (call, step): Position) {
+        if draw_memory.inner_call_index != call {
+            draw_memory.current_mem_startline = 0;
+            draw_memory.current_stack_startline = 0;
+        }
+        draw_memory.inner_call_index = call;
+        *current_step = step;
+    }
+
     /// Grab number from buffer. Used for something like '10k' to move up 10 operations
     fn buffer_as_number(buffer: &str, default_value: usize) -> usize {
         if let Ok(num) = buffer.parse() {
@@ -140,6 +174,7 @@ impl Tui {
         current_step: usize,
         call_kind: CallKind,
         draw_memory: &mut DrawMemory,
+        storage: &BTreeMap<U256, U256>,
         stack_labels: bool,
         mem_utf: bool,
     ) {
@@ -157,6 +192,7 @@ impl Tui {
                 current_step,
                 call_kind,
                 draw_memory,
+                storage,
                 stack_labels,
                 mem_utf,
             );
@@ -173,6 +209,7 @@ impl Tui {
                 current_step,
                 call_kind,
                 draw_memory,
+                storage,
                 stack_labels,
                 mem_utf,
             );
@@ -192,6 +229,7 @@ impl Tui {
         current_step: usize,
         call_kind: CallKind,
         draw_memory: &mut DrawMemory,
+        storage: &BTreeMap<U256, U256>,
         stack_labels: bool,
         mem_utf: bool,
     ) {
@@ -201,14 +239,15 @@ impl Tui {
             .constraints([Constraint::Ratio(98, 100), Constraint::Ratio(2, 100)].as_ref())
             .split(total_size)[..]
         {
-            if let [op_pane, stack_pane, memory_pane, src_pane] = Layout::default()
+            if let [op_pane, stack_pane, memory_pane, storage_pane, src_pane] = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints(
                     [
-                        Constraint::Ratio(1, 6),
-                        Constraint::Ratio(1, 6),
-                        Constraint::Ratio(1, 6),
-                        Constraint::Ratio(3, 6),
+                        Constraint::Ratio(2, 12),
+                        Constraint::Ratio(2, 12),
+                        Constraint::Ratio(2, 12),
+                        Constraint::Ratio(1, 12),
+                        Constraint::Ratio(5, 12),
                     ]
                     .as_ref(),
                 )
@@ -244,6 +283,7 @@ impl Tui {
                     draw_memory,
                 );
                 Tui::draw_memory(f, debug_steps, current_step, memory_pane, mem_utf, draw_memory);
+                Tui::draw_storage(f, storage, storage_pane);
             } else {
                 panic!("unable to create vertical panes")
             }
@@ -265,6 +305,7 @@ impl Tui {
         current_step: usize,
         call_kind: CallKind,
         draw_memory: &mut DrawMemory,
+        storage: &BTreeMap<U256, U256>,
         stack_labels: bool,
         mem_utf: bool,
     ) {
@@ -288,9 +329,16 @@ impl Tui {
                     .constraints([Constraint::Ratio(1, 4), Constraint::Ratio(3, 4)].as_ref())
                     .split(left_pane)[..]
                 {
-                    if let [stack_pane, memory_pane] = Layout::default()
+                    if let [stack_pane, memory_pane, storage_pane] = Layout::default()
                         .direction(Direction::Vertical)
-                        .constraints([Constraint::Ratio(1, 4), Constraint::Ratio(3, 4)].as_ref())
+                        .constraints(
+                            [
+                                Constraint::Ratio(1, 4),
+                                Constraint::Ratio(2, 4),
+                                Constraint::Ratio(1, 4),
+                            ]
+                            .as_ref(),
+                        )
                         .split(right_pane)[..]
                     {
                         Tui::draw_footer(f, footer);
@@ -330,6 +378,7 @@ impl Tui {
                             mem_utf,
                             draw_memory,
                         );
+                        Tui::draw_storage(f, storage, storage_pane);
                     }
                 } else {
                     panic!("Couldn't generate horizontal split layout 1:2.");
@@ -346,7 +395,7 @@ impl Tui {
         let block_controls = Block::default();
 
         let text_output = Text::from(Span::styled(
-            "[q]: quit | [k/j]: prev/next op | [a/s]: prev/next jump | [c/C]: prev/next call | [g/G]: start/end | [t]: toggle stack labels | [m]: toggle memory decoding | [shift + j/k]: scroll stack | [ctrl + j/k]: scroll memory",
+            "[q]: quit | [k/j]: prev/next op | [p/n]: prev/next source line | [a/s]: prev/next jump | [D/d]: prev/next JUMPDEST | [W/w]: prev/next SSTORE | [B/b]: prev/next breakpoint | [c/C]: prev/next call | [g/G]: start/end | [t]: toggle stack labels | [m]: toggle memory decoding | [shift + j/k]: scroll stack | [ctrl + j/k]: scroll memory",
             Style::default().add_modifier(Modifier::DIM)
         ));
         let paragraph = Paragraph::new(text_output)
@@ -949,6 +998,26 @@ impl Tui {
         let paragraph = Paragraph::new(text).block(stack_space).wrap(Wrap { trim: true });
         f.render_widget(paragraph, area);
     }
+
+    /// Draw the storage writes observed so far into the storage pane
+    fn draw_storage<B: Backend>(f: &mut Frame<B>, storage: &BTreeMap<U256, U256>, area: Rect) {
+        let storage_space = Block::default()
+            .title(format!("Storage writes: {}", storage.len()))
+            .borders(Borders::ALL);
+        let word = |value: &U256| format!("0x{}", hex::encode(<[u8; 32]>::from(*value)));
+        let text: Vec<Spans> = storage
+            .iter()
+            .map(|(slot, value)| {
+                Spans::from(vec![
+                    Span::styled(word(slot), Style::default().fg(Color::White)),
+                    Span::raw(" => "),
+                    Span::styled(word(value), Style::default().fg(Color::Cyan)),
+                ])
+            })
+            .collect();
+        let paragraph = Paragraph::new(text).block(storage_space).wrap(Wrap { trim: true });
+        f.render_widget(paragraph, area);
+    }
 }
 
 impl Ui for Tui {
@@ -997,7 +1066,7 @@ impl Ui for Tui {
         self.terminal.clear()?;
         let mut draw_memory: DrawMemory = DrawMemory::default();
 
-        let debug_call: Vec<(Address, Vec<DebugStep>, CallKind)> = self.debug_arena.clone();
+        let debug_call = self.journal.calls();
         let mut opcode_list: Vec<String> =
             debug_call[0].1.iter().map(|step| step.pretty_opcode()).collect();
         let mut last_index = 0;
@@ -1167,6 +1236,45 @@ impl Ui for Tui {
                         }
                         self.key_buffer.clear();
                     }
+                    // Move to the next or previous source line
+                    KeyCode::Char(key @ ('n' | 'p')) => {
+                        for _ in 0..Tui::buffer_as_number(&self.key_buffer, 1) {
+                            let pos = (draw_memory.inner_call_index, self.current_step);
+                            let next = if key == 'n' {
+                                self.journal.next_line(pos)
+                            } else {
+                                self.journal.prev_line(pos)
+                            };
+                            match next {
+                                Some(next) => {
+                                    Tui::goto(&mut draw_memory, &mut self.current_step, next)
+                                }
+                                None => break,
+                            }
+                        }
+                        self.key_buffer.clear();
+                    }
+                    // Move to the next or previous JUMPDEST, SSTORE or breakpoint
+                    KeyCode::Char(key @ ('d' | 'D' | 'w' | 'W' | 'b' | 'B')) => {
+                        for _ in 0..Tui::buffer_as_number(&self.key_buffer, 1) {
+                            let pos = (draw_memory.inner_call_index, self.current_step);
+                            let next = match key {
+                                'd' => self.journal.next_op(pos, opcode::JUMPDEST),
+                                'D' => self.journal.prev_op(pos, opcode::JUMPDEST),
+                                'w' => self.journal.next_op(pos, opcode::SSTORE),
+                                'W' => self.journal.prev_op(pos, opcode::SSTORE),
+                                'b' => self.journal.next_breakpoint(pos),
+                                _ => self.journal.prev_breakpoint(pos),
+                            };
+                            match next {
+                                Some(next) => {
+                                    Tui::goto(&mut draw_memory, &mut self.current_step, next)
+                                }
+                                None => break,
+                            }
+                        }
+                        self.key_buffer.clear();
+                    }
                     // toggle stack labels
                     KeyCode::Char('t') => {
                         stack_labels = !stack_labels;
@@ -1216,6 +1324,7 @@ impl Ui for Tui {
             }
             // Draw
             let current_step = self.current_step;
+            let storage = self.journal.storage_writes((draw_memory.inner_call_index, current_step));
             self.terminal.draw(|f| {
                 Tui::draw_layout(
                     f,
@@ -1229,6 +1338,7 @@ impl Ui for Tui {
                     current_step,
                     debug_call[draw_memory.inner_call_index].2,
                     &mut draw_memory,
+                    &storage,
                     stack_labels,
                     mem_utf,
                 )